@@ -0,0 +1,53 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use auction::constants::{MAX_EXTENSION_SECONDS, STATE_SPACE};
+use auction::{process_bid, State};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+const DEADLINE: i64 = 1_000_000;
+const HIGHEST_BID: u64 = 1_000_000_000;
+
+// An auction running until `DEADLINE`. Zeroed account data decodes to the default
+// value of every field
+fn auction_with_highest_bid(highest_bid_amount: u64) -> State {
+    let mut state = State::try_deserialize_unchecked(&mut [0u8; STATE_SPACE].as_slice()).unwrap();
+    state.deadline = DEADLINE;
+    state.highest_bid_amount = highest_bid_amount;
+    state
+}
+
+fn bench_bid(c: &mut Criterion, name: &str, highest_bid_amount: u64, amount: u64, timestamp: i64) {
+    let bidder = Pubkey::new_unique();
+    c.bench_function(name, |b| {
+        b.iter_batched_ref(
+            || auction_with_highest_bid(highest_bid_amount),
+            |state| {
+                let _ = process_bid(
+                    state,
+                    black_box(bidder),
+                    0,
+                    black_box(amount),
+                    black_box(timestamp),
+                );
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bid_benches(c: &mut Criterion) {
+    let now = DEADLINE - 3_600;
+
+    bench_bid(c, "cold bid", 0, HIGHEST_BID, now);
+    bench_bid(c, "warm bid", HIGHEST_BID, HIGHEST_BID + 1, now);
+    bench_bid(c, "bid below highest", HIGHEST_BID, HIGHEST_BID - 1, now);
+    bench_bid(
+        c,
+        "bid in extension window",
+        HIGHEST_BID,
+        HIGHEST_BID + 1,
+        DEADLINE - MAX_EXTENSION_SECONDS / 2,
+    );
+}
+
+criterion_group!(benches, bid_benches);
+criterion_main!(benches);
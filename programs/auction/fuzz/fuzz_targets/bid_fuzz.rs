@@ -0,0 +1,43 @@
+#![no_main]
+
+use anchor_lang::{error::Error, prelude::Pubkey, AccountDeserialize};
+use auction::constants::STATE_SPACE;
+use auction::errors::AuctionError;
+use auction::{process_bid, State};
+use libfuzzer_sys::fuzz_target;
+
+const DEADLINE: i64 = 1_000_000;
+
+// A freshly initialized auction, before any bid. Zeroed account data decodes to
+// the default value of every field
+fn new_auction() -> State {
+    let mut state = State::try_deserialize_unchecked(&mut [0u8; STATE_SPACE].as_slice()).unwrap();
+    state.deadline = DEADLINE;
+    state
+}
+
+// Each call is `(amount, is_first_bid, time_offset)`. A first bid starts a new
+// auction, the others keep bidding on the current one
+fuzz_target!(|calls: Vec<(u64, bool, i64)>| {
+    let mut state = new_auction();
+
+    for (amount, is_first_bid, time_offset) in calls {
+        if is_first_bid {
+            state = new_auction();
+        }
+        let highest_bid_amount = state.highest_bid_amount;
+        let timestamp = DEADLINE.saturating_add(time_offset);
+
+        let result = process_bid(&mut state, Pubkey::new_unique(), 0, amount, timestamp);
+
+        if let Err(error) = result {
+            let expected: [Error; 2] = [
+                AuctionError::Finished.into(),
+                AuctionError::BidAmountTooSmall.into(),
+            ];
+            assert!(expected.contains(&error), "unexpected error {:?}", error);
+            assert_eq!(state.highest_bid_amount, highest_bid_amount);
+        }
+        assert!(state.highest_bid_amount >= highest_bid_amount);
+    }
+});
@@ -0,0 +1,47 @@
+use crate::state::BidRecord;
+
+/// Slot at which bidding closes in a candle auction, drawn from `randomness` among the
+/// `window_slots` slots following `current_slot`. An empty window closes bidding at
+/// the next slot
+pub fn candle_end_slot(randomness: [u8; 32], current_slot: u64, window_slots: u64) -> u64 {
+    let mut random_bytes = [0u8; 8];
+    random_bytes.copy_from_slice(&randomness[..8]);
+    let offset = u64::from_le_bytes(random_bytes) % window_slots.max(1);
+
+    current_slot.saturating_add(1).saturating_add(offset)
+}
+
+/// Approximate number of slots in `seconds`, at `ms_per_slot` milliseconds per slot
+pub fn seconds_to_slots(seconds: i64, ms_per_slot: u64) -> u64 {
+    if seconds <= 0 || ms_per_slot == 0 {
+        return 0;
+    }
+    (seconds as u64).saturating_mul(1_000) / ms_per_slot
+}
+
+/// Effective end of a candle auction, drawn from `randomness` among the last `window`
+/// seconds before `deadline`. Only bids placed before it count, so the cutoff is at
+/// least one second into the window. An empty window ends the auction at the deadline
+pub fn candle_cutoff(randomness: [u8; 32], deadline: i64, window: i64) -> i64 {
+    if window <= 0 {
+        return deadline;
+    }
+    let mut random_bytes = [0u8; 8];
+    random_bytes.copy_from_slice(&randomness[..8]);
+    let offset = u64::from_le_bytes(random_bytes) % window as u64;
+
+    deadline
+        .saturating_sub(window)
+        .saturating_add(1)
+        .saturating_add(offset as i64)
+}
+
+/// Bid that wins a candle auction ending at `cutoff`: the last bid of `history`, oldest
+/// first, placed before the cutoff. Every accepted bid led the auction when it was placed
+pub fn candle_winner(history: &[BidRecord], cutoff: i64) -> Option<BidRecord> {
+    history
+        .iter()
+        .rev()
+        .find(|record| record.timestamp < cutoff)
+        .copied()
+}
@@ -0,0 +1,146 @@
+//! Instructions of the program built off chain, with every account the program checks
+//! derived through `PdaHelpers`. Only compiled with the `client` feature
+
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::pda::PdaHelpers;
+use crate::state::AuctionConfig;
+
+/// Builders of the instructions of the program, ready to be signed and sent
+pub struct AuctionInstructions;
+
+impl AuctionInstructions {
+    /// Creates the auction `auction_index` of `initializer`. `registry_page` is the page
+    /// the next auction is listed on, the auction count of the registry divided by
+    /// `REGISTRY_PAGE_SIZE`
+    pub fn initialize(
+        initializer: &Pubkey,
+        auction_index: u32,
+        registry_page: u64,
+        auction_config: AuctionConfig,
+    ) -> Instruction {
+        let (state, _) = PdaHelpers::indexed_state_pda(initializer, auction_index);
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::Initialize {
+                state,
+                initializer: *initializer,
+                seller_counter: PdaHelpers::seller_counter_pda(initializer).0,
+                seller_profile: PdaHelpers::seller_profile_pda(initializer).0,
+                treasury: PdaHelpers::treasury_pda(&state).0,
+                treasury_authority: PdaHelpers::treasury_authority_pda(&state).0,
+                seller_bond: PdaHelpers::seller_bond_pda(&state).0,
+                leaderboard: PdaHelpers::leaderboard_pda(&state).0,
+                config: PdaHelpers::config_pda().0,
+                registry: PdaHelpers::registry_pda().0,
+                registry_page: PdaHelpers::registry_page_pda(registry_page).0,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Initialize {
+                auction_index,
+                auction_config,
+            }
+            .data(),
+        }
+    }
+
+    /// Bid of `amount` SOL from `user`, who pays the rent of its user bid and bid escrow
+    pub fn bid(state: &Pubkey, user: &Pubkey, amount: f64) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::Bid {
+                state: *state,
+                user: *user,
+                payer: *user,
+                user_bid: PdaHelpers::user_bid_pda(user, state).0,
+                bid_escrow: PdaHelpers::bid_escrow_pda(user, state).0,
+                leaderboard: PdaHelpers::leaderboard_pda(state).0,
+                profile: PdaHelpers::profile_pda(user).0,
+                config: PdaHelpers::config_pda().0,
+                sybil_check: PdaHelpers::sybil_check_pda(user, state).0,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Bid { amount }.data(),
+        }
+    }
+
+    /// Settles an auction of `initializer` without price feed nor condition, paying the
+    /// bid of `winner` to `beneficiary` and the platform fee to `fee_recipient`, the fee
+    /// recipient of the config. `authority` is the seller or its settlement authority
+    pub fn end_auction(
+        state: &Pubkey,
+        initializer: &Pubkey,
+        authority: &Pubkey,
+        winner: &Pubkey,
+        beneficiary: &Pubkey,
+        fee_recipient: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::EndAuction {
+                state: *state,
+                authority: *authority,
+                treasury: PdaHelpers::treasury_pda(state).0,
+                treasury_authority: PdaHelpers::treasury_authority_pda(state).0,
+                beneficiary: *beneficiary,
+                user_bid: PdaHelpers::user_bid_pda(winner, state).0,
+                bid_escrow: PdaHelpers::bid_escrow_pda(winner, state).0,
+                leaderboard: PdaHelpers::leaderboard_pda(state).0,
+                highest_bidder: *winner,
+                winner_profile: PdaHelpers::profile_pda(winner).0,
+                seller_profile: PdaHelpers::seller_profile_pda(initializer).0,
+                summary: PdaHelpers::summary_pda(state).0,
+                tax_record: PdaHelpers::tax_record_pda(state).0,
+                config: PdaHelpers::config_pda().0,
+                fee_recipient: *fee_recipient,
+                winner_loyalty: PdaHelpers::loyalty_pda(winner).0,
+                // placeholders, the auction has no price feed nor condition
+                price_feed: system_program::ID,
+                condition_program: system_program::ID,
+                condition_account: system_program::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::EndAuction {}.data(),
+        }
+    }
+
+    /// Refund of the bid of `user` once the auction is settled, the rent of its user bid
+    /// and bid escrow goes back to `rent_payer`
+    pub fn refund(state: &Pubkey, user: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::Refund {
+                state: *state,
+                user: *user,
+                user_bid: PdaHelpers::user_bid_pda(user, state).0,
+                bid_escrow: PdaHelpers::bid_escrow_pda(user, state).0,
+                profile: PdaHelpers::profile_pda(user).0,
+                rent_payer: *rent_payer,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Refund {}.data(),
+        }
+    }
+
+    /// Withdrawal of the bid of an outbid `user` before the deadline, the rent of its
+    /// user bid and bid escrow goes back to `rent_payer`
+    pub fn withdraw_outbid(state: &Pubkey, user: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::WithdrawOutbid {
+                state: *state,
+                user: *user,
+                user_bid: PdaHelpers::user_bid_pda(user, state).0,
+                bid_escrow: PdaHelpers::bid_escrow_pda(user, state).0,
+                rent_payer: *rent_payer,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::WithdrawOutbid {}.data(),
+        }
+    }
+}
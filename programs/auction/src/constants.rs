@@ -0,0 +1,84 @@
+use static_assertions::const_assert;
+
+use crate::{
+    AuctionSummary, BidEscrow, BidderProfile, CommitBid, Config, DelegateAuth, FraudFlag,
+    Leaderboard, LoyaltyDiscount, RecoveryConfig, Registry, RegistryPage, SellerCounter,
+    SellerProfile, State, SybilCheck, TaxRecord, Treasury, UserBid,
+};
+
+/// Size of the account discriminator Anchor prepends to every account
+pub const DISCRIMINATOR_SIZE: usize = 8;
+
+/// Longest auction of a freshly deployed config (30 days)
+pub const MAX_AUCTION_DURATION_SECS: i64 = 30 * 24 * 60 * 60;
+/// Shortest auction any config can allow
+pub const MIN_AUCTION_DURATION_SECS: i64 = 1;
+/// Largest deadline extension granted by a single late bid
+pub const MAX_EXTENSION_SECONDS: i64 = 10 * 60;
+/// Maximum number of deadline extensions of an auction
+pub const MAX_EXTENSIONS: u8 = 10;
+/// Balance a bidder must keep on top of its bid (0.05 SOL), throwaway accounts can
+/// not spam bids
+pub const MIN_BIDDER_BALANCE: u64 = 50_000_000;
+/// Balance a bidder keeps for the rent of its future accounts (0.01 SOL)
+pub const RENT_RESERVE: u64 = 10_000_000;
+/// Cooldown between two bids of a bidder in the same auction of a freshly deployed
+/// config
+pub const BID_COOLDOWN_SECONDS: i64 = 5;
+/// Time a wallet funded by a seller can not bid on the seller's auctions with a freshly
+/// deployed config (30 days)
+pub const SHILL_LOOKBACK_SECS: i64 = 30 * 24 * 60 * 60;
+/// Auctions a bidder has to participate in before it is granted loyalty status
+pub const LOYALTY_THRESHOLD: u32 = 10;
+/// Fee discount of loyal bidders with a freshly deployed config (10%)
+pub const LOYALTY_DISCOUNT_BPS: u16 = 1_000;
+/// Community flags that pause an auction until the platform authority reviews them
+pub const FLAG_THRESHOLD: u32 = 3;
+/// Length of the reason of a fraud flag, in bytes
+pub const FLAG_REASON_LEN: usize = 128;
+/// Time given to the seller to settle an auction after its deadline (7 days)
+pub const SELLER_GRACE_PERIOD_SECS: i64 = 7 * 24 * 60 * 60;
+/// Layout version of the `State` accounts written by this program. Fields are only
+/// ever appended to `State`, and every addition bumps this version
+pub const CURRENT_STATE_VERSION: u8 = 38;
+/// Longest auction title, in bytes
+pub const MAX_TITLE_LEN: usize = 64;
+/// Longest item URI, in bytes
+pub const MAX_URI_LEN: usize = 200;
+/// Longest auction description, in bytes
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+
+/// Most items sold together in a bundle auction
+pub const MAX_BUNDLE_ITEMS: usize = 8;
+
+/// Auctions listed per registry page
+pub const REGISTRY_PAGE_SIZE: usize = 32;
+/// Bidders listed on the leaderboard of an auction
+pub const LEADERBOARD_SIZE: usize = 10;
+/// Approximate duration of a slot, used to turn the deadline into a slot window
+pub const MS_PER_SLOT: u64 = 400;
+/// Basis points in 100%, used for the cancellation compensation
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+pub const STATE_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<State>();
+pub const USER_BID_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<UserBid>();
+pub const BID_ESCROW_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<BidEscrow>();
+pub const RECOVERY_CONFIG_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<RecoveryConfig>();
+pub const TREASURY_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<Treasury>();
+pub const CONFIG_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<Config>();
+pub const COMMIT_BID_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<CommitBid>();
+pub const REGISTRY_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<Registry>();
+pub const REGISTRY_PAGE_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<RegistryPage>();
+pub const SELLER_COUNTER_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<SellerCounter>();
+pub const DELEGATE_AUTH_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<DelegateAuth>();
+pub const LEADERBOARD_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<Leaderboard>();
+pub const BIDDER_PROFILE_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<BidderProfile>();
+pub const SELLER_PROFILE_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<SellerProfile>();
+pub const FRAUD_FLAG_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<FraudFlag>();
+pub const AUCTION_SUMMARY_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<AuctionSummary>();
+pub const SYBIL_CHECK_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<SybilCheck>();
+pub const TAX_RECORD_SPACE: usize = DISCRIMINATOR_SIZE + std::mem::size_of::<TaxRecord>();
+pub const LOYALTY_DISCOUNT_SPACE: usize =
+    DISCRIMINATOR_SIZE + std::mem::size_of::<LoyaltyDiscount>();
+
+const_assert!(STATE_SPACE == 8 + std::mem::size_of::<State>());
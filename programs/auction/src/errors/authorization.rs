@@ -0,0 +1,16 @@
+use super::error_category;
+
+error_category! {
+    /// Errors about signers and accounts that are not allowed to take part
+    AuthorizationError {
+        NotTheWinner,
+        Unauthorized,
+        InvalidBeneficiary,
+        InvalidRentPayer,
+        InvalidHighestBidder,
+        InvalidPayoutBeneficiary,
+        NoPendingOwner,
+        DelegateExpired,
+        InvalidPrizeReceiver,
+    }
+}
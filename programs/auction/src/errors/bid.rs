@@ -0,0 +1,51 @@
+use super::error_category;
+
+error_category! {
+    /// Errors about placing, revealing, withdrawing and refunding bids
+    BidError {
+        BidAmountTooSmall,
+        SuspectedShillBid,
+        NoBids,
+        HighestBidChanged,
+        AlreadyRefunded,
+        InvalidEscrow,
+        BidBelowAskingPrice,
+        SealedBidsOnly,
+        InvalidBidCommitment,
+        BidAlreadyRevealed,
+        HighestBidderCannotWithdraw,
+        WithdrawalNotAllowed,
+        BidBelowMinimum,
+        SellerCannotBid,
+        AlreadyHighestBidder,
+        ProxyBidsNotAllowed,
+        CandleHistoryFull,
+        InsufficientSharesAvailable,
+        InvalidShareRequest,
+        FractionalBidsOnly,
+        InvalidProxyBid,
+        DelegateLimitExceeded,
+        AllPayNoRefund,
+        OfferAboveMaximumPrice,
+        OfferNotLowest,
+        BidderBalanceTooLow,
+        BidCooldownNotExpired,
+        DutchBidNotAllowed,
+        NotDutchAuction,
+        NoCommitDeposit,
+        BidNotAtRoundPrice,
+        RoundAlreadyAccepted,
+        LoyaltyThresholdNotReached,
+        InvalidPennyBid,
+        OpenPhaseClosed,
+        NotAFinalist,
+        UnitAlreadyBought,
+        TokenInstructionRequired,
+        NotTokenAuction,
+        PrizeNotDeposited,
+        VaultNotSupported,
+        BidInVault,
+        BidNotInVault,
+        BidEscrowMigrationRequired,
+    }
+}
@@ -0,0 +1,22 @@
+use super::error_category;
+
+error_category! {
+    /// Errors about auction options that exclude each other
+    ConfigError {
+        SealedBidsNotSupported,
+        UsdReserveWithoutFeed,
+        ReverseAuctionWithReserve,
+        CommitDepositWithoutCommitPhase,
+        MilestonesNotSupported,
+        ConditionAccountWithoutProgram,
+        CandleWindowNotSupported,
+        FractionalNotSupported,
+        MultiUnitNotSupported,
+        RoundsNotSupported,
+        PennyOptionsWithoutPenny,
+        PayAsBidSingleUnit,
+        FinalRoundNotSupported,
+        TokenBidsNotSupported,
+        PrizeEscrowNotSupported,
+    }
+}
@@ -0,0 +1,83 @@
+use super::error_category;
+
+error_category! {
+    /// Errors about the phases, parameters and settlement of an auction
+    LifecycleError {
+        StillActive,
+        Finished,
+        UnclaimedPrize,
+        AlreadyClaimedPrize,
+        InvalidPriceFeed,
+        StalePrice,
+        PriceConfidenceTooWide,
+        ReserveNotMet,
+        InvalidDisputeStatus,
+        DisputeWindowClosed,
+        DisputeWindowOpen,
+        InvalidRecoveryTimeout,
+        RecoveryTimeoutNotElapsed,
+        InvalidAuctionDuration,
+        RefundWindowOpen,
+        SellerClaimWindowOpen,
+        AllMilestonesReleased,
+        MilestonesPending,
+        FinalMilestone,
+        ConditionNotMet,
+        InvalidConditionProgram,
+        RefundWindowClosed,
+        InvalidCompensation,
+        AuctionCancelled,
+        AlreadySettled,
+        InvalidStartPrice,
+        InvalidPriceStep,
+        CommitPhaseClosed,
+        InvalidCommitDuration,
+        AuctionHasBids,
+        RevealPhaseNotOpen,
+        InvalidPenalty,
+        InvalidConfig,
+        StateMigrationRequired,
+        StateAlreadyMigrated,
+        CannotChangeAfterBids,
+        ReserveCanOnlyDecrease,
+        MetadataTooLong,
+        InvalidVrfAccount,
+        RandomnessNotResolved,
+        CandleEndAlreadySet,
+        InvalidCandleWindow,
+        NotCandleAuction,
+        CandleNotResolved,
+        NotFractionalAuction,
+        InvalidShareAccount,
+        NotRaffle,
+        RaffleAlreadyDrawn,
+        RaffleBidsMissing,
+        InvalidQuantity,
+        NotMultiUnitAuction,
+        MultiUnitSettlementRequired,
+        InvalidRoundSchedule,
+        InvalidPennySettings,
+        AuctionPaused,
+        AuctionNotPaused,
+        CannotReopen,
+        InvalidAuctionIndex,
+        AuctionAccountMismatch,
+        NotAllPay,
+        DeadlineOverflow,
+        ConflictingAuctionOptions,
+        BundleItemCountExceeded,
+        RegistryPageFull,
+        PrizeAlreadyClaimed,
+        AuctionFlagged,
+        InvalidFinalRound,
+        OpenPhaseNotOver,
+        FinalCommitPhaseNotOpen,
+        FinalRevealPhaseNotOpen,
+        DescriptionTooLong,
+        InvalidPrizeEscrow,
+        NoPrizeEscrow,
+        PrizeAlreadyDeposited,
+        PrizeNotReclaimable,
+        PrizeStillEscrowed,
+    }
+}
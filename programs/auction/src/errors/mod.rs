@@ -0,0 +1,360 @@
+use anchor_lang::prelude::*;
+
+mod authorization;
+mod bid;
+mod config;
+mod lifecycle;
+mod treasury;
+
+pub use authorization::AuthorizationError;
+pub use bid::BidError;
+pub use config::ConfigError;
+pub use lifecycle::LifecycleError;
+pub use treasury::TreasuryError;
+
+/// Declares a category of `AuctionError` variants. Anchor raises a single error type
+/// per program, so each category converts into `AuctionError` and keeps its code
+macro_rules! error_category {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl From<$name> for crate::errors::AuctionError {
+            fn from(error: $name) -> Self {
+                match error {
+                    $($name::$variant => crate::errors::AuctionError::$variant),*
+                }
+            }
+        }
+
+        impl From<$name> for anchor_lang::error::Error {
+            fn from(error: $name) -> Self {
+                crate::errors::AuctionError::from(error).into()
+            }
+        }
+    };
+}
+pub(crate) use error_category;
+
+/// Errors of the program. Variants are only ever appended so error codes never change,
+/// see the categories above to find the errors of one concern
+#[error_code]
+pub enum AuctionError {
+    #[msg("Auction is still active!")]
+    StillActive,
+    #[msg("Auction has finished!")]
+    Finished,    
+    #[msg("Seller didn't claim the highest bid yet")]
+    UnclaimedPrize,
+    #[msg("Seller already claimed the highest bid")]
+    AlreadyClaimedPrize, 
+    #[msg("Insufficient funds on treasury!!!")]
+    TreasuryInsufficientFunds,
+    #[msg("Bid amount is smaller than highest bid")]
+    BidAmountTooSmall,
+    #[msg("Price feed account does not match the auction price feed")]
+    InvalidPriceFeed,
+    #[msg("Price feed is stale or unavailable")]
+    StalePrice,
+    #[msg("Price feed confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("Winning bid is below the reserve price")]
+    ReserveNotMet,
+    #[msg("Only the highest bidder can perform this action")]
+    NotTheWinner,
+    #[msg("Auction has no seller bond")]
+    NoSellerBond,
+    #[msg("Dispute is not in the expected status")]
+    InvalidDisputeStatus,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    #[msg("Bid was already refunded")]
+    AlreadyRefunded,
+    #[msg("Auction has no bids")]
+    NoBids,
+    #[msg("Highest bid changed since it was observed")]
+    HighestBidChanged,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Signer is not allowed to perform this action")]
+    Unauthorized,
+    #[msg("Recovery timeout must be positive")]
+    InvalidRecoveryTimeout,
+    #[msg("Auction ended too recently to be recovered")]
+    RecoveryTimeoutNotElapsed,
+    #[msg("Treasury has no funds to recover")]
+    NothingToRecover,
+    #[msg("Auction duration is out of bounds")]
+    InvalidAuctionDuration,
+    #[msg("Refund window is still open")]
+    RefundWindowOpen,
+    #[msg("Refund window has closed")]
+    RefundWindowClosed,
+    #[msg("Destination is not the unclaimed beneficiary")]
+    InvalidBeneficiary,
+    #[msg("Transfer would leave the treasury below its rent exempt minimum")]
+    WouldBreakRentExemption,
+    #[msg("Remaining account is not a bid escrow of this auction")]
+    InvalidEscrow,
+    #[msg("Auction funds do not cover what it owes")]
+    InvariantViolated,
+    #[msg("Account is not the rent payer of the user bid")]
+    InvalidRentPayer,
+    #[msg("Compensation can not exceed 10000 basis points")]
+    InvalidCompensation,
+    #[msg("Auction was cancelled by the seller")]
+    AuctionCancelled,
+    #[msg("Account is not the highest bidder")]
+    InvalidHighestBidder,
+    #[msg("Auction was already settled")]
+    AlreadySettled,
+    #[msg("Account is not the payout beneficiary")]
+    InvalidPayoutBeneficiary,
+    #[msg("Start price is below the reserve price")]
+    InvalidStartPrice,
+    #[msg("Bid amount is below the current asking price")]
+    BidBelowAskingPrice,
+    #[msg("Commit phase has closed")]
+    CommitPhaseClosed,
+    #[msg("Commit phase must end before the deadline")]
+    InvalidCommitDuration,
+    #[msg("Auction already has bids")]
+    AuctionHasBids,
+    #[msg("Auction only accepts sealed bids")]
+    SealedBidsOnly,
+    #[msg("Reveal phase is not open")]
+    RevealPhaseNotOpen,
+    #[msg("Revealed bid does not match its commitment")]
+    InvalidBidCommitment,
+    #[msg("Sealed bid was already revealed")]
+    BidAlreadyRevealed,
+    #[msg("Withdrawal penalty can not exceed 10000 basis points")]
+    InvalidPenalty,
+    #[msg("The highest bidder can not withdraw")]
+    HighestBidderCannotWithdraw,
+    #[msg("Bids of this auction can not be withdrawn")]
+    WithdrawalNotAllowed,
+    #[msg("No withdrawal penalties to claim")]
+    NoPenalties,
+    #[msg("Config parameters are out of bounds")]
+    InvalidConfig,
+    #[msg("Bid amount is below the minimum bid")]
+    BidBelowMinimum,
+    #[msg("Auction state has to be migrated first")]
+    StateMigrationRequired,
+    #[msg("Auction state is already up to date")]
+    StateAlreadyMigrated,
+    #[msg("Auction can not be changed once it has bids")]
+    CannotChangeAfterBids,
+    #[msg("Reserve price can only be lowered")]
+    ReserveCanOnlyDecrease,
+    #[msg("Title or URI is too long")]
+    MetadataTooLong,
+    #[msg("Seller can not bid on its own auction")]
+    SellerCannotBid,
+    #[msg("Bidder already holds the highest bid with this amount")]
+    AlreadyHighestBidder,
+    #[msg("Auction has no pending owner")]
+    NoPendingOwner,
+    #[msg("VRF account is not the one of the auction")]
+    InvalidVrfAccount,
+    #[msg("VRF randomness is not resolved yet")]
+    RandomnessNotResolved,
+    #[msg("Candle end slot is already set")]
+    CandleEndAlreadySet,
+    #[msg("Bidding is paused by the seller")]
+    AuctionPaused,
+    #[msg("Bidding is not paused")]
+    AuctionNotPaused,
+    #[msg("Only English auctions accept proxy bids")]
+    ProxyBidsNotAllowed,
+    #[msg("Proxy maximum is below the bid")]
+    InvalidProxyBid,
+    #[msg("Only auctions that missed their reserve can be reopened")]
+    CannotReopen,
+    #[msg("Bid delegation has expired")]
+    DelegateExpired,
+    #[msg("Bid exceeds the remaining delegated amount")]
+    DelegateLimitExceeded,
+    #[msg("Auction index was already used by this seller")]
+    InvalidAuctionIndex,
+    #[msg("Auction accounts do not match their expected PDAs")]
+    AuctionAccountMismatch,
+    #[msg("Bids of an all-pay auction are not refunded")]
+    AllPayNoRefund,
+    #[msg("Only all-pay auctions collect the losing bids")]
+    NotAllPay,
+    #[msg("Auction deadline does not fit in a timestamp")]
+    DeadlineOverflow,
+    #[msg("Auction options can not be combined")]
+    ConflictingAuctionOptions,
+    #[msg("Bundle can not hold more items")]
+    BundleItemCountExceeded,
+    #[msg("Registry page is full, the batch has to continue on the next page")]
+    RegistryPageFull,
+    #[msg("Offer is above the maximum acceptable price")]
+    OfferAboveMaximumPrice,
+    #[msg("Offer must be below the lowest offer")]
+    OfferNotLowest,
+    #[msg("Winner already claimed the prize")]
+    PrizeAlreadyClaimed,
+    #[msg("Account is not the receive address of the prize")]
+    InvalidPrizeReceiver,
+    #[msg("Bidder balance is too low to cover the bid and the minimum balance")]
+    BidderBalanceTooLow,
+    #[msg("Bidder has to wait for the end of its bid cooldown")]
+    BidCooldownNotExpired,
+    #[msg("Auction is paused until the platform reviews its fraud flags")]
+    AuctionFlagged,
+    #[msg("Price step of a Dutch auction can not be negative")]
+    InvalidPriceStep,
+    #[msg("Dutch auctions are bought at the asking price through accept_price")]
+    DutchBidNotAllowed,
+    #[msg("Only Dutch auctions are sold at an asking price")]
+    NotDutchAuction,
+    #[msg("Seller can still settle the auction before its bond is claimed")]
+    SellerClaimWindowOpen,
+    #[msg("Bidder already claimed its share of the seller bond")]
+    SellerBondAlreadyClaimed,
+    #[msg("Sealed bid holds no deposit")]
+    NoCommitDeposit,
+    #[msg("Every milestone was already released")]
+    AllMilestonesReleased,
+    #[msg("Winning bid still has milestones to release")]
+    MilestonesPending,
+    #[msg("The last milestone is released with claim_final_milestone")]
+    FinalMilestone,
+    #[msg("Condition of the auction is not met")]
+    ConditionNotMet,
+    #[msg("Condition program or account does not match the auction")]
+    InvalidConditionProgram,
+    #[msg("Candle window must be within the auction duration")]
+    InvalidCandleWindow,
+    #[msg("Bid history of the candle window is full")]
+    CandleHistoryFull,
+    #[msg("Auction is not a candle auction")]
+    NotCandleAuction,
+    #[msg("Effective end of the candle auction is not drawn yet")]
+    CandleNotResolved,
+    #[msg("Not enough shares are left for the request")]
+    InsufficientSharesAvailable,
+    #[msg("At least one share has to be requested")]
+    InvalidShareRequest,
+    #[msg("Fractional auctions only take bids through fractional_bid")]
+    FractionalBidsOnly,
+    #[msg("Auction is not a fractional auction")]
+    NotFractionalAuction,
+    #[msg("Share account must hold the share mint for the bidder")]
+    InvalidShareAccount,
+    #[msg("Auction is not a raffle")]
+    NotRaffle,
+    #[msg("Winner of the raffle is already drawn")]
+    RaffleAlreadyDrawn,
+    #[msg("Every contribution has to be passed once, sorted by bidder")]
+    RaffleBidsMissing,
+    #[msg("Quantity exceeds the bids the leaderboard ranks")]
+    InvalidQuantity,
+    #[msg("Auction is not a multi-unit auction")]
+    NotMultiUnitAuction,
+    #[msg("Multi-unit auctions settle with settle_multi_unit")]
+    MultiUnitSettlementRequired,
+    #[msg("Bidder is related to the seller of the auction")]
+    SuspectedShillBid,
+    #[msg("Rounds need a positive length within the auction and a positive tick")]
+    InvalidRoundSchedule,
+    #[msg("Bid has to match the price of the current round")]
+    BidNotAtRoundPrice,
+    #[msg("Price of the current round was already accepted")]
+    RoundAlreadyAccepted,
+    #[msg("Bidder did not participate in enough auctions for loyalty status")]
+    LoyaltyThresholdNotReached,
+    #[msg("Penny auctions need a bid fee, a bid increment and an extension that is not negative")]
+    InvalidPennySettings,
+    #[msg("Penny auction bids raise the price by exactly the bid increment")]
+    InvalidPennyBid,
+    #[msg("Final rounds need finalists the leaderboard ranks, and an open phase and a commit phase that end before the deadline")]
+    InvalidFinalRound,
+    #[msg("Open phase has closed")]
+    OpenPhaseClosed,
+    #[msg("Open phase is not over yet")]
+    OpenPhaseNotOver,
+    #[msg("Best-and-final commit phase is not open")]
+    FinalCommitPhaseNotOpen,
+    #[msg("Best-and-final reveal phase is not open")]
+    FinalRevealPhaseNotOpen,
+    #[msg("Bidder is not a finalist of the auction")]
+    NotAFinalist,
+    #[msg("Buyer already bought a unit of the auction")]
+    UnitAlreadyBought,
+    #[msg("Auction takes its bids in an SPL token, use the token instructions")]
+    TokenInstructionRequired,
+    #[msg("Auction takes its bids in lamports")]
+    NotTokenAuction,
+    #[msg("Mint or token account does not match the bid mint of the auction or its owner")]
+    InvalidBidTokenAccount,
+    #[msg("Description is longer than 1024 bytes")]
+    DescriptionTooLong,
+    #[msg("Prize escrow needs both a prize mint and a prize amount")]
+    InvalidPrizeEscrow,
+    #[msg("Auction escrows no prize")]
+    NoPrizeEscrow,
+    #[msg("Bids are taken once the seller deposits the prize")]
+    PrizeNotDeposited,
+    #[msg("Prize was already deposited")]
+    PrizeAlreadyDeposited,
+    #[msg("Prize can only be reclaimed from a voided auction, or one that missed its reserve or took no bid")]
+    PrizeNotReclaimable,
+    #[msg("Prize is still escrowed, reclaim it first")]
+    PrizeStillEscrowed,
+    #[msg("Prize vault or token account does not match the prize of the auction or its owner")]
+    InvalidPrizeTokenAccount,
+    #[msg("Fee recipient does not match the one of the config")]
+    InvalidFeeRecipient,
+    #[msg("Bids of this auction can not be deposited into a yield vault")]
+    VaultNotSupported,
+    #[msg("Bid is deposited into a yield vault, it is refunded with refund_with_yield")]
+    BidInVault,
+    #[msg("Bid is not deposited into a yield vault")]
+    BidNotInVault,
+    #[msg("Vault does not match the one holding the bid, or is not owned by the vault program")]
+    InvalidVault,
+    #[msg("Yield vault returned an invalid response")]
+    InvalidVaultResponse,
+    #[msg("User bid still holds its bid, migrate it to its bid escrow first")]
+    BidEscrowMigrationRequired,
+    #[msg("Only English and Vickrey auctions can take sealed bids")]
+    SealedBidsNotSupported,
+    #[msg("USD reserve needs a price feed and can not be set on a raffle")]
+    UsdReserveWithoutFeed,
+    #[msg("Reverse auction caps offers with its maximum price and takes no reserve")]
+    ReverseAuctionWithReserve,
+    #[msg("Commit deposit needs a commit phase")]
+    CommitDepositWithoutCommitPhase,
+    #[msg("Milestones are only paid out by English, Vickrey and Dutch auctions")]
+    MilestonesNotSupported,
+    #[msg("Condition account needs a condition program")]
+    ConditionAccountWithoutProgram,
+    #[msg("Candle window is only drawn over the open bids of an English auction")]
+    CandleWindowNotSupported,
+    #[msg("Shares are only sold by a plain English auction")]
+    FractionalNotSupported,
+    #[msg("Units are only sold by a plain English or Dutch auction")]
+    MultiUnitNotSupported,
+    #[msg("Rounds only price the open bids of a single item English auction")]
+    RoundsNotSupported,
+    #[msg("Bid fee and deadline extension are only taken by penny auctions")]
+    PennyOptionsWithoutPenny,
+    #[msg("Paying as bid needs more than one unit")]
+    PayAsBidSingleUnit,
+    #[msg("Final round needs finalists and only follows a plain single item English auction")]
+    FinalRoundNotSupported,
+    #[msg("Bids in an SPL token are only taken by a plain single item English auction")]
+    TokenBidsNotSupported,
+    #[msg("Escrowed prize can only go to the single winner of a single item auction")]
+    PrizeEscrowNotSupported,
+}
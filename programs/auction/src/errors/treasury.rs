@@ -0,0 +1,20 @@
+use super::error_category;
+
+error_category! {
+    /// Errors about the lamports and tokens held by the treasury and the bid escrows
+    TreasuryError {
+        TreasuryInsufficientFunds,
+        NoSellerBond,
+        ArithmeticOverflow,
+        NothingToRecover,
+        WouldBreakRentExemption,
+        InvariantViolated,
+        NoPenalties,
+        SellerBondAlreadyClaimed,
+        InvalidBidTokenAccount,
+        InvalidPrizeTokenAccount,
+        InvalidFeeRecipient,
+        InvalidVault,
+        InvalidVaultResponse,
+    }
+}
@@ -0,0 +1,472 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{AuctionType, BidRecord, BidderProfile, HealthViolation};
+
+/// Emitted when the admin sweeps a stuck treasury into the recovery vault
+#[event]
+pub struct EmergencyRecovery {
+    pub auction: Pubkey,
+    pub initializer: Pubkey,
+    pub treasury: Pubkey,
+    pub recovery_vault: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub deadline: i64,
+    pub recovered_at: i64,
+    pub seller_payed: bool,
+    pub reserve_not_met: bool,
+    pub highest_bid_amount: u64,
+    pub highest_bidder_account: Pubkey,
+}
+
+/// Emitted by `verify_auction` with the figures the solvency check was run on
+#[event]
+pub struct AuctionHealth {
+    pub auction: Pubkey,
+    pub treasury_available: u64,
+    pub bond_available: u64,
+    pub bond_outstanding: u64,
+    pub escrowed: u64,
+    pub escrow_outstanding: u64,
+    pub bids_checked: u32,
+    pub healthy: bool,
+}
+
+/// Emitted by `health_check` with the first invariant the auction breaks
+#[event]
+pub struct HealthCheckFailed {
+    pub auction: Pubkey,
+    pub violation: HealthViolation,
+}
+
+/// Emitted by `end_auction` when a Vickrey winner gets back the part of its bid
+/// above the second highest bid
+#[event]
+pub struct VickreyRefund {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub winner: Pubkey,
+    pub winning_bid: u64,
+    pub price: u64,
+    pub refunded: u64,
+}
+
+/// Emitted by `end_auction` when a round of a perpetual auction was sold and the next
+/// round started
+#[event]
+pub struct AuctionRoundCompleted {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub round: u16,
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub next_deadline: i64,
+}
+
+/// Emitted by `end_auction` when the items of a bundle were sold together
+#[event]
+pub struct BundleSaleCompleted {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub items: Vec<Pubkey>,
+    pub winner: Pubkey,
+    pub total_amount: u64,
+}
+
+/// Emitted when the seller adds an item to the bundle
+#[event]
+pub struct BundleItemAdded {
+    pub auction: Pubkey,
+    pub item: Pubkey,
+    pub bundle_size: u8,
+}
+
+/// Emitted when the winner claims the prize, with the hash of its delivery note when
+/// it gave one
+#[event]
+pub struct PrizeClaimed {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub winner: Pubkey,
+    pub delivery_note_hash: Option<[u8; 32]>,
+}
+
+/// Emitted when the winner chooses the wallet the prize is sent to
+#[event]
+pub struct ReceiveAddressChanged {
+    pub auction: Pubkey,
+    pub winner: Pubkey,
+    pub address: Pubkey,
+}
+
+/// Emitted with the new statistics of a bidder whenever its profile changes
+#[event]
+pub struct ProfileUpdated {
+    pub bidder: Pubkey,
+    pub total_auctions_participated: u32,
+    pub total_bids_placed: u64,
+    pub total_lamports_bid: u64,
+    pub auctions_won: u32,
+    pub auctions_lost: u32,
+}
+
+impl ProfileUpdated {
+    pub fn new(bidder: Pubkey, profile: &BidderProfile) -> Self {
+        Self {
+            bidder,
+            total_auctions_participated: profile.total_auctions_participated,
+            total_bids_placed: profile.total_bids_placed,
+            total_lamports_bid: profile.total_lamports_bid,
+            auctions_won: profile.auctions_won,
+            auctions_lost: profile.auctions_lost,
+        }
+    }
+}
+
+/// Emitted for every offer of a reverse auction that undercut the lowest one
+#[event]
+pub struct LowestBidUpdated {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when the losing bids of an all-pay auction are paid to the beneficiary
+#[event]
+pub struct AllPayBidsCollected {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub bids: u32,
+    pub amount: u64,
+}
+
+/// Emitted when the seller changes the payout beneficiary after the deadline,
+/// while the winning bid is waiting to be paid out
+#[event]
+pub struct BeneficiaryChanged {
+    pub auction: Pubkey,
+    pub previous_beneficiary: Pubkey,
+    pub beneficiary: Pubkey,
+    pub changed_at: i64,
+}
+
+/// Emitted when a bidder commits or recommits a sealed bid, or a finalist commits its
+/// best-and-final offer. The amount stays hidden
+#[event]
+pub struct BidCommitted {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub commitment: [u8; 32],
+    pub committed_at: i64,
+}
+
+/// Emitted when the seller cancels an auction nobody bid on, its accounts are closed
+#[event]
+pub struct AuctionCancelled {
+    pub auction: Pubkey,
+    pub initializer: Pubkey,
+    pub cancelled_at: i64,
+}
+
+/// Emitted when `migrate_state` brings an auction state to the current layout
+#[event]
+pub struct MigrationCompleted {
+    pub auction: Pubkey,
+    pub old_version: u8,
+    pub new_version: u8,
+}
+
+/// Emitted when `migrate_bid_escrow` moves a bid out of its user bid into its bid escrow
+#[event]
+pub struct BidEscrowMigrated {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when the seller moves the deadline of an auction nobody bid on yet
+#[event]
+pub struct DeadlineChanged {
+    pub auction: Pubkey,
+    pub old_deadline: i64,
+    pub new_deadline: i64,
+}
+
+/// Emitted when the seller lowers the reserve price of a running auction
+#[event]
+pub struct ReserveLowered {
+    pub auction: Pubkey,
+    pub old_reserve: u64,
+    pub new_reserve: u64,
+}
+
+/// Emitted by `get_bid_history` with the recorded bids, oldest first
+#[event]
+pub struct BidHistoryQueried {
+    pub auction: Pubkey,
+    pub bid_history_count: u32,
+    pub bids: Vec<BidRecord>,
+}
+
+/// Emitted when the seller updates the title or URI of an auction
+#[event]
+pub struct MetadataUpdated {
+    pub auction: Pubkey,
+    pub title: String,
+    pub uri: String,
+}
+
+/// Emitted when a seller creates an auction, with what it sells
+#[event]
+pub struct AuctionInitialized {
+    pub auction: Pubkey,
+    pub initializer: Pubkey,
+    pub deadline: i64,
+    pub auction_type: AuctionType,
+    pub title: String,
+    pub item_hash: [u8; 32],
+    pub uri: String,
+}
+
+/// Emitted when the owner of an auction proposes a new owner, or withdraws the
+/// proposal with `proposed_owner` set to `None`
+#[event]
+pub struct OwnershipProposed {
+    pub auction: Pubkey,
+    pub owner: Pubkey,
+    pub proposed_owner: Option<Pubkey>,
+}
+
+/// Emitted when the proposed owner accepts the ownership of an auction
+#[event]
+pub struct OwnershipTransferred {
+    pub auction: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+/// Emitted when the VRF resolved the slot at which bidding on a candle auction closes
+#[event]
+pub struct CandleEndSet {
+    pub auction: Pubkey,
+    pub end_slot: u64,
+}
+
+/// Emitted when the effective end of a candle auction is drawn after its deadline. The
+/// winner is the default key when nothing was bid before the cutoff
+#[event]
+pub struct CandleCutoffSet {
+    pub auction: Pubkey,
+    pub cutoff: i64,
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when a user flags an auction as fraudulent
+#[event]
+pub struct AuctionFlagged {
+    pub auction: Pubkey,
+    pub flagger: Pubkey,
+    pub flag_count: u32,
+}
+
+/// Emitted when the platform authority confirms a fraud and cancels the auction
+#[event]
+pub struct FraudConfirmed {
+    pub auction: Pubkey,
+    pub flag_count: u32,
+}
+
+/// Emitted when the seller pauses bidding
+#[event]
+pub struct BiddingPaused {
+    pub auction: Pubkey,
+    pub paused_at: i64,
+}
+
+/// Emitted when the seller resumes bidding, `deadline` includes the paused time when
+/// the seller gave it back to the bidders
+#[event]
+pub struct BiddingResumed {
+    pub auction: Pubkey,
+    pub paused_secs: i64,
+    pub deadline: i64,
+}
+
+/// Emitted when the seller reopens an auction that missed its reserve, `round` tells
+/// the attempts of the same auction apart
+#[event]
+pub struct AuctionReopened {
+    pub auction: Pubkey,
+    pub round: u16,
+    pub deadline: i64,
+    pub reserve_price: u64,
+}
+
+/// Emitted when a bidder lets a delegate bid on its behalf, `max_amount` is 0 once
+/// the delegation was revoked
+#[event]
+pub struct DelegateChanged {
+    pub auction: Pubkey,
+    pub principal: Pubkey,
+    pub delegate: Pubkey,
+    pub max_amount: u64,
+    pub expiry: i64,
+}
+
+/// Emitted when the winner releases a milestone of the winning bid to the seller,
+/// `milestone` counts the milestones released so far
+#[event]
+pub struct MilestoneReleased {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub milestone: u8,
+    pub milestone_count: u8,
+    pub amount: u64,
+}
+
+/// Emitted when a fractional bid was allocated its shares
+#[event]
+pub struct SharesAllocated {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub shares: u64,
+    pub amount: u64,
+    pub shares_left: u64,
+}
+
+/// Emitted by `settle_fractional` with the shares minted and the payments collected by
+/// the call
+#[event]
+pub struct FractionalSaleSettled {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub shares: u64,
+    pub amount: u64,
+    pub minted_shares: u64,
+    pub allocated_shares: u64,
+}
+
+/// Emitted when the winner of a raffle is drawn, the winner is the default key when the
+/// pool did not reach the reserve price
+#[event]
+pub struct RaffleWinnerDrawn {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub winner: Pubkey,
+    pub contribution: u64,
+    pub pool: u64,
+}
+
+/// Emitted when a multi-unit auction settles, every winner paid the clearing price, or
+/// its own bid in a pay-as-bid auction. No unit was sold when no bid reached the reserve
+/// price
+#[event]
+pub struct MultiUnitSaleSettled {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub winners: Vec<Pubkey>,
+    pub clearing_price: u64,
+    pub amount: u64,
+}
+
+/// Emitted when the platform authority registers a wallet funded by the seller, which
+/// can not bid on the auction during the shill lookback
+#[event]
+pub struct RelatedAccountRegistered {
+    pub auction: Pubkey,
+    pub related: Pubkey,
+    pub funded_at: i64,
+}
+
+/// Emitted when a bidder is granted loyalty status
+#[event]
+pub struct LoyaltyStatusGranted {
+    pub bidder: Pubkey,
+    pub auctions_participated: u32,
+}
+
+/// Emitted when a finalist reveals its best-and-final offer, with the bidder that leads
+/// the final round afterwards
+#[event]
+pub struct FinalOfferRevealed {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub leader: Pubkey,
+}
+
+/// Emitted when a buyer takes a unit of a multi-unit Dutch auction at the asking price
+#[event]
+pub struct UnitSold {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub buyer: Pubkey,
+    pub price: u64,
+    pub units_remaining: u16,
+}
+
+/// Emitted when the seller adds or edits the description of an auction
+#[event]
+pub struct DescriptionUpdated {
+    pub auction: Pubkey,
+    pub description: Pubkey,
+    pub len: u32,
+}
+
+/// Emitted when the seller deposits the prize of an auction in its vault, bidding opens
+#[event]
+pub struct PrizeDeposited {
+    pub auction: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when the seller takes back the prize of an auction that did not sell it
+#[event]
+pub struct PrizeReclaimed {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when an outbid bidder deposits its escrowed bid into a yield vault
+#[event]
+pub struct BidDepositedToVault {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+/// Emitted when a bid deposited into a yield vault is refunded with its yield
+#[event]
+pub struct RefundedWithYield {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub yield_accrued: u64,
+}
+
+/// Emitted when an auction settles with a sale, `amount` is what the winner paid, the
+/// lowest offer paid to the winner of a reverse auction
+#[event]
+pub struct AuctionSettled {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when a bid is refunded and its escrow closed, `amount` is what the bidder got
+/// back on top of the rent. The losers of a paid all-pay auction or raffle only get the
+/// rent back
+#[event]
+pub struct BidRefunded {
+    pub auction: Pubkey,
+    pub auction_type: AuctionType,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
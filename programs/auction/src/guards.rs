@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::{AuctionStatus, State};
+
+/// Pre-condition of the instructions that run while bids are accepted
+pub fn check_auction_active(state: &State) -> Result<()> {
+    if state.status(Clock::get()?.unix_timestamp) != AuctionStatus::Active {
+        return err!(AuctionError::Finished);
+    }
+    Ok(())
+}
+
+/// Pre-condition of the instructions that run once the deadline passed
+pub fn check_auction_ended(state: &State) -> Result<()> {
+    if state.status(Clock::get()?.unix_timestamp) != AuctionStatus::Ended {
+        return err!(AuctionError::StillActive);
+    }
+    Ok(())
+}
+
+/// Pre-condition of the instructions that run while sealed bids can be committed
+pub fn check_commit_phase(state: &State) -> Result<()> {
+    if Clock::get()?.unix_timestamp >= state.reveal_start {
+        return err!(AuctionError::CommitPhaseClosed);
+    }
+    Ok(())
+}
+
+/// Pre-condition of the instructions that run while sealed bids can be revealed,
+/// from the end of the commit phase until the deadline
+pub fn check_reveal_phase(state: &State) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if state.reveal_start == 0
+        || now < state.reveal_start
+        || state.status(now) != AuctionStatus::Active
+    {
+        return err!(AuctionError::RevealPhaseNotOpen);
+    }
+    Ok(())
+}
+
+/// Pre-condition of the instructions that run once the open phase of an auction with a
+/// final round is over, until the deadline
+pub fn check_open_phase_over(state: &State) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if !state.has_final_round() || now < state.open_deadline {
+        return err!(AuctionError::OpenPhaseNotOver);
+    }
+    if state.status(now) != AuctionStatus::Active {
+        return err!(AuctionError::Finished);
+    }
+    Ok(())
+}
+
+/// Pre-condition of the instructions that run while finalists can commit their
+/// best-and-final offer, from the end of the open phase until `final_deadline`
+pub fn check_final_commit_phase(state: &State) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if !state.has_final_round() || now < state.open_deadline || now >= state.final_deadline {
+        return err!(AuctionError::FinalCommitPhaseNotOpen);
+    }
+    Ok(())
+}
+
+/// Pre-condition of the instructions that run while finalists can reveal their
+/// best-and-final offer, from `final_deadline` until the deadline
+pub fn check_final_reveal_phase(state: &State) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if !state.has_final_round()
+        || now < state.final_deadline
+        || state.status(now) != AuctionStatus::Active
+    {
+        return err!(AuctionError::FinalRevealPhaseNotOpen);
+    }
+    Ok(())
+}
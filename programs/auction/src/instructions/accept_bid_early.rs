@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::State;
+
+pub fn accept_bid_early(
+    ctx: Context<AcceptBidEarly>,
+    expected_winner: Pubkey,
+    expected_amount: u64,
+) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if state.highest_bid_amount == 0 {
+        return err!(AuctionError::NoBids);
+    }
+    if state.highest_bidder_account != expected_winner
+        || state.highest_bid_amount != expected_amount
+    {
+        return err!(AuctionError::HighestBidChanged);
+    }
+    // once the open phase is over, the finalists are owed their final round
+    if state.has_final_round() && clock.unix_timestamp >= state.open_deadline {
+        return err!(AuctionError::OpenPhaseClosed);
+    }
+
+    // closing the bidding window lets the regular settlement flow run
+    state.deadline = clock.unix_timestamp;
+    state.ended_early = true;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptBidEarly<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller
+    pub initializer: Signer<'info>,
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::BundleItemAdded;
+use crate::instructions::SellerAction;
+
+pub fn add_bundle_item(ctx: Context<SellerAction>, item: Pubkey) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    // bidders bid on the bundle as it was when they joined
+    if state.highest_bid_amount != 0 || state.bid_count != 0 {
+        return err!(AuctionError::CannotChangeAfterBids);
+    }
+    state.add_bundle_item(item)?;
+
+    emit!(BundleItemAdded {
+        auction: state.key(),
+        item,
+        bundle_size: state.bundle_size,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv, AccountsClose};
+
+use crate::constants::COMMIT_BID_SPACE;
+use crate::errors::AuctionError;
+use crate::events::FinalOfferRevealed;
+use crate::instructions::store_commitment;
+use crate::state::{BidEscrow, CommitBid, Leaderboard, State, UserBid};
+use crate::utils::{deposit_lamports, transfer_from_escrow};
+
+pub fn commit_final_offer(ctx: Context<CommitFinalOffer>, commitment: [u8; 32]) -> Result<()> {
+    let state = &ctx.accounts.state;
+
+    // the leaderboard stopped moving with the open phase, its top bids are the finalists
+    if !ctx
+        .accounts
+        .leaderboard
+        .is_finalist(ctx.accounts.user.key(), state.finalist_count)
+    {
+        return err!(AuctionError::NotAFinalist);
+    }
+
+    store_commitment(
+        &mut ctx.accounts.commit_bid,
+        state.key(),
+        ctx.accounts.user.key(),
+        commitment,
+    )
+}
+
+pub fn reveal_final_offer(
+    ctx: Context<RevealFinalOffer>,
+    amount: u64,
+    nonce: [u8; 32],
+) -> Result<()> {
+    let user_bid = &mut ctx.accounts.user_bid;
+
+    if hashv(&[&amount.to_le_bytes(), &nonce]).to_bytes() != ctx.accounts.commit_bid.commitment {
+        return err!(AuctionError::InvalidBidCommitment);
+    }
+    // the open bid of the finalist stays escrowed, its final offer can only raise it
+    if amount < user_bid.amount {
+        return err!(AuctionError::BidAmountTooSmall);
+    }
+
+    let top_up = amount.saturating_sub(user_bid.escrowed());
+    if top_up > 0 {
+        deposit_lamports(
+            &ctx.accounts.user,
+            ctx.accounts.bid_escrow.to_account_info(),
+            &ctx.accounts.system_program,
+            top_up,
+        )?;
+    }
+    user_bid.amount = amount;
+
+    let state = &mut ctx.accounts.state;
+    state.record_final_offer(
+        ctx.accounts.user.key(),
+        amount,
+        *ctx.bumps.get("user_bid").unwrap(),
+    )?;
+
+    emit!(FinalOfferRevealed {
+        auction: state.key(),
+        bidder: ctx.accounts.user.key(),
+        amount,
+        leader: state.highest_bidder_account,
+    });
+
+    Ok(())
+}
+
+pub fn refund_non_finalist(ctx: Context<RefundNonFinalist>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let user = ctx.accounts.user.key();
+
+    // the bid of a finalist stands until the auction is settled
+    if ctx
+        .accounts
+        .leaderboard
+        .is_finalist(user, state.finalist_count)
+    {
+        return err!(AuctionError::WithdrawalNotAllowed);
+    }
+    // a bid tied with a finalist can lead the open phase, it wins when no finalist
+    // reveals an offer
+    if user == state.highest_bidder_account {
+        return err!(AuctionError::HighestBidderCannotWithdraw);
+    }
+
+    let amount = ctx.accounts.user_bid.escrowed();
+    if amount > 0 {
+        transfer_from_escrow(
+            &ctx.accounts.bid_escrow.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            amount,
+        )?;
+    }
+
+    state.bid_count = state
+        .bid_count
+        .checked_sub(1)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+
+    // closing the escrow returns its rent to whoever paid it
+    ctx.accounts
+        .bid_escrow
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+    ctx.accounts
+        .user_bid
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CommitFinalOffer<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Finalist
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Highest bids of the open phase
+    #[account(seeds = [b"leaderboard", state.key().as_ref()], bump)]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    /// Best-and-final offer, a finalist commits a single one
+    #[account(
+        init,
+        payer = user,
+        space = COMMIT_BID_SPACE,
+        seeds = [b"commit", user.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub commit_bid: Account<'info, CommitBid>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealFinalOffer<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Finalist
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Best-and-final offer, closed to the finalist once revealed
+    #[account(mut, close = user, seeds = [b"commit", user.key().as_ref(), state.key().as_ref()], bump)]
+    pub commit_bid: Account<'info, CommitBid>,
+    /// Open bid of the finalist, raised to its final offer
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump)]
+    pub user_bid: Account<'info, UserBid>,
+    /// Escrow of the open bid of the finalist, topped up to its final offer
+    #[account(mut, seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundNonFinalist<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump)]
+    pub user_bid: Account<'info, UserBid>,
+    #[account(mut, seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Highest bids of the open phase
+    #[account(seeds = [b"leaderboard", state.key().as_ref()], bump)]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    /// Payer of the user bid rent, the bidder itself unless the bid was sponsored
+    #[account(mut, constraint = rent_payer.key() == user_bid.rent_payer @ AuctionError::InvalidRentPayer)]
+    /// CHECK:
+    pub rent_payer: AccountInfo<'info>,
+}
@@ -0,0 +1,535 @@
+use anchor_lang::{prelude::*, solana_program::native_token::sol_to_lamports, AccountsClose};
+
+use crate::constants::{BIDDER_PROFILE_SPACE, BID_ESCROW_SPACE, LEADERBOARD_SIZE, USER_BID_SPACE};
+use crate::errors::AuctionError;
+use crate::events::{LowestBidUpdated, ProfileUpdated, UnitSold};
+use crate::instructions::check_shill_bid;
+use crate::state::{
+    AuctionStatus, AuctionType, BidEscrow, BidderProfile, Config, Leaderboard, LeaderboardEntry,
+    State, UserBid,
+};
+use crate::utils::{deposit_lamports, load_user_bid, transfer_from_escrow};
+
+/// Bid rules, without any account or runtime access: the seller can not bid, the
+/// highest bidder can not repeat its bid, bids are only accepted before the deadline
+/// and must be at least the current highest bid, except in Vickrey auctions. Bids in
+/// a Dutch auction must also be at least the asking price at `timestamp`. Offers of a
+/// reverse auction have to undercut the lowest offer instead, and raffle contributions
+/// are accepted at any amount. A round-based auction takes one bid per round, at the
+/// price of the round, and a penny auction one bid raising the price by its increment.
+/// Accepted bids are written to the bid history, which has to keep every bid that may
+/// win a candle auction
+pub fn process_bid(
+    state: &mut State,
+    bidder: Pubkey,
+    bidder_bump: u8,
+    amount: u64,
+    timestamp: i64,
+) -> Result<()> {
+    // a seller bidding on its own auction inflates the price
+    if bidder == state.initializer || bidder == state.authority {
+        return err!(AuctionError::SellerCannotBid);
+    }
+    if bidder == state.highest_bidder_account && amount == state.highest_bid_amount {
+        return err!(AuctionError::AlreadyHighestBidder);
+    }
+    if state.status(timestamp) != AuctionStatus::Active {
+        return err!(AuctionError::Finished);
+    }
+    if state.is_fractional() {
+        return err!(AuctionError::FractionalBidsOnly);
+    }
+    if state.candle_history_full(timestamp) {
+        return err!(AuctionError::CandleHistoryFull);
+    }
+    if state.auction_type == AuctionType::Dutch {
+        if amount < state.asking_price(timestamp) {
+            return err!(AuctionError::BidBelowAskingPrice);
+        }
+    }
+    if state.is_round_based() {
+        state.accept_round(amount, timestamp)?;
+    }
+    if state.auction_type == AuctionType::Penny {
+        state.record_penny_bid(amount)?;
+    }
+
+    if state.auction_type == AuctionType::Reverse {
+        state.record_offer(bidder, amount, bidder_bump)?;
+    } else if state.auction_type == AuctionType::Raffle {
+        state.record_contribution()?;
+    } else {
+        state.record_bid(bidder, amount, bidder_bump)?;
+    }
+    state.push_bid_history(bidder, amount, timestamp)
+}
+
+/// Sets the bid of `bidder` on the leaderboard to `amount` and keeps it sorted from the
+/// highest bid down. A bidder holds a single entry, an equal bid ranks after the
+/// bidders who got there first and bids below the last entry of a full leaderboard
+/// are left out
+pub fn update_leaderboard(leaderboard: &mut Leaderboard, bidder: Pubkey, amount: u64) {
+    let mut entries: Vec<LeaderboardEntry> = leaderboard
+        .entries
+        .iter()
+        .filter(|entry| entry.rank > 0 && entry.bidder != bidder)
+        .copied()
+        .collect();
+    entries.push(LeaderboardEntry {
+        rank: 0,
+        bidder,
+        amount,
+    });
+    // the sort is stable, earlier entries keep their rank on ties
+    entries.sort_by(|a, b| b.amount.cmp(&a.amount));
+    entries.truncate(LEADERBOARD_SIZE);
+
+    leaderboard.entries = [LeaderboardEntry::default(); LEADERBOARD_SIZE];
+    for (index, entry) in entries.into_iter().enumerate() {
+        leaderboard.entries[index] = LeaderboardEntry {
+            rank: index as u8 + 1,
+            ..entry
+        };
+    }
+}
+
+/// Rejects a bid of `amount` from a bidder that would not keep `MIN_BIDDER_BALANCE`
+/// and a rent reserve, which at least keeps its wallet rent exempt
+#[cfg(not(feature = "no-balance-check"))]
+pub(crate) fn check_bidder_balance(user: &Signer, amount: u64) -> Result<()> {
+    use crate::constants::{MIN_BIDDER_BALANCE, RENT_RESERVE};
+
+    let rent_reserve = RENT_RESERVE.max(Rent::get()?.minimum_balance(0));
+    let required = MIN_BIDDER_BALANCE
+        .checked_add(amount)
+        .and_then(|required| required.checked_add(rent_reserve))
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if user.lamports() < required {
+        return err!(AuctionError::BidderBalanceTooLow);
+    }
+    Ok(())
+}
+
+/// Rejects a bid placed again with `user_bid` less than `cooldown` seconds after its
+/// last bid. A freshly created user bid has no last bid
+pub(crate) fn check_bid_cooldown(user_bid: &UserBid, cooldown: i64) -> Result<()> {
+    if user_bid.last_bid_unix_timestamp == 0 {
+        return Ok(());
+    }
+    let next_bid = user_bid
+        .last_bid_unix_timestamp
+        .checked_add(cooldown)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    let now = Clock::get()?.unix_timestamp;
+    if now < next_bid {
+        msg!("Wait {} more seconds", next_bid - now);
+        return err!(AuctionError::BidCooldownNotExpired);
+    }
+    Ok(())
+}
+
+pub fn bid<'info>(ctx: Context<'_, '_, '_, 'info, Bid<'info>>, amount: f64) -> Result<()> {
+    // sealed-bid auctions only take bids through `reveal_bid`
+    if ctx.accounts.state.reveal_start != 0 {
+        return err!(AuctionError::SealedBidsOnly);
+    }
+    if ctx.accounts.state.auction_type == AuctionType::Dutch {
+        return err!(AuctionError::DutchBidNotAllowed);
+    }
+    // a raise only escrows its difference with the bid already escrowed
+    #[cfg(not(feature = "no-balance-check"))]
+    check_bidder_balance(
+        &ctx.accounts.user,
+        sol_to_lamports(amount)
+            .saturating_sub(ctx.accounts.user_bid.escrowed())
+            .saturating_add(ctx.accounts.state.bid_fee),
+    )?;
+    check_shill_bid(
+        &ctx.accounts.sybil_check,
+        ctx.accounts.config.shill_lookback_secs,
+        Clock::get()?.unix_timestamp,
+    )?;
+    check_bid_cooldown(
+        &ctx.accounts.user_bid,
+        ctx.accounts.config.bid_cooldown_seconds,
+    )?;
+
+    escrow_bid(
+        &mut ctx.accounts.state,
+        &ctx.accounts.user,
+        &ctx.accounts.payer,
+        &mut ctx.accounts.user_bid,
+        &mut ctx.accounts.bid_escrow,
+        &mut ctx.accounts.leaderboard,
+        &mut ctx.accounts.profile,
+        &ctx.accounts.system_program,
+        *ctx.bumps.get("user_bid").unwrap(),
+        *ctx.bumps.get("bid_escrow").unwrap(),
+        sol_to_lamports(amount),
+        0,
+        ctx.accounts.config.min_bid_amount,
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn proxy_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, Bid<'info>>,
+    amount: f64,
+    max_proxy_bid: f64,
+) -> Result<()> {
+    if ctx.accounts.state.auction_type != AuctionType::English
+        || ctx.accounts.state.reveal_start != 0
+        || ctx.accounts.state.candle_window > 0
+        || ctx.accounts.state.is_multi_unit()
+        || ctx.accounts.state.is_round_based()
+    {
+        return err!(AuctionError::ProxyBidsNotAllowed);
+    }
+    if max_proxy_bid < amount {
+        return err!(AuctionError::InvalidProxyBid);
+    }
+    // the whole proxy maximum is escrowed upfront
+    #[cfg(not(feature = "no-balance-check"))]
+    check_bidder_balance(
+        &ctx.accounts.user,
+        sol_to_lamports(max_proxy_bid)
+            .saturating_sub(ctx.accounts.user_bid.escrowed())
+            .saturating_add(ctx.accounts.state.bid_fee),
+    )?;
+    check_shill_bid(
+        &ctx.accounts.sybil_check,
+        ctx.accounts.config.shill_lookback_secs,
+        Clock::get()?.unix_timestamp,
+    )?;
+    check_bid_cooldown(
+        &ctx.accounts.user_bid,
+        ctx.accounts.config.bid_cooldown_seconds,
+    )?;
+
+    escrow_bid(
+        &mut ctx.accounts.state,
+        &ctx.accounts.user,
+        &ctx.accounts.payer,
+        &mut ctx.accounts.user_bid,
+        &mut ctx.accounts.bid_escrow,
+        &mut ctx.accounts.leaderboard,
+        &mut ctx.accounts.profile,
+        &ctx.accounts.system_program,
+        *ctx.bumps.get("user_bid").unwrap(),
+        *ctx.bumps.get("bid_escrow").unwrap(),
+        sol_to_lamports(amount),
+        sol_to_lamports(max_proxy_bid),
+        ctx.accounts.config.min_bid_amount,
+        ctx.remaining_accounts,
+    )
+}
+
+pub fn accept_price<'info>(ctx: Context<'_, '_, '_, 'info, Bid<'info>>) -> Result<()> {
+    if ctx.accounts.state.auction_type != AuctionType::Dutch {
+        return err!(AuctionError::NotDutchAuction);
+    }
+    // a buyer takes a single unit, its user bid records the price it paid
+    if ctx.accounts.state.is_multi_unit() && ctx.accounts.user_bid.last_bid_unix_timestamp != 0 {
+        return err!(AuctionError::UnitAlreadyBought);
+    }
+    let now = Clock::get()?.unix_timestamp;
+    let price = ctx.accounts.state.asking_price(now);
+    check_shill_bid(
+        &ctx.accounts.sybil_check,
+        ctx.accounts.config.shill_lookback_secs,
+        now,
+    )?;
+    #[cfg(not(feature = "no-balance-check"))]
+    check_bidder_balance(&ctx.accounts.user, price)?;
+
+    escrow_bid(
+        &mut ctx.accounts.state,
+        &ctx.accounts.user,
+        &ctx.accounts.payer,
+        &mut ctx.accounts.user_bid,
+        &mut ctx.accounts.bid_escrow,
+        &mut ctx.accounts.leaderboard,
+        &mut ctx.accounts.profile,
+        &ctx.accounts.system_program,
+        *ctx.bumps.get("user_bid").unwrap(),
+        *ctx.bumps.get("bid_escrow").unwrap(),
+        price,
+        0,
+        ctx.accounts.config.min_bid_amount,
+        ctx.remaining_accounts,
+    )?;
+
+    let state = &mut ctx.accounts.state;
+    if state.is_multi_unit() {
+        // every taker buys a unit until none is left, `settle_multi_unit` charges each
+        // buyer its price once the auction ends
+        state.sell_unit(now)?;
+        emit!(UnitSold {
+            auction: state.key(),
+            auction_type: state.auction_type,
+            buyer: ctx.accounts.user.key(),
+            price,
+            units_remaining: state.units_remaining,
+        });
+    } else {
+        // the first taker wins, closing the bidding window lets the regular settlement run
+        state.deadline = now;
+        state.ended_early = true;
+    }
+
+    Ok(())
+}
+
+/// Registers a bid of `amount_in_lamports` in `user_bid` and escrows it in `bid_escrow`,
+/// or the whole `max_proxy_bid` of a proxy bid. A bid placed again only escrows what it
+/// adds to the escrow, a user bid that still holds an older bid has to be migrated to
+/// its bid escrow first. The bid fee of a penny auction is paid to the state account,
+/// which holds it for the seller. Auctions paid in an SPL token take `bid_token` instead.
+/// See `register_bid` for the rules applied before the bidder pays
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn escrow_bid<'info>(
+    state: &mut Account<'info, State>,
+    user: &Signer<'info>,
+    payer: &Signer<'info>,
+    user_bid: &mut Account<'info, UserBid>,
+    bid_escrow: &mut Account<'info, BidEscrow>,
+    leaderboard: &mut Leaderboard,
+    profile: &mut BidderProfile,
+    system_program: &Program<'info, System>,
+    bidder_bump: u8,
+    escrow_bump: u8,
+    amount_in_lamports: u64,
+    max_proxy_bid: u64,
+    min_bid_amount: u64,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if state.is_token_denominated() {
+        return err!(AuctionError::TokenInstructionRequired);
+    }
+    // bids placed before the bid escrows are held by their user bid, see
+    // `migrate_bid_escrow`
+    let user_bid_info = user_bid.to_account_info();
+    if user_bid_info.lamports() > Rent::get()?.minimum_balance(user_bid_info.data_len()) {
+        return err!(AuctionError::BidEscrowMigrationRequired);
+    }
+    bid_escrow.bump = escrow_bump;
+    let already_escrowed = state.escrow_required(user_bid);
+    register_bid(
+        state,
+        user.key(),
+        payer,
+        user_bid,
+        leaderboard,
+        profile,
+        bidder_bump,
+        amount_in_lamports,
+        max_proxy_bid,
+        min_bid_amount,
+        remaining_accounts,
+    )?;
+
+    // the bid is escrowed in the bid escrow PDA, next to its rent
+    let escrowed = state
+        .escrow_required(user_bid)
+        .saturating_sub(already_escrowed);
+    if escrowed > 0 {
+        deposit_lamports(user, bid_escrow.to_account_info(), system_program, escrowed)?;
+    }
+    if state.bid_fee > 0 {
+        deposit_lamports(user, state.to_account_info(), system_program, state.bid_fee)?;
+    }
+    Ok(())
+}
+
+/// Pays the bid fees of a penny auction held in its state account to `destination`.
+/// The seller keeps them whatever the outcome of the auction
+pub(crate) fn pay_bid_fees<'info>(
+    state: &mut Account<'info, State>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let fees = state.fees_collected;
+    if fees == 0 {
+        return Ok(());
+    }
+    transfer_from_escrow(&state.to_account_info(), destination, fees)?;
+    state.fees_collected = 0;
+    Ok(())
+}
+
+/// Registers a bid of `bidder` in `user_bid`, without moving any lamports: the caller
+/// escrows `user_bid.escrowed()` afterwards. A freshly created user bid is closed again
+/// when the bid is rejected. A bid placed again with an existing user bid has to come
+/// from an outbid bidder and raise its previous bid. Bids below the `min_bid_amount`
+/// of the config, while bidding is paused or waits for the prize, after the end of a
+/// candle auction or of an open phase are rejected before anything else. When the bid
+/// outbids a proxy, the proxy answers it and the user bid of the previous highest bidder
+/// is expected as `(bidder, user_bid)` remaining accounts. The leaderboard gets the
+/// resulting bids and the profile of the bidder counts the bid
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn register_bid<'info>(
+    state: &mut Account<'info, State>,
+    bidder: Pubkey,
+    payer: &Signer<'info>,
+    user_bid: &mut Account<'info, UserBid>,
+    leaderboard: &mut Leaderboard,
+    profile: &mut BidderProfile,
+    bidder_bump: u8,
+    amount_in_lamports: u64,
+    max_proxy_bid: u64,
+    min_bid_amount: u64,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if amount_in_lamports < min_bid_amount {
+        return err!(AuctionError::BidBelowMinimum);
+    }
+    if state.paused {
+        return err!(AuctionError::AuctionPaused);
+    }
+    if state.awaits_prize() {
+        return err!(AuctionError::PrizeNotDeposited);
+    }
+    let clock = Clock::get()?;
+    // the candle went out, bidding closed before the deadline
+    if state.candle_ended(clock.slot) {
+        return err!(AuctionError::Finished);
+    }
+    // the open phase of an auction with a final round closes before the deadline
+    if state.has_final_round() && clock.unix_timestamp >= state.open_deadline {
+        return err!(AuctionError::OpenPhaseClosed);
+    }
+
+    // a user bid only has a last bid when the bidder bids again with it
+    let rebid = user_bid.last_bid_unix_timestamp != 0;
+    if rebid {
+        if bidder == state.winner() {
+            return err!(AuctionError::AlreadyHighestBidder);
+        }
+        if amount_in_lamports <= user_bid.amount {
+            return err!(AuctionError::BidAmountTooSmall);
+        }
+    }
+
+    let previous_bidder = state.highest_bidder_account;
+    let previous_bump = state.highest_bidder_bump;
+    let previous_max = state.highest_proxy_max;
+
+    // register highest bid in state, bids lower than the highest one are rejected
+    // unless they can set the Vickrey price
+    if let Err(error) = process_bid(
+        state,
+        bidder,
+        bidder_bump,
+        amount_in_lamports,
+        clock.unix_timestamp,
+    ) {
+        if !rebid {
+            user_bid.close(payer.to_account_info())?;
+        }
+        return Err(error);
+    }
+
+    // register user amunt bid in PDA
+    user_bid.amount = amount_in_lamports;
+    user_bid.max_proxy_bid = max_proxy_bid;
+    user_bid.last_bid_unix_timestamp = clock.unix_timestamp;
+    user_bid.fees_paid = user_bid
+        .fees_paid
+        .checked_add(state.bid_fee)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if rebid {
+        // the user bid was counted with its first bid, its rent payer stays the same
+        state.bid_count = state.bid_count.saturating_sub(1);
+    } else {
+        user_bid.rent_payer = payer.key();
+    }
+
+    if state.auction_type == AuctionType::Reverse {
+        emit!(LowestBidUpdated {
+            auction: state.key(),
+            bidder,
+            amount: amount_in_lamports,
+        });
+    }
+
+    if state.highest_bidder_account == bidder {
+        let state_key = state.key();
+        let previous_kept_lead =
+            state.apply_proxy_bids(previous_bidder, previous_bump, previous_max, max_proxy_bid)?;
+        if previous_kept_lead {
+            let (previous, previous_bid) = match remaining_accounts {
+                [previous, previous_bid, ..] if previous.key() == previous_bidder => {
+                    (previous, previous_bid)
+                }
+                _ => return err!(AuctionError::InvalidEscrow),
+            };
+            let mut previous_bid = load_user_bid(state_key, previous, previous_bid)?;
+            previous_bid.amount = state.highest_bid_amount;
+            previous_bid.exit(&crate::ID)?;
+            update_leaderboard(leaderboard, previous_bidder, previous_bid.amount);
+        } else {
+            user_bid.amount = state.highest_bid_amount;
+        }
+    }
+    update_leaderboard(leaderboard, bidder, user_bid.amount);
+
+    if rebid {
+        profile.record_rebid(amount_in_lamports)?;
+    } else {
+        profile.record_bid(amount_in_lamports)?;
+    }
+    emit!(ProfileUpdated::new(bidder, profile));
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Bid<'info> {
+    /// State of our auction program (up to you)
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Pays the rent of the user bid and bid escrow PDAs, either the bidder or a sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// Bid of the bidder. An outbid bidder bids again with its existing user bid
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = USER_BID_SPACE,
+        seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()],
+        bump,
+        constraint = user_bid.deposit_share == 0 @ AuctionError::BidInVault,
+    )]
+    pub user_bid: Account<'info, UserBid>,
+    /// Escrow of the bid, it holds the bidded lamports until settlement or refund
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BID_ESCROW_SPACE,
+        seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Highest bids of the auction
+    #[account(mut, seeds = [b"leaderboard", state.key().as_ref()], bump)]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    /// Lifetime statistics of the bidder, created with its first bid
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BIDDER_PROFILE_SPACE,
+        seeds = [b"profile", user.key().as_ref()],
+        bump,
+    )]
+    pub profile: Box<Account<'info, BidderProfile>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    /// Registration of the bidder as a wallet funded by the seller, empty unless the
+    /// platform authority registered it
+    /// CHECK: only read when it is not empty
+    #[account(seeds = [b"sybil", state.key().as_ref(), user.key().as_ref()], bump)]
+    pub sybil_check: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::AuctionCancelled;
+use crate::state::{RegistryPage, State, Treasury};
+use crate::utils::{transfer_from_seller_bond, transfer_from_treasury_authority};
+
+pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+    let state = &ctx.accounts.state;
+
+    if state.highest_bid_amount != 0 || state.bid_count != 0 {
+        return err!(AuctionError::AuctionHasBids);
+    }
+    // the prize vault outlives the state, it is emptied first
+    if state.prize_deposited {
+        return err!(AuctionError::PrizeStillEscrowed);
+    }
+    // the registry keeps the slot of the closed auction so later entries do not move
+    if state.sequence != 0 {
+        ctx.accounts.registry_page.tombstone(state.sequence);
+    }
+
+    // the treasury authority gives everything it holds back, its rent included
+    let treasury_authority = ctx.accounts.treasury_authority.to_account_info();
+    let lamports = treasury_authority.lamports();
+    if lamports > 0 {
+        transfer_from_treasury_authority(
+            &treasury_authority,
+            &ctx.accounts.initializer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            state.key(),
+            state.treasury_authority_bump,
+            lamports,
+        )?;
+    }
+    // and so does the seller bond PDA
+    let seller_bond = ctx.accounts.seller_bond.to_account_info();
+    let bond_lamports = seller_bond.lamports();
+    if bond_lamports > 0 {
+        transfer_from_seller_bond(
+            &seller_bond,
+            &ctx.accounts.initializer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            state.key(),
+            state.seller_bond_bump,
+            bond_lamports,
+        )?;
+    }
+
+    // both accounts are closed to the initializer once the instruction returns
+    emit!(AuctionCancelled {
+        auction: state.key(),
+        initializer: state.initializer,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(mut, close = initializer, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    #[account(mut, close = initializer, seeds = [b"treasury", state.key().as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// Holds the treasury lamports and signs their transfers, it is emptied
+    #[account(mut, seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    /// Holds the seller bond and signs its transfers, it is emptied
+    #[account(mut, seeds = [b"bond", state.key().as_ref()], bump = state.seller_bond_bump)]
+    pub seller_bond: SystemAccount<'info>,
+    /// Registry page listing the auction
+    #[account(mut, seeds = [b"registry-page", state.registry_page_seed().as_ref()], bump)]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::instructions::pay_bid_fees;
+use crate::state::State;
+use crate::utils::deposit_lamports;
+
+pub fn cancel_with_compensation(ctx: Context<CancelWithCompensation>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if state.highest_bid_amount == 0 {
+        return err!(AuctionError::NoBids);
+    }
+
+    // the compensation comes out of the seller's wallet, the bids stay in their escrows
+    let compensation = state.cancel_compensation()?;
+    if compensation > 0 {
+        deposit_lamports(
+            &ctx.accounts.initializer,
+            ctx.accounts.highest_bidder.to_account_info(),
+            &ctx.accounts.system_program,
+            compensation,
+        )?;
+    }
+
+    // the seller keeps the bid fees of a penny auction
+    pay_bid_fees(state, &ctx.accounts.initializer.to_account_info())?;
+
+    // closing the bidding window lets every bidder refund right away
+    state.cancelled = true;
+    state.deadline = clock.unix_timestamp;
+    state.settled_at = clock.unix_timestamp;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelWithCompensation<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    /// Receives the compensation
+    #[account(
+        mut,
+        constraint = highest_bidder.key() == state.highest_bidder_account @ AuctionError::InvalidHighestBidder
+    )]
+    /// CHECK:
+    pub highest_bidder: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
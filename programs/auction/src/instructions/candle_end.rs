@@ -0,0 +1,185 @@
+use anchor_lang::{prelude::*, solana_program::sysvar::slot_hashes};
+use anchor_spl::token::{Token, TokenAccount};
+use switchboard_v2::{
+    OracleQueueAccountData, PermissionAccountData, SbState, VrfAccountData, VrfRequestRandomness,
+    SWITCHBOARD_PROGRAM_ID,
+};
+
+use crate::candle::{candle_cutoff, candle_end_slot, candle_winner, seconds_to_slots};
+use crate::constants::MS_PER_SLOT;
+use crate::errors::AuctionError;
+use crate::events::{CandleCutoffSet, CandleEndSet};
+use crate::state::State;
+use crate::utils::latest_slot_hash;
+
+pub fn request_random_end(
+    ctx: Context<RequestRandomEnd>,
+    switchboard_state_bump: u8,
+    permission_bump: u8,
+) -> Result<()> {
+    if ctx.accounts.state.random_end_slot != 0 {
+        return err!(AuctionError::CandleEndAlreadySet);
+    }
+    // a candle window already draws the end of the auction
+    if ctx.accounts.state.candle_window > 0 {
+        return err!(AuctionError::ConflictingAuctionOptions);
+    }
+    ctx.accounts.state.vrf_account = ctx.accounts.vrf.key();
+
+    let request = VrfRequestRandomness {
+        authority: ctx.accounts.state.to_account_info(),
+        vrf: ctx.accounts.vrf.to_account_info(),
+        oracle_queue: ctx.accounts.oracle_queue.to_account_info(),
+        queue_authority: ctx.accounts.queue_authority.to_account_info(),
+        data_buffer: ctx.accounts.data_buffer.to_account_info(),
+        permission: ctx.accounts.permission.to_account_info(),
+        escrow: ctx.accounts.escrow.clone(),
+        payer_wallet: ctx.accounts.payer_wallet.clone(),
+        payer_authority: ctx.accounts.initializer.to_account_info(),
+        recent_blockhashes: ctx.accounts.recent_blockhashes.to_account_info(),
+        program_state: ctx.accounts.program_state.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+    };
+
+    // the state PDA is the authority of the VRF account
+    let state = &ctx.accounts.state;
+    let index_seed = state.index_seed();
+    let state_seeds: &[&[u8]] = &[
+        b"state",
+        state.initializer.as_ref(),
+        &index_seed,
+        &[state.state_bump],
+    ];
+    request.invoke_signed(
+        ctx.accounts.switchboard_program.to_account_info(),
+        switchboard_state_bump,
+        permission_bump,
+        &[state_seeds],
+    )?;
+
+    Ok(())
+}
+
+pub fn fulfill_random_end(ctx: Context<FulfillRandomEnd>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if state.random_end_slot != 0 {
+        return err!(AuctionError::CandleEndAlreadySet);
+    }
+    let randomness = ctx.accounts.vrf.load()?.get_result()?;
+    if randomness == [0u8; 32] {
+        return err!(AuctionError::RandomnessNotResolved);
+    }
+
+    // bidding closes somewhere between now and the deadline
+    let window_slots = seconds_to_slots(
+        state.deadline.saturating_sub(clock.unix_timestamp),
+        MS_PER_SLOT,
+    );
+    state.random_end_slot = candle_end_slot(randomness, clock.slot, window_slots);
+
+    emit!(CandleEndSet {
+        auction: state.key(),
+        end_slot: state.random_end_slot,
+    });
+
+    Ok(())
+}
+
+pub fn resolve_candle(ctx: Context<ResolveCandle>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if state.candle_window <= 0 {
+        return err!(AuctionError::NotCandleAuction);
+    }
+    if state.candle_cutoff != 0 {
+        return err!(AuctionError::CandleEndAlreadySet);
+    }
+    if state.is_settled() {
+        return err!(AuctionError::AlreadySettled);
+    }
+
+    let randomness =
+        latest_slot_hash(&ctx.accounts.slot_hashes)?.ok_or(AuctionError::CandleNotResolved)?;
+    state.candle_cutoff = candle_cutoff(randomness, state.deadline, state.candle_window);
+
+    match candle_winner(&state.recent_bids(), state.candle_cutoff) {
+        Some(record) => {
+            let state_key = state.key();
+            let (_, bump) = Pubkey::find_program_address(
+                &[b"user-bid", record.bidder.as_ref(), state_key.as_ref()],
+                &crate::ID,
+            );
+            state.highest_bidder_account = record.bidder;
+            state.highest_bidder_bump = bump;
+            state.highest_bid_amount = record.amount;
+        }
+        // nothing was bid before the cutoff, every bid goes back to its bidder
+        None => {
+            state.highest_bidder_account = Pubkey::default();
+            state.highest_bidder_bump = 0;
+            state.highest_bid_amount = 0;
+            state.reserve_not_met = true;
+            state.settled_at = clock.unix_timestamp;
+        }
+    }
+
+    emit!(CandleCutoffSet {
+        auction: state.key(),
+        cutoff: state.candle_cutoff,
+        winner: state.highest_bidder_account,
+        amount: state.highest_bid_amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestRandomEnd<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller, it pays the VRF request from `payer_wallet`
+    pub initializer: Signer<'info>,
+    #[account(mut, constraint = vrf.load()?.authority == state.key() @ AuctionError::InvalidVrfAccount)]
+    pub vrf: AccountLoader<'info, VrfAccountData>,
+    #[account(mut, has_one = data_buffer)]
+    pub oracle_queue: AccountLoader<'info, OracleQueueAccountData>,
+    /// CHECK: checked by the Switchboard program
+    pub queue_authority: UncheckedAccount<'info>,
+    /// CHECK: checked by `oracle_queue`
+    #[account(mut)]
+    pub data_buffer: AccountInfo<'info>,
+    #[account(mut)]
+    pub permission: AccountLoader<'info, PermissionAccountData>,
+    #[account(mut, constraint = escrow.owner == program_state.key())]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(mut, constraint = payer_wallet.owner == initializer.key())]
+    pub payer_wallet: Account<'info, TokenAccount>,
+    /// CHECK: checked by its address
+    #[account(address = anchor_lang::solana_program::sysvar::recent_blockhashes::ID)]
+    pub recent_blockhashes: AccountInfo<'info>,
+    pub program_state: AccountLoader<'info, SbState>,
+    /// CHECK: checked by its address
+    #[account(address = SWITCHBOARD_PROGRAM_ID)]
+    pub switchboard_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillRandomEnd<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    #[account(constraint = vrf.key() == state.vrf_account @ AuctionError::InvalidVrfAccount)]
+    pub vrf: AccountLoader<'info, VrfAccountData>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveCandle<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// CHECK: checked by its address
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: AccountInfo<'info>,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::{DisputeStatus, State};
+use crate::utils::transfer_from_seller_bond;
+
+pub fn claim_bond(ctx: Context<ClaimBond>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if state.dispute_status != DisputeStatus::Open {
+        return err!(AuctionError::InvalidDisputeStatus);
+    }
+    let resolution_deadline = state
+        .dispute_opened_at
+        .checked_add(state.dispute_window)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if clock.unix_timestamp <= resolution_deadline {
+        return err!(AuctionError::DisputeWindowOpen);
+    }
+
+    transfer_from_seller_bond(
+        &ctx.accounts.seller_bond.to_account_info(),
+        &ctx.accounts.winner.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        state.key(),
+        state.seller_bond_bump,
+        state.bond_outstanding(),
+    )?;
+
+    state.dispute_status = DisputeStatus::Slashed;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimBond<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Highest bidder
+    #[account(mut, constraint = winner.key() == state.highest_bidder_account @ AuctionError::NotTheWinner)]
+    pub winner: Signer<'info>,
+    /// Holds the seller bond and signs its transfers
+    #[account(mut, seeds = [b"bond", state.key().as_ref()], bump = state.seller_bond_bump)]
+    pub seller_bond: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
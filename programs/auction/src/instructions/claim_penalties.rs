@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::{State, Treasury};
+use crate::utils::transfer_from_treasury;
+
+pub fn claim_penalties(ctx: Context<ClaimPenalties>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if state.penalties_owed == 0 {
+        return err!(AuctionError::NoPenalties);
+    }
+
+    transfer_from_treasury(
+        &mut ctx.accounts.treasury,
+        &ctx.accounts.treasury_authority.to_account_info(),
+        &ctx.accounts.beneficiary.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        state.key(),
+        state.treasury_authority_bump,
+        state.penalties_owed,
+    )?;
+
+    state.penalties_owed = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimPenalties<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller
+    pub initializer: Signer<'info>,
+    /// Receives the penalties
+    #[account(
+        mut,
+        constraint = beneficiary.key() == state.beneficiary @ AuctionError::InvalidPayoutBeneficiary
+    )]
+    /// CHECK:
+    pub beneficiary: AccountInfo<'info>,
+    #[account(mut, seeds = [b"treasury", state.key().as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// Holds the treasury lamports and signs their transfers
+    #[account(mut, seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
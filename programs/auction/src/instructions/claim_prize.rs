@@ -0,0 +1,75 @@
+use anchor_lang::{prelude::*, AccountsClose};
+
+use crate::errors::AuctionError;
+use crate::events::PrizeClaimed;
+use crate::instructions::release_prize;
+use crate::state::{BidEscrow, State, UserBid};
+use crate::utils::transfer_from_escrow;
+
+pub fn claim_prize<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimPrize<'info>>,
+    delivery_note_hash: Option<[u8; 32]>,
+) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if !state.seller_payed {
+        return err!(AuctionError::UnclaimedPrize);
+    }
+    if state.prize_claimed {
+        return err!(AuctionError::PrizeAlreadyClaimed);
+    }
+
+    state.prize_claimed = true;
+
+    // an escrowed prize goes to the token account of the receive address
+    if state.has_prize_escrow() {
+        release_prize(state, ctx.accounts.receiver.key(), ctx.remaining_accounts)?;
+    }
+
+    // the escrow only holds the rent once the seller was paid, and the rest of a
+    // proxy maximum above the winning bid, which is sent with the prize
+    let escrow = ctx.accounts.bid_escrow.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+    let surplus = escrow.lamports().saturating_sub(rent_floor);
+    if surplus > 0 {
+        transfer_from_escrow(&escrow, &ctx.accounts.receiver, surplus)?;
+    }
+    ctx.accounts
+        .bid_escrow
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+    ctx.accounts
+        .user_bid
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+
+    emit!(PrizeClaimed {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        winner: ctx.accounts.winner.key(),
+        delivery_note_hash,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimPrize<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Winner of the auction
+    #[account(mut, constraint = winner.key() == state.winner() @ AuctionError::NotTheWinner)]
+    pub winner: Signer<'info>,
+    /// Winning bid, closed by the claim
+    #[account(mut, seeds = [b"user-bid", winner.key().as_ref(), state.key().as_ref()], bump)]
+    pub user_bid: Account<'info, UserBid>,
+    /// Escrow of the winning bid, closed by the claim
+    #[account(mut, seeds = [b"escrow", winner.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Receive address chosen by the winner, the winner itself when there is none
+    #[account(mut, constraint = receiver.key() == state.prize_receiver() @ AuctionError::InvalidPrizeReceiver)]
+    /// CHECK:
+    pub receiver: AccountInfo<'info>,
+    /// Payer of the user bid rent, the winner itself unless the bid was sponsored
+    #[account(mut, constraint = rent_payer.key() == user_bid.rent_payer @ AuctionError::InvalidRentPayer)]
+    /// CHECK:
+    pub rent_payer: AccountInfo<'info>,
+}
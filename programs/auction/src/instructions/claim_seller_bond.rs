@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::{State, UserBid};
+use crate::utils::transfer_from_seller_bond;
+
+pub fn claim_seller_bond(ctx: Context<ClaimSellerBond>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let user_bid = &mut ctx.accounts.user_bid;
+    let clock = Clock::get()?;
+
+    if state.is_settled() {
+        return err!(AuctionError::AlreadySettled);
+    }
+    if state.bond_outstanding() == 0 {
+        return err!(AuctionError::NoSellerBond);
+    }
+    if state.seller_claim_window <= 0 {
+        return err!(AuctionError::SellerClaimWindowOpen);
+    }
+    let claim_deadline = state
+        .deadline
+        .checked_add(state.seller_claim_window)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if clock.unix_timestamp <= claim_deadline {
+        return err!(AuctionError::SellerClaimWindowOpen);
+    }
+    if user_bid.bond_claimed {
+        return err!(AuctionError::SellerBondAlreadyClaimed);
+    }
+    if state.bid_count == 0 {
+        return err!(AuctionError::NoBids);
+    }
+
+    // every bidder gets the same share, the last claims are capped by what is left
+    let share = (state.seller_bond / state.bid_count as u64).min(state.bond_outstanding());
+    transfer_from_seller_bond(
+        &ctx.accounts.seller_bond.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        state.key(),
+        state.seller_bond_bump,
+        share,
+    )?;
+
+    state.bond_claimed = state
+        .bond_claimed
+        .checked_add(share)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    user_bid.bond_claimed = true;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimSellerBond<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder of the auction
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump)]
+    pub user_bid: Account<'info, UserBid>,
+    /// Holds the seller bond and signs its transfers
+    #[account(mut, seeds = [b"bond", state.key().as_ref()], bump = state.seller_bond_bump)]
+    pub seller_bond: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,239 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv, AccountsClose};
+
+use crate::constants::{BIDDER_PROFILE_SPACE, BID_ESCROW_SPACE, COMMIT_BID_SPACE, USER_BID_SPACE};
+use crate::errors::AuctionError;
+use crate::events::BidCommitted;
+use crate::instructions::{check_shill_bid, escrow_bid};
+use crate::state::{BidEscrow, BidderProfile, CommitBid, Config, Leaderboard, State, UserBid};
+use crate::utils::{deposit_lamports, transfer_from_escrow};
+
+pub fn commit_bid(ctx: Context<CommitSealedBid>, commitment: [u8; 32]) -> Result<()> {
+    // the deposit makes committing without revealing cost the bidder
+    let deposit = ctx.accounts.state.commit_deposit;
+    if deposit > 0 {
+        deposit_lamports(
+            &ctx.accounts.user,
+            ctx.accounts.commit_bid.to_account_info(),
+            &ctx.accounts.system_program,
+            deposit,
+        )?;
+        ctx.accounts.commit_bid.deposit = deposit;
+    }
+
+    store_commitment(
+        &mut ctx.accounts.commit_bid,
+        ctx.accounts.state.key(),
+        ctx.accounts.user.key(),
+        commitment,
+    )
+}
+
+pub fn recommit_bid(ctx: Context<RecommitSealedBid>, commitment: [u8; 32]) -> Result<()> {
+    store_commitment(
+        &mut ctx.accounts.commit_bid,
+        ctx.accounts.state.key(),
+        ctx.accounts.user.key(),
+        commitment,
+    )
+}
+
+pub fn reveal_bid(ctx: Context<RevealSealedBid>, amount: u64, nonce: [u8; 32]) -> Result<()> {
+    let commit_bid = &mut ctx.accounts.commit_bid;
+
+    if commit_bid.revealed {
+        return err!(AuctionError::BidAlreadyRevealed);
+    }
+    if hashv(&[&amount.to_le_bytes(), &nonce]).to_bytes() != commit_bid.commitment {
+        return err!(AuctionError::InvalidBidCommitment);
+    }
+    commit_bid.revealed = true;
+    check_shill_bid(
+        &ctx.accounts.sybil_check,
+        ctx.accounts.config.shill_lookback_secs,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    let state = &ctx.accounts.state;
+    if amount < state.highest_bid_amount && !state.takes_lower_bids() {
+        // an honest bid below an earlier reveal lost, it is not placed and its user bid
+        // and bid escrow are closed again, but it keeps its deposit like any revealed bid
+        ctx.accounts
+            .bid_escrow
+            .close(ctx.accounts.user.to_account_info())?;
+        ctx.accounts
+            .user_bid
+            .close(ctx.accounts.user.to_account_info())?;
+    } else {
+        // the revealed amount goes through the same rules and escrow as an open bid
+        escrow_bid(
+            &mut ctx.accounts.state,
+            &ctx.accounts.user,
+            &ctx.accounts.user,
+            &mut ctx.accounts.user_bid,
+            &mut ctx.accounts.bid_escrow,
+            &mut ctx.accounts.leaderboard,
+            &mut ctx.accounts.profile,
+            &ctx.accounts.system_program,
+            *ctx.bumps.get("user_bid").unwrap(),
+            *ctx.bumps.get("bid_escrow").unwrap(),
+            amount,
+            0,
+            ctx.accounts.config.min_bid_amount,
+            &[],
+        )?;
+    }
+
+    // a revealed bid gets its deposit back
+    let deposit = ctx.accounts.commit_bid.deposit;
+    if deposit > 0 {
+        transfer_from_escrow(
+            &ctx.accounts.commit_bid.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            deposit,
+        )?;
+        ctx.accounts.commit_bid.deposit = 0;
+    }
+
+    Ok(())
+}
+
+pub fn forfeit_deposit(ctx: Context<ForfeitDeposit>) -> Result<()> {
+    let commit_bid = &ctx.accounts.commit_bid;
+
+    if commit_bid.revealed {
+        return err!(AuctionError::BidAlreadyRevealed);
+    }
+    if commit_bid.deposit == 0 {
+        return err!(AuctionError::NoCommitDeposit);
+    }
+
+    // the deposit goes to the beneficiary, the rent back to the bidder on close
+    transfer_from_escrow(
+        &commit_bid.to_account_info(),
+        &ctx.accounts.beneficiary,
+        commit_bid.deposit,
+    )?;
+
+    Ok(())
+}
+
+/// Stores `commitment` in the commit bid of `bidder` and publishes it
+pub(crate) fn store_commitment(
+    commit_bid: &mut CommitBid,
+    auction: Pubkey,
+    bidder: Pubkey,
+    commitment: [u8; 32],
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    commit_bid.commitment = commitment;
+    commit_bid.committed_at = clock.unix_timestamp;
+
+    // only the hash is published, the amount stays sealed until the reveal
+    emit!(BidCommitted {
+        auction,
+        bidder,
+        commitment,
+        committed_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CommitSealedBid<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        init,
+        payer = user,
+        space = COMMIT_BID_SPACE,
+        seeds = [b"commit", user.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub commit_bid: Account<'info, CommitBid>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecommitSealedBid<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"commit", user.key().as_ref(), state.key().as_ref()], bump)]
+    pub commit_bid: Account<'info, CommitBid>,
+}
+
+#[derive(Accounts)]
+pub struct RevealSealedBid<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"commit", user.key().as_ref(), state.key().as_ref()], bump)]
+    pub commit_bid: Account<'info, CommitBid>,
+    /// Revealed bid
+    #[account(
+        init,
+        payer = user,
+        space = USER_BID_SPACE,
+        seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub user_bid: Account<'info, UserBid>,
+    /// Escrow of the revealed bid
+    #[account(
+        init,
+        payer = user,
+        space = BID_ESCROW_SPACE,
+        seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Highest bids of the auction
+    #[account(mut, seeds = [b"leaderboard", state.key().as_ref()], bump)]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    /// Lifetime statistics of the bidder, created with its first bid
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = BIDDER_PROFILE_SPACE,
+        seeds = [b"profile", user.key().as_ref()],
+        bump,
+    )]
+    pub profile: Box<Account<'info, BidderProfile>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    /// Registration of the bidder as a wallet funded by the seller, empty unless the
+    /// platform authority registered it
+    /// CHECK: only read when it is not empty
+    #[account(seeds = [b"sybil", state.key().as_ref(), user.key().as_ref()], bump)]
+    pub sybil_check: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ForfeitDeposit<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller
+    pub initializer: Signer<'info>,
+    /// Bidder who did not reveal its sealed bid, it gets the rent back
+    #[account(mut)]
+    /// CHECK:
+    pub user: AccountInfo<'info>,
+    #[account(mut, close = user, seeds = [b"commit", user.key().as_ref(), state.key().as_ref()], bump)]
+    pub commit_bid: Account<'info, CommitBid>,
+    /// Receives the deposit
+    #[account(
+        mut,
+        constraint = beneficiary.key() == state.beneficiary @ AuctionError::InvalidPayoutBeneficiary
+    )]
+    /// CHECK:
+    pub beneficiary: AccountInfo<'info>,
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::instructions::WinnerAction;
+use crate::state::DisputeStatus;
+
+pub fn confirm_delivery(ctx: Context<WinnerAction>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if !state.seller_payed {
+        return err!(AuctionError::UnclaimedPrize);
+    }
+    if state.dispute_status != DisputeStatus::Idle && state.dispute_status != DisputeStatus::Open {
+        return err!(AuctionError::InvalidDisputeStatus);
+    }
+
+    state.dispute_status = DisputeStatus::Confirmed;
+
+    Ok(())
+}
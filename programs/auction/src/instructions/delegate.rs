@@ -0,0 +1,222 @@
+use anchor_lang::{prelude::*, solana_program::native_token::sol_to_lamports};
+
+use crate::constants::{
+    BIDDER_PROFILE_SPACE, BID_ESCROW_SPACE, DELEGATE_AUTH_SPACE, USER_BID_SPACE,
+};
+use crate::errors::AuctionError;
+use crate::events::DelegateChanged;
+#[cfg(not(feature = "no-balance-check"))]
+use crate::instructions::check_bidder_balance;
+use crate::instructions::{check_bid_cooldown, check_shill_bid, register_bid};
+use crate::state::{
+    AuctionType, BidEscrow, BidderProfile, Config, DelegateAuth, Leaderboard, State, UserBid,
+};
+use crate::utils::{deposit_lamports, transfer_from_escrow};
+
+pub fn grant_delegate(
+    ctx: Context<GrantDelegate>,
+    delegate: Pubkey,
+    max_amount: f64,
+    expiry: i64,
+) -> Result<()> {
+    if expiry <= Clock::get()?.unix_timestamp {
+        return err!(AuctionError::DelegateExpired);
+    }
+
+    let delegate_auth = &mut ctx.accounts.delegate_auth;
+    delegate_auth.delegate = delegate;
+    delegate_auth.max_amount = sol_to_lamports(max_amount);
+    delegate_auth.expiry = expiry;
+
+    // the principal does not sign the delegate's bids, so the allowance is held upfront
+    deposit_lamports(
+        &ctx.accounts.principal,
+        delegate_auth.to_account_info(),
+        &ctx.accounts.system_program,
+        delegate_auth.max_amount,
+    )?;
+
+    emit!(DelegateChanged {
+        auction: ctx.accounts.state.key(),
+        principal: ctx.accounts.principal.key(),
+        delegate,
+        max_amount: delegate_auth.max_amount,
+        expiry,
+    });
+
+    Ok(())
+}
+
+pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+    // closing the PDA gives the unspent allowance back together with the rent
+    emit!(DelegateChanged {
+        auction: ctx.accounts.state.key(),
+        principal: ctx.accounts.principal.key(),
+        delegate: ctx.accounts.delegate_auth.delegate,
+        max_amount: 0,
+        expiry: ctx.accounts.delegate_auth.expiry,
+    });
+
+    Ok(())
+}
+
+pub fn delegated_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, DelegatedBid<'info>>,
+    amount: f64,
+) -> Result<()> {
+    // sealed-bid auctions only take bids through `reveal_bid`
+    if ctx.accounts.state.reveal_start != 0 {
+        return err!(AuctionError::SealedBidsOnly);
+    }
+    if ctx.accounts.state.auction_type == AuctionType::Dutch {
+        return err!(AuctionError::DutchBidNotAllowed);
+    }
+    // the allowance is held in lamports
+    if ctx.accounts.state.is_token_denominated() {
+        return err!(AuctionError::TokenInstructionRequired);
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if now >= ctx.accounts.delegate_auth.expiry {
+        return err!(AuctionError::DelegateExpired);
+    }
+    // a delegate does not hide a principal the seller funded
+    check_shill_bid(
+        &ctx.accounts.sybil_check,
+        ctx.accounts.config.shill_lookback_secs,
+        now,
+    )?;
+    // the allowance pays the bid, the delegate still keeps the minimum balance
+    #[cfg(not(feature = "no-balance-check"))]
+    check_bidder_balance(&ctx.accounts.delegate, 0)?;
+    check_bid_cooldown(
+        &ctx.accounts.user_bid,
+        ctx.accounts.config.bid_cooldown_seconds,
+    )?;
+    let amount_in_lamports = sol_to_lamports(amount);
+    if amount_in_lamports > ctx.accounts.delegate_auth.max_amount {
+        return err!(AuctionError::DelegateLimitExceeded);
+    }
+
+    // the bid belongs to the principal, only the rent of its escrow is paid by the delegate
+    ctx.accounts.bid_escrow.bump = *ctx.bumps.get("bid_escrow").unwrap();
+    register_bid(
+        &mut ctx.accounts.state,
+        ctx.accounts.principal.key(),
+        &ctx.accounts.delegate,
+        &mut ctx.accounts.user_bid,
+        &mut ctx.accounts.leaderboard,
+        &mut ctx.accounts.profile,
+        *ctx.bumps.get("user_bid").unwrap(),
+        amount_in_lamports,
+        0,
+        ctx.accounts.config.min_bid_amount,
+        ctx.remaining_accounts,
+    )?;
+
+    let escrowed = ctx.accounts.state.escrow_required(&ctx.accounts.user_bid);
+    if escrowed > 0 {
+        transfer_from_escrow(
+            &ctx.accounts.delegate_auth.to_account_info(),
+            &ctx.accounts.bid_escrow.to_account_info(),
+            escrowed,
+        )?;
+    }
+    // the bid fee of a penny auction comes out of the allowance as well
+    let bid_fee = ctx.accounts.state.bid_fee;
+    if bid_fee > 0 {
+        transfer_from_escrow(
+            &ctx.accounts.delegate_auth.to_account_info(),
+            &ctx.accounts.state.to_account_info(),
+            bid_fee,
+        )?;
+    }
+    let delegate_auth = &mut ctx.accounts.delegate_auth;
+    delegate_auth.max_amount = delegate_auth
+        .max_amount
+        .checked_sub(escrowed)
+        .and_then(|max_amount| max_amount.checked_sub(bid_fee))
+        .ok_or(AuctionError::DelegateLimitExceeded)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GrantDelegate<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder the delegate bids for, it funds the allowance
+    #[account(mut)]
+    pub principal: Signer<'info>,
+    #[account(
+        init,
+        payer = principal,
+        space = DELEGATE_AUTH_SPACE,
+        seeds = [b"delegate", principal.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub delegate_auth: Account<'info, DelegateAuth>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    #[account(mut)]
+    pub principal: Signer<'info>,
+    #[account(mut, close = principal, seeds = [b"delegate", principal.key().as_ref(), state.key().as_ref()], bump)]
+    pub delegate_auth: Account<'info, DelegateAuth>,
+}
+
+#[derive(Accounts)]
+pub struct DelegatedBid<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Wallet bidding on behalf of the principal, it pays the rent of the user bid and bid
+    /// escrow PDAs
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+    /// Bidder the bid is placed for, the delegate auth PDA ties it to the delegate
+    /// CHECK:
+    pub principal: AccountInfo<'info>,
+    #[account(mut, seeds = [b"delegate", principal.key().as_ref(), state.key().as_ref()], bump, has_one = delegate @ AuctionError::Unauthorized)]
+    pub delegate_auth: Account<'info, DelegateAuth>,
+    /// Bid of the principal
+    #[account(
+        init,
+        payer = delegate,
+        space = USER_BID_SPACE,
+        seeds = [b"user-bid", principal.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub user_bid: Account<'info, UserBid>,
+    /// Escrow of the bid, derived from the principal so the bid is refunded to it
+    #[account(
+        init,
+        payer = delegate,
+        space = BID_ESCROW_SPACE,
+        seeds = [b"escrow", principal.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Highest bids of the auction
+    #[account(mut, seeds = [b"leaderboard", state.key().as_ref()], bump)]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    /// Lifetime statistics of the principal, created with its first bid
+    #[account(
+        init_if_needed,
+        payer = delegate,
+        space = BIDDER_PROFILE_SPACE,
+        seeds = [b"profile", principal.key().as_ref()],
+        bump,
+    )]
+    pub profile: Box<Account<'info, BidderProfile>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    /// Registration of the principal as a wallet funded by the seller, empty unless the
+    /// platform authority registered it
+    /// CHECK: only read when it is not empty
+    #[account(seeds = [b"sybil", state.key().as_ref(), principal.key().as_ref()], bump)]
+    pub sybil_check: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_DESCRIPTION_LEN;
+use crate::errors::AuctionError;
+use crate::events::DescriptionUpdated;
+use crate::state::{DescriptionAccount, State};
+
+pub fn add_description(ctx: Context<AddDescription>, text: Vec<u8>) -> Result<()> {
+    check_description(&ctx.accounts.state, &text)?;
+
+    ctx.accounts.description.text = text;
+    ctx.accounts.state.has_description = true;
+
+    emit!(DescriptionUpdated {
+        auction: ctx.accounts.state.key(),
+        description: ctx.accounts.description.key(),
+        len: ctx.accounts.description.text.len() as u32,
+    });
+
+    Ok(())
+}
+
+pub fn update_description(ctx: Context<UpdateDescription>, text: Vec<u8>) -> Result<()> {
+    check_description(&ctx.accounts.state, &text)?;
+
+    ctx.accounts.description.text = text;
+
+    emit!(DescriptionUpdated {
+        auction: ctx.accounts.state.key(),
+        description: ctx.accounts.description.key(),
+        len: ctx.accounts.description.text.len() as u32,
+    });
+
+    Ok(())
+}
+
+fn check_description(state: &State, text: &[u8]) -> Result<()> {
+    // bidders joined for the item as it was described
+    if state.highest_bid_amount != 0 || state.bid_count != 0 {
+        return err!(AuctionError::CannotChangeAfterBids);
+    }
+    if text.len() > MAX_DESCRIPTION_LEN {
+        return err!(AuctionError::DescriptionTooLong);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(text: Vec<u8>)]
+pub struct AddDescription<'info> {
+    #[account(
+        mut,
+        seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()],
+        bump = state.state_bump,
+        constraint = state.is_current_version() @ AuctionError::StateMigrationRequired,
+        constraint = state.authority == initializer.key() @ AuctionError::Unauthorized,
+    )]
+    pub state: Account<'info, State>,
+    /// Sized to the text, the seller pays its rent
+    #[account(
+        init,
+        payer = initializer,
+        space = DescriptionAccount::space(&text),
+        seeds = [b"desc", state.key().as_ref()],
+        bump,
+    )]
+    pub description: Account<'info, DescriptionAccount>,
+    /// Seller
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(text: Vec<u8>)]
+pub struct UpdateDescription<'info> {
+    #[account(
+        seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()],
+        bump = state.state_bump,
+        constraint = state.is_current_version() @ AuctionError::StateMigrationRequired,
+        constraint = state.authority == initializer.key() @ AuctionError::Unauthorized,
+    )]
+    pub state: Account<'info, State>,
+    /// Resized to fit the new text, the seller pays the rent of a larger account and
+    /// gets the rent of a smaller one back
+    #[account(
+        mut,
+        seeds = [b"desc", state.key().as_ref()],
+        bump,
+        realloc = DescriptionAccount::space(&text),
+        realloc::payer = initializer,
+        realloc::zero = false,
+    )]
+    pub description: Account<'info, DescriptionAccount>,
+    /// Seller
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
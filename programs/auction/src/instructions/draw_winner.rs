@@ -0,0 +1,91 @@
+use anchor_lang::{prelude::*, solana_program::sysvar::slot_hashes};
+
+use crate::errors::AuctionError;
+use crate::events::RaffleWinnerDrawn;
+use crate::raffle::raffle_winner;
+use crate::state::{AuctionType, State};
+use crate::utils::{latest_slot_hash, load_user_bid};
+
+pub fn draw_winner<'info>(ctx: Context<'_, '_, '_, 'info, DrawWinner<'info>>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if state.auction_type != AuctionType::Raffle {
+        return err!(AuctionError::NotRaffle);
+    }
+    if state.raffle_randomness != [0u8; 32] {
+        return err!(AuctionError::RaffleAlreadyDrawn);
+    }
+    if state.is_settled() {
+        return err!(AuctionError::AlreadySettled);
+    }
+
+    // the ranges of the contributions follow the order of the bidder keys, whoever
+    // passes them can not change the outcome
+    let mut bidders: Vec<Pubkey> = Vec::new();
+    let mut totals: Vec<u64> = Vec::new();
+    for accounts in ctx.remaining_accounts.chunks(2) {
+        let (bidder, user_bid) = match accounts {
+            [bidder, user_bid] => (bidder, user_bid),
+            _ => return err!(AuctionError::InvalidEscrow),
+        };
+        if bidders
+            .last()
+            .map_or(false, |previous| bidder.key() <= *previous)
+        {
+            return err!(AuctionError::RaffleBidsMissing);
+        }
+        let user_bid = load_user_bid(state.key(), bidder, user_bid)?;
+        bidders.push(bidder.key());
+        totals.push(user_bid.amount);
+    }
+    if bidders.len() != state.bid_count as usize {
+        return err!(AuctionError::RaffleBidsMissing);
+    }
+    let pool = totals
+        .iter()
+        .try_fold(0u64, |pool, total| pool.checked_add(*total))
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+
+    state.raffle_randomness =
+        latest_slot_hash(&ctx.accounts.slot_hashes)?.ok_or(AuctionError::RandomnessNotResolved)?;
+    match raffle_winner(state.raffle_randomness, &totals) {
+        Some(index) if pool >= state.reserve_price => {
+            let state_key = state.key();
+            let (_, bump) = Pubkey::find_program_address(
+                &[b"user-bid", bidders[index].as_ref(), state_key.as_ref()],
+                &crate::ID,
+            );
+            state.highest_bidder_account = bidders[index];
+            state.highest_bidder_bump = bump;
+            state.highest_bid_amount = totals[index];
+        }
+        // nothing or too little was contributed, every bid goes back to its bidder
+        _ => {
+            state.reserve_not_met = true;
+            state.settled_at = clock.unix_timestamp;
+        }
+    }
+
+    emit!(RaffleWinnerDrawn {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        winner: state.highest_bidder_account,
+        contribution: state.highest_bid_amount,
+        pool,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DrawWinner<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Seller, or the settlement authority it delegated to
+    #[account(constraint = state.can_settle(authority.key()) @ AuctionError::Unauthorized)]
+    pub authority: Signer<'info>,
+    /// CHECK: checked by its address
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: AccountInfo<'info>,
+}
@@ -0,0 +1,99 @@
+use anchor_lang::{prelude::*, AccountsClose};
+
+use crate::errors::AuctionError;
+use crate::events::EmergencyRecovery;
+use crate::state::{RecoveryConfig, State, Treasury};
+use crate::utils::{
+    load_bid_escrow, load_user_bid, transfer_from_treasury, treasury_available_lamports,
+};
+
+pub fn emergency_recover<'info>(
+    ctx: Context<'_, '_, '_, 'info, EmergencyRecover<'info>>,
+) -> Result<()> {
+    let state = &ctx.accounts.state;
+    let config = &ctx.accounts.recovery_config;
+    let clock = Clock::get()?;
+
+    let recoverable_at = state
+        .deadline
+        .checked_add(config.recovery_timeout)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if clock.unix_timestamp <= recoverable_at {
+        return err!(AuctionError::RecoveryTimeoutNotElapsed);
+    }
+
+    // keep the treasury authority rent exempt, everything above it is unresolved. The
+    // seller bond PDA is left alone, its bond stays claimable by the winner or the bidders
+    let treasury_amount = treasury_available_lamports(&ctx.accounts.treasury_authority)?;
+    if treasury_amount > 0 {
+        transfer_from_treasury(
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.treasury_authority.to_account_info(),
+            &ctx.accounts.recovery_vault,
+            &ctx.accounts.system_program.to_account_info(),
+            state.key(),
+            state.treasury_authority_bump,
+            treasury_amount,
+        )?;
+    }
+
+    // bids are escrowed in their bid escrow PDAs, which are passed with their user bids
+    // as `(bidder, user_bid, bid_escrow)` triples in the remaining accounts
+    let mut amount = treasury_amount;
+    for accounts in ctx.remaining_accounts.chunks(3) {
+        if accounts.len() != 3 {
+            return err!(AuctionError::InvalidEscrow);
+        }
+        let (bidder, user_bid, escrow) = (&accounts[0], &accounts[1], &accounts[2]);
+        if !user_bid.is_writable || !escrow.is_writable {
+            return err!(AuctionError::InvalidEscrow);
+        }
+
+        let user_bid_account = load_user_bid(state.key(), bidder, user_bid)?;
+        let bid_escrow = load_bid_escrow(state.key(), bidder, escrow)?;
+        amount = amount
+            .checked_add(escrow.lamports())
+            .and_then(|amount| amount.checked_add(user_bid.lamports()))
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        bid_escrow.close(ctx.accounts.recovery_vault.to_account_info())?;
+        user_bid_account.close(ctx.accounts.recovery_vault.to_account_info())?;
+    }
+    if amount == 0 {
+        return err!(AuctionError::NothingToRecover);
+    }
+
+    emit!(EmergencyRecovery {
+        auction: state.key(),
+        initializer: state.initializer,
+        treasury: ctx.accounts.treasury.key(),
+        recovery_vault: ctx.accounts.recovery_vault.key(),
+        admin: ctx.accounts.admin.key(),
+        amount,
+        deadline: state.deadline,
+        recovered_at: clock.unix_timestamp,
+        seller_payed: state.seller_payed,
+        reserve_not_met: state.reserve_not_met,
+        highest_bid_amount: state.highest_bid_amount,
+        highest_bidder_account: state.highest_bidder_account,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct EmergencyRecover<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    #[account(mut, seeds = [b"treasury", state.key().as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// Holds the treasury lamports and signs their transfers
+    #[account(mut, seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    #[account(seeds = [b"recovery-config"], bump, has_one = admin @ AuctionError::Unauthorized, has_one = recovery_vault)]
+    pub recovery_config: Account<'info, RecoveryConfig>,
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    /// CHECK:
+    pub recovery_vault: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,566 @@
+use anchor_lang::{prelude::*, AccountsClose};
+
+use crate::constants::{
+    AUCTION_SUMMARY_SPACE, BIDDER_PROFILE_SPACE, LEADERBOARD_SIZE, SELLER_PROFILE_SPACE,
+    TAX_RECORD_SPACE,
+};
+use crate::errors::AuctionError;
+use crate::events::{
+    AllPayBidsCollected, AuctionRoundCompleted, AuctionSettled, BundleSaleCompleted,
+    ProfileUpdated, VickreyRefund,
+};
+use crate::instructions::pay_bid_fees;
+use crate::interface;
+use crate::oracle;
+use crate::state::{
+    AuctionSummary, AuctionType, BidEscrow, BidderProfile, Config, Leaderboard, LeaderboardEntry,
+    SellerProfile, State, TaxRecord, Treasury, UserBid,
+};
+use crate::utils::{
+    load_bid_escrow, load_user_bid, pay_sale_from_escrow, transfer_from_escrow,
+    transfer_from_treasury,
+};
+
+pub fn end_auction<'info>(ctx: Context<'_, '_, '_, 'info, EndAuction<'info>>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    check_settleable(state)?;
+    if state.is_token_denominated() {
+        return err!(AuctionError::TokenInstructionRequired);
+    }
+    if state.is_multi_unit() {
+        return err!(AuctionError::MultiUnitSettlementRequired);
+    }
+    // the winner of a candle auction is only known once its effective end is drawn
+    if state.candle_window > 0 && state.candle_cutoff == 0 {
+        return err!(AuctionError::CandleNotResolved);
+    }
+    // a conditional auction only settles once its condition program agrees
+    if state.condition_program != Pubkey::default()
+        && !interface::check_condition(
+            &ctx.accounts.condition_program,
+            &ctx.accounts.condition_account,
+            &state.to_account_info(),
+        )?
+    {
+        return err!(AuctionError::ConditionNotMet);
+    }
+    // the lowest offer of a reverse auction is paid out of the budget the buyer escrowed,
+    // less the platform fee, the providers escrowed nothing. The rest of the budget goes
+    // back to the beneficiary, the buyer unless it was changed
+    if state.auction_type == AuctionType::Reverse {
+        let remainder = state
+            .budget
+            .checked_sub(state.lowest_bid_amount)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        let platform_fee = ctx.accounts.config.platform_fee(
+            state.lowest_bid_amount,
+            !ctx.accounts.winner_loyalty.data_is_empty(),
+        )?;
+        let offer_proceeds = state
+            .lowest_bid_amount
+            .checked_sub(platform_fee)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        if platform_fee > 0 {
+            transfer_from_treasury(
+                &mut ctx.accounts.treasury,
+                &ctx.accounts.treasury_authority.to_account_info(),
+                &ctx.accounts.fee_recipient.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                state.key(),
+                state.treasury_authority_bump,
+                platform_fee,
+            )?;
+        }
+        transfer_from_treasury(
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.treasury_authority.to_account_info(),
+            &ctx.accounts.highest_bidder.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            state.key(),
+            state.treasury_authority_bump,
+            offer_proceeds,
+        )?;
+        if remainder > 0 {
+            transfer_from_treasury(
+                &mut ctx.accounts.treasury,
+                &ctx.accounts.treasury_authority.to_account_info(),
+                &ctx.accounts.beneficiary.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                state.key(),
+                state.treasury_authority_bump,
+                remainder,
+            )?;
+        }
+        state.budget = 0;
+        state.seller_payed = true;
+        state.winning_amount = state.lowest_bid_amount;
+        state.settled_at = clock.unix_timestamp;
+        ctx.accounts
+            .summary
+            .record(state, state.lowest_bid_amount, clock.slot);
+        // the buyer of a reverse auction is its seller, the lowest bidder sells to it
+        ctx.accounts.tax_record.record(
+            state.winner(),
+            state.authority,
+            state.lowest_bid_amount,
+            platform_fee,
+            clock.slot,
+            clock.unix_timestamp,
+        );
+        // the buyer paid instead of earning anything
+        ctx.accounts
+            .seller_profile
+            .record_settlement(0, state.bid_count)?;
+        emit!(AuctionSettled {
+            auction: state.key(),
+            auction_type: state.auction_type,
+            winner: state.winner(),
+            amount: state.lowest_bid_amount,
+        });
+        return record_win(state.winner(), &mut ctx.accounts.winner_profile);
+    }
+    // get highest bid and send to seller. The winner of a candle auction may have
+    // raised its bid after the cutoff, only its bid before the cutoff counts
+    let escrowed_bid = ctx.accounts.user_bid.amount;
+    let winning_bid = if state.candle_window > 0 {
+        state.highest_bid_amount
+    } else {
+        escrowed_bid
+    };
+
+    // the reserve of a raffle applies to the whole pool and was checked by the draw
+    let mut reserve_met =
+        state.auction_type == AuctionType::Raffle || winning_bid >= state.reserve_price;
+    // enforce the USD reserve price when the auction has a price feed
+    if reserve_met && state.price_feed != Pubkey::default() {
+        let reserve_in_lamports = oracle::reserve_price_in_lamports(
+            &ctx.accounts.price_feed,
+            state.reserve_price_usd_cents,
+            clock.unix_timestamp,
+        )?;
+        reserve_met = winning_bid >= reserve_in_lamports;
+    }
+
+    // nothing is paid out and every bidder, including the highest one, gets refunded
+    if !reserve_met {
+        state.reserve_not_met = true;
+        state.settled_at = clock.unix_timestamp;
+        ctx.accounts.summary.record(state, 0, clock.slot);
+        // the bid fees of a penny auction are not refunded
+        return pay_bid_fees(state, &ctx.accounts.beneficiary);
+    }
+
+    // a Vickrey winner only pays the second highest bid and gets the rest back first,
+    // like the raise of a candle winner after the cutoff
+    let amount_to_pay = state.price_to_pay(winning_bid);
+    let winner_refund = escrowed_bid
+        .checked_sub(amount_to_pay)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if winner_refund > 0 {
+        transfer_from_escrow(
+            &ctx.accounts.bid_escrow.to_account_info(),
+            &ctx.accounts.highest_bidder.to_account_info(),
+            winner_refund,
+        )?;
+        if state.auction_type == AuctionType::Vickrey {
+            emit!(VickreyRefund {
+                auction: state.key(),
+                auction_type: state.auction_type,
+                winner: state.highest_bidder_account,
+                winning_bid,
+                price: amount_to_pay,
+                refunded: winner_refund,
+            });
+        }
+    }
+
+    // only the winner's escrow is debited, the other bids are never touched.
+    // Proceeds go to the beneficiary, which is the seller unless it was changed, less
+    // the platform fee. With milestones they stay in the escrow until the winner
+    // releases them, each milestone is charged its part of the fee
+    let platform_fee = ctx
+        .accounts
+        .config
+        .platform_fee(amount_to_pay, !ctx.accounts.winner_loyalty.data_is_empty())?;
+    if state.milestone_count == 0 {
+        pay_sale_from_escrow(
+            &ctx.accounts.bid_escrow.to_account_info(),
+            &ctx.accounts.beneficiary,
+            &ctx.accounts.fee_recipient,
+            amount_to_pay,
+            platform_fee,
+        )?;
+    }
+
+    state.seller_payed = true;
+    state.winning_amount = amount_to_pay;
+    state.highest_bid_amount = 0;
+    state.settled_at = clock.unix_timestamp;
+    ctx.accounts
+        .summary
+        .record(state, amount_to_pay, clock.slot);
+    ctx.accounts.tax_record.record(
+        state.authority,
+        state.highest_bidder_account,
+        amount_to_pay,
+        platform_fee,
+        clock.slot,
+        clock.unix_timestamp,
+    );
+    record_win(
+        state.highest_bidder_account,
+        &mut ctx.accounts.winner_profile,
+    )?;
+    ctx.accounts
+        .seller_profile
+        .record_settlement(amount_to_pay, state.bid_count)?;
+    emit!(AuctionSettled {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        winner: state.highest_bidder_account,
+        amount: amount_to_pay,
+    });
+
+    if state.bundle_size > 0 {
+        emit!(BundleSaleCompleted {
+            auction: state.key(),
+            auction_type: state.auction_type,
+            items: state.bundle().to_vec(),
+            winner: state.highest_bidder_account,
+            total_amount: amount_to_pay,
+        });
+    }
+
+    // the losing bids of an all-pay auction or a raffle are paid out with the winning one
+    if state.pays_losing_bids() {
+        collect_losing_bids(
+            state,
+            &ctx.accounts.config,
+            &ctx.accounts.beneficiary,
+            &ctx.accounts.fee_recipient,
+            ctx.remaining_accounts,
+        )?;
+    }
+    pay_bid_fees(state, &ctx.accounts.beneficiary)?;
+    if state.auction_type == AuctionType::Perpetual {
+        start_next_round(
+            state,
+            &ctx.accounts.user_bid,
+            &ctx.accounts.bid_escrow,
+            &mut ctx.accounts.leaderboard,
+            amount_to_pay,
+            clock.unix_timestamp,
+            ctx.remaining_accounts,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Rejects the settlement of a cancelled or paused auction, or of an auction that
+/// already settled, whether the seller was paid or the reserve was not met
+pub(crate) fn check_settleable(state: &State) -> Result<()> {
+    if state.cancelled {
+        return err!(AuctionError::AuctionCancelled);
+    }
+    // bidders may get the paused time back when bidding resumes
+    if state.paused {
+        return err!(AuctionError::AuctionPaused);
+    }
+    if state.seller_payed {
+        return err!(AuctionError::AlreadyClaimedPrize);
+    }
+    if state.reserve_not_met {
+        return err!(AuctionError::ReserveNotMet);
+    }
+    Ok(())
+}
+
+/// Counts the auction in the profile of its winner
+pub(crate) fn record_win(winner: Pubkey, profile: &mut BidderProfile) -> Result<()> {
+    profile.record_win()?;
+    emit!(ProfileUpdated::new(winner, profile));
+    Ok(())
+}
+
+/// Starts the next round of a perpetual auction once the winner paid. The winner's user
+/// bid and escrow are closed, their rent goes to the first remaining account, the rent
+/// payer of the user bid. Losing bids follow as `(bidder, user_bid, bid_escrow,
+/// rent_payer)` remaining accounts, they go back to their bidders before their PDAs are
+/// closed. The leaderboard is cleared, the next round ranks its own bids
+fn start_next_round<'info>(
+    state: &mut Account<'info, State>,
+    winner_bid: &Account<'info, UserBid>,
+    winner_escrow: &Account<'info, BidEscrow>,
+    leaderboard: &mut Leaderboard,
+    amount: u64,
+    now: i64,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let (winner_rent_payer, losing_escrows) = match remaining_accounts {
+        [rent_payer, losing_escrows @ ..] if rent_payer.key() == winner_bid.rent_payer => {
+            (rent_payer, losing_escrows)
+        }
+        _ => return err!(AuctionError::InvalidRentPayer),
+    };
+    winner_escrow.close(winner_rent_payer.clone())?;
+    winner_bid.close(winner_rent_payer.clone())?;
+    let mut closed: u32 = 1;
+
+    for accounts in losing_escrows.chunks(4) {
+        let (bidder, user_bid, escrow, rent_payer) = match accounts {
+            [bidder, user_bid, escrow, rent_payer] => (bidder, user_bid, escrow, rent_payer),
+            _ => return err!(AuctionError::InvalidEscrow),
+        };
+        if bidder.key() == state.highest_bidder_account {
+            return err!(AuctionError::InvalidEscrow);
+        }
+        let user_bid = load_user_bid(state.key(), bidder, user_bid)?;
+        let bid_escrow = load_bid_escrow(state.key(), bidder, escrow)?;
+        if rent_payer.key() != user_bid.rent_payer {
+            return err!(AuctionError::InvalidRentPayer);
+        }
+
+        let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+        let refund = escrow.lamports().saturating_sub(rent_floor);
+        if refund > 0 {
+            transfer_from_escrow(escrow, bidder, refund)?;
+        }
+        bid_escrow.close(rent_payer.clone())?;
+        user_bid.close(rent_payer.clone())?;
+        closed = closed
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    }
+
+    let winner = state.highest_bidder_account;
+    state.round = state
+        .round
+        .checked_add(1)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    state.deadline = now
+        .checked_add(state.auction_duration)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    state.start_time = now;
+    state.seller_payed = false;
+    state.settled_at = 0;
+    state.bid_count = state.bid_count.saturating_sub(closed);
+    state.highest_bidder_account = Pubkey::default();
+    state.highest_bidder_bump = 0;
+    state.second_highest_bid_amount = 0;
+    state.highest_proxy_max = 0;
+    state.random_end_slot = 0;
+    leaderboard.entries = [LeaderboardEntry::default(); LEADERBOARD_SIZE];
+
+    emit!(AuctionRoundCompleted {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        round: state.round,
+        winner,
+        amount,
+        next_deadline: state.deadline,
+    });
+
+    Ok(())
+}
+
+pub fn collect_bids<'info>(ctx: Context<'_, '_, '_, 'info, CollectBids<'info>>) -> Result<()> {
+    let state = &ctx.accounts.state;
+
+    if !state.pays_losing_bids() {
+        return err!(AuctionError::NotAllPay);
+    }
+    if !state.seller_payed {
+        return err!(AuctionError::UnclaimedPrize);
+    }
+
+    collect_losing_bids(
+        state,
+        &ctx.accounts.config,
+        &ctx.accounts.beneficiary,
+        &ctx.accounts.fee_recipient,
+        ctx.remaining_accounts,
+    )
+}
+
+/// Pays the losing bids of an all-pay auction, passed as `(bidder, user_bid, bid_escrow)`
+/// remaining accounts, to the beneficiary less the platform fee. The loyalty discount is
+/// only granted to the winner. The escrows keep their rent, which their bidders get back
+/// with `refund`
+fn collect_losing_bids<'info>(
+    state: &Account<'info, State>,
+    config: &Config,
+    beneficiary: &AccountInfo<'info>,
+    fee_recipient: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let mut bids: u32 = 0;
+    let mut amount: u64 = 0;
+    for accounts in remaining_accounts.chunks(3) {
+        let (bidder, user_bid, escrow) = match accounts {
+            [bidder, user_bid, escrow] => (bidder, user_bid, escrow),
+            _ => return err!(AuctionError::InvalidEscrow),
+        };
+        let mut user_bid = load_user_bid(state.key(), bidder, user_bid)?;
+        load_bid_escrow(state.key(), bidder, escrow)?;
+        let bid = state.escrow_outstanding(bidder.key(), &user_bid);
+        if bid == 0 {
+            continue;
+        }
+
+        let platform_fee = config.platform_fee(bid, false)?;
+        pay_sale_from_escrow(escrow, beneficiary, fee_recipient, bid, platform_fee)?;
+        user_bid.amount = 0;
+        user_bid.exit(&crate::ID)?;
+
+        bids = bids
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        amount = amount
+            .checked_add(bid)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    }
+
+    emit!(AllPayBidsCollected {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        bids,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct EndAuction<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Seller, or the settlement authority it delegated to
+    #[account(mut, constraint = state.can_settle(authority.key()) @ AuctionError::Unauthorized)]
+    pub authority: Signer<'info>,
+    /// Ledger of the budget of a reverse auction
+    #[account(mut, seeds = [b"treasury", state.key().as_ref()], bump = treasury.bump)]
+    pub treasury: Box<Account<'info, Treasury>>,
+    /// Holds the budget of a reverse auction and signs its transfers
+    #[account(mut, seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    /// Receives the winning bid, or the rest of the budget of a reverse auction
+    #[account(
+        mut,
+        constraint = beneficiary.key() == state.beneficiary @ AuctionError::InvalidPayoutBeneficiary
+    )]
+    /// CHECK:
+    pub beneficiary: AccountInfo<'info>,
+    /// Highest bid, or the lowest offer of a reverse auction
+    #[account(mut, seeds = [b"user-bid", &state.winner().to_bytes(), state.key().as_ref()], bump = state.winner_bump())]
+    pub user_bid: Account<'info, UserBid>,
+    /// Escrow of the highest bid, the lowest offer of a reverse auction escrows nothing
+    #[account(mut, seeds = [b"escrow", &state.winner().to_bytes(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Highest bids of the auction, cleared when a perpetual auction starts its next round
+    #[account(mut, seeds = [b"leaderboard", state.key().as_ref()], bump)]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    /// Highest bidder, receives the part of a Vickrey winning bid above the price. In a
+    /// reverse auction the lowest bidder, which is paid its offer less the platform fee
+    /// out of the budget
+    #[account(
+        mut,
+        constraint = highest_bidder.key() == state.winner() @ AuctionError::InvalidHighestBidder
+    )]
+    /// CHECK:
+    pub highest_bidder: AccountInfo<'info>,
+    /// Lifetime statistics of the winner, created here for bids that predate profiles
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = BIDDER_PROFILE_SPACE,
+        seeds = [b"profile", state.winner().as_ref()],
+        bump,
+    )]
+    pub winner_profile: Box<Account<'info, BidderProfile>>,
+    /// Sale history of the seller, created here for auctions that predate profiles
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = SELLER_PROFILE_SPACE,
+        seeds = [b"seller-profile", state.initializer.as_ref()],
+        bump,
+    )]
+    pub seller_profile: Box<Account<'info, SellerProfile>>,
+    /// Final statistics of the auction, kept once the auction state is closed
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = AUCTION_SUMMARY_SPACE,
+        seeds = [b"summary", state.key().as_ref()],
+        bump,
+    )]
+    pub summary: Box<Account<'info, AuctionSummary>>,
+    /// Record of the sale for tax reporting, written with the first sale
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TAX_RECORD_SPACE,
+        seeds = [b"tax", state.key().as_ref()],
+        bump,
+    )]
+    pub tax_record: Box<Account<'info, TaxRecord>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+    /// Receives the platform fee taken from the winning bid, and from the losing bids of
+    /// an all-pay auction or a raffle
+    #[account(mut, address = config.fee_recipient @ AuctionError::InvalidFeeRecipient)]
+    /// CHECK:
+    pub fee_recipient: AccountInfo<'info>,
+    /// Loyalty status of the winner, empty unless it was granted
+    /// CHECK: only checked for being empty
+    #[account(seeds = [b"loyalty", state.winner().as_ref()], bump)]
+    pub winner_loyalty: UncheckedAccount<'info>,
+    /// Pyth SOL/USD price account, ignored when the auction has no price feed
+    #[account(
+        constraint = state.price_feed == Pubkey::default()
+            || price_feed.key() == state.price_feed @ AuctionError::InvalidPriceFeed
+    )]
+    /// CHECK:
+    pub price_feed: AccountInfo<'info>,
+    /// Program deciding whether a conditional auction may settle, ignored when the
+    /// auction has no condition
+    #[account(
+        constraint = state.condition_program == Pubkey::default()
+            || condition_program.key() == state.condition_program @ AuctionError::InvalidConditionProgram
+    )]
+    /// CHECK:
+    pub condition_program: AccountInfo<'info>,
+    /// Account the condition program reads, ignored when the auction has no condition
+    #[account(
+        constraint = state.condition_program == Pubkey::default()
+            || condition_account.key() == state.condition_account @ AuctionError::InvalidConditionProgram
+    )]
+    /// CHECK:
+    pub condition_account: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CollectBids<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Seller, or the settlement authority it delegated to
+    #[account(constraint = state.can_settle(authority.key()) @ AuctionError::Unauthorized)]
+    pub authority: Signer<'info>,
+    /// Receives the losing bids
+    #[account(
+        mut,
+        constraint = beneficiary.key() == state.beneficiary @ AuctionError::InvalidPayoutBeneficiary
+    )]
+    /// CHECK:
+    pub beneficiary: AccountInfo<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+    /// Receives the platform fee taken from the losing bids
+    #[account(mut, address = config.fee_recipient @ AuctionError::InvalidFeeRecipient)]
+    /// CHECK:
+    pub fee_recipient: AccountInfo<'info>,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::DeadlineChanged;
+use crate::state::{Config, State};
+use crate::utils::validate_auction_duration;
+
+pub fn extend_deadline(ctx: Context<ExtendDeadline>, new_duration: i64) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    // bidders joined under the current deadline, it can not move under their feet
+    if state.highest_bid_amount != 0 || state.bid_count != 0 {
+        return err!(AuctionError::CannotChangeAfterBids);
+    }
+    validate_auction_duration(
+        new_duration,
+        config.min_auction_duration,
+        config.max_auction_duration,
+    )?;
+
+    let new_deadline = state
+        .start_time
+        .checked_add(new_duration)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if new_deadline <= clock.unix_timestamp {
+        return err!(AuctionError::InvalidAuctionDuration);
+    }
+    if state.reveal_start != 0 && new_deadline <= state.reveal_start {
+        return err!(AuctionError::InvalidCommitDuration);
+    }
+    if state.has_final_round() && new_deadline <= state.final_deadline {
+        return err!(AuctionError::InvalidFinalRound);
+    }
+
+    let old_deadline = state.deadline;
+    state.deadline = new_deadline;
+    state.auction_duration = new_duration;
+
+    emit!(DeadlineChanged {
+        auction: state.key(),
+        old_deadline,
+        new_deadline,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExtendDeadline<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller
+    pub initializer: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+}
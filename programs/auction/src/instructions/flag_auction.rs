@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{FLAG_THRESHOLD, FRAUD_FLAG_SPACE};
+use crate::errors::AuctionError;
+use crate::events::{AuctionFlagged, BiddingPaused, FraudConfirmed};
+use crate::instructions::resume;
+use crate::state::{AuctionStatus, Config, FraudFlag, State};
+
+pub fn flag_auction(ctx: Context<FlagAuction>, reason: [u8; 128]) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    // once bidding closed a pause would only hold up the settlement
+    if state.status(clock.unix_timestamp) != AuctionStatus::Active {
+        return err!(AuctionError::Finished);
+    }
+    if state.is_settled() {
+        return err!(AuctionError::AlreadySettled);
+    }
+
+    let flag = &mut ctx.accounts.flag;
+    flag.flagger = ctx.accounts.flagger.key();
+    flag.reason = reason;
+    flag.flagged_at = clock.unix_timestamp;
+
+    state.flag_count = state
+        .flag_count
+        .checked_add(1)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    emit!(AuctionFlagged {
+        auction: state.key(),
+        flagger: flag.flagger,
+        flag_count: state.flag_count,
+    });
+
+    // enough flags halt bidding until the platform reviews them
+    if state.flag_count >= FLAG_THRESHOLD && !state.paused {
+        state.paused = true;
+        state.paused_at = clock.unix_timestamp;
+        emit!(BiddingPaused {
+            auction: state.key(),
+            paused_at: state.paused_at,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn dismiss_flag(ctx: Context<DismissFlag>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let was_flagged = state.flag_count >= FLAG_THRESHOLD;
+    state.flag_count = state.flag_count.saturating_sub(1);
+
+    // bidding resumes once the flags no longer reach the threshold, bidders get the
+    // time the flags took from them back
+    if was_flagged && state.flag_count < FLAG_THRESHOLD && state.paused {
+        resume(state, Clock::get()?.unix_timestamp, true)?;
+    }
+    Ok(())
+}
+
+pub fn confirm_fraud(ctx: Context<ConfirmFraud>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if state.is_settled() {
+        return err!(AuctionError::AlreadySettled);
+    }
+
+    // same as a cancellation by the seller, without compensation: the bids stay in
+    // their escrows and every bidder refunds in full right away
+    state.cancelled = true;
+    state.paused = false;
+    state.paused_at = 0;
+    state.deadline = clock.unix_timestamp;
+    state.settled_at = clock.unix_timestamp;
+
+    emit!(FraudConfirmed {
+        auction: state.key(),
+        flag_count: state.flag_count,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FlagAuction<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// A user can only flag an auction once
+    #[account(
+        init,
+        payer = flagger,
+        space = FRAUD_FLAG_SPACE,
+        seeds = [b"flag", flagger.key().as_ref(), state.key().as_ref()],
+        bump
+    )]
+    pub flag: Account<'info, FraudFlag>,
+    #[account(mut)]
+    pub flagger: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DismissFlag<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    #[account(
+        mut,
+        close = flagger,
+        seeds = [b"flag", flagger.key().as_ref(), state.key().as_ref()],
+        bump
+    )]
+    pub flag: Account<'info, FraudFlag>,
+    /// Paid the rent of the flag, it gets it back
+    #[account(mut)]
+    /// CHECK:
+    pub flagger: AccountInfo<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.platform_authority == platform_authority.key() @ AuctionError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub platform_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmFraud<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.platform_authority == platform_authority.key() @ AuctionError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub platform_authority: Signer<'info>,
+}
@@ -0,0 +1,266 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+
+use crate::constants::{BIDDER_PROFILE_SPACE, BID_ESCROW_SPACE, USER_BID_SPACE};
+use crate::errors::AuctionError;
+use crate::events::{FractionalSaleSettled, ProfileUpdated, SharesAllocated};
+use crate::instructions::{check_settleable, check_shill_bid};
+use crate::state::{AuctionStatus, BidEscrow, BidderProfile, Config, State, UserBid};
+use crate::utils::{deposit_lamports, load_bid_escrow, load_user_bid, pay_sale_from_escrow};
+
+/// Fractional bid rules, without any account or runtime access: the seller can not
+/// bid, bids are only accepted before the deadline and `shares` have to be left and
+/// paid at least their share price
+pub fn process_fractional_bid(
+    state: &mut State,
+    bidder: Pubkey,
+    amount: u64,
+    shares: u64,
+    timestamp: i64,
+) -> Result<()> {
+    if !state.is_fractional() {
+        return err!(AuctionError::NotFractionalAuction);
+    }
+    if bidder == state.initializer || bidder == state.authority {
+        return err!(AuctionError::SellerCannotBid);
+    }
+    if state.status(timestamp) != AuctionStatus::Active {
+        return err!(AuctionError::Finished);
+    }
+    state.allocate_shares(shares, amount)
+}
+
+pub fn fractional_bid(
+    ctx: Context<FractionalBid>,
+    amount: u64,
+    shares_requested: u64,
+) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if state.paused {
+        return err!(AuctionError::AuctionPaused);
+    }
+    check_shill_bid(
+        &ctx.accounts.sybil_check,
+        ctx.accounts.config.shill_lookback_secs,
+        clock.unix_timestamp,
+    )?;
+    process_fractional_bid(
+        state,
+        ctx.accounts.user.key(),
+        amount,
+        shares_requested,
+        clock.unix_timestamp,
+    )?;
+
+    // a bidder holds a single fractional bid, escrowed like any other bid
+    let user_bid = &mut ctx.accounts.user_bid;
+    user_bid.amount = amount;
+    user_bid.shares = shares_requested;
+    user_bid.rent_payer = ctx.accounts.user.key();
+    user_bid.last_bid_unix_timestamp = clock.unix_timestamp;
+    ctx.accounts.bid_escrow.bump = *ctx.bumps.get("bid_escrow").unwrap();
+    deposit_lamports(
+        &ctx.accounts.user,
+        ctx.accounts.bid_escrow.to_account_info(),
+        &ctx.accounts.system_program,
+        amount,
+    )?;
+
+    let profile = &mut ctx.accounts.profile;
+    profile.record_bid(amount)?;
+    emit!(ProfileUpdated::new(ctx.accounts.user.key(), profile));
+    emit!(SharesAllocated {
+        auction: state.key(),
+        bidder: ctx.accounts.user.key(),
+        shares: shares_requested,
+        amount,
+        shares_left: state.total_shares - state.allocated_shares,
+    });
+
+    Ok(())
+}
+
+/// Settles the fractional bids passed as `(bidder, user_bid, bid_escrow, share_account)`
+/// remaining accounts: their shares are minted to the share accounts and their payments
+/// go from their escrows to the beneficiary, less the platform fee. Settled bids are
+/// skipped, so the bids can be settled in several batches. The auction counts as paid
+/// once every allocated share was minted
+pub fn settle_fractional<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleFractional<'info>>,
+) -> Result<()> {
+    let state_info = ctx.accounts.state.to_account_info();
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if !state.is_fractional() {
+        return err!(AuctionError::NotFractionalAuction);
+    }
+    check_settleable(state)?;
+
+    // the state PDA is the mint authority of the shares
+    let index_seed = state.index_seed();
+    let state_seeds: &[&[u8]] = &[
+        b"state",
+        state.initializer.as_ref(),
+        &index_seed,
+        &[state.state_bump],
+    ];
+
+    let mut shares: u64 = 0;
+    let mut amount: u64 = 0;
+    for accounts in ctx.remaining_accounts.chunks(4) {
+        let (bidder, user_bid, escrow, share_account) = match accounts {
+            [bidder, user_bid, escrow, share_account] => (bidder, user_bid, escrow, share_account),
+            _ => return err!(AuctionError::InvalidEscrow),
+        };
+        let mut user_bid = load_user_bid(state.key(), bidder, user_bid)?;
+        load_bid_escrow(state.key(), bidder, escrow)?;
+        if user_bid.shares == 0 || user_bid.amount == 0 {
+            continue;
+        }
+        let token_account = Account::<TokenAccount>::try_from(share_account)?;
+        if token_account.mint != ctx.accounts.share_mint.key()
+            || token_account.owner != bidder.key()
+        {
+            return err!(AuctionError::InvalidShareAccount);
+        }
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    to: share_account.clone(),
+                    authority: state_info.clone(),
+                },
+                &[state_seeds],
+            ),
+            user_bid.shares,
+        )?;
+        let platform_fee = ctx.accounts.config.platform_fee(user_bid.amount, false)?;
+        pay_sale_from_escrow(
+            escrow,
+            &ctx.accounts.beneficiary,
+            &ctx.accounts.fee_recipient,
+            user_bid.amount,
+            platform_fee,
+        )?;
+
+        shares = shares
+            .checked_add(user_bid.shares)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        amount = amount
+            .checked_add(user_bid.amount)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        // the escrow only keeps its rent, which the bidder gets back with `refund`
+        user_bid.amount = 0;
+        user_bid.exit(&crate::ID)?;
+    }
+
+    state.minted_shares = state
+        .minted_shares
+        .checked_add(shares)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    state.winning_amount = state
+        .winning_amount
+        .checked_add(amount)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if state.minted_shares >= state.allocated_shares {
+        state.seller_payed = true;
+        state.settled_at = clock.unix_timestamp;
+    }
+
+    emit!(FractionalSaleSettled {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        shares,
+        amount,
+        minted_shares: state.minted_shares,
+        allocated_shares: state.allocated_shares,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FractionalBid<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder, it pays the rent of its user bid and of the share mint of the first bid
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Fractional bid, with the shares it was allocated
+    #[account(
+        init,
+        payer = user,
+        space = USER_BID_SPACE,
+        seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub user_bid: Account<'info, UserBid>,
+    /// Escrow of the fractional bid
+    #[account(
+        init,
+        payer = user,
+        space = BID_ESCROW_SPACE,
+        seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Mint of the shares of the item, created with the first fractional bid
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"share-mint", state.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = state,
+    )]
+    pub share_mint: Account<'info, Mint>,
+    /// Lifetime statistics of the bidder, created with its first bid
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = BIDDER_PROFILE_SPACE,
+        seeds = [b"profile", user.key().as_ref()],
+        bump,
+    )]
+    pub profile: Box<Account<'info, BidderProfile>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    /// Registration of the bidder as a wallet funded by the seller, empty unless the
+    /// platform authority registered it
+    /// CHECK: only read when it is not empty
+    #[account(seeds = [b"sybil", state.key().as_ref(), user.key().as_ref()], bump)]
+    pub sybil_check: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SettleFractional<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Seller, or the settlement authority it delegated to
+    #[account(constraint = state.can_settle(authority.key()) @ AuctionError::Unauthorized)]
+    pub authority: Signer<'info>,
+    /// Receives the payments of the fractional bids
+    #[account(
+        mut,
+        constraint = beneficiary.key() == state.beneficiary @ AuctionError::InvalidPayoutBeneficiary
+    )]
+    /// CHECK:
+    pub beneficiary: AccountInfo<'info>,
+    #[account(mut, seeds = [b"share-mint", state.key().as_ref()], bump)]
+    pub share_mint: Account<'info, Mint>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+    /// Receives the platform fee taken from the payment of every fractional bid
+    #[account(mut, address = config.fee_recipient @ AuctionError::InvalidFeeRecipient)]
+    /// CHECK:
+    pub fee_recipient: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+}
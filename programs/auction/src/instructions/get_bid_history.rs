@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::BidHistoryQueried;
+use crate::state::State;
+
+pub fn get_bid_history(ctx: Context<GetBidHistory>) -> Result<()> {
+    let state = &ctx.accounts.state;
+
+    emit!(BidHistoryQueried {
+        auction: state.key(),
+        bid_history_count: state.bid_history_count,
+        bids: state.recent_bids(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetBidHistory<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::{RemainingTime, State};
+
+pub fn get_remaining_time(ctx: Context<GetRemainingTime>) -> Result<RemainingTime> {
+    let deadline = ctx.accounts.state.deadline;
+    let now = Clock::get()?.unix_timestamp;
+
+    Ok(RemainingTime {
+        remaining_secs: deadline.saturating_sub(now).max(0),
+        deadline,
+        now,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetRemainingTime<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+}
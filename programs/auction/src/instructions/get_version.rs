@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::CURRENT_STATE_VERSION;
+use crate::state::ProgramVersion;
+
+pub fn get_version(_ctx: Context<GetVersion>) -> Result<ProgramVersion> {
+    let features = [
+        ("client", cfg!(feature = "client")),
+        ("cpi", cfg!(feature = "cpi")),
+        ("no-balance-check", cfg!(feature = "no-balance-check")),
+        ("no-entrypoint", cfg!(feature = "no-entrypoint")),
+        ("no-idl", cfg!(feature = "no-idl")),
+        ("no-log-ix-name", cfg!(feature = "no-log-ix-name")),
+        ("serde", cfg!(feature = "serde")),
+    ];
+
+    Ok(ProgramVersion {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        state_schema: CURRENT_STATE_VERSION,
+        features: features
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name.to_string())
+            .collect(),
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetVersion {}
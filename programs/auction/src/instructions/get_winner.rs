@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::{AuctionType, State, WinnerInfo};
+
+pub fn get_winner(ctx: Context<GetWinner>) -> Result<WinnerInfo> {
+    let state = &ctx.accounts.state;
+
+    let amount = if state.seller_payed {
+        state.winning_amount
+    } else if state.auction_type == AuctionType::Reverse {
+        state.lowest_bid_amount
+    } else {
+        state.price_to_pay(state.highest_bid_amount)
+    };
+
+    Ok(WinnerInfo {
+        winner: state.winner(),
+        amount,
+        settled: state.seller_payed,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetWinner<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::LOYALTY_DISCOUNT_SPACE;
+use crate::errors::AuctionError;
+use crate::events::LoyaltyStatusGranted;
+use crate::state::{BidderProfile, LoyaltyDiscount};
+
+pub fn grant_loyalty_status(ctx: Context<GrantLoyaltyStatus>) -> Result<()> {
+    let profile = &ctx.accounts.profile;
+    if !profile.is_loyal() {
+        return err!(AuctionError::LoyaltyThresholdNotReached);
+    }
+
+    let loyalty_discount = &mut ctx.accounts.loyalty_discount;
+    loyalty_discount.bidder = ctx.accounts.user.key();
+    loyalty_discount.auctions_participated = profile.total_auctions_participated;
+    loyalty_discount.granted_at = Clock::get()?.unix_timestamp;
+
+    emit!(LoyaltyStatusGranted {
+        bidder: loyalty_discount.bidder,
+        auctions_participated: loyalty_discount.auctions_participated,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GrantLoyaltyStatus<'info> {
+    /// Bidder granted the status
+    /// CHECK: only used as a seed
+    pub user: UncheckedAccount<'info>,
+    #[account(seeds = [b"profile", user.key().as_ref()], bump)]
+    pub profile: Account<'info, BidderProfile>,
+    #[account(
+        init,
+        payer = payer,
+        space = LOYALTY_DISCOUNT_SPACE,
+        seeds = [b"loyalty", user.key().as_ref()],
+        bump
+    )]
+    pub loyalty_discount: Account<'info, LoyaltyDiscount>,
+    /// Pays the rent of the loyalty status, anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,268 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    CURRENT_STATE_VERSION, LEADERBOARD_SPACE, REGISTRY_PAGE_SPACE, REGISTRY_SPACE,
+    SELLER_COUNTER_SPACE, SELLER_PROFILE_SPACE, TREASURY_SPACE,
+};
+use crate::errors::AuctionError;
+use crate::events::AuctionInitialized;
+use crate::state::{
+    auction_index_seed, AuctionConfig, AuctionType, Config, Leaderboard, Registry, RegistryPage,
+    SellerCounter, SellerProfile, State, Treasury,
+};
+use crate::utils::{deposit_lamports, treasury_rent_floor};
+
+pub fn initialize(
+    ctx: Context<Initialize>,
+    auction_index: u32,
+    auction_config: AuctionConfig,
+) -> Result<()> {
+    ctx.accounts.seller_counter.reserve(auction_index, 1)?;
+    ctx.accounts.seller_profile.record_auctions(1)?;
+    let state = &mut ctx.accounts.state;
+    configure_auction(
+        state,
+        auction_config,
+        &ctx.accounts.config,
+        ctx.accounts.initializer.key(),
+    )?;
+    let escrowed = state.treasury_outstanding()?;
+    state.auction_index = auction_index;
+    state.sequence = ctx
+        .accounts
+        .registry
+        .register(&mut ctx.accounts.registry_page, state.key())?;
+    state.state_bump = *ctx.bumps.get("state").unwrap();
+    state.treasury_bump = *ctx.bumps.get("treasury").unwrap();
+    state.treasury_authority_bump = *ctx.bumps.get("treasury_authority").unwrap();
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.bump = state.treasury_bump;
+
+    // escrow the budget of a reverse auction in the treasury authority, on top of its rent
+    let treasury_authority = ctx.accounts.treasury_authority.to_account_info();
+    let deposit = treasury_rent_floor()?
+        .saturating_sub(treasury_authority.lamports())
+        .checked_add(escrowed)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if deposit > 0 {
+        deposit_lamports(
+            &ctx.accounts.initializer,
+            treasury_authority,
+            &ctx.accounts.system_program,
+            deposit,
+        )?;
+    }
+    treasury.total_collected = escrowed;
+
+    state.seller_bond_bump = *ctx.bumps.get("seller_bond").unwrap();
+    post_seller_bond(
+        &ctx.accounts.initializer,
+        ctx.accounts.seller_bond.to_account_info(),
+        &ctx.accounts.system_program,
+        state.seller_bond,
+    )?;
+
+    emit!(AuctionInitialized {
+        auction: state.key(),
+        initializer: state.initializer,
+        deadline: state.deadline,
+        auction_type: state.auction_type,
+        title: state.title.clone(),
+        item_hash: state.item_hash,
+        uri: state.uri.clone(),
+    });
+
+    Ok(())
+}
+
+/// Funds the seller bond PDA with `bond` on top of its rent, apart from the treasury so
+/// the bond is never mixed with the lamports of the bidders. It stays empty without a
+/// bond
+pub(crate) fn post_seller_bond<'info>(
+    initializer: &Signer<'info>,
+    seller_bond: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    bond: u64,
+) -> Result<()> {
+    if bond == 0 {
+        return Ok(());
+    }
+    let deposit = treasury_rent_floor()?
+        .saturating_sub(seller_bond.lamports())
+        .checked_add(bond)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    deposit_lamports(initializer, seller_bond, system_program, deposit)
+}
+
+/// Validates `auction_config` against the program config and writes it to a new
+/// auction state, its bumps are left to the caller
+pub(crate) fn configure_auction(
+    state: &mut State,
+    auction_config: AuctionConfig,
+    config: &Config,
+    initializer: Pubkey,
+) -> Result<()> {
+    // Get the clock sysvar via syscall
+    let clock = Clock::get()?;
+    auction_config.validate(
+        config.min_auction_duration,
+        config.max_auction_duration,
+        clock.unix_timestamp,
+    )?;
+
+    state.deadline = clock
+        .unix_timestamp
+        .checked_add(auction_config.auction_duration)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    state.version = CURRENT_STATE_VERSION;
+    state.initializer = initializer;
+    state.authority = initializer;
+    state.beneficiary = initializer;
+    state.reserve_price = auction_config.reserve_price;
+    state.reserve_price_usd_cents = auction_config.reserve_price_usd_cents;
+    state.price_feed = auction_config.price_feed;
+    state.seller_bond = auction_config.seller_bond;
+    state.dispute_window = auction_config.dispute_window;
+    state.refund_window = auction_config.refund_window;
+    state.seller_claim_window = auction_config.seller_claim_window;
+    state.commit_deposit = auction_config.commit_deposit;
+    state.milestone_count = auction_config.milestone_count;
+    state.condition_program = auction_config.condition_program;
+    state.condition_account = auction_config.condition_account;
+    state.candle_window = auction_config.candle_window;
+    state.total_shares = auction_config.total_shares;
+    state.quantity = auction_config.quantity;
+    state.pay_as_bid = auction_config.pay_as_bid;
+    state.bid_mint = auction_config.bid_mint;
+    state.prize_mint = auction_config.prize_mint;
+    state.prize_amount = auction_config.prize_amount;
+    state.round_duration_secs = auction_config.round_duration_secs;
+    state.tick = auction_config.tick;
+    state.bid_fee = auction_config.bid_fee;
+    state.extension_secs = auction_config.extension_secs;
+    state.unclaimed_beneficiary = auction_config.unclaimed_beneficiary;
+    state.cancel_compensation_bps = auction_config.cancel_compensation_bps;
+    state.withdrawal_penalty_bps = auction_config.withdrawal_penalty_bps;
+    state.auction_type = auction_config.auction_type;
+    state.start_time = clock.unix_timestamp;
+    state.auction_duration = auction_config.auction_duration;
+    state.start_price = auction_config.start_price;
+    state.price_step_secs = auction_config.price_step_secs;
+    state.min_bid_increment = auction_config.min_bid_increment;
+    state.max_acceptable_price = auction_config.max_acceptable_price;
+    if state.auction_type == AuctionType::Reverse {
+        state.budget = auction_config.max_acceptable_price;
+    }
+    // a multi-unit Dutch auction sells its units one at a time
+    if state.auction_type == AuctionType::Dutch && state.is_multi_unit() {
+        state.units_remaining = auction_config.quantity;
+    }
+    state.title = auction_config.title;
+    state.item_hash = auction_config.item_hash;
+    state.uri = auction_config.uri;
+    for item in auction_config.bundle_items {
+        state.add_bundle_item(item)?;
+    }
+    // without a commit phase the reveal phase never starts, bids are open
+    if auction_config.commit_duration > 0 {
+        state.reveal_start = clock
+            .unix_timestamp
+            .checked_add(auction_config.commit_duration)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    }
+    // the final round starts once the open phase is over
+    if auction_config.finalist_count > 0 {
+        state.finalist_count = auction_config.finalist_count;
+        state.open_deadline = clock
+            .unix_timestamp
+            .checked_add(auction_config.open_duration)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        state.final_deadline = state
+            .open_deadline
+            .checked_add(auction_config.final_commit_duration)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_index: u32, auction_config: AuctionConfig)]
+pub struct Initialize<'info> {
+    /// State of our auction program (up to you), sized for its metadata
+    #[account(
+        init,
+        payer = initializer,
+        space = State::space(&auction_config.title, &auction_config.uri),
+        seeds = [b"state", initializer.key().as_ref(), auction_index_seed(auction_index).as_ref()],
+        bump
+    )]
+    pub state: Account<'info, State>,
+    /// Seller
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    /// Next auction index of the seller, created with its first auction
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        space = SELLER_COUNTER_SPACE,
+        seeds = [b"seller-counter", initializer.key().as_ref()],
+        bump
+    )]
+    pub seller_counter: Account<'info, SellerCounter>,
+    /// Sale history of the seller, created with its first auction
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        space = SELLER_PROFILE_SPACE,
+        seeds = [b"seller-profile", initializer.key().as_ref()],
+        bump
+    )]
+    pub seller_profile: Box<Account<'info, SellerProfile>>,
+    /// Ledger of the treasury, bids are escrowed in their bid escrow PDAs
+    #[account(
+        init,
+        payer = initializer,
+        space = TREASURY_SPACE,
+        seeds = [b"treasury", state.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    /// Holds the treasury lamports and signs their transfers, funded with its rent here
+    #[account(mut, seeds = [b"treasury-auth", state.key().as_ref()], bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    /// Holds the seller bond apart from the treasury, funded only with a bond
+    #[account(mut, seeds = [b"bond", state.key().as_ref()], bump)]
+    pub seller_bond: SystemAccount<'info>,
+    /// Highest bids of the auction, empty until the first bid
+    #[account(
+        init,
+        payer = initializer,
+        space = LEADERBOARD_SPACE,
+        seeds = [b"leaderboard", state.key().as_ref()],
+        bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    /// Program-wide count of the auctions, created with the first auction
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        space = REGISTRY_SPACE,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+    /// Registry page the new auction is listed on, created with its first auction
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        space = REGISTRY_PAGE_SPACE,
+        seeds = [b"registry-page", registry.next_page_seed().as_ref()],
+        bump
+    )]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    BID_COOLDOWN_SECONDS, CONFIG_SPACE, LOYALTY_DISCOUNT_BPS, SHILL_LOOKBACK_SECS,
+};
+use crate::errors::AuctionError;
+use crate::state::Config;
+
+pub fn initialize_config(
+    ctx: Context<InitializeConfig>,
+    max_auction_duration: i64,
+    min_auction_duration: i64,
+    platform_fee_bps: u16,
+    fee_recipient: Pubkey,
+    min_bid_amount: u64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.authority = ctx.accounts.authority.key();
+    config.max_auction_duration = max_auction_duration;
+    config.min_auction_duration = min_auction_duration;
+    config.platform_fee_bps = platform_fee_bps;
+    config.fee_recipient = fee_recipient;
+    config.min_bid_amount = min_bid_amount;
+    config.bid_cooldown_seconds = BID_COOLDOWN_SECONDS;
+    config.platform_authority = ctx.accounts.authority.key();
+    config.shill_lookback_secs = SHILL_LOOKBACK_SECS;
+    config.loyalty_discount_bps = LOYALTY_DISCOUNT_BPS;
+
+    config.validate()
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = CONFIG_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    /// Program upgrade authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Auction>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ AuctionError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
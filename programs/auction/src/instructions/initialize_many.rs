@@ -0,0 +1,230 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{create_account, CreateAccount},
+};
+
+use crate::constants::{
+    LEADERBOARD_SPACE, REGISTRY_PAGE_SPACE, REGISTRY_SPACE, SELLER_COUNTER_SPACE,
+    SELLER_PROFILE_SPACE, TREASURY_SPACE,
+};
+use crate::errors::AuctionError;
+use crate::events::AuctionInitialized;
+use crate::instructions::{configure_auction, post_seller_bond};
+use crate::pda::PdaHelpers;
+use crate::state::{
+    auction_index_seed, AuctionConfig, Config, Leaderboard, Registry, RegistryPage, SellerCounter,
+    SellerProfile, State, Treasury,
+};
+use crate::utils::{deposit_lamports, treasury_rent_floor};
+
+pub fn initialize_many<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeMany<'info>>,
+    first_index: u32,
+    auctions: Vec<AuctionConfig>,
+) -> Result<()> {
+    if ctx.remaining_accounts.len() != auctions.len() * 5 {
+        return err!(AuctionError::AuctionAccountMismatch);
+    }
+    let count =
+        u32::try_from(auctions.len()).map_err(|_| error!(AuctionError::ArithmeticOverflow))?;
+    ctx.accounts.seller_counter.reserve(first_index, count)?;
+    ctx.accounts.seller_profile.record_auctions(count)?;
+
+    let initializer = &ctx.accounts.initializer;
+    for (offset, (auction_config, accounts)) in auctions
+        .into_iter()
+        .zip(ctx.remaining_accounts.chunks(5))
+        .enumerate()
+    {
+        let auction_index = first_index
+            .checked_add(offset as u32)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        let (
+            state_info,
+            treasury_info,
+            treasury_authority_info,
+            seller_bond_info,
+            leaderboard_info,
+        ) = (
+            &accounts[0],
+            &accounts[1],
+            &accounts[2],
+            &accounts[3],
+            &accounts[4],
+        );
+
+        let index_seed = auction_index_seed(auction_index);
+        let (state_key, state_bump) =
+            PdaHelpers::indexed_state_pda(&initializer.key(), auction_index);
+        let (treasury_key, treasury_bump) = PdaHelpers::treasury_pda(&state_key);
+        let (treasury_authority_key, treasury_authority_bump) =
+            PdaHelpers::treasury_authority_pda(&state_key);
+        let (seller_bond_key, seller_bond_bump) = PdaHelpers::seller_bond_pda(&state_key);
+        let (leaderboard_key, leaderboard_bump) = PdaHelpers::leaderboard_pda(&state_key);
+        if state_info.key() != state_key
+            || treasury_info.key() != treasury_key
+            || treasury_authority_info.key() != treasury_authority_key
+            || seller_bond_info.key() != seller_bond_key
+            || leaderboard_info.key() != leaderboard_key
+        {
+            return err!(AuctionError::AuctionAccountMismatch);
+        }
+
+        create_pda(
+            initializer,
+            state_info,
+            &ctx.accounts.system_program,
+            State::space(&auction_config.title, &auction_config.uri),
+            &[
+                b"state",
+                initializer.key().as_ref(),
+                &index_seed,
+                &[state_bump],
+            ],
+        )?;
+        create_pda(
+            initializer,
+            treasury_info,
+            &ctx.accounts.system_program,
+            TREASURY_SPACE,
+            &[b"treasury", state_key.as_ref(), &[treasury_bump]],
+        )?;
+        create_pda(
+            initializer,
+            leaderboard_info,
+            &ctx.accounts.system_program,
+            LEADERBOARD_SPACE,
+            &[b"leaderboard", state_key.as_ref(), &[leaderboard_bump]],
+        )?;
+
+        // the accounts were just created zeroed, `exit` writes their discriminators
+        let mut state = Account::<State>::try_from_unchecked(state_info)?;
+        configure_auction(
+            &mut state,
+            auction_config,
+            &ctx.accounts.config,
+            initializer.key(),
+        )?;
+        let escrowed = state.treasury_outstanding()?;
+        state.auction_index = auction_index;
+        state.sequence = ctx
+            .accounts
+            .registry
+            .register(&mut ctx.accounts.registry_page, state_key)?;
+        state.state_bump = state_bump;
+        state.treasury_bump = treasury_bump;
+        state.treasury_authority_bump = treasury_authority_bump;
+        state.seller_bond_bump = seller_bond_bump;
+
+        let mut treasury = Account::<Treasury>::try_from_unchecked(treasury_info)?;
+        treasury.bump = treasury_bump;
+
+        // escrow the budget of a reverse auction in the treasury authority, on top of
+        // its rent
+        let deposit = treasury_rent_floor()?
+            .saturating_sub(treasury_authority_info.lamports())
+            .checked_add(escrowed)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        if deposit > 0 {
+            deposit_lamports(
+                initializer,
+                treasury_authority_info.clone(),
+                &ctx.accounts.system_program,
+                deposit,
+            )?;
+        }
+        treasury.total_collected = escrowed;
+        post_seller_bond(
+            initializer,
+            seller_bond_info.clone(),
+            &ctx.accounts.system_program,
+            state.seller_bond,
+        )?;
+
+        emit!(AuctionInitialized {
+            auction: state_key,
+            initializer: state.initializer,
+            deadline: state.deadline,
+            auction_type: state.auction_type,
+            title: state.title.clone(),
+            item_hash: state.item_hash,
+            uri: state.uri.clone(),
+        });
+
+        state.exit(&crate::ID)?;
+        treasury.exit(&crate::ID)?;
+        Account::<Leaderboard>::try_from_unchecked(leaderboard_info)?.exit(&crate::ID)?;
+    }
+
+    Ok(())
+}
+
+/// Creates the program owned PDA `account` of `space` bytes, rent paid by `payer`
+fn create_pda<'info>(
+    payer: &Signer<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            CreateAccount {
+                from: payer.to_account_info(),
+                to: account.clone(),
+            },
+            &[seeds],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )
+}
+
+#[derive(Accounts)]
+pub struct InitializeMany<'info> {
+    /// Seller, it pays the rent and bond of every auction
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    /// Next auction index of the seller
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        space = SELLER_COUNTER_SPACE,
+        seeds = [b"seller-counter", initializer.key().as_ref()],
+        bump
+    )]
+    pub seller_counter: Account<'info, SellerCounter>,
+    /// Sale history of the seller
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        space = SELLER_PROFILE_SPACE,
+        seeds = [b"seller-profile", initializer.key().as_ref()],
+        bump
+    )]
+    pub seller_profile: Box<Account<'info, SellerProfile>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    /// Program-wide count of the auctions, created with the first auction
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        space = REGISTRY_SPACE,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+    /// Registry page the batch is listed on, a batch can not span two pages. Created
+    /// with its first auction
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        space = REGISTRY_PAGE_SPACE,
+        seeds = [b"registry-page", registry.next_page_seed().as_ref()],
+        bump
+    )]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+    pub system_program: Program<'info, System>,
+}
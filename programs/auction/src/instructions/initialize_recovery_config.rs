@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::RECOVERY_CONFIG_SPACE;
+use crate::errors::AuctionError;
+use crate::state::RecoveryConfig;
+
+pub fn initialize_recovery_config(
+    ctx: Context<InitializeRecoveryConfig>,
+    recovery_vault: Pubkey,
+    recovery_timeout: i64,
+) -> Result<()> {
+    if recovery_timeout <= 0 {
+        return err!(AuctionError::InvalidRecoveryTimeout);
+    }
+
+    let config = &mut ctx.accounts.recovery_config;
+    config.admin = ctx.accounts.authority.key();
+    config.recovery_vault = recovery_vault;
+    config.recovery_timeout = recovery_timeout;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeRecoveryConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = RECOVERY_CONFIG_SPACE,
+        seeds = [b"recovery-config"],
+        bump
+    )]
+    pub recovery_config: Account<'info, RecoveryConfig>,
+    /// Program upgrade authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Auction>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ AuctionError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,198 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{BID_ESCROW_SPACE, CURRENT_STATE_VERSION, LEADERBOARD_SPACE, STATE_SPACE};
+use crate::errors::AuctionError;
+use crate::events::{BidEscrowMigrated, MigrationCompleted};
+use crate::state::{BidEscrow, Leaderboard, State, Treasury, UserBid};
+use crate::utils::{
+    deposit_lamports, transfer_from_escrow, transfer_from_treasury, treasury_rent_floor,
+};
+
+pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+    let state_info = ctx.accounts.state.to_account_info();
+
+    // fields are only ever appended, so an older state reads as the current layout
+    // with its new fields zeroed
+    let mut data = state_info.try_borrow_data()?.to_vec();
+    data.resize(STATE_SPACE.max(data.len()), 0);
+    let mut state = State::try_deserialize(&mut data.as_slice())?;
+
+    // auctions created before ownership transfers are owned by their initializer
+    if state.authority == Pubkey::default() {
+        state.authority = state.initializer;
+    }
+    if state.authority != ctx.accounts.initializer.key() {
+        return err!(AuctionError::Unauthorized);
+    }
+    let expected_state = Pubkey::create_program_address(
+        &[
+            b"state",
+            state.initializer.as_ref(),
+            &state.index_seed(),
+            &[state.state_bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(AuctionError::Unauthorized))?;
+    if state_info.key() != expected_state {
+        return err!(AuctionError::Unauthorized);
+    }
+    if state.version >= CURRENT_STATE_VERSION {
+        return err!(AuctionError::StateAlreadyMigrated);
+    }
+
+    // the seller pays the rent of the grown account
+    if state_info.data_len() < STATE_SPACE {
+        let rent_floor = Rent::get()?.minimum_balance(STATE_SPACE);
+        let top_up = rent_floor.saturating_sub(state_info.lamports());
+        if top_up > 0 {
+            deposit_lamports(
+                &ctx.accounts.initializer,
+                state_info.clone(),
+                &ctx.accounts.system_program,
+                top_up,
+            )?;
+        }
+        state_info.realloc(STATE_SPACE, true)?;
+    }
+
+    // older treasuries held their lamports themselves, they move to the treasury
+    // authority, whose rent the seller pays
+    let treasury_authority = ctx.accounts.treasury_authority.to_account_info();
+    let top_up = treasury_rent_floor()?.saturating_sub(treasury_authority.lamports());
+    if top_up > 0 {
+        deposit_lamports(
+            &ctx.accounts.initializer,
+            treasury_authority.clone(),
+            &ctx.accounts.system_program,
+            top_up,
+        )?;
+    }
+    let treasury = ctx.accounts.treasury.to_account_info();
+    let treasury_rent = Rent::get()?.minimum_balance(treasury.data_len());
+    let held = treasury.lamports().saturating_sub(treasury_rent);
+    if held > 0 {
+        transfer_from_escrow(&treasury, &treasury_authority, held)?;
+    }
+    state.treasury_authority_bump = *ctx.bumps.get("treasury_authority").unwrap();
+
+    // the seller bond of older auctions sits in the treasury, it moves to the seller bond
+    // PDA, whose rent the seller pays as well
+    if state.seller_bond_bump == 0 {
+        let bond = state.bond_outstanding();
+        if bond > 0 {
+            let seller_bond = ctx.accounts.seller_bond.to_account_info();
+            let top_up = treasury_rent_floor()?.saturating_sub(seller_bond.lamports());
+            if top_up > 0 {
+                deposit_lamports(
+                    &ctx.accounts.initializer,
+                    seller_bond.clone(),
+                    &ctx.accounts.system_program,
+                    top_up,
+                )?;
+            }
+            transfer_from_treasury(
+                &mut ctx.accounts.treasury,
+                &treasury_authority,
+                &seller_bond,
+                &ctx.accounts.system_program.to_account_info(),
+                state_info.key(),
+                state.treasury_authority_bump,
+                bond,
+            )?;
+        }
+        state.seller_bond_bump = *ctx.bumps.get("seller_bond").unwrap();
+    }
+
+    let old_version = state.version;
+    state.version = CURRENT_STATE_VERSION;
+    state.try_serialize(&mut &mut state_info.try_borrow_mut_data()?[..])?;
+
+    emit!(MigrationCompleted {
+        auction: state_info.key(),
+        old_version,
+        new_version: CURRENT_STATE_VERSION,
+    });
+
+    Ok(())
+}
+
+/// Moves the bid escrowed by a user bid created before bid escrows into its own bid
+/// escrow PDA. Anyone may migrate a bid, the lamports can only go to its escrow
+pub fn migrate_bid_escrow(ctx: Context<MigrateBidEscrow>) -> Result<()> {
+    let user_bid = ctx.accounts.user_bid.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(user_bid.data_len());
+    let amount = user_bid.lamports().saturating_sub(rent_floor);
+
+    ctx.accounts.bid_escrow.bump = *ctx.bumps.get("bid_escrow").unwrap();
+    if amount > 0 {
+        transfer_from_escrow(
+            &user_bid,
+            &ctx.accounts.bid_escrow.to_account_info(),
+            amount,
+        )?;
+    }
+
+    emit!(BidEscrowMigrated {
+        auction: ctx.accounts.state.key(),
+        bidder: ctx.accounts.bidder.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    /// State of any version, it is deserialized by the instruction since older
+    /// layouts do not fit `Account<State>`
+    #[account(mut, owner = crate::ID)]
+    /// CHECK:
+    pub state: UncheckedAccount<'info>,
+    /// Seller
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    /// Highest bids of the auction, created for auctions that predate leaderboards
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        space = LEADERBOARD_SPACE,
+        seeds = [b"leaderboard", state.key().as_ref()],
+        bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    #[account(mut, seeds = [b"treasury", state.key().as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// Holds the treasury lamports from now on, funded with its rent here
+    #[account(mut, seeds = [b"treasury-auth", state.key().as_ref()], bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    /// Holds the seller bond from now on, funded with its rent when there is a bond
+    #[account(mut, seeds = [b"bond", state.key().as_ref()], bump)]
+    pub seller_bond: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateBidEscrow<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// CHECK: only used for the seeds
+    pub bidder: AccountInfo<'info>,
+    /// Bid that still holds its lamports
+    #[account(mut, seeds = [b"user-bid", bidder.key().as_ref(), state.key().as_ref()], bump)]
+    pub user_bid: Account<'info, UserBid>,
+    /// Escrow of the bid from now on. It may already exist when lamports were sent to
+    /// its address, which must not block the migration
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BID_ESCROW_SPACE,
+        seeds = [b"escrow", bidder.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Pays the rent of the bid escrow
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
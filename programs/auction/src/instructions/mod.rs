@@ -0,0 +1,109 @@
+pub mod accept_bid_early;
+pub mod add_bundle_item;
+pub mod best_and_final;
+pub mod bid;
+pub mod cancel_auction;
+pub mod cancel_with_compensation;
+pub mod candle_end;
+pub mod claim_bond;
+pub mod claim_penalties;
+pub mod claim_prize;
+pub mod claim_seller_bond;
+pub mod commit_bid;
+pub mod confirm_delivery;
+pub mod delegate;
+pub mod description;
+pub mod draw_winner;
+pub mod emergency_recover;
+pub mod end_auction;
+pub mod extend_deadline;
+pub mod flag_auction;
+pub mod fractional_bid;
+pub mod get_bid_history;
+pub mod get_remaining_time;
+pub mod get_version;
+pub mod get_winner;
+pub mod grant_loyalty_status;
+pub mod initialize;
+pub mod initialize_config;
+pub mod initialize_many;
+pub mod initialize_recovery_config;
+pub mod migrate_state;
+pub mod open_dispute;
+pub mod pause_bidding;
+pub mod preview_refund;
+pub mod prize_escrow;
+pub mod reclaim_bond;
+pub mod refund;
+pub mod register_related_account;
+pub mod release_milestone;
+pub mod reopen_auction;
+pub mod set_beneficiary;
+pub mod set_receive_address;
+pub mod set_settlement_authority;
+pub mod settle_multi_unit;
+pub mod sweep_unclaimed;
+pub mod token_auction;
+pub mod transfer_ownership;
+pub mod update_config;
+pub mod update_metadata;
+pub mod update_reserve;
+pub mod verify_auction;
+pub mod withdraw_bid;
+pub mod withdraw_outbid;
+pub mod yield_vault;
+
+pub use accept_bid_early::*;
+pub use add_bundle_item::*;
+pub use best_and_final::*;
+pub use bid::*;
+pub use cancel_auction::*;
+pub use cancel_with_compensation::*;
+pub use candle_end::*;
+pub use claim_bond::*;
+pub use claim_penalties::*;
+pub use claim_prize::*;
+pub use claim_seller_bond::*;
+pub use commit_bid::*;
+pub use confirm_delivery::*;
+pub use delegate::*;
+pub use description::*;
+pub use draw_winner::*;
+pub use emergency_recover::*;
+pub use end_auction::*;
+pub use extend_deadline::*;
+pub use flag_auction::*;
+pub use fractional_bid::*;
+pub use get_bid_history::*;
+pub use get_remaining_time::*;
+pub use get_version::*;
+pub use get_winner::*;
+pub use grant_loyalty_status::*;
+pub use initialize::*;
+pub use initialize_config::*;
+pub use initialize_many::*;
+pub use initialize_recovery_config::*;
+pub use migrate_state::*;
+pub use open_dispute::*;
+pub use pause_bidding::*;
+pub use preview_refund::*;
+pub use prize_escrow::*;
+pub use reclaim_bond::*;
+pub use refund::*;
+pub use register_related_account::*;
+pub use release_milestone::*;
+pub use reopen_auction::*;
+pub use set_beneficiary::*;
+pub use set_receive_address::*;
+pub use set_settlement_authority::*;
+pub use settle_multi_unit::*;
+pub use sweep_unclaimed::*;
+pub use token_auction::*;
+pub use transfer_ownership::*;
+pub use update_config::*;
+pub use update_metadata::*;
+pub use update_reserve::*;
+pub use verify_auction::*;
+pub use withdraw_bid::*;
+pub use withdraw_outbid::*;
+pub use yield_vault::*;
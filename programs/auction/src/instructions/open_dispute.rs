@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::{DisputeStatus, State};
+
+pub fn open_dispute(ctx: Context<WinnerAction>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if !state.seller_payed {
+        return err!(AuctionError::UnclaimedPrize);
+    }
+    if state.seller_bond == 0 {
+        return err!(AuctionError::NoSellerBond);
+    }
+    if state.dispute_status != DisputeStatus::Idle {
+        return err!(AuctionError::InvalidDisputeStatus);
+    }
+    let dispute_deadline = state
+        .settled_at
+        .checked_add(state.dispute_window)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if clock.unix_timestamp > dispute_deadline {
+        return err!(AuctionError::DisputeWindowClosed);
+    }
+
+    state.dispute_status = DisputeStatus::Open;
+    state.dispute_opened_at = clock.unix_timestamp;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WinnerAction<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Highest bidder
+    #[account(constraint = winner.key() == state.highest_bidder_account @ AuctionError::NotTheWinner)]
+    pub winner: Signer<'info>,
+}
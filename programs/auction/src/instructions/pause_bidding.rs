@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::FLAG_THRESHOLD;
+use crate::errors::AuctionError;
+use crate::events::{BiddingPaused, BiddingResumed};
+use crate::instructions::SellerAction;
+use crate::state::State;
+
+pub fn pause_bidding(ctx: Context<SellerAction>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if state.paused {
+        return err!(AuctionError::AuctionPaused);
+    }
+    state.paused = true;
+    state.paused_at = Clock::get()?.unix_timestamp;
+
+    emit!(BiddingPaused {
+        auction: state.key(),
+        paused_at: state.paused_at,
+    });
+
+    Ok(())
+}
+
+pub fn resume_bidding(ctx: Context<SellerAction>, extend_deadline: bool) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if !state.paused {
+        return err!(AuctionError::AuctionNotPaused);
+    }
+    if state.flag_count >= FLAG_THRESHOLD {
+        return err!(AuctionError::AuctionFlagged);
+    }
+
+    resume(state, Clock::get()?.unix_timestamp, extend_deadline)
+}
+
+/// Resumes the bidding of a paused auction at `now`, the pause is counted and the
+/// deadline pushed back by it when `extend_deadline` is set
+pub(crate) fn resume(state: &mut Account<State>, now: i64, extend_deadline: bool) -> Result<()> {
+    let paused_secs = now
+        .checked_sub(state.paused_at)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    state.total_paused_secs = state
+        .total_paused_secs
+        .checked_add(paused_secs)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if extend_deadline {
+        state.deadline = state
+            .deadline
+            .checked_add(paused_secs)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    }
+    state.paused = false;
+    state.paused_at = 0;
+
+    emit!(BiddingResumed {
+        auction: state.key(),
+        paused_secs,
+        deadline: state.deadline,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::instructions::refund_blocker;
+use crate::state::{BidEscrow, RefundPreview, State, UserBid};
+
+/// Runs the checks of `refund` on the bid of `user` without moving any lamport
+pub fn preview_refund(ctx: Context<PreviewRefund>) -> Result<RefundPreview> {
+    let user_bid = &ctx.accounts.user_bid;
+    let includes_rent = user_bid.rent_payer == ctx.accounts.user.key();
+
+    if let Some(error) = refund_blocker(&ctx.accounts.state, ctx.accounts.user.key(), user_bid) {
+        return Ok(RefundPreview {
+            refundable_lamports: 0,
+            includes_rent: false,
+            reason: Some(error.into()),
+        });
+    }
+
+    // same split as `refund`: the escrow above the rent floor goes to the bidder, the
+    // rent of both PDAs to whoever paid it
+    let escrow = ctx.accounts.bid_escrow.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+    let refundable_lamports = if includes_rent {
+        escrow
+            .lamports()
+            .checked_add(user_bid.to_account_info().lamports())
+            .ok_or(AuctionError::ArithmeticOverflow)?
+    } else {
+        escrow.lamports().saturating_sub(rent_floor)
+    };
+
+    Ok(RefundPreview {
+        refundable_lamports,
+        includes_rent,
+        reason: None,
+    })
+}
+
+#[derive(Accounts)]
+pub struct PreviewRefund<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// CHECK: only used to derive the user bid
+    pub user: AccountInfo<'info>,
+    #[account(seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump)]
+    pub user_bid: Account<'info, UserBid>,
+    #[account(seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+}
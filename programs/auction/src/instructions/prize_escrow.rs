@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use crate::errors::AuctionError;
+use crate::events::{PrizeDeposited, PrizeReclaimed};
+use crate::pda::PdaHelpers;
+use crate::state::State;
+use crate::utils::transfer_from_prize_vault;
+
+pub fn deposit_prize(ctx: Context<DepositPrize>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if state.prize_deposited {
+        return err!(AuctionError::PrizeAlreadyDeposited);
+    }
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.seller_token.to_account_info(),
+                to: ctx.accounts.prize_vault.to_account_info(),
+                authority: ctx.accounts.initializer.to_account_info(),
+            },
+        ),
+        state.prize_amount,
+    )?;
+    state.prize_deposited = true;
+    state.prize_authority_bump = *ctx.bumps.get("prize_authority").unwrap();
+
+    emit!(PrizeDeposited {
+        auction: state.key(),
+        mint: state.prize_mint,
+        amount: state.prize_amount,
+    });
+
+    Ok(())
+}
+
+pub fn reclaim_prize(ctx: Context<ReclaimPrize>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if !state.prize_reclaimable() {
+        return err!(AuctionError::PrizeNotReclaimable);
+    }
+
+    // an auction that took no bid waits for a new deposit before bidding opens again
+    state.prize_deposited = false;
+    state.prize_reclaimed = true;
+    transfer_from_prize_vault(
+        &ctx.accounts.prize_vault.to_account_info(),
+        &ctx.accounts.seller_token.to_account_info(),
+        &ctx.accounts.prize_authority.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        state.key(),
+        state.prize_authority_bump,
+        state.prize_amount,
+    )?;
+
+    emit!(PrizeReclaimed {
+        auction: state.key(),
+        seller: ctx.accounts.initializer.key(),
+        amount: state.prize_amount,
+    });
+
+    Ok(())
+}
+
+/// Sends the escrowed prize of `state` to the token account of `receiver`. The prize
+/// accounts are the `(prize_authority, prize_vault, receiver_token, token_program)`
+/// remaining accounts of `claim_prize`
+pub(crate) fn release_prize<'info>(
+    state: &Account<'info, State>,
+    receiver: Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if !state.prize_deposited {
+        return err!(AuctionError::PrizeNotDeposited);
+    }
+    let (prize_authority, prize_vault, receiver_token, token_program) = match remaining_accounts {
+        [prize_authority, prize_vault, receiver_token, token_program] => {
+            (prize_authority, prize_vault, receiver_token, token_program)
+        }
+        _ => return err!(AuctionError::InvalidPrizeTokenAccount),
+    };
+    let (authority_key, _) = PdaHelpers::prize_authority_pda(&state.key());
+    if prize_authority.key() != authority_key
+        || prize_vault.key() != PdaHelpers::prize_vault_address(&state.key(), &state.prize_mint)
+        || token_program.key() != token::ID
+    {
+        return err!(AuctionError::InvalidPrizeTokenAccount);
+    }
+    let destination = Account::<TokenAccount>::try_from(receiver_token)?;
+    if destination.mint != state.prize_mint || destination.owner != receiver {
+        return err!(AuctionError::InvalidPrizeTokenAccount);
+    }
+
+    transfer_from_prize_vault(
+        prize_vault,
+        receiver_token,
+        prize_authority,
+        token_program,
+        state.key(),
+        state.prize_authority_bump,
+        state.prize_amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct DepositPrize<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized, constraint = state.has_prize_escrow() @ AuctionError::NoPrizeEscrow)]
+    pub state: Box<Account<'info, State>>,
+    /// Seller, it pays the rent of the prize vault
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    #[account(address = state.prize_mint @ AuctionError::InvalidPrizeTokenAccount)]
+    pub prize_mint: Box<Account<'info, Mint>>,
+    /// Token account the prize is paid from
+    #[account(
+        mut,
+        constraint = seller_token.mint == state.prize_mint && seller_token.owner == initializer.key() @ AuctionError::InvalidPrizeTokenAccount
+    )]
+    pub seller_token: Box<Account<'info, TokenAccount>>,
+    /// Signs the transfers out of the prize vault
+    #[account(seeds = [b"prize", state.key().as_ref()], bump)]
+    pub prize_authority: SystemAccount<'info>,
+    /// Holds the prize until the winner claims it, the associated token account of the
+    /// prize authority
+    #[account(
+        init_if_needed,
+        payer = initializer,
+        associated_token::mint = prize_mint,
+        associated_token::authority = prize_authority,
+    )]
+    pub prize_vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimPrize<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized, constraint = state.has_prize_escrow() @ AuctionError::NoPrizeEscrow)]
+    pub state: Box<Account<'info, State>>,
+    /// Seller
+    pub initializer: Signer<'info>,
+    #[account(address = state.prize_mint @ AuctionError::InvalidPrizeTokenAccount)]
+    pub prize_mint: Box<Account<'info, Mint>>,
+    /// Signs the transfers out of the prize vault
+    #[account(seeds = [b"prize", state.key().as_ref()], bump = state.prize_authority_bump)]
+    pub prize_authority: SystemAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = prize_mint,
+        associated_token::authority = prize_authority,
+    )]
+    pub prize_vault: Box<Account<'info, TokenAccount>>,
+    /// Token account the prize goes back to
+    #[account(
+        mut,
+        constraint = seller_token.mint == state.prize_mint && seller_token.owner == initializer.key() @ AuctionError::InvalidPrizeTokenAccount
+    )]
+    pub seller_token: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::{DisputeStatus, State, Treasury};
+use crate::utils::{transfer_from_seller_bond, transfer_from_treasury};
+
+pub fn reclaim_bond(ctx: Context<ReclaimBond>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if !state.is_settled() {
+        return err!(AuctionError::UnclaimedPrize);
+    }
+    // the budget of a reverse auction cancelled before settlement goes back with the bond
+    if state.seller_bond == 0 && state.budget == 0 {
+        return err!(AuctionError::NoSellerBond);
+    }
+    // when the reserve was not met or the auction was cancelled nothing was sold,
+    // so there is nothing to dispute
+    match state.dispute_status {
+        DisputeStatus::Idle if state.reserve_not_met || state.cancelled => {}
+        DisputeStatus::Confirmed => {}
+        DisputeStatus::Idle => {
+            let dispute_deadline = state
+                .settled_at
+                .checked_add(state.dispute_window)
+                .ok_or(AuctionError::ArithmeticOverflow)?;
+            if clock.unix_timestamp <= dispute_deadline {
+                return err!(AuctionError::DisputeWindowOpen);
+            }
+        }
+        _ => return err!(AuctionError::InvalidDisputeStatus),
+    }
+
+    let bond = state.bond_outstanding();
+    if bond > 0 {
+        transfer_from_seller_bond(
+            &ctx.accounts.seller_bond.to_account_info(),
+            &ctx.accounts.initializer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            state.key(),
+            state.seller_bond_bump,
+            bond,
+        )?;
+    }
+    if state.budget > 0 {
+        transfer_from_treasury(
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.treasury_authority.to_account_info(),
+            &ctx.accounts.initializer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            state.key(),
+            state.treasury_authority_bump,
+            state.budget,
+        )?;
+    }
+
+    state.budget = 0;
+    state.dispute_status = DisputeStatus::Reclaimed;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReclaimBond<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    #[account(mut, seeds = [b"treasury", state.key().as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// Holds the treasury lamports and signs their transfers
+    #[account(mut, seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    /// Holds the seller bond and signs its transfers
+    #[account(mut, seeds = [b"bond", state.key().as_ref()], bump = state.seller_bond_bump)]
+    pub seller_bond: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
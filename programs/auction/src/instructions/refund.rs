@@ -0,0 +1,119 @@
+use anchor_lang::{prelude::*, AccountsClose};
+
+use crate::constants::BIDDER_PROFILE_SPACE;
+use crate::errors::AuctionError;
+use crate::events::{BidRefunded, ProfileUpdated};
+use crate::state::{BidEscrow, BidderProfile, State, UserBid};
+use crate::utils::transfer_from_escrow;
+
+pub fn refund(ctx: Context<Refund>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if state.is_token_denominated() {
+        return err!(AuctionError::TokenInstructionRequired);
+    }
+    if let Some(error) = refund_blocker(state, ctx.accounts.user.key(), &ctx.accounts.user_bid) {
+        return Err(error!(error));
+    }
+
+    ctx.accounts.user_bid.refunded = true;
+    // every bidder but the winners of a paid auction lost it, unless it was cancelled
+    let won = state.seller_payed
+        && (ctx.accounts.user.key() == state.winner() || ctx.accounts.user_bid.unit_won);
+    if !won && !state.cancelled {
+        let profile = &mut ctx.accounts.profile;
+        profile.record_loss()?;
+        emit!(ProfileUpdated::new(ctx.accounts.user.key(), profile));
+    }
+    // a reserve-not-met auction can be reopened once every bid is refunded, bids
+    // placed before the counter existed never made it count
+    state.bid_count = state.bid_count.saturating_sub(1);
+
+    // the bid goes back to the bidder. The winner's escrow only holds the rent once
+    // the seller was paid, a cancelled auction refunds every bid in full
+    let escrow = ctx.accounts.bid_escrow.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+    let amount_to_refund = escrow.lamports().saturating_sub(rent_floor);
+    if amount_to_refund > 0 {
+        transfer_from_escrow(
+            &escrow,
+            &ctx.accounts.user.to_account_info(),
+            amount_to_refund,
+        )?;
+    }
+
+    emit!(BidRefunded {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        bidder: ctx.accounts.user.key(),
+        amount: amount_to_refund,
+    });
+
+    // closing the escrow returns its rent to whoever paid it
+    ctx.accounts
+        .bid_escrow
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+    ctx.accounts
+        .user_bid
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+
+    Ok(())
+}
+
+/// Error `refund` fails with for the bid of `user`, `None` when it can be refunded
+pub(crate) fn refund_blocker(
+    state: &State,
+    user: Pubkey,
+    user_bid: &UserBid,
+) -> Option<AuctionError> {
+    if !state.is_settled() {
+        return Some(AuctionError::UnclaimedPrize);
+    }
+    if state.unclaimed_swept {
+        return Some(AuctionError::RefundWindowClosed);
+    }
+    if user_bid.refunded {
+        return Some(AuctionError::AlreadyRefunded);
+    }
+    // a paid all-pay auction or raffle only gives back the rent, once the seller
+    // collected the bid
+    if state.pays_losing_bids()
+        && state.seller_payed
+        && state.escrow_outstanding(user, user_bid) > 0
+    {
+        return Some(AuctionError::AllPayNoRefund);
+    }
+    // the winner's escrow holds the milestones the seller was not paid yet
+    if state.seller_payed && user == state.winner() && state.milestones_outstanding() > 0 {
+        return Some(AuctionError::MilestonesPending);
+    }
+    None
+}
+
+// validation struct
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    #[account(mut)]
+    /// CHECK:
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump, constraint = user_bid.deposit_share == 0 @ AuctionError::BidInVault)]
+    pub user_bid: Account<'info, UserBid>,
+    #[account(mut, seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Lifetime statistics of the bidder, created here for bids that predate profiles
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = BIDDER_PROFILE_SPACE,
+        seeds = [b"profile", user.key().as_ref()],
+        bump,
+    )]
+    pub profile: Box<Account<'info, BidderProfile>>,
+    /// Payer of the user bid rent, the bidder itself unless the bid was sponsored
+    #[account(mut, constraint = rent_payer.key() == user_bid.rent_payer @ AuctionError::InvalidRentPayer)]
+    /// CHECK:
+    pub rent_payer: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::SYBIL_CHECK_SPACE;
+use crate::errors::AuctionError;
+use crate::events::RelatedAccountRegistered;
+use crate::state::{Config, State, SybilCheck};
+
+pub fn register_related_account(
+    ctx: Context<RegisterRelatedAccount>,
+    related: Pubkey,
+    funded_at: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    let sybil_check = &mut ctx.accounts.sybil_check;
+    sybil_check.related = related;
+    sybil_check.funded_at = funded_at;
+    sybil_check.registered_at = clock.unix_timestamp;
+
+    emit!(RelatedAccountRegistered {
+        auction: ctx.accounts.state.key(),
+        related,
+        funded_at,
+    });
+
+    Ok(())
+}
+
+/// Rejects a bid when `sybil_check` registers the bidder as funded by the seller less
+/// than `lookback` seconds before `now`. Bidders that were never registered pass an
+/// empty account
+pub(crate) fn check_shill_bid(sybil_check: &AccountInfo, lookback: i64, now: i64) -> Result<()> {
+    if sybil_check.data_is_empty() {
+        return Ok(());
+    }
+    let sybil_check = Account::<SybilCheck>::try_from(sybil_check)?;
+    if now.saturating_sub(sybil_check.funded_at) <= lookback {
+        return err!(AuctionError::SuspectedShillBid);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(related: Pubkey)]
+pub struct RegisterRelatedAccount<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    #[account(
+        init,
+        payer = platform_authority,
+        space = SYBIL_CHECK_SPACE,
+        seeds = [b"sybil", state.key().as_ref(), related.as_ref()],
+        bump
+    )]
+    pub sybil_check: Account<'info, SybilCheck>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.platform_authority == platform_authority.key() @ AuctionError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub platform_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
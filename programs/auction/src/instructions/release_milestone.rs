@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::MilestoneReleased;
+use crate::state::{BidEscrow, Config, State};
+use crate::utils::pay_sale_from_escrow;
+
+pub fn release_milestone(ctx: Context<ReleaseMilestone>) -> Result<()> {
+    let state = &ctx.accounts.state;
+    if state.milestones_released >= state.milestone_count {
+        return err!(AuctionError::AllMilestonesReleased);
+    }
+    if state.milestones_released + 1 == state.milestone_count {
+        return err!(AuctionError::FinalMilestone);
+    }
+
+    let amount = state.milestone_amount();
+    let platform_fee = ctx
+        .accounts
+        .config
+        .platform_fee(amount, !ctx.accounts.winner_loyalty.data_is_empty())?;
+    pay_milestone(ctx, amount, platform_fee)
+}
+
+pub fn claim_final_milestone(ctx: Context<ReleaseMilestone>) -> Result<()> {
+    let state = &ctx.accounts.state;
+    if state.milestones_released >= state.milestone_count {
+        return err!(AuctionError::AllMilestonesReleased);
+    }
+    if state.milestones_released + 1 != state.milestone_count {
+        return err!(AuctionError::MilestonesPending);
+    }
+
+    // the last milestone also gets the rounding remainder of the others, and pays the
+    // rest of the fee charged on the winning amount
+    let amount = state.milestones_outstanding();
+    let config = &ctx.accounts.config;
+    let loyal = !ctx.accounts.winner_loyalty.data_is_empty();
+    let fee_charged = config
+        .platform_fee(state.milestone_amount(), loyal)?
+        .checked_mul(state.milestones_released as u64)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    let platform_fee = config
+        .platform_fee(state.winning_amount, loyal)?
+        .checked_sub(fee_charged)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    pay_milestone(ctx, amount, platform_fee)
+}
+
+/// Pays `amount` of the winner's escrow to the beneficiary for the next milestone, less
+/// its part of the platform fee
+fn pay_milestone(ctx: Context<ReleaseMilestone>, amount: u64, platform_fee: u64) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    if !state.seller_payed {
+        return err!(AuctionError::UnclaimedPrize);
+    }
+
+    pay_sale_from_escrow(
+        &ctx.accounts.bid_escrow.to_account_info(),
+        &ctx.accounts.beneficiary,
+        &ctx.accounts.fee_recipient,
+        amount,
+        platform_fee,
+    )?;
+    state.milestones_released += 1;
+
+    emit!(MilestoneReleased {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        milestone: state.milestones_released,
+        milestone_count: state.milestone_count,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReleaseMilestone<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Highest bidder, it approves the milestones
+    #[account(constraint = winner.key() == state.highest_bidder_account @ AuctionError::NotTheWinner)]
+    pub winner: Signer<'info>,
+    /// Escrow of the winning bid
+    #[account(mut, seeds = [b"escrow", winner.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Receives the milestones
+    #[account(
+        mut,
+        constraint = beneficiary.key() == state.beneficiary @ AuctionError::InvalidPayoutBeneficiary
+    )]
+    /// CHECK:
+    pub beneficiary: AccountInfo<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+    /// Receives the part of the platform fee charged on each milestone
+    #[account(mut, address = config.fee_recipient @ AuctionError::InvalidFeeRecipient)]
+    /// CHECK:
+    pub fee_recipient: AccountInfo<'info>,
+    /// Loyalty status of the winner, empty unless it was granted
+    /// CHECK: only checked for being empty
+    #[account(seeds = [b"loyalty", winner.key().as_ref()], bump)]
+    pub winner_loyalty: UncheckedAccount<'info>,
+}
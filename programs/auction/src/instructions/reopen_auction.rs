@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::AuctionReopened;
+use crate::state::{AuctionType, BidRecord, Config, State};
+use crate::utils::validate_auction_duration;
+
+pub fn reopen_auction(
+    ctx: Context<ReopenAuction>,
+    new_duration: i64,
+    new_reserve: u64,
+) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    // the leaderboard of a multi-unit auction or of an auction with a final round still
+    // ranks the bids of the first round
+    if !state.reserve_not_met || state.cancelled || state.is_multi_unit() || state.has_final_round()
+    {
+        return err!(AuctionError::CannotReopen);
+    }
+    // the user bid PDAs of the previous round must be closed before bidders come back
+    if state.bid_count != 0 {
+        return err!(AuctionError::AuctionHasBids);
+    }
+    validate_auction_duration(
+        new_duration,
+        config.min_auction_duration,
+        config.max_auction_duration,
+    )?;
+    if state.auction_type == AuctionType::Dutch && state.start_price < new_reserve {
+        return err!(AuctionError::InvalidStartPrice);
+    }
+
+    let deadline = clock
+        .unix_timestamp
+        .checked_add(new_duration)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    // a sealed-bid auction keeps the length of its commit phase
+    if state.reveal_start != 0 {
+        let commit_duration = state.reveal_start.saturating_sub(state.start_time);
+        state.reveal_start = clock
+            .unix_timestamp
+            .checked_add(commit_duration)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        if state.reveal_start >= deadline {
+            return err!(AuctionError::InvalidCommitDuration);
+        }
+    }
+
+    state.round = state
+        .round
+        .checked_add(1)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    state.deadline = deadline;
+    state.start_time = clock.unix_timestamp;
+    state.rounds_accepted = 0;
+    state.last_accepted_round = 0;
+    state.auction_duration = new_duration;
+    state.reserve_price = new_reserve;
+    state.reserve_not_met = false;
+    state.settled_at = 0;
+    state.unclaimed_swept = false;
+    state.highest_bid_amount = 0;
+    state.highest_bidder_account = Pubkey::default();
+    state.highest_bidder_bump = 0;
+    state.second_highest_bid_amount = 0;
+    state.highest_proxy_max = 0;
+    state.random_end_slot = 0;
+    state.candle_cutoff = 0;
+    state.raffle_randomness = [0u8; 32];
+    state.bid_history_count = 0;
+    state.bid_history = [BidRecord::default(); 10];
+
+    emit!(AuctionReopened {
+        auction: state.key(),
+        round: state.round,
+        deadline: state.deadline,
+        reserve_price: state.reserve_price,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReopenAuction<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller
+    pub initializer: Signer<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+}
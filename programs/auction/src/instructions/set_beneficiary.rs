@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::BeneficiaryChanged;
+use crate::state::{AuctionStatus, State};
+
+pub fn set_beneficiary(ctx: Context<SellerAction>, beneficiary: Pubkey) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if state.is_settled() {
+        return err!(AuctionError::AlreadySettled);
+    }
+
+    // bidders may have relied on the previous beneficiary once bidding closed
+    if state.status(clock.unix_timestamp) == AuctionStatus::Ended {
+        emit!(BeneficiaryChanged {
+            auction: state.key(),
+            previous_beneficiary: state.beneficiary,
+            beneficiary,
+            changed_at: clock.unix_timestamp,
+        });
+    }
+
+    state.beneficiary = beneficiary;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SellerAction<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.authority == initializer.key() @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Seller
+    pub initializer: Signer<'info>,
+}
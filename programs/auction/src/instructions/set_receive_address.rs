@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::ReceiveAddressChanged;
+use crate::instructions::WinnerAction;
+
+pub fn set_receive_address(ctx: Context<WinnerAction>, address: Pubkey) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if !state.seller_payed {
+        return err!(AuctionError::UnclaimedPrize);
+    }
+    // the prize already left for the previous address
+    if state.prize_claimed {
+        return err!(AuctionError::PrizeAlreadyClaimed);
+    }
+
+    state.receive_address = address;
+
+    emit!(ReceiveAddressChanged {
+        auction: state.key(),
+        winner: ctx.accounts.winner.key(),
+        address,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::instructions::SellerAction;
+
+pub fn set_settlement_authority(
+    ctx: Context<SellerAction>,
+    authority: Option<Pubkey>,
+) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if state.is_settled() {
+        return err!(AuctionError::AlreadySettled);
+    }
+
+    state.settlement_authority = authority;
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::MultiUnitSaleSettled;
+use crate::state::{Config, Leaderboard, State};
+use crate::utils::{load_bid_escrow, load_user_bid, pay_sale_from_escrow};
+
+pub fn settle_multi_unit<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleMultiUnit<'info>>,
+) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if !state.is_multi_unit() {
+        return err!(AuctionError::NotMultiUnitAuction);
+    }
+    if state.cancelled {
+        return err!(AuctionError::AuctionCancelled);
+    }
+    if state.paused {
+        return err!(AuctionError::AuctionPaused);
+    }
+    if state.is_settled() {
+        return err!(AuctionError::AlreadySettled);
+    }
+
+    let winners = ctx
+        .accounts
+        .leaderboard
+        .unit_winners(state.quantity, state.reserve_price)
+        .to_vec();
+    // no bid reached the reserve, every bid goes back to its bidder
+    let last_winner = match winners.last() {
+        Some(entry) => *entry,
+        None => {
+            state.reserve_not_met = true;
+            state.settled_at = clock.unix_timestamp;
+            emit!(MultiUnitSaleSettled {
+                auction: state.key(),
+                auction_type: state.auction_type,
+                winners: Vec::new(),
+                clearing_price: 0,
+                amount: 0,
+            });
+            return Ok(());
+        }
+    };
+    let clearing_price = last_winner.amount;
+
+    // each winner, passed as `(bidder, user_bid, bid_escrow)` remaining accounts, pays
+    // the clearing price, or its own bid, out of its escrow, which keeps the rest of the
+    // bid for `refund`. Every unit is charged the platform fee, without a loyalty discount
+    if ctx.remaining_accounts.len() != winners.len() * 3 {
+        return err!(AuctionError::InvalidEscrow);
+    }
+    let mut amount: u64 = 0;
+    for (entry, accounts) in winners.iter().zip(ctx.remaining_accounts.chunks(3)) {
+        let (bidder, user_bid, escrow) = match accounts {
+            [bidder, user_bid, escrow] if bidder.key() == entry.bidder => {
+                (bidder, user_bid, escrow)
+            }
+            _ => return err!(AuctionError::InvalidEscrow),
+        };
+        let mut user_bid = load_user_bid(state.key(), bidder, user_bid)?;
+        load_bid_escrow(state.key(), bidder, escrow)?;
+        let price = state.unit_price(entry.amount, clearing_price);
+        let platform_fee = ctx.accounts.config.platform_fee(price, false)?;
+        pay_sale_from_escrow(
+            escrow,
+            &ctx.accounts.beneficiary,
+            &ctx.accounts.fee_recipient,
+            price,
+            platform_fee,
+        )?;
+        user_bid.amount = user_bid
+            .amount
+            .checked_sub(price)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        user_bid.unit_won = true;
+        user_bid.exit(&crate::ID)?;
+        amount = amount
+            .checked_add(price)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    }
+
+    // the first winner stands for the auction wherever a single winner is expected
+    let state_key = state.key();
+    let (_, bump) = Pubkey::find_program_address(
+        &[b"user-bid", winners[0].bidder.as_ref(), state_key.as_ref()],
+        &crate::ID,
+    );
+    state.highest_bidder_account = winners[0].bidder;
+    state.highest_bidder_bump = bump;
+    state.highest_bid_amount = 0;
+    state.seller_payed = true;
+    state.winning_amount = amount;
+    state.settled_at = clock.unix_timestamp;
+
+    emit!(MultiUnitSaleSettled {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        winners: winners.iter().map(|entry| entry.bidder).collect(),
+        clearing_price,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleMultiUnit<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Seller, or the settlement authority it delegated to
+    #[account(constraint = state.can_settle(authority.key()) @ AuctionError::Unauthorized)]
+    pub authority: Signer<'info>,
+    /// Receives the price of every unit sold
+    #[account(
+        mut,
+        constraint = beneficiary.key() == state.beneficiary @ AuctionError::InvalidPayoutBeneficiary
+    )]
+    /// CHECK:
+    pub beneficiary: AccountInfo<'info>,
+    /// Highest bids of the auction, its first entries win the units
+    #[account(seeds = [b"leaderboard", state.key().as_ref()], bump)]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+    /// Receives the platform fee taken from the price of every unit
+    #[account(mut, address = config.fee_recipient @ AuctionError::InvalidFeeRecipient)]
+    /// CHECK:
+    pub fee_recipient: AccountInfo<'info>,
+}
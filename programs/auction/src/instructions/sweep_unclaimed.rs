@@ -0,0 +1,60 @@
+use anchor_lang::{prelude::*, AccountsClose};
+
+use crate::errors::AuctionError;
+use crate::state::{BidEscrow, State, UserBid};
+
+pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    if !state.is_settled() {
+        return err!(AuctionError::UnclaimedPrize);
+    }
+    // the tokens of an unclaimed bid stay in the vault, closing the escrow would lock them
+    if state.is_token_denominated() {
+        return err!(AuctionError::TokenInstructionRequired);
+    }
+    if state.refund_window <= 0 {
+        return err!(AuctionError::RefundWindowOpen);
+    }
+    let refund_deadline = state
+        .settled_at
+        .checked_add(state.refund_window)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if clock.unix_timestamp <= refund_deadline {
+        return err!(AuctionError::RefundWindowOpen);
+    }
+    if ctx.accounts.user.key() == state.winner() && state.milestones_outstanding() > 0 {
+        return err!(AuctionError::MilestonesPending);
+    }
+    // the escrow of an unclaimed bid is closed to the destination, so the bid and
+    // its rent are swept at once
+    ctx.accounts
+        .bid_escrow
+        .close(ctx.accounts.destination.to_account_info())?;
+    ctx.accounts
+        .user_bid
+        .close(ctx.accounts.destination.to_account_info())?;
+
+    state.unclaimed_swept = true;
+    state.bid_count = state.bid_count.saturating_sub(1);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SweepUnclaimed<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder who did not claim the refund, only used to derive the user bid PDA
+    /// CHECK:
+    pub user: AccountInfo<'info>,
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump, constraint = user_bid.deposit_share == 0 @ AuctionError::BidInVault)]
+    pub user_bid: Account<'info, UserBid>,
+    #[account(mut, seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Unclaimed beneficiary, or the initializer when there is none
+    #[account(mut, constraint = destination.key() == state.unclaimed_destination() @ AuctionError::InvalidBeneficiary)]
+    /// CHECK:
+    pub destination: AccountInfo<'info>,
+}
@@ -0,0 +1,374 @@
+use anchor_lang::{prelude::*, AccountsClose};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use crate::constants::{
+    AUCTION_SUMMARY_SPACE, BIDDER_PROFILE_SPACE, SELLER_PROFILE_SPACE, TAX_RECORD_SPACE,
+    USER_BID_SPACE,
+};
+use crate::errors::AuctionError;
+use crate::events::{AuctionSettled, BidRefunded, ProfileUpdated};
+use crate::instructions::{
+    check_bid_cooldown, check_settleable, check_shill_bid, record_win, refund_blocker, register_bid,
+};
+use crate::state::{
+    AuctionSummary, BidderProfile, Config, Leaderboard, SellerProfile, State, TaxRecord, UserBid,
+};
+use crate::utils::transfer_from_token_vault;
+
+/// Bid of `amount` base units of the bid mint. Only the raise over a previous bid of the
+/// bidder moves from its token account to the vault, `user_bid` keeps the whole bid
+pub fn bid_token(ctx: Context<BidToken>, amount: u64) -> Result<()> {
+    check_shill_bid(
+        &ctx.accounts.sybil_check,
+        ctx.accounts.config.shill_lookback_secs,
+        Clock::get()?.unix_timestamp,
+    )?;
+    check_bid_cooldown(
+        &ctx.accounts.user_bid,
+        ctx.accounts.config.bid_cooldown_seconds,
+    )?;
+
+    let already_escrowed = ctx.accounts.state.escrow_required(&ctx.accounts.user_bid);
+    // the minimum bid of the config is in lamports, it does not apply to token bids.
+    // Token auctions take no proxy bids, no proxy escrow is ever expected
+    register_bid(
+        &mut ctx.accounts.state,
+        ctx.accounts.user.key(),
+        &ctx.accounts.user,
+        &mut ctx.accounts.user_bid,
+        &mut ctx.accounts.leaderboard,
+        &mut ctx.accounts.profile,
+        *ctx.bumps.get("user_bid").unwrap(),
+        amount,
+        0,
+        0,
+        &[],
+    )?;
+
+    let escrowed = ctx
+        .accounts
+        .state
+        .escrow_required(&ctx.accounts.user_bid)
+        .saturating_sub(already_escrowed);
+    if escrowed > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.bidder_token.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            escrowed,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Settles a token auction: the winning bid moves from the vault to the token account
+/// of the beneficiary, unless it is below the reserve price
+pub fn end_auction_token(ctx: Context<EndAuctionToken>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let clock = Clock::get()?;
+
+    check_settleable(state)?;
+
+    // nothing is paid out and every bidder, including the highest one, gets refunded
+    let winning_bid = ctx.accounts.user_bid.amount;
+    if winning_bid < state.reserve_price {
+        state.reserve_not_met = true;
+        state.settled_at = clock.unix_timestamp;
+        ctx.accounts.summary.record(state, 0, clock.slot);
+        return Ok(());
+    }
+
+    // the platform fee is charged in base units of the bid mint
+    let platform_fee = ctx
+        .accounts
+        .config
+        .platform_fee(winning_bid, !ctx.accounts.winner_loyalty.data_is_empty())?;
+    let proceeds = winning_bid
+        .checked_sub(platform_fee)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if platform_fee > 0 {
+        transfer_from_token_vault(
+            &ctx.accounts.vault,
+            &ctx.accounts.fee_recipient_token,
+            &ctx.accounts.treasury_authority.to_account_info(),
+            &ctx.accounts.token_program,
+            state.key(),
+            state.treasury_authority_bump,
+            platform_fee,
+        )?;
+    }
+    if proceeds > 0 {
+        transfer_from_token_vault(
+            &ctx.accounts.vault,
+            &ctx.accounts.beneficiary_token,
+            &ctx.accounts.treasury_authority.to_account_info(),
+            &ctx.accounts.token_program,
+            state.key(),
+            state.treasury_authority_bump,
+            proceeds,
+        )?;
+    }
+
+    state.seller_payed = true;
+    state.winning_amount = winning_bid;
+    state.highest_bid_amount = 0;
+    state.settled_at = clock.unix_timestamp;
+    ctx.accounts.summary.record(state, winning_bid, clock.slot);
+    ctx.accounts.tax_record.record(
+        state.authority,
+        state.highest_bidder_account,
+        winning_bid,
+        platform_fee,
+        clock.slot,
+        clock.unix_timestamp,
+    );
+    record_win(
+        state.highest_bidder_account,
+        &mut ctx.accounts.winner_profile,
+    )?;
+    ctx.accounts
+        .seller_profile
+        .record_settlement(winning_bid, state.bid_count)?;
+
+    emit!(AuctionSettled {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        winner: state.highest_bidder_account,
+        amount: winning_bid,
+    });
+
+    Ok(())
+}
+
+/// Refunds the tokens a bid still holds in the vault to the token account of its bidder,
+/// under the rules of `refund`, and closes its user bid
+pub fn refund_token(ctx: Context<RefundToken>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let user = ctx.accounts.user.key();
+
+    if let Some(error) = refund_blocker(state, user, &ctx.accounts.user_bid) {
+        return Err(error!(error));
+    }
+
+    // the winning bid already left the vault, every other bid is still there in full
+    let amount = state.escrow_outstanding(user, &ctx.accounts.user_bid);
+    ctx.accounts.user_bid.refunded = true;
+    if !(state.seller_payed && user == state.winner()) && !state.cancelled {
+        let profile = &mut ctx.accounts.profile;
+        profile.record_loss()?;
+        emit!(ProfileUpdated::new(user, profile));
+    }
+    state.bid_count = state.bid_count.saturating_sub(1);
+
+    if amount > 0 {
+        transfer_from_token_vault(
+            &ctx.accounts.vault,
+            &ctx.accounts.bidder_token,
+            &ctx.accounts.treasury_authority.to_account_info(),
+            &ctx.accounts.token_program,
+            state.key(),
+            state.treasury_authority_bump,
+            amount,
+        )?;
+    }
+
+    emit!(BidRefunded {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        bidder: user,
+        amount,
+    });
+
+    // closing the escrow returns its rent to whoever paid it
+    ctx.accounts
+        .user_bid
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BidToken<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.is_token_denominated() @ AuctionError::NotTokenAuction)]
+    pub state: Box<Account<'info, State>>,
+    /// Bidder, it pays the rent of its user bid and of the vault when it bids first
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Bid of the bidder in base units of the bid mint, the tokens are in the vault. An
+    /// outbid bidder bids again with its existing user bid
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = USER_BID_SPACE,
+        seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()],
+        bump,
+    )]
+    pub user_bid: Box<Account<'info, UserBid>>,
+    /// Highest bids of the auction
+    #[account(mut, seeds = [b"leaderboard", state.key().as_ref()], bump)]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    /// Lifetime statistics of the bidder, created with its first bid
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = BIDDER_PROFILE_SPACE,
+        seeds = [b"profile", user.key().as_ref()],
+        bump,
+    )]
+    pub profile: Box<Account<'info, BidderProfile>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+    /// Registration of the bidder as a wallet funded by the seller, empty unless the
+    /// platform authority registered it
+    /// CHECK: only read when it is not empty
+    #[account(seeds = [b"sybil", state.key().as_ref(), user.key().as_ref()], bump)]
+    pub sybil_check: UncheckedAccount<'info>,
+    #[account(address = state.bid_mint @ AuctionError::InvalidBidTokenAccount)]
+    pub bid_mint: Box<Account<'info, Mint>>,
+    /// Token account the bid is paid from
+    #[account(
+        mut,
+        constraint = bidder_token.mint == state.bid_mint && bidder_token.owner == user.key() @ AuctionError::InvalidBidTokenAccount
+    )]
+    pub bidder_token: Box<Account<'info, TokenAccount>>,
+    /// Signs the transfers out of the vault
+    #[account(seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    /// Treasury of the auction, the associated token account of the treasury authority,
+    /// created with the first bid
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = bid_mint,
+        associated_token::authority = treasury_authority,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct EndAuctionToken<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.is_token_denominated() @ AuctionError::NotTokenAuction)]
+    pub state: Box<Account<'info, State>>,
+    /// Seller, or the settlement authority it delegated to
+    #[account(mut, constraint = state.can_settle(authority.key()) @ AuctionError::Unauthorized)]
+    pub authority: Signer<'info>,
+    /// Bid of the highest bidder
+    #[account(seeds = [b"user-bid", state.highest_bidder_account.as_ref(), state.key().as_ref()], bump = state.highest_bidder_bump)]
+    pub user_bid: Box<Account<'info, UserBid>>,
+    #[account(address = state.bid_mint @ AuctionError::InvalidBidTokenAccount)]
+    pub bid_mint: Box<Account<'info, Mint>>,
+    /// Signs the transfers out of the vault
+    #[account(seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = bid_mint,
+        associated_token::authority = treasury_authority,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    /// Token account of the beneficiary, receives the winning bid
+    #[account(
+        mut,
+        constraint = beneficiary_token.mint == state.bid_mint && beneficiary_token.owner == state.beneficiary @ AuctionError::InvalidBidTokenAccount
+    )]
+    pub beneficiary_token: Box<Account<'info, TokenAccount>>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Box<Account<'info, Config>>,
+    /// Token account of the fee recipient of the config, receives the platform fee
+    #[account(
+        mut,
+        constraint = fee_recipient_token.mint == state.bid_mint && fee_recipient_token.owner == config.fee_recipient @ AuctionError::InvalidFeeRecipient
+    )]
+    pub fee_recipient_token: Box<Account<'info, TokenAccount>>,
+    /// Loyalty status of the winner, empty unless it was granted
+    /// CHECK: only checked for being empty
+    #[account(seeds = [b"loyalty", state.highest_bidder_account.as_ref()], bump)]
+    pub winner_loyalty: UncheckedAccount<'info>,
+    /// Lifetime statistics of the winner
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = BIDDER_PROFILE_SPACE,
+        seeds = [b"profile", state.highest_bidder_account.as_ref()],
+        bump,
+    )]
+    pub winner_profile: Box<Account<'info, BidderProfile>>,
+    /// Sale history of the seller
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = SELLER_PROFILE_SPACE,
+        seeds = [b"seller-profile", state.initializer.as_ref()],
+        bump,
+    )]
+    pub seller_profile: Box<Account<'info, SellerProfile>>,
+    /// Final statistics of the auction, kept once the auction state is closed
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = AUCTION_SUMMARY_SPACE,
+        seeds = [b"summary", state.key().as_ref()],
+        bump,
+    )]
+    pub summary: Box<Account<'info, AuctionSummary>>,
+    /// Record of the sale for tax reporting, in base units of the bid mint
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TAX_RECORD_SPACE,
+        seeds = [b"tax", state.key().as_ref()],
+        bump,
+    )]
+    pub tax_record: Box<Account<'info, TaxRecord>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundToken<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.is_token_denominated() @ AuctionError::NotTokenAuction)]
+    pub state: Box<Account<'info, State>>,
+    /// Bidder
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump)]
+    pub user_bid: Box<Account<'info, UserBid>>,
+    /// Lifetime statistics of the bidder
+    #[account(mut, seeds = [b"profile", user.key().as_ref()], bump)]
+    pub profile: Box<Account<'info, BidderProfile>>,
+    #[account(address = state.bid_mint @ AuctionError::InvalidBidTokenAccount)]
+    pub bid_mint: Box<Account<'info, Mint>>,
+    /// Signs the transfers out of the vault
+    #[account(seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = bid_mint,
+        associated_token::authority = treasury_authority,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    /// Token account the refund is paid to
+    #[account(
+        mut,
+        constraint = bidder_token.mint == state.bid_mint && bidder_token.owner == user.key() @ AuctionError::InvalidBidTokenAccount
+    )]
+    pub bidder_token: Box<Account<'info, TokenAccount>>,
+    /// Payer of the user bid rent, the bidder itself
+    #[account(mut, constraint = rent_payer.key() == user_bid.rent_payer @ AuctionError::InvalidRentPayer)]
+    /// CHECK:
+    pub rent_payer: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::{OwnershipProposed, OwnershipTransferred};
+use crate::instructions::SellerAction;
+use crate::state::State;
+
+pub fn propose_owner(ctx: Context<SellerAction>, new_owner: Pubkey) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    state.pending_authority = Some(new_owner);
+
+    emit!(OwnershipProposed {
+        auction: state.key(),
+        owner: state.authority,
+        proposed_owner: state.pending_authority,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_owner_proposal(ctx: Context<SellerAction>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    if state.pending_authority.is_none() {
+        return err!(AuctionError::NoPendingOwner);
+    }
+    state.pending_authority = None;
+
+    emit!(OwnershipProposed {
+        auction: state.key(),
+        owner: state.authority,
+        proposed_owner: None,
+    });
+
+    Ok(())
+}
+
+pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    // the state PDA keeps the seeds of the original initializer, only the authority moves
+    let previous_owner = state.authority;
+    state.authority = ctx.accounts.new_owner.key();
+    state.pending_authority = None;
+
+    emit!(OwnershipTransferred {
+        auction: state.key(),
+        previous_owner,
+        new_owner: state.authority,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired, constraint = state.pending_authority.is_some() @ AuctionError::NoPendingOwner, constraint = state.pending_authority == Some(new_owner.key()) @ AuctionError::Unauthorized)]
+    pub state: Account<'info, State>,
+    /// Proposed owner of the auction
+    pub new_owner: Signer<'info>,
+}
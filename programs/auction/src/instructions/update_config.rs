@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::state::Config;
+
+pub fn update_config(
+    ctx: Context<UpdateConfig>,
+    max_auction_duration: i64,
+    min_auction_duration: i64,
+    platform_fee_bps: u16,
+    fee_recipient: Pubkey,
+    min_bid_amount: u64,
+    bid_cooldown_seconds: i64,
+    platform_authority: Pubkey,
+    shill_lookback_secs: i64,
+    loyalty_discount_bps: u16,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.max_auction_duration = max_auction_duration;
+    config.min_auction_duration = min_auction_duration;
+    config.platform_fee_bps = platform_fee_bps;
+    config.fee_recipient = fee_recipient;
+    config.min_bid_amount = min_bid_amount;
+    config.bid_cooldown_seconds = bid_cooldown_seconds;
+    config.platform_authority = platform_authority;
+    config.shill_lookback_secs = shill_lookback_secs;
+    config.loyalty_discount_bps = loyalty_discount_bps;
+
+    config.validate()
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        constraint = config.authority == authority.key() @ AuctionError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub authority: Signer<'info>,
+}
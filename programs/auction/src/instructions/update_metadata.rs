@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_TITLE_LEN, MAX_URI_LEN};
+use crate::errors::AuctionError;
+use crate::events::MetadataUpdated;
+use crate::state::State;
+
+pub fn update_metadata(ctx: Context<UpdateMetadata>, title: String, uri: String) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    // bidders joined for the item as it was described
+    if state.highest_bid_amount != 0 || state.bid_count != 0 {
+        return err!(AuctionError::CannotChangeAfterBids);
+    }
+    if title.len() > MAX_TITLE_LEN || uri.len() > MAX_URI_LEN {
+        return err!(AuctionError::MetadataTooLong);
+    }
+
+    state.title = title;
+    state.uri = uri;
+
+    emit!(MetadataUpdated {
+        auction: state.key(),
+        title: state.title.clone(),
+        uri: state.uri.clone(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(title: String, uri: String)]
+pub struct UpdateMetadata<'info> {
+    /// Resized to fit the new metadata, the seller pays the rent of a larger account
+    /// and gets the rent of a smaller one back
+    #[account(
+        mut,
+        seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()],
+        bump = state.state_bump,
+        constraint = state.is_current_version() @ AuctionError::StateMigrationRequired,
+        constraint = state.authority == initializer.key() @ AuctionError::Unauthorized,
+        realloc = State::space(&title, &uri),
+        realloc::payer = initializer,
+        realloc::zero = false,
+    )]
+    pub state: Account<'info, State>,
+    /// Seller
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
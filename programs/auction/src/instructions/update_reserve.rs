@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::ReserveLowered;
+use crate::instructions::SellerAction;
+
+pub fn update_reserve(ctx: Context<SellerAction>, new_reserve: u64) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    // a higher reserve would retroactively fail bids placed under the current one
+    if new_reserve >= state.reserve_price {
+        return err!(AuctionError::ReserveCanOnlyDecrease);
+    }
+
+    let old_reserve = state.reserve_price;
+    state.reserve_price = new_reserve;
+
+    emit!(ReserveLowered {
+        auction: state.key(),
+        old_reserve,
+        new_reserve,
+    });
+
+    Ok(())
+}
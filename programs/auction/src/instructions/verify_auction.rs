@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AuctionError;
+use crate::events::{AuctionHealth, HealthCheckFailed};
+use crate::state::{HealthViolation, State, Treasury};
+use crate::utils::{load_bid_escrow, load_user_bid, treasury_available_lamports};
+
+pub fn verify_auction<'info>(ctx: Context<'_, '_, '_, 'info, VerifyAuction<'info>>) -> Result<()> {
+    let state = &ctx.accounts.state;
+
+    // the treasury only holds the withdrawal penalties and the budget of a reverse auction
+    // on top of its rent, the seller bond sits in its own PDA
+    let treasury_available = treasury_available_lamports(&ctx.accounts.treasury_authority)?;
+    let bond_available = treasury_available_lamports(&ctx.accounts.seller_bond)?;
+    let bond_outstanding = state.bond_outstanding();
+    let mut healthy =
+        treasury_available >= state.treasury_outstanding()? && bond_available >= bond_outstanding;
+
+    // every escrow of the page has to cover its own outstanding bid
+    let mut escrowed: u64 = 0;
+    let mut escrow_outstanding: u64 = 0;
+    let mut bids_checked: u32 = 0;
+    for accounts in ctx.remaining_accounts.chunks(3) {
+        if accounts.len() != 3 {
+            return err!(AuctionError::InvalidEscrow);
+        }
+        let (bidder, user_bid, escrow) = (&accounts[0], &accounts[1], &accounts[2]);
+        let user_bid = load_user_bid(state.key(), bidder, user_bid)?;
+        load_bid_escrow(state.key(), bidder, escrow)?;
+
+        let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+        let available = escrow.lamports().saturating_sub(rent_floor);
+        let outstanding = state.escrow_outstanding(bidder.key(), &user_bid);
+        healthy &= available >= outstanding;
+
+        escrowed = escrowed
+            .checked_add(available)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        escrow_outstanding = escrow_outstanding
+            .checked_add(outstanding)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        bids_checked = bids_checked
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    }
+
+    emit!(AuctionHealth {
+        auction: state.key(),
+        treasury_available,
+        bond_available,
+        bond_outstanding,
+        escrowed,
+        escrow_outstanding,
+        bids_checked,
+        healthy,
+    });
+
+    if !healthy {
+        return err!(AuctionError::InvariantViolated);
+    }
+
+    Ok(())
+}
+
+pub fn health_check<'info>(ctx: Context<'_, '_, '_, 'info, VerifyAuction<'info>>) -> Result<()> {
+    let state = &ctx.accounts.state;
+
+    if let Some(violation) = find_violation(
+        state,
+        &ctx.accounts.treasury_authority,
+        &ctx.accounts.seller_bond,
+        ctx.remaining_accounts,
+    )? {
+        emit!(HealthCheckFailed {
+            auction: state.key(),
+            violation,
+        });
+        return err!(AuctionError::InvariantViolated);
+    }
+
+    Ok(())
+}
+
+/// First invariant broken by the auction, with every bid escrow of the auction passed as
+/// `(bidder, user_bid, bid_escrow)` accounts sorted by bidder, so none can be counted twice
+fn find_violation<'info>(
+    state: &Account<'info, State>,
+    treasury_authority: &SystemAccount<'info>,
+    seller_bond: &SystemAccount<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<Option<HealthViolation>> {
+    if state.deadline <= 0 {
+        return Ok(Some(HealthViolation::InvalidDeadline));
+    }
+    if treasury_available_lamports(treasury_authority)? < state.treasury_outstanding()? {
+        return Ok(Some(HealthViolation::TreasuryShortfall));
+    }
+    if treasury_available_lamports(seller_bond)? < state.bond_outstanding() {
+        return Ok(Some(HealthViolation::BondShortfall));
+    }
+
+    let mut previous_bidder: Option<Pubkey> = None;
+    let mut amounts: Vec<u64> = Vec::new();
+    for accounts in remaining_accounts.chunks(3) {
+        let (bidder, user_bid, escrow) = match accounts {
+            [bidder, user_bid, escrow] => (bidder, user_bid, escrow),
+            _ => return err!(AuctionError::InvalidEscrow),
+        };
+        if previous_bidder.map_or(false, |previous| bidder.key() <= previous) {
+            return err!(AuctionError::InvalidEscrow);
+        }
+        previous_bidder = Some(bidder.key());
+        let user_bid = load_user_bid(state.key(), bidder, user_bid)?;
+        load_bid_escrow(state.key(), bidder, escrow)?;
+
+        let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+        let available = escrow.lamports().saturating_sub(rent_floor);
+        if available < state.escrow_outstanding(bidder.key(), &user_bid) {
+            return Ok(Some(HealthViolation::EscrowShortfall));
+        }
+        amounts.push(user_bid.amount);
+    }
+
+    Ok(state.bid_violation(&amounts))
+}
+
+#[derive(Accounts)]
+pub struct VerifyAuction<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    #[account(seeds = [b"treasury", state.key().as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// Holds the treasury lamports
+    #[account(seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+    /// Holds the seller bond
+    #[account(seeds = [b"bond", state.key().as_ref()], bump = state.seller_bond_bump)]
+    pub seller_bond: SystemAccount<'info>,
+}
@@ -0,0 +1,104 @@
+use anchor_lang::{prelude::*, AccountsClose};
+
+use crate::errors::AuctionError;
+use crate::state::{AuctionType, BidEscrow, State, Treasury, UserBid};
+use crate::utils::{bps_of, transfer_from_escrow};
+
+pub fn withdraw_bid(ctx: Context<WithdrawBid>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    check_withdrawable(state, ctx.accounts.user.key())?;
+
+    // the penalty is charged on the bid, the rest of a proxy maximum goes back in full
+    let amount = ctx.accounts.user_bid.escrowed();
+    let penalty = bps_of(ctx.accounts.user_bid.amount, state.withdrawal_penalty_bps)?;
+    let escrow = ctx.accounts.bid_escrow.to_account_info();
+
+    // the penalty stays in the treasury until the seller claims it
+    if penalty > 0 {
+        transfer_from_escrow(
+            &escrow,
+            &ctx.accounts.treasury_authority.to_account_info(),
+            penalty,
+        )?;
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_collected = treasury
+            .total_collected
+            .checked_add(penalty)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        state.penalties_owed = state
+            .penalties_owed
+            .checked_add(penalty)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    }
+    if amount > penalty {
+        transfer_from_escrow(
+            &escrow,
+            &ctx.accounts.user.to_account_info(),
+            amount - penalty,
+        )?;
+    }
+
+    state.bid_count = state
+        .bid_count
+        .checked_sub(1)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+
+    // closing the escrow returns its rent to whoever paid it
+    ctx.accounts
+        .bid_escrow
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+    ctx.accounts
+        .user_bid
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+
+    Ok(())
+}
+
+/// Only outbid bidders can take their bid back before the deadline: the highest bid
+/// keeps the auction meaningful, losing Vickrey bids set the price, losing all-pay
+/// bids are paid to the seller and offers of a reverse auction are binding
+pub(crate) fn check_withdrawable(state: &State, bidder: Pubkey) -> Result<()> {
+    if state.highest_bidder_account == bidder {
+        return err!(AuctionError::HighestBidderCannotWithdraw);
+    }
+    // a bid outbid after the effective end of a candle auction may still win it, every
+    // bid of a fractional auction buys shares and an outbid bid may still win a unit.
+    // The leaderboard picks the finalists of a final round among the open bids, which
+    // stay until non-finalists are refunded at the end of the open phase. Token bids only
+    // leave the vault through `refund_token`
+    if matches!(
+        state.auction_type,
+        AuctionType::Vickrey | AuctionType::AllPay | AuctionType::Reverse | AuctionType::Raffle
+    ) || state.candle_window > 0
+        || state.is_fractional()
+        || state.is_multi_unit()
+        || state.has_final_round()
+        || state.is_token_denominated()
+    {
+        return err!(AuctionError::WithdrawalNotAllowed);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBid<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump, constraint = user_bid.deposit_share == 0 @ AuctionError::BidInVault)]
+    pub user_bid: Account<'info, UserBid>,
+    #[account(mut, seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Payer of the user bid rent, the bidder itself unless the bid was sponsored
+    #[account(mut, constraint = rent_payer.key() == user_bid.rent_payer @ AuctionError::InvalidRentPayer)]
+    /// CHECK:
+    pub rent_payer: AccountInfo<'info>,
+    #[account(mut, seeds = [b"treasury", state.key().as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// Holds the treasury lamports, the penalty among them
+    #[account(mut, seeds = [b"treasury-auth", state.key().as_ref()], bump = state.treasury_authority_bump)]
+    pub treasury_authority: SystemAccount<'info>,
+}
@@ -0,0 +1,56 @@
+use anchor_lang::{prelude::*, AccountsClose};
+
+use crate::errors::AuctionError;
+use crate::instructions::check_withdrawable;
+use crate::state::{BidEscrow, State, UserBid};
+use crate::utils::transfer_from_escrow;
+
+pub fn withdraw_outbid(ctx: Context<WithdrawOutbid>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+
+    check_withdrawable(state, ctx.accounts.user.key())?;
+
+    // an outbid bid serves no purpose anymore, so it goes back in full, with the rest
+    // of its proxy maximum
+    let amount = ctx.accounts.user_bid.escrowed();
+    if amount > 0 {
+        transfer_from_escrow(
+            &ctx.accounts.bid_escrow.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            amount,
+        )?;
+    }
+
+    state.bid_count = state
+        .bid_count
+        .checked_sub(1)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+
+    // closing the escrow returns its rent to whoever paid it, and frees the PDAs
+    // for a new bid
+    ctx.accounts
+        .bid_escrow
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+    ctx.accounts
+        .user_bid
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawOutbid<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump, constraint = user_bid.deposit_share == 0 @ AuctionError::BidInVault)]
+    pub user_bid: Account<'info, UserBid>,
+    #[account(mut, seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Payer of the user bid rent, the bidder itself unless the bid was sponsored
+    #[account(mut, constraint = rent_payer.key() == user_bid.rent_payer @ AuctionError::InvalidRentPayer)]
+    /// CHECK:
+    pub rent_payer: AccountInfo<'info>,
+}
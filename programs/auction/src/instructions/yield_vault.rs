@@ -0,0 +1,196 @@
+use anchor_lang::{prelude::*, AccountsClose};
+
+use crate::constants::BIDDER_PROFILE_SPACE;
+use crate::errors::AuctionError;
+use crate::events::{BidDepositedToVault, ProfileUpdated, RefundedWithYield};
+use crate::instructions::refund_blocker;
+use crate::interface;
+use crate::state::{AuctionType, BidEscrow, BidderProfile, State, UserBid};
+use crate::utils::transfer_from_escrow;
+
+/// Moves the escrowed lamports of an outbid bid into a yield vault until the auction
+/// settles. Only the outbid bids of a single-item English or Vickrey auction can never
+/// win it again, so only they may leave their escrow
+pub fn deposit_to_vault<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositToVault<'info>>,
+) -> Result<()> {
+    let state = &ctx.accounts.state;
+    let user = ctx.accounts.user.key();
+
+    if !matches!(
+        state.auction_type,
+        AuctionType::English | AuctionType::Vickrey
+    ) || state.candle_window > 0
+        || state.is_fractional()
+        || state.is_multi_unit()
+        || state.is_token_denominated()
+    {
+        return err!(AuctionError::VaultNotSupported);
+    }
+    if state.cancelled {
+        return err!(AuctionError::AuctionCancelled);
+    }
+    if state.is_settled() {
+        return err!(AuctionError::AlreadySettled);
+    }
+    if user == state.highest_bidder_account {
+        return err!(AuctionError::HighestBidderCannotWithdraw);
+    }
+    if ctx.accounts.user_bid.refunded {
+        return err!(AuctionError::AlreadyRefunded);
+    }
+
+    let amount = ctx.accounts.user_bid.escrowed();
+    if amount == 0 {
+        return err!(AuctionError::NoBids);
+    }
+    transfer_from_escrow(
+        &ctx.accounts.bid_escrow.to_account_info(),
+        &ctx.accounts.vault,
+        amount,
+    )?;
+    let shares =
+        interface::vault_deposit(&ctx.accounts.vault_program, &ctx.accounts.vault, amount)?;
+    if shares == 0 {
+        return err!(AuctionError::InvalidVaultResponse);
+    }
+
+    let user_bid = &mut ctx.accounts.user_bid;
+    user_bid.deposit_share = shares;
+    user_bid.vault = ctx.accounts.vault.key();
+
+    emit!(BidDepositedToVault {
+        auction: state.key(),
+        bidder: user,
+        vault: user_bid.vault,
+        amount,
+        shares,
+    });
+
+    Ok(())
+}
+
+/// Refunds a bid deposited into a yield vault under the rules of `refund`: the vault
+/// redeems the shares of the bid and pays the bidder their value, the bid and its part
+/// of the yield, `vault_value * deposit_share / total_shares`
+pub fn refund_with_yield<'info>(
+    ctx: Context<'_, '_, '_, 'info, RefundWithYield<'info>>,
+) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    let user = ctx.accounts.user.key();
+
+    if let Some(error) = refund_blocker(state, user, &ctx.accounts.user_bid) {
+        return Err(error!(error));
+    }
+
+    let deposit_share = ctx.accounts.user_bid.deposit_share;
+    let (vault_value, total_shares) =
+        interface::vault_value(&ctx.accounts.vault_program, &ctx.accounts.vault)?;
+    if total_shares < deposit_share {
+        return err!(AuctionError::InvalidVaultResponse);
+    }
+    let payout = (vault_value as u128)
+        .checked_mul(deposit_share as u128)
+        .and_then(|value| value.checked_div(total_shares as u128))
+        .and_then(|value| u64::try_from(value).ok())
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+
+    // the vault pays the bidder directly, it has to pay the whole value of the shares
+    let user_info = ctx.accounts.user.to_account_info();
+    let balance_before = user_info.lamports();
+    interface::vault_withdraw(
+        &ctx.accounts.vault_program,
+        &ctx.accounts.vault,
+        &user_info,
+        deposit_share,
+    )?;
+    if user_info.lamports().saturating_sub(balance_before) < payout {
+        return err!(AuctionError::InvalidVaultResponse);
+    }
+    // a vault that lost value pays back less than the bid, without any yield
+    let amount = ctx.accounts.user_bid.escrowed();
+    let yield_accrued = payout.saturating_sub(amount);
+
+    ctx.accounts.user_bid.refunded = true;
+    // an outbid bid lost the auction, unless it was cancelled
+    if !state.cancelled {
+        let profile = &mut ctx.accounts.profile;
+        profile.record_loss()?;
+        emit!(ProfileUpdated::new(user, profile));
+    }
+    state.bid_count = state.bid_count.saturating_sub(1);
+
+    emit!(RefundedWithYield {
+        auction: state.key(),
+        auction_type: state.auction_type,
+        bidder: user,
+        amount,
+        yield_accrued,
+    });
+
+    // the escrow only holds its rent, which goes back to whoever paid it
+    ctx.accounts
+        .bid_escrow
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+    ctx.accounts
+        .user_bid
+        .close(ctx.accounts.rent_payer.to_account_info())?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositToVault<'info> {
+    #[account(seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Outbid bidder
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump, constraint = user_bid.deposit_share == 0 @ AuctionError::BidInVault)]
+    pub user_bid: Account<'info, UserBid>,
+    #[account(mut, seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Program of the yield vault, see `interface::YieldVault`
+    /// CHECK: only invoked
+    pub vault_program: AccountInfo<'info>,
+    /// Yield vault the bid is deposited into, chosen by the bidder
+    #[account(mut, constraint = *vault.owner == vault_program.key() @ AuctionError::InvalidVault)]
+    /// CHECK: owned by the vault program, which reads it
+    pub vault: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundWithYield<'info> {
+    #[account(mut, seeds = [b"state", state.initializer.as_ref(), state.index_seed().as_ref()], bump = state.state_bump, constraint = state.is_current_version() @ AuctionError::StateMigrationRequired)]
+    pub state: Account<'info, State>,
+    /// Bidder, receives the bid and its yield from the vault
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump, constraint = user_bid.deposit_share > 0 @ AuctionError::BidNotInVault)]
+    pub user_bid: Account<'info, UserBid>,
+    #[account(mut, seeds = [b"escrow", user.key().as_ref(), state.key().as_ref()], bump = bid_escrow.bump)]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// Lifetime statistics of the bidder
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = BIDDER_PROFILE_SPACE,
+        seeds = [b"profile", user.key().as_ref()],
+        bump,
+    )]
+    pub profile: Box<Account<'info, BidderProfile>>,
+    /// Payer of the user bid rent, the bidder itself unless the bid was sponsored
+    #[account(mut, constraint = rent_payer.key() == user_bid.rent_payer @ AuctionError::InvalidRentPayer)]
+    /// CHECK:
+    pub rent_payer: AccountInfo<'info>,
+    /// Program of the yield vault, see `interface::YieldVault`
+    /// CHECK: only invoked
+    pub vault_program: AccountInfo<'info>,
+    /// Yield vault holding the bid
+    #[account(
+        mut,
+        constraint = vault.key() == user_bid.vault && *vault.owner == vault_program.key() @ AuctionError::InvalidVault
+    )]
+    /// CHECK: owned by the vault program, which reads it
+    pub vault: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,162 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        hash::hash,
+        instruction::{AccountMeta, Instruction},
+        program::{get_return_data, invoke},
+    },
+};
+
+use crate::errors::AuctionError;
+
+/// Value a condition program returns when its condition is met
+pub const CONDITION_MET: u8 = 1;
+
+/// Interface of the programs a conditional auction settles through. `end_auction` calls
+/// the `check_condition` instruction of the program with the condition account and
+/// the auction state, in that order, both read-only. The program returns a single
+/// byte, `CONDITION_MET` when the auction may settle, as the return data of the
+/// instruction. The instruction is identified like an Anchor instruction, by the first
+/// 8 bytes of the sha256 of `global:check_condition`
+pub trait ConditionProgram {
+    fn check_condition(condition_account: AccountInfo, auction_state: AccountInfo) -> bool;
+}
+
+/// Calls `check_condition` on `condition_program` and tells whether it returned
+/// `CONDITION_MET`. Return data from any other program counts as a false condition
+pub fn check_condition<'info>(
+    condition_program: &AccountInfo<'info>,
+    condition_account: &AccountInfo<'info>,
+    auction_state: &AccountInfo<'info>,
+) -> Result<bool> {
+    if !condition_program.executable {
+        return err!(AuctionError::InvalidConditionProgram);
+    }
+    let instruction = Instruction {
+        program_id: condition_program.key(),
+        accounts: vec![
+            AccountMeta::new_readonly(condition_account.key(), false),
+            AccountMeta::new_readonly(auction_state.key(), false),
+        ],
+        data: hash(b"global:check_condition").to_bytes()[..8].to_vec(),
+    };
+    invoke(
+        &instruction,
+        &[
+            condition_account.clone(),
+            auction_state.clone(),
+            condition_program.clone(),
+        ],
+    )?;
+
+    Ok(match get_return_data() {
+        Some((program_id, data)) => {
+            program_id == condition_program.key() && data == [CONDITION_MET]
+        }
+        None => false,
+    })
+}
+
+/// Interface of the yield vaults an outbid bid can be deposited into. The vault account
+/// is owned by the vault program, which identifies its instructions like Anchor does,
+/// by the first 8 bytes of the sha256 of `global:<name>`, followed by their arguments
+/// as little-endian integers:
+///
+/// * `deposit(amount: u64)` with the vault account, writable. The lamports were already
+///   moved into the vault, it returns the vault shares they bought as a `u64`
+/// * `vault_value()` with the vault account, read-only. It returns the lamports the
+///   vault holds for its depositors and its total shares, two `u64`
+/// * `withdraw(shares: u64)` with the vault account and the destination, both
+///   writable. It redeems `shares` and pays their value to the destination
+pub trait YieldVault {
+    fn deposit(vault: AccountInfo, amount: u64) -> u64;
+    fn vault_value(vault: AccountInfo) -> (u64, u64);
+    fn withdraw(vault: AccountInfo, destination: AccountInfo, shares: u64);
+}
+
+/// Records the deposit of `amount` lamports, already moved into `vault`, and returns
+/// the shares of the vault they bought
+pub fn vault_deposit<'info>(
+    vault_program: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    invoke_vault(
+        vault_program,
+        "deposit",
+        &amount.to_le_bytes(),
+        vec![AccountMeta::new(vault.key(), false)],
+        &[vault.clone(), vault_program.clone()],
+    )?;
+    let data = vault_return_data(vault_program, 8)?;
+    Ok(u64::from_le_bytes(data[..8].try_into().unwrap()))
+}
+
+/// Lamports `vault` holds for its depositors and its total shares
+pub fn vault_value<'info>(
+    vault_program: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+) -> Result<(u64, u64)> {
+    invoke_vault(
+        vault_program,
+        "vault_value",
+        &[],
+        vec![AccountMeta::new_readonly(vault.key(), false)],
+        &[vault.clone(), vault_program.clone()],
+    )?;
+    let data = vault_return_data(vault_program, 16)?;
+    Ok((
+        u64::from_le_bytes(data[..8].try_into().unwrap()),
+        u64::from_le_bytes(data[8..16].try_into().unwrap()),
+    ))
+}
+
+/// Redeems `shares` of `vault`, their value goes to `destination`
+pub fn vault_withdraw<'info>(
+    vault_program: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    shares: u64,
+) -> Result<()> {
+    invoke_vault(
+        vault_program,
+        "withdraw",
+        &shares.to_le_bytes(),
+        vec![
+            AccountMeta::new(vault.key(), false),
+            AccountMeta::new(destination.key(), false),
+        ],
+        &[vault.clone(), destination.clone(), vault_program.clone()],
+    )
+}
+
+fn invoke_vault<'info>(
+    vault_program: &AccountInfo<'info>,
+    name: &str,
+    args: &[u8],
+    accounts: Vec<AccountMeta>,
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    if !vault_program.executable {
+        return err!(AuctionError::InvalidVault);
+    }
+    let mut data = hash(format!("global:{}", name).as_bytes()).to_bytes()[..8].to_vec();
+    data.extend_from_slice(args);
+    let instruction = Instruction {
+        program_id: vault_program.key(),
+        accounts,
+        data,
+    };
+    invoke(&instruction, account_infos)?;
+    Ok(())
+}
+
+/// Return data of the last vault instruction, at least `len` bytes of it
+fn vault_return_data(vault_program: &AccountInfo, len: usize) -> Result<Vec<u8>> {
+    match get_return_data() {
+        Some((program_id, data)) if program_id == vault_program.key() && data.len() >= len => {
+            Ok(data)
+        }
+        _ => err!(AuctionError::InvalidVaultResponse),
+    }
+}
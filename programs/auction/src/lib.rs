@@ -1,254 +1,705 @@
-use anchor_lang::{
-    prelude::*,
-    solana_program::{native_token::sol_to_lamports, program::invoke, system_instruction},
+use anchor_lang::prelude::*;
+
+pub mod candle;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod constants;
+pub mod errors;
+mod events;
+mod guards;
+pub mod instructions;
+pub mod interface;
+mod oracle;
+pub mod pda;
+pub mod pricing;
+pub mod raffle;
+pub mod state;
+mod utils;
+pub use crate::instructions::*;
+pub use crate::pda::PdaHelpers;
+pub use crate::state::*;
+pub use crate::utils::{check_treasury_withdrawal, validate_all_bumps, validate_auction_duration};
+
+use crate::guards::{
+    check_auction_active, check_auction_ended, check_commit_phase, check_final_commit_phase,
+    check_final_reveal_phase, check_open_phase_over, check_reveal_phase,
 };
 
-mod errors;
-use crate::errors::AuctionError;
-
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
 #[program]
 pub mod auction {
-    use anchor_lang::AccountsClose;
-
     use super::*;
-    /// Creates and initialize a new state of our program
+
+    /// Creates and initialize a new state of our program, describing the item sold with
+    /// a title, the hash of its description and an URI. A seller runs one auction per
+    /// `auction_index`, the next free index is kept in its seller counter PDA
     pub fn initialize(
         ctx: Context<Initialize>,
-        auction_duration: i64, /* optional parameters */
+        auction_index: u32,
+        auction_config: AuctionConfig,
+    ) -> Result<()> {
+        instructions::initialize(ctx, auction_index, auction_config)
+    }
+
+    /// Creates one auction per entry of `auctions` in a single transaction, indexed from
+    /// `first_index`. Their state, treasury, treasury authority, seller bond and
+    /// leaderboard PDAs are passed as remaining accounts in
+    /// `(state, treasury, treasury_authority, seller_bond, leaderboard)` groups
+    pub fn initialize_many<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeMany<'info>>,
+        first_index: u32,
+        auctions: Vec<AuctionConfig>,
+    ) -> Result<()> {
+        instructions::initialize_many(ctx, first_index, auctions)
+    }
+
+    /// Bid. Dutch auctions are bought through `accept_price` and sealed-bid auctions
+    /// reject open bids. Auctions with tick rounds take a bid at the price of the
+    /// current round. An outbid bidder bids again with its user
+    /// bid once the bid cooldown of the config passed, only the raise is escrowed
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn bid<'info>(ctx: Context<'_, '_, '_, 'info, Bid<'info>>, amount: f64) -> Result<()> {
+        instructions::bid(ctx, amount)
+    }
+
+    /// Bids `amount` and lets a proxy raise it by `min_bid_increment` over competing
+    /// bids, up to `max_proxy_bid`. The whole maximum is escrowed, English auctions only.
+    /// When outbidding a proxy, its `(bidder, user_bid)` are passed as remaining accounts
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn proxy_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, Bid<'info>>,
+        amount: f64,
+        max_proxy_bid: f64,
+    ) -> Result<()> {
+        instructions::proxy_bid(ctx, amount, max_proxy_bid)
+    }
+
+    /// Buys a Dutch auction at its current asking price: the price is escrowed like a
+    /// bid, the caller becomes the winner and bidding closes for settlement. A multi-unit
+    /// Dutch auction sells one unit per buyer instead and closes once every unit is sold,
+    /// its buyers pay their prices at `settle_multi_unit`
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn accept_price<'info>(ctx: Context<'_, '_, '_, 'info, Bid<'info>>) -> Result<()> {
+        instructions::accept_price(ctx)
+    }
+
+    /// Bids `amount` lamports for `shares_requested` shares of a fractional auction.
+    /// Shares go first come first served, at least at their part of the reserve price,
+    /// and a bidder places a single fractional bid
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn fractional_bid(
+        ctx: Context<FractionalBid>,
+        amount: u64,
+        shares_requested: u64,
+    ) -> Result<()> {
+        instructions::fractional_bid(ctx, amount, shares_requested)
+    }
+
+    /// The bidder lets `delegate` bid up to `max_amount` on its behalf until `expiry`.
+    /// The allowance is deposited in the delegate auth PDA
+    pub fn grant_delegate(
+        ctx: Context<GrantDelegate>,
+        delegate: Pubkey,
+        max_amount: f64,
+        expiry: i64,
+    ) -> Result<()> {
+        instructions::grant_delegate(ctx, delegate, max_amount, expiry)
+    }
+
+    /// The bidder takes back its delegation together with the unspent allowance
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        instructions::revoke_delegate(ctx)
+    }
+
+    /// Bid placed by a delegate for its principal, escrowed out of the allowance. The
+    /// bid and its refund belong to the principal
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn delegated_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, DelegatedBid<'info>>,
+        amount: f64,
+    ) -> Result<()> {
+        instructions::delegated_bid(ctx, amount)
+    }
+
+    /// Before the first bid, the seller sets the auction duration again. The deadline
+    /// becomes `new_duration` seconds after the start of the auction
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn extend_deadline(ctx: Context<ExtendDeadline>, new_duration: i64) -> Result<()> {
+        instructions::extend_deadline(ctx, new_duration)
+    }
+
+    /// Commits a sealed bid during the first `commit_duration` seconds of the auction.
+    /// `commitment` is the sha256 of the amount in lamports (little endian) and a nonce.
+    /// The `commit_deposit` of the auction is held with the commitment
+    #[access_control(check_commit_phase(&ctx.accounts.state))]
+    pub fn commit_bid(ctx: Context<CommitSealedBid>, commitment: [u8; 32]) -> Result<()> {
+        instructions::commit_bid(ctx, commitment)
+    }
+
+    /// Replaces the commitment of a sealed bid while the commit phase is open
+    #[access_control(check_commit_phase(&ctx.accounts.state))]
+    pub fn recommit_bid(ctx: Context<RecommitSealedBid>, commitment: [u8; 32]) -> Result<()> {
+        instructions::recommit_bid(ctx, commitment)
+    }
+
+    /// Reveals a sealed bid once the commit phase is over and places it like an open bid.
+    /// `amount` (in lamports) and `nonce` must hash to the stored commitment. The commit
+    /// deposit goes back to the bidder. In an auction that only takes higher bids, a bid
+    /// below an earlier reveal lost: it is not placed but still gets its deposit back
+    #[access_control(check_reveal_phase(&ctx.accounts.state))]
+    pub fn reveal_bid(ctx: Context<RevealSealedBid>, amount: u64, nonce: [u8; 32]) -> Result<()> {
+        instructions::reveal_bid(ctx, amount, nonce)
+    }
+
+    /// Once the deadline passed, the seller takes the deposit of a sealed bid that was
+    /// never revealed for the beneficiary. The commitment is closed to the bidder
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn forfeit_deposit(ctx: Context<ForfeitDeposit>) -> Result<()> {
+        instructions::forfeit_deposit(ctx)
+    }
+
+    /// Once the open phase of an auction with a final round is over, a finalist commits a
+    /// single best-and-final offer until `final_deadline`. `commitment` is the sha256 of
+    /// the amount in lamports (little endian) and a nonce
+    #[access_control(check_final_commit_phase(&ctx.accounts.state))]
+    pub fn commit_final_offer(ctx: Context<CommitFinalOffer>, commitment: [u8; 32]) -> Result<()> {
+        instructions::commit_final_offer(ctx, commitment)
+    }
+
+    /// Reveals a best-and-final offer between `final_deadline` and the deadline. The
+    /// offer can not go below the open bid of the finalist, its escrow is topped up to
+    /// it. The highest revealed offer wins and is paid, the open phase result stands
+    /// when no finalist reveals
+    #[access_control(check_final_reveal_phase(&ctx.accounts.state))]
+    pub fn reveal_final_offer(
+        ctx: Context<RevealFinalOffer>,
+        amount: u64,
+        nonce: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_final_offer(ctx, amount, nonce)
+    }
+
+    /// Once the open phase of an auction with a final round is over, a bidder who did
+    /// not make the finalists takes its whole bid back
+    #[access_control(check_open_phase_over(&ctx.accounts.state))]
+    pub fn refund_non_finalist(ctx: Context<RefundNonFinalist>) -> Result<()> {
+        instructions::refund_non_finalist(ctx)
+    }
+
+    /// A bidder who is not the highest bidder takes its bid back before the deadline.
+    /// `withdrawal_penalty_bps` of the bid stays in the treasury for the seller
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn withdraw_bid(ctx: Context<WithdrawBid>) -> Result<()> {
+        instructions::withdraw_bid(ctx)
+    }
+
+    /// An outbid bidder takes its whole bid back before the deadline, without penalty.
+    /// It can bid again afterwards
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn withdraw_outbid(ctx: Context<WithdrawOutbid>) -> Result<()> {
+        instructions::withdraw_outbid(ctx)
+    }
+
+    /// The seller accepts the current highest bid and ends the auction right away.
+    /// `expected_winner` and `expected_amount` protect the seller from a higher bid
+    /// landing right before this instruction
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn accept_bid_early(
+        ctx: Context<AcceptBidEarly>,
+        expected_winner: Pubkey,
+        expected_amount: u64,
     ) -> Result<()> {
-        // Get the clock sysvar via syscall
-        let clock = Clock::get()?;
-        let state = &mut ctx.accounts.state;
+        instructions::accept_bid_early(ctx, expected_winner, expected_amount)
+    }
+
+    /// The seller cancels an auction nobody bid on. The state and the treasury are
+    /// closed and the treasury authority and seller bond PDA emptied, their rent and the
+    /// seller bond go back to the seller
+    pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+        instructions::cancel_auction(ctx)
+    }
+
+    /// The seller cancels the auction before its deadline by paying the highest bidder
+    /// `cancel_compensation_bps` of the highest bid. Every bid becomes refundable in full
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn cancel_with_compensation(ctx: Context<CancelWithCompensation>) -> Result<()> {
+        instructions::cancel_with_compensation(ctx)
+    }
+
+    /// The seller sends the proceeds of the auction to `beneficiary` instead of itself.
+    /// It can be changed until settlement, a change after the deadline emits an event
+    pub fn set_beneficiary(ctx: Context<SellerAction>, beneficiary: Pubkey) -> Result<()> {
+        instructions::set_beneficiary(ctx, beneficiary)
+    }
 
-        state.deadline = clock.unix_timestamp + auction_duration;
-        state.initializer = ctx.accounts.initializer.key().clone();
+    /// The seller lets `authority` settle the auction on its behalf, or revokes it with
+    /// `None`. The proceeds still go to the beneficiary
+    pub fn set_settlement_authority(
+        ctx: Context<SellerAction>,
+        authority: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_settlement_authority(ctx, authority)
+    }
 
-        Ok(())
+    /// Before the first bid, the seller sets the title and item URI of the auction. The
+    /// state account is resized to fit them, the seller pays or gets back the rent
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn update_metadata(ctx: Context<UpdateMetadata>, title: String, uri: String) -> Result<()> {
+        instructions::update_metadata(ctx, title, uri)
     }
-    /// Bid
-    pub fn bid(ctx: Context<Bid>, amount: f64) -> Result<()> {
-        let state = &mut ctx.accounts.state;
-        let clock = Clock::get()?;
 
-        if clock.unix_timestamp >= state.deadline {
-            return err!(AuctionError::Finished);
-        }
+    /// Before the first bid, the seller adds `item` to the items sold together. A bundle
+    /// holds at most `MAX_BUNDLE_ITEMS` items
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn add_bundle_item(ctx: Context<SellerAction>, item: Pubkey) -> Result<()> {
+        instructions::add_bundle_item(ctx, item)
+    }
 
-        let amount_in_lamports = sol_to_lamports(amount);
-        let user_bid = &mut ctx.accounts.user_bid;
+    /// The seller moves the prize of the auction into its prize vault, bids are taken
+    /// once it is there
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn deposit_prize(ctx: Context<DepositPrize>) -> Result<()> {
+        instructions::deposit_prize(ctx)
+    }
 
-        if amount_in_lamports < state.highest_bid_amount {
-            user_bid.close(ctx.accounts.user.to_account_info())?;
-            return err!(AuctionError::BidAmountTooSmall);
-        }
+    /// The seller takes back the prize of an auction that was voided, missed its reserve
+    /// or took no bid. Bidding waits for a new deposit afterwards
+    pub fn reclaim_prize(ctx: Context<ReclaimPrize>) -> Result<()> {
+        instructions::reclaim_prize(ctx)
+    }
 
-        // register user amunt bid in PDA
-        user_bid.amount = amount_in_lamports;
+    /// Before the first bid, the seller describes the auction in a description account
+    /// sized to `text`, at most `MAX_DESCRIPTION_LEN` bytes. The seller pays its rent
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn add_description(ctx: Context<AddDescription>, text: Vec<u8>) -> Result<()> {
+        instructions::add_description(ctx, text)
+    }
 
-        // send funds to treasury account
-        invoke(
-            &system_instruction::transfer(
-                &ctx.accounts.user.key(),
-                &ctx.accounts.treasury.key(),
-                amount_in_lamports,
-            ),
-            &[
-                ctx.accounts.user.to_account_info().clone(),
-                ctx.accounts.treasury.clone(),
-            ],
-        )?;
+    /// Before the first bid, the seller replaces the description of the auction. The
+    /// description account is resized to fit it, the seller pays or gets back the rent
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn update_description(ctx: Context<UpdateDescription>, text: Vec<u8>) -> Result<()> {
+        instructions::update_description(ctx, text)
+    }
 
-        // register highest bid in state
-        state.highest_bid_amount = amount_in_lamports;
-        state.highest_bidder_account = ctx.accounts.user.key();
-        state.highest_bidder_bump = *ctx.bumps.get("user_bid").unwrap();
+    /// Before the deadline, the seller lowers the reserve price to `new_reserve` so the
+    /// auction can clear. The reserve can never be raised
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn update_reserve(ctx: Context<SellerAction>, new_reserve: u64) -> Result<()> {
+        instructions::update_reserve(ctx, new_reserve)
+    }
 
-        Ok(())
+    /// Turns the auction into a candle auction: the seller requests randomness from
+    /// the Switchboard VRF account `vrf`, whose authority is the state PDA
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn request_random_end(
+        ctx: Context<RequestRandomEnd>,
+        switchboard_state_bump: u8,
+        permission_bump: u8,
+    ) -> Result<()> {
+        instructions::request_random_end(ctx, switchboard_state_bump, permission_bump)
     }
+
+    /// VRF callback, closes bidding at a slot drawn from the VRF result between now
+    /// and the deadline. Emits a `CandleEndSet` event
+    pub fn fulfill_random_end(ctx: Context<FulfillRandomEnd>) -> Result<()> {
+        instructions::fulfill_random_end(ctx)
+    }
+
+    /// Draws the effective end of a candle auction among the final `candle_window`
+    /// seconds, from the most recent slot hash once the deadline passed. The last bid
+    /// placed before it becomes the highest bid, an auction without such a bid is
+    /// refunded. Anyone can call it, `end_auction` settles a candle auction after it
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn resolve_candle(ctx: Context<ResolveCandle>) -> Result<()> {
+        instructions::resolve_candle(ctx)
+    }
+
+    /// The seller halts bidding without cancelling the auction
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn pause_bidding(ctx: Context<SellerAction>) -> Result<()> {
+        instructions::pause_bidding(ctx)
+    }
+
+    /// The seller resumes bidding. With `extend_deadline`, the deadline moves by the
+    /// time bidding was paused so bidders don't lose time. An auction paused by its
+    /// fraud flags stays paused until the platform authority dismisses them
+    pub fn resume_bidding(ctx: Context<SellerAction>, extend_deadline: bool) -> Result<()> {
+        instructions::resume_bidding(ctx, extend_deadline)
+    }
+
+    /// The seller runs a new round of an auction that missed its reserve, once every
+    /// bid was refunded. The state and treasury are kept, bidding starts over
+    pub fn reopen_auction(
+        ctx: Context<ReopenAuction>,
+        new_duration: i64,
+        new_reserve: u64,
+    ) -> Result<()> {
+        instructions::reopen_auction(ctx, new_duration, new_reserve)
+    }
+
+    /// The owner of the auction proposes `new_owner` as the next owner, who has to
+    /// accept it with `accept_ownership`
+    pub fn propose_owner(ctx: Context<SellerAction>, new_owner: Pubkey) -> Result<()> {
+        instructions::propose_owner(ctx, new_owner)
+    }
+
+    /// The owner of the auction withdraws its pending ownership proposal
+    pub fn cancel_owner_proposal(ctx: Context<SellerAction>) -> Result<()> {
+        instructions::cancel_owner_proposal(ctx)
+    }
+
+    /// The proposed owner takes over the auction, the previous owner loses its powers
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> Result<()> {
+        instructions::accept_ownership(ctx)
+    }
+
     /// After an auction ends (determined by `auction_duration`), a seller can claim the
-    /// heighest bid by calling this instruction
-    pub fn end_auction(ctx: Context<EndAuction>) -> Result<()> {
-        let state = &mut ctx.accounts.state;
-        let clock = Clock::get()?;
-
-        if clock.unix_timestamp < state.deadline {
-            return err!(AuctionError::StillActive);
-        }
-
-        if state.seller_payed {
-            return err!(AuctionError::AlreadyClaimedPrize);
-        }
-        // get highest bid and send to seller
-        let amount_to_pay = ctx.accounts.user_bid.amount;
-
-        // transfer amount from treasury account to initializer account
-        if amount_to_pay > 0 {
-            transfer_from_treasury(
-                &ctx.accounts.treasury,
-                &ctx.accounts.initializer.to_account_info(),
-                amount_to_pay,
-            )?;
-        }
-
-        state.seller_payed = true;
-        state.highest_bid_amount = 0;
-
-        Ok(())
+    /// heighest bid by calling this instruction, or have its settlement authority call it.
+    /// The platform fee of the config is taken from the sale and sent to its fee
+    /// recipient, less the loyalty discount when the winner has loyalty status.
+    /// In a Vickrey auction the seller gets the second highest bid, or the reserve price
+    /// without a second bid, and the rest of the winning bid goes back to the winner. In
+    /// an all-pay auction the losing bids, passed as `(bidder, user_bid, bid_escrow)`
+    /// remaining accounts, are paid to the seller as well. A reverse auction pays its
+    /// lowest offer out of the budget and returns the rest of it to the beneficiary.
+    /// A perpetual auction closes the escrows of the round and starts the next round. A
+    /// conditional auction only settles once its condition program returns true
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn end_auction<'info>(ctx: Context<'_, '_, '_, 'info, EndAuction<'info>>) -> Result<()> {
+        instructions::end_auction(ctx)
+    }
+
+    /// Draws the winner of a raffle from the most recent slot hash, with a chance
+    /// proportional to its contribution. Every contribution is passed as a
+    /// `(bidder, user_bid)` remaining account, sorted by bidder key, so the draw only
+    /// depends on the slot hash, which is kept in the state. The winner is drawn once,
+    /// `end_auction` settles the raffle after it
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn draw_winner<'info>(ctx: Context<'_, '_, '_, 'info, DrawWinner<'info>>) -> Result<()> {
+        instructions::draw_winner(ctx)
+    }
+
+    /// Collects the losing bids of a paid all-pay auction or raffle that did not fit in
+    /// the `end_auction` transaction
+    pub fn collect_bids<'info>(ctx: Context<'_, '_, '_, 'info, CollectBids<'info>>) -> Result<()> {
+        instructions::collect_bids(ctx)
     }
+
+    /// Settles a fractional auction in place of `end_auction`: mints the shares of the
+    /// bids passed as `(bidder, user_bid, bid_escrow, share_account)` remaining accounts
+    /// and pays their bids to the beneficiary, in as many batches as needed
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn settle_fractional<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleFractional<'info>>,
+    ) -> Result<()> {
+        instructions::settle_fractional(ctx)
+    }
+
+    /// Settles a multi-unit auction in place of `end_auction`: the highest bids of the
+    /// leaderboard win a unit each and pay the lowest winning bid, the clearing price, or
+    /// their own bids when the auction is pay-as-bid or Dutch. The escrows of the winners
+    /// are passed as `(bidder, user_bid, bid_escrow)` remaining accounts in leaderboard
+    /// order, they keep the rest of their bids for `refund`
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn settle_multi_unit<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleMultiUnit<'info>>,
+    ) -> Result<()> {
+        instructions::settle_multi_unit(ctx)
+    }
+
     /// After an auction ends (the initializer/seller already received the winning bid),
-    /// the unsuccessfull bidders can claim their money back by calling this instruction
+    /// the unsuccessfull bidders can claim their money back by calling this instruction.
+    /// If the reserve price was not met, the highest bidder is refunded as well
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
     pub fn refund(ctx: Context<Refund>) -> Result<()> {
-        let state = &mut ctx.accounts.state;
-        let clock = Clock::get()?;
-
-        if clock.unix_timestamp < state.deadline {
-            return err!(AuctionError::StillActive);
-        }
-        if !state.seller_payed {
-            return err!(AuctionError::UnclaimedPrize);
-        }
-
-        // The highest bidder will get refunded only the rent payed for the user_bid PDA
-        if state.highest_bidder_account != ctx.accounts.user.key() {
-            let amount_to_refund = ctx.accounts.user_bid.amount;
-
-            // transfer amount from treasury account to initializer account
-            if amount_to_refund > 0 {
-                transfer_from_treasury(
-                    &ctx.accounts.treasury,
-                    &ctx.accounts.user.to_account_info(),
-                    amount_to_refund,
-                )?;
-            }
-        }
-
-        ctx.accounts
-            .user_bid
-            .close(ctx.accounts.user.to_account_info())?;
-
-        Ok(())
+        instructions::refund(ctx)
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    /// State of our auction program (up to you)
-    #[account(
-        init,
-        payer = initializer,
-        space = 8 + std::mem::size_of::<State>(),
-        seeds = [b"state", initializer.key().as_ref()],
-        bump
-    )]
-    pub state: Account<'info, State>,
-    /// Seller
-    #[account(mut)]
-    pub initializer: Signer<'info>,
-    /// Account which holds tokens bidded by biders
-    /// CHECK:
-    #[account(
-        init,
-        payer = initializer,
-        space = 8, seeds = [b"treasury", state.key().as_ref()],
-        bump
-    )]
-    pub treasury: AccountInfo<'info>,
-    pub system_program: Program<'info, System>,
-}
+    /// An outbid bidder of a single-item English or Vickrey auction moves its escrowed
+    /// bid into a yield vault of its choice, see `interface::YieldVault`. The bid can no
+    /// longer be raised and only comes back with `refund_with_yield`
+    pub fn deposit_to_vault<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositToVault<'info>>,
+    ) -> Result<()> {
+        instructions::deposit_to_vault(ctx)
+    }
 
-#[account]
-pub struct State {
-    deadline: i64,
-    initializer: Pubkey,
-    seller_payed: bool,
-    highest_bid_amount: u64,
-    highest_bidder_account: Pubkey,
-    highest_bidder_bump: u8,
-}
+    /// Refunds a bid deposited into a yield vault once the auction ended, under the rules
+    /// of `refund`. The vault pays the bidder the bid and its share of the yield
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn refund_with_yield<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefundWithYield<'info>>,
+    ) -> Result<()> {
+        instructions::refund_with_yield(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct Bid<'info> {
-    /// State of our auction program (up to you)
-    #[account(mut, seeds = [b"state", state.initializer.as_ref()], bump)]
-    pub state: Account<'info, State>,
-    /// Account which holds tokens bidded by biders
-    /// Bidder
-    #[account(mut)]
-    pub user: Signer<'info>,
-    #[account(mut, seeds = [b"treasury", state.key().as_ref()], bump)]
-    /// CHECK:
-    pub treasury: AccountInfo<'info>,
-    #[account(
-        init,
-        payer = user,
-        space = 8 + std::mem::size_of::<UserBid>(),
-        seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()],
-        bump,
-    )]
-    pub user_bid: Account<'info, UserBid>,
-    pub system_program: Program<'info, System>,
-}
+    /// Bid of an auction paid in its SPL token `bid_mint`, in base units of the mint.
+    /// The tokens are escrowed in the vault, the associated token account of the
+    /// treasury authority, created with the first bid. Lamport bids are rejected by
+    /// these auctions
+    #[access_control(check_auction_active(&ctx.accounts.state))]
+    pub fn bid_token(ctx: Context<BidToken>, amount: u64) -> Result<()> {
+        instructions::bid_token(ctx, amount)
+    }
 
-#[account]
-pub struct UserBid {
-    amount: u64,
-}
+    /// Settles an auction paid in an SPL token in place of `end_auction`, the vault pays
+    /// the winning bid to the token account of the beneficiary, less the platform fee
+    /// charged in the bid mint
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn end_auction_token(ctx: Context<EndAuctionToken>) -> Result<()> {
+        instructions::end_auction_token(ctx)
+    }
 
-// validation struct
-#[derive(Accounts)]
-pub struct Refund<'info> {
-    #[account(seeds = [b"state", state.initializer.as_ref()], bump)]
-    pub state: Account<'info, State>,
-    #[account(mut, seeds = [b"treasury", state.key().as_ref()], bump)]
-    /// CHECK:
-    pub treasury: AccountInfo<'info>,
-    #[account(mut)]
-    /// CHECK:
-    pub user: Signer<'info>,
-    #[account(mut, seeds = [b"user-bid", user.key().as_ref(), state.key().as_ref()], bump)]
-    pub user_bid: Account<'info, UserBid>,
-    pub system_program: Program<'info, System>,
-}
+    /// Refund of a bid paid in an SPL token, in place of `refund`: the vault pays the
+    /// bid back to the token account of the bidder
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn refund_token(ctx: Context<RefundToken>) -> Result<()> {
+        instructions::refund_token(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct EndAuction<'info> {
-    #[account(mut, has_one = initializer, seeds = [b"state", state.initializer.as_ref()], bump)]
-    pub state: Account<'info, State>,
-    /// Seller
-    #[account(mut)]
-    /// CHECK:
-    pub initializer: Signer<'info>,
-    /// Account which holds tokens bidded by biders
-    #[account(mut, seeds = [b"treasury", state.key().as_ref()], bump)]
-    /// CHECK:
-    pub treasury: AccountInfo<'info>,
-    #[account(seeds = [b"user-bid", &state.highest_bidder_account.to_bytes(), state.key().as_ref()], bump = state.highest_bidder_bump)]
-    pub user_bid: Account<'info, UserBid>,
-    pub system_program: Program<'info, System>,
-}
+    /// After settlement, the winner can dispute the delivery of the item within
+    /// `dispute_window` seconds
+    pub fn open_dispute(ctx: Context<WinnerAction>) -> Result<()> {
+        instructions::open_dispute(ctx)
+    }
+
+    /// The winner confirms the item was delivered, which also resolves an open dispute
+    pub fn confirm_delivery(ctx: Context<WinnerAction>) -> Result<()> {
+        instructions::confirm_delivery(ctx)
+    }
+
+    /// Once the seller was paid, the winner can have the prize sent to another wallet
+    /// until it is claimed
+    pub fn set_receive_address(ctx: Context<WinnerAction>, address: Pubkey) -> Result<()> {
+        instructions::set_receive_address(ctx, address)
+    }
+
+    /// Once the seller was paid, the winner acknowledges the sale with an optional
+    /// hash of its delivery note and gets the rent of its user bid back. An escrowed
+    /// prize is sent to the token account of the receive address, passed with the prize
+    /// authority, the prize vault and the token program as remaining accounts
+    pub fn claim_prize<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimPrize<'info>>,
+        delivery_note_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::claim_prize(ctx, delivery_note_hash)
+    }
+
+    /// The winner approves the next milestone of the winning bid, its share of the
+    /// winning amount goes from the escrow to the beneficiary, less its share of the
+    /// platform fee
+    pub fn release_milestone(ctx: Context<ReleaseMilestone>) -> Result<()> {
+        instructions::release_milestone(ctx)
+    }
+
+    /// The winner approves the last milestone, which pays what is left of the winning
+    /// amount including the rounding remainder
+    pub fn claim_final_milestone(ctx: Context<ReleaseMilestone>) -> Result<()> {
+        instructions::claim_final_milestone(ctx)
+    }
+
+    /// A bidder takes an equal share of the seller bond once `seller_claim_window`
+    /// seconds passed after the deadline without the seller settling the auction
+    pub fn claim_seller_bond(ctx: Context<ClaimSellerBond>) -> Result<()> {
+        instructions::claim_seller_bond(ctx)
+    }
+
+    /// If the seller did not resolve a dispute within `dispute_window` seconds,
+    /// the winner receives the seller bond
+    pub fn claim_bond(ctx: Context<ClaimBond>) -> Result<()> {
+        instructions::claim_bond(ctx)
+    }
+
+    /// The seller gets the bond back once the winner confirmed the delivery,
+    /// or once the dispute window passed without a dispute. Shares already claimed by
+    /// the bidders are not returned
+    pub fn reclaim_bond(ctx: Context<ReclaimBond>) -> Result<()> {
+        instructions::reclaim_bond(ctx)
+    }
+
+    /// After the deadline, sends the withdrawal penalties kept in the treasury to the
+    /// beneficiary
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn claim_penalties(ctx: Context<ClaimPenalties>) -> Result<()> {
+        instructions::claim_penalties(ctx)
+    }
+
+    /// Once `refund_window` seconds passed after settlement, sends the escrow of a bid
+    /// nobody refunded to the unclaimed beneficiary, or to the initializer when it is
+    /// not set. The first sweep closes the refunds of every remaining bid
+    pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+        instructions::sweep_unclaimed(ctx)
+    }
 
-//
-/// A small utility function that allows us to transfer funds out of the Treasury.
-///
-/// # Arguments
-///
-/// * `treasury` - The escrow Token account
-/// * `destination_wallet` - The public key of the destination address (where to send funds)
-/// * `amount` - the amount of lamport that is sent from `treasury` to `user_receiving`
-///
-fn transfer_from_treasury<'info>(
-    treasury: &AccountInfo,
-    destination_wallet: &AccountInfo,
-    amount: u64,
-) -> Result<()> {
-    if **treasury.try_borrow_lamports()? < amount {
-        return err!(AuctionError::TreasuryInsufficientFunds);
-    }
-
-    **treasury.try_borrow_mut_lamports()? -= amount;
-    **destination_wallet.try_borrow_mut_lamports()? += amount;
-
-    Ok(())
+    /// Returns through return data the version of the program, the layout version of
+    /// its state accounts and the features it was built with
+    pub fn get_version(ctx: Context<GetVersion>) -> Result<ProgramVersion> {
+        instructions::get_version(ctx)
+    }
+
+    /// Returns through return data the seconds left before the deadline according to
+    /// the cluster clock, so countdowns do not depend on the client clock
+    pub fn get_remaining_time(ctx: Context<GetRemainingTime>) -> Result<RemainingTime> {
+        instructions::get_remaining_time(ctx)
+    }
+
+    /// After the deadline, returns through return data the winner of the auction and
+    /// the price it pays. Integrators should rely on it rather than on the layout of
+    /// the state account
+    #[access_control(check_auction_ended(&ctx.accounts.state))]
+    pub fn get_winner(ctx: Context<GetWinner>) -> Result<WinnerInfo> {
+        instructions::get_winner(ctx)
+    }
+
+    /// Anyone flags the auction as fraudulent once, with a `reason`, until its deadline.
+    /// Bidding is paused when the auction reaches `FLAG_THRESHOLD` flags
+    pub fn flag_auction(ctx: Context<FlagAuction>, reason: [u8; 128]) -> Result<()> {
+        instructions::flag_auction(ctx, reason)
+    }
+
+    /// The platform authority dismisses a flag, its rent goes back to the flagger. Bidding
+    /// resumes and the deadline moves by the pause once the flags fall below the threshold
+    pub fn dismiss_flag(ctx: Context<DismissFlag>) -> Result<()> {
+        instructions::dismiss_flag(ctx)
+    }
+
+    /// The platform authority registers `related` as a wallet the seller funded at
+    /// `funded_at`. It can not bid on the auction for the shill lookback of the config
+    pub fn register_related_account(
+        ctx: Context<RegisterRelatedAccount>,
+        related: Pubkey,
+        funded_at: i64,
+    ) -> Result<()> {
+        instructions::register_related_account(ctx, related, funded_at)
+    }
+
+    /// Grants loyalty status to a bidder whose profile counts `LOYALTY_THRESHOLD`
+    /// auctions. Anyone can call it, the profile is the proof
+    pub fn grant_loyalty_status(ctx: Context<GrantLoyaltyStatus>) -> Result<()> {
+        instructions::grant_loyalty_status(ctx)
+    }
+
+    /// The platform authority confirms the fraud and cancels the auction. Every bid
+    /// becomes refundable in full
+    pub fn confirm_fraud(ctx: Context<ConfirmFraud>) -> Result<()> {
+        instructions::confirm_fraud(ctx)
+    }
+
+    /// Returns through return data what `refund` would give back to `user` for its bid,
+    /// or the code of the error it would fail with. Nothing is modified
+    pub fn preview_refund(ctx: Context<PreviewRefund>) -> Result<RefundPreview> {
+        instructions::preview_refund(ctx)
+    }
+
+    /// Emits a `BidHistoryQueried` event with the most recent bids of the auction,
+    /// oldest first. Clients can also read `bid_history` from the state account
+    pub fn get_bid_history(ctx: Context<GetBidHistory>) -> Result<()> {
+        instructions::get_bid_history(ctx)
+    }
+
+    /// Checks that the treasury covers the outstanding withdrawal penalties, the seller
+    /// bond PDA the outstanding seller bond, and that every bid escrow passed as
+    /// `(bidder, user_bid, bid_escrow)` remaining accounts covers its outstanding bid.
+    /// Emits an `AuctionHealth` event and fails with `InvariantViolated` otherwise
+    pub fn verify_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, VerifyAuction<'info>>,
+    ) -> Result<()> {
+        instructions::verify_auction(ctx)
+    }
+
+    /// Checks every invariant of the auction at once, without changing anything: a
+    /// deadline, a treasury covering the penalties, a seller bond PDA covering the bond,
+    /// and escrows covering their bids. Before settlement the bid count has to match the
+    /// escrows and the highest bid their highest amount. Every escrow is passed as a
+    /// `(bidder, user_bid, bid_escrow)` remaining account, sorted by bidder. Emits a
+    /// `HealthCheckFailed` event with the first violation and fails with
+    /// `InvariantViolated`
+    pub fn health_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, VerifyAuction<'info>>,
+    ) -> Result<()> {
+        instructions::health_check(ctx)
+    }
+
+    /// Brings the state of an auction created by an older program version to the
+    /// current layout. The account grows if needed and the new fields start zeroed
+    pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+        instructions::migrate_state(ctx)
+    }
+
+    /// Moves the lamports of a bid placed before bid escrows from its user bid into its
+    /// `BidEscrow` PDA. Anyone may call it and pay the escrow rent, which goes to the rent
+    /// payer of the user bid once both are closed
+    pub fn migrate_bid_escrow(ctx: Context<MigrateBidEscrow>) -> Result<()> {
+        instructions::migrate_bid_escrow(ctx)
+    }
+
+    /// Creates the program-wide config. Only the program upgrade authority can call it,
+    /// and it becomes the authority allowed to update the config. The bid cooldown
+    /// starts at `BID_COOLDOWN_SECONDS` and the loyalty discount at `LOYALTY_DISCOUNT_BPS`
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        max_auction_duration: i64,
+        min_auction_duration: i64,
+        platform_fee_bps: u16,
+        fee_recipient: Pubkey,
+        min_bid_amount: u64,
+    ) -> Result<()> {
+        instructions::initialize_config(
+            ctx,
+            max_auction_duration,
+            min_auction_duration,
+            platform_fee_bps,
+            fee_recipient,
+            min_bid_amount,
+        )
+    }
+
+    /// Updates the program-wide config, only its authority can call it. New values
+    /// apply to the auctions initialized and the bids placed afterwards
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        max_auction_duration: i64,
+        min_auction_duration: i64,
+        platform_fee_bps: u16,
+        fee_recipient: Pubkey,
+        min_bid_amount: u64,
+        bid_cooldown_seconds: i64,
+        platform_authority: Pubkey,
+        shill_lookback_secs: i64,
+        loyalty_discount_bps: u16,
+    ) -> Result<()> {
+        instructions::update_config(
+            ctx,
+            max_auction_duration,
+            min_auction_duration,
+            platform_fee_bps,
+            fee_recipient,
+            min_bid_amount,
+            bid_cooldown_seconds,
+            platform_authority,
+            shill_lookback_secs,
+            loyalty_discount_bps,
+        )
+    }
+
+    /// Creates the program-wide recovery config. Only the program upgrade authority
+    /// can call it, and it becomes the admin allowed to recover stuck treasuries
+    pub fn initialize_recovery_config(
+        ctx: Context<InitializeRecoveryConfig>,
+        recovery_vault: Pubkey,
+        recovery_timeout: i64,
+    ) -> Result<()> {
+        instructions::initialize_recovery_config(ctx, recovery_vault, recovery_timeout)
+    }
+
+    /// Moves the funds left in a treasury, and in the bid escrows passed as remaining
+    /// accounts, to the recovery vault once the auction deadline is more than
+    /// `recovery_timeout` seconds in the past
+    pub fn emergency_recover<'info>(
+        ctx: Context<'_, '_, '_, 'info, EmergencyRecover<'info>>,
+    ) -> Result<()> {
+        instructions::emergency_recover(ctx)
+    }
 }
@@ -0,0 +1,71 @@
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+use pyth_sdk_solana::load_price_feed_from_account_info;
+
+use crate::errors::AuctionError;
+
+/// Prices older than this (in seconds) are rejected
+pub const MAX_PRICE_AGE_SECS: u64 = 60;
+/// Maximum accepted confidence interval, in basis points of the price
+pub const MAX_CONFIDENCE_BPS: u64 = 200;
+
+const CENTS_PER_USD: u128 = 100;
+
+/// Reads the SOL/USD price from a Pyth price feed account and returns the
+/// minimum amount of lamports that is worth `reserve_price_usd_cents`.
+///
+/// # Arguments
+///
+/// * `price_feed` - The Pyth SOL/USD price account
+/// * `reserve_price_usd_cents` - The reserve price of the auction in USD cents
+/// * `now` - The current unix timestamp, used to reject stale prices
+///
+pub fn reserve_price_in_lamports(
+    price_feed: &AccountInfo,
+    reserve_price_usd_cents: u64,
+    now: i64,
+) -> Result<u64> {
+    let feed = load_price_feed_from_account_info(price_feed)
+        .map_err(|_| error!(AuctionError::InvalidPriceFeed))?;
+    let price = feed
+        .get_price_no_older_than(now, MAX_PRICE_AGE_SECS)
+        .ok_or(AuctionError::StalePrice)?;
+
+    if price.price <= 0 {
+        return err!(AuctionError::StalePrice);
+    }
+    let sol_usd_price = price.price as u128;
+
+    // reject prices whose confidence interval is too wide to be trusted
+    let max_conf = sol_usd_price
+        .checked_mul(MAX_CONFIDENCE_BPS as u128)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if (price.conf as u128) * 10_000 > max_conf {
+        return err!(AuctionError::PriceConfidenceTooWide);
+    }
+
+    // the feed reports `sol_usd_price * 10^expo` USD per SOL, so the reserve in
+    // lamports is `cents * LAMPORTS_PER_SOL * 10^-expo / (100 * sol_usd_price)`
+    let scale = 10u128
+        .checked_pow(price.expo.unsigned_abs())
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    let mut numerator = (reserve_price_usd_cents as u128)
+        .checked_mul(LAMPORTS_PER_SOL as u128)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    let mut denominator = sol_usd_price
+        .checked_mul(CENTS_PER_USD)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if price.expo < 0 {
+        numerator = numerator
+            .checked_mul(scale)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    } else {
+        denominator = denominator
+            .checked_mul(scale)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+    }
+    let reserve_in_lamports = numerator
+        .checked_div(denominator)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+
+    Ok(u64::try_from(reserve_in_lamports).unwrap_or(u64::MAX))
+}
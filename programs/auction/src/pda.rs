@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+
+use crate::state::auction_index_seed;
+
+/// Addresses and bumps of the PDAs of the program, derived the way its instructions
+/// check them. Usable on chain and by off-chain clients alike
+pub struct PdaHelpers;
+
+impl PdaHelpers {
+    /// State of the first auction of `initializer`
+    pub fn state_pda(initializer: &Pubkey) -> (Pubkey, u8) {
+        Self::indexed_state_pda(initializer, 0)
+    }
+
+    /// State of the auction `auction_index` of `initializer`
+    pub fn indexed_state_pda(initializer: &Pubkey, auction_index: u32) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"state",
+                initializer.as_ref(),
+                &auction_index_seed(auction_index),
+            ],
+            &crate::ID,
+        )
+    }
+
+    /// Ledger of the treasury of `state`
+    pub fn treasury_pda(state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"treasury", state.as_ref()], &crate::ID)
+    }
+
+    /// System account holding the treasury lamports of `state`
+    pub fn treasury_authority_pda(state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"treasury-auth", state.as_ref()], &crate::ID)
+    }
+
+    /// System account holding the seller bond of `state`, apart from the treasury
+    pub fn seller_bond_pda(state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"bond", state.as_ref()], &crate::ID)
+    }
+
+    /// Token vault of `state`, the associated token account of its treasury authority
+    /// for `bid_mint`. Not a PDA of the program, it has no bump
+    pub fn token_vault_address(state: &Pubkey, bid_mint: &Pubkey) -> Pubkey {
+        get_associated_token_address(&Self::treasury_authority_pda(state).0, bid_mint)
+    }
+
+    /// System account owning the prize vault of `state`
+    pub fn prize_authority_pda(state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"prize", state.as_ref()], &crate::ID)
+    }
+
+    /// Prize vault of `state`, the associated token account of its prize authority for
+    /// `prize_mint`. Not a PDA of the program, it has no bump
+    pub fn prize_vault_address(state: &Pubkey, prize_mint: &Pubkey) -> Pubkey {
+        get_associated_token_address(&Self::prize_authority_pda(state).0, prize_mint)
+    }
+
+    /// Highest bids of `state`
+    pub fn leaderboard_pda(state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"leaderboard", state.as_ref()], &crate::ID)
+    }
+
+    /// Bid of `user` on `state`
+    pub fn user_bid_pda(user: &Pubkey, state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"user-bid", user.as_ref(), state.as_ref()], &crate::ID)
+    }
+
+    /// Escrow holding the lamports of the bid of `user` on `state`
+    pub fn bid_escrow_pda(user: &Pubkey, state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"escrow", user.as_ref(), state.as_ref()], &crate::ID)
+    }
+
+    /// Sealed bid or best-and-final offer of `user` on `state`
+    pub fn commit_bid_pda(user: &Pubkey, state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"commit", user.as_ref(), state.as_ref()], &crate::ID)
+    }
+
+    /// Wallet `related` found funded by the seller of `state`
+    pub fn sybil_check_pda(related: &Pubkey, state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"sybil", state.as_ref(), related.as_ref()], &crate::ID)
+    }
+
+    /// Lifetime statistics of `bidder`
+    pub fn profile_pda(bidder: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"profile", bidder.as_ref()], &crate::ID)
+    }
+
+    /// Loyalty status of `bidder`
+    pub fn loyalty_pda(bidder: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"loyalty", bidder.as_ref()], &crate::ID)
+    }
+
+    /// Sale history of `seller`
+    pub fn seller_profile_pda(seller: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"seller-profile", seller.as_ref()], &crate::ID)
+    }
+
+    /// Next auction index of `initializer`
+    pub fn seller_counter_pda(initializer: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"seller-counter", initializer.as_ref()], &crate::ID)
+    }
+
+    /// Description of `state`
+    pub fn description_pda(state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"desc", state.as_ref()], &crate::ID)
+    }
+
+    /// Final statistics of `state`
+    pub fn summary_pda(state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"summary", state.as_ref()], &crate::ID)
+    }
+
+    /// Tax record of the sale of `state`
+    pub fn tax_record_pda(state: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"tax", state.as_ref()], &crate::ID)
+    }
+
+    /// Program config
+    pub fn config_pda() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"config"], &crate::ID)
+    }
+
+    /// Program-wide count of the auctions
+    pub fn registry_pda() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"registry"], &crate::ID)
+    }
+
+    /// Registry page `page`, listing `REGISTRY_PAGE_SIZE` auctions
+    pub fn registry_page_pda(page: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"registry-page", &page.to_le_bytes()], &crate::ID)
+    }
+}
@@ -0,0 +1,38 @@
+/// Asking price of a Dutch auction `elapsed` seconds after its start. The price falls
+/// linearly from `start_price` to `reserve_price` over `duration` seconds and stays at
+/// `reserve_price` afterwards
+pub fn compute_dutch_price(
+    start_price: u64,
+    reserve_price: u64,
+    duration: i64,
+    elapsed: i64,
+) -> u64 {
+    if start_price <= reserve_price || duration <= 0 || elapsed >= duration {
+        return reserve_price;
+    }
+    if elapsed <= 0 {
+        return start_price;
+    }
+
+    // 0 < elapsed < duration, so the drop is below start_price - reserve_price
+    let drop = (start_price - reserve_price) as u128 * elapsed as u128 / duration as u128;
+    start_price - drop as u64
+}
+
+/// Asking price of a Dutch auction that drops its price every `step` seconds instead of
+/// continuously: the price stays at the price of the last full step. A `step` of 0
+/// falls linearly like `compute_dutch_price`
+pub fn compute_stepped_dutch_price(
+    start_price: u64,
+    reserve_price: u64,
+    duration: i64,
+    elapsed: i64,
+    step: i64,
+) -> u64 {
+    let elapsed = if step > 0 && elapsed > 0 {
+        elapsed - elapsed % step
+    } else {
+        elapsed
+    };
+    compute_dutch_price(start_price, reserve_price, duration, elapsed)
+}
@@ -0,0 +1,19 @@
+/// Index of the winner of a raffle among the contributions `totals`. Each contribution
+/// holds the range of tickets that follows the range of the previous one, the ticket
+/// drawn from `randomness` picks the range it falls in, so a bidder wins with a chance
+/// proportional to its contribution. `None` when nothing was contributed
+pub fn raffle_winner(randomness: [u8; 32], totals: &[u64]) -> Option<usize> {
+    let pool: u128 = totals.iter().map(|total| *total as u128).sum();
+    if pool == 0 {
+        return None;
+    }
+    let mut random_bytes = [0u8; 16];
+    random_bytes.copy_from_slice(&randomness[..16]);
+    let ticket = u128::from_le_bytes(random_bytes) % pool;
+
+    let mut range_end: u128 = 0;
+    totals.iter().position(|total| {
+        range_end += *total as u128;
+        ticket < range_end
+    })
+}
@@ -0,0 +1,1621 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    BPS_DENOMINATOR, CURRENT_STATE_VERSION, DISCRIMINATOR_SIZE, FLAG_REASON_LEN, LEADERBOARD_SIZE,
+    LOYALTY_THRESHOLD, MAX_BUNDLE_ITEMS, MAX_TITLE_LEN, MAX_URI_LEN, MIN_AUCTION_DURATION_SECS,
+    REGISTRY_PAGE_SIZE, STATE_SPACE,
+};
+use crate::errors::AuctionError;
+use crate::pricing::compute_stepped_dutch_price;
+use crate::utils::{bps_of, validate_auction_duration};
+
+#[account]
+pub struct State {
+    pub deadline: i64,
+    pub initializer: Pubkey,
+    pub seller_payed: bool,
+    pub highest_bid_amount: u64,
+    pub highest_bidder_account: Pubkey,
+    pub highest_bidder_bump: u8,
+    pub state_bump: u8,
+    pub treasury_bump: u8,
+    pub reserve_price: u64,
+    pub reserve_price_usd_cents: u64,
+    pub price_feed: Pubkey,
+    pub reserve_not_met: bool,
+    pub seller_bond: u64,
+    pub dispute_window: i64,
+    pub dispute_status: DisputeStatus,
+    pub dispute_opened_at: i64,
+    pub settled_at: i64,
+    pub ended_early: bool,
+    pub refund_window: i64,
+    pub unclaimed_beneficiary: Pubkey,
+    pub unclaimed_swept: bool,
+    pub cancel_compensation_bps: u16,
+    pub cancelled: bool,
+    pub auction_type: AuctionType,
+    pub second_highest_bid_amount: u64,
+    pub beneficiary: Pubkey,
+    pub settlement_authority: Option<Pubkey>,
+    pub start_time: i64,
+    pub start_price: u64,
+    pub reveal_start: i64,
+    pub bid_count: u32,
+    pub withdrawal_penalty_bps: u16,
+    pub penalties_owed: u64,
+    pub version: u8,
+    pub bid_history_count: u32,
+    pub bid_history: [BidRecord; 10],
+    pub title: String,
+    pub uri: String,
+    pub item_hash: [u8; 32],
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub vrf_account: Pubkey,
+    pub random_end_slot: u64,
+    pub paused: bool,
+    pub paused_at: i64,
+    pub total_paused_secs: i64,
+    pub min_bid_increment: u64,
+    pub highest_proxy_max: u64,
+    pub round: u16,
+    /// Index of the auction among the auctions of its initializer, 0 for its first
+    /// auction and for every auction created before indexes existed
+    pub auction_index: u32,
+    /// Length of a round, a perpetual auction starts its next round with it
+    pub auction_duration: i64,
+    /// Items sold together to the winner, the first `bundle_size` entries are set
+    pub bundle_items: [Pubkey; MAX_BUNDLE_ITEMS],
+    pub bundle_size: u8,
+    /// Position of the auction in the registry, starting at 1. 0 for auctions created
+    /// before the registry
+    pub sequence: u64,
+    /// Highest offer a reverse auction accepts
+    pub max_acceptable_price: u64,
+    /// Lowest offer of a reverse auction, paid to the lowest bidder out of the budget
+    pub lowest_bid_amount: u64,
+    pub lowest_bidder_account: Pubkey,
+    pub lowest_bidder_bump: u8,
+    /// Whether the winner claimed the prize, which closed its user bid
+    pub prize_claimed: bool,
+    /// Wallet the winner wants the prize sent to, the winner itself when unset
+    pub receive_address: Pubkey,
+    /// Price the winner paid, set at settlement
+    pub winning_amount: u64,
+    /// Fraud flags raised by the community and not dismissed
+    pub flag_count: u32,
+    /// Seconds between two drops of the asking price of a Dutch auction, 0 when the
+    /// price falls linearly
+    pub price_step_secs: i64,
+    /// Seconds after the deadline the seller has to settle before the bidders can claim
+    /// the seller bond, 0 when the bond is never claimed by the bidders
+    pub seller_claim_window: i64,
+    /// Part of the seller bond already paid to bidders of an auction left unsettled
+    pub bond_claimed: u64,
+    /// Lamports a sealed bid deposits with its commitment, forfeited to the beneficiary
+    /// when the bid is never revealed
+    pub commit_deposit: u64,
+    /// Tranches the winning bid is paid to the seller in, 0 when it is paid at settlement
+    pub milestone_count: u8,
+    /// Tranches the winner approved so far
+    pub milestones_released: u8,
+    /// Program that has to agree before the auction settles, see `interface`. Default
+    /// for auctions without a condition
+    pub condition_program: Pubkey,
+    /// Account the condition program reads the condition from
+    pub condition_account: Pubkey,
+    /// Final seconds of a candle auction its effective end is drawn from, 0 when every
+    /// bid placed before the deadline counts
+    pub candle_window: i64,
+    /// Effective end of a candle auction drawn after the deadline, 0 until it is drawn
+    pub candle_cutoff: i64,
+    /// Shares of the item a fractional auction sells, 0 when the item is sold whole
+    pub total_shares: u64,
+    /// Shares the fractional bids were allocated so far
+    pub allocated_shares: u64,
+    /// Shares minted to their bidders at settlement
+    pub minted_shares: u64,
+    /// Slot hash the winner of a raffle was drawn from, zero until the draw
+    pub raffle_randomness: [u8; 32],
+    /// Identical units sold to the highest bidders at a uniform price, 0 or 1 when a
+    /// single item is sold
+    pub quantity: u16,
+    /// Maximum price the buyer of a reverse auction escrowed in the treasury at
+    /// initialize, until settlement pays the lowest offer out of it
+    pub budget: u64,
+    /// Length of a round of an English auction that ascends in ticks, 0 when bids
+    /// are free. Bids of round N have to match `start_price + N * tick`
+    pub round_duration_secs: i64,
+    /// Price increase from one round to the next
+    pub tick: u64,
+    /// Rounds whose price was accepted so far
+    pub rounds_accepted: u32,
+    /// Last round whose price was accepted, the auction ends one round later
+    pub last_accepted_round: u64,
+    /// Fee every bid of a penny auction pays to the seller, on top of its price
+    pub bid_fee: u64,
+    /// Seconds every bid of a penny auction adds to the deadline
+    pub extension_secs: i64,
+    /// Bid fees held in the state account until they are paid to the seller
+    pub fees_collected: u64,
+    /// Whether each winner of a multi-unit auction pays its own bid instead of the
+    /// clearing price
+    pub pay_as_bid: bool,
+    /// Bump of the treasury authority, the system account holding the treasury lamports
+    pub treasury_authority_bump: u8,
+    /// Top bidders of the open phase who make a sealed best-and-final offer, 0 when the
+    /// auction has no final round
+    pub finalist_count: u8,
+    /// End of the open phase of an auction with a final round, bids are taken until then
+    pub open_deadline: i64,
+    /// End of the final commit phase, the finalists reveal their offers until the deadline
+    pub final_deadline: i64,
+    /// Best-and-final offers revealed so far
+    pub finals_revealed: u8,
+    /// Units of a multi-unit Dutch auction still for sale, each `accept_price` buys one
+    pub units_remaining: u16,
+    /// SPL token mint the bids are paid in, default when they are paid in lamports
+    pub bid_mint: Pubkey,
+    /// Whether the seller added a description account to the auction
+    pub has_description: bool,
+    /// SPL token mint of the prize escrowed for the winner, default without a prize
+    pub prize_mint: Pubkey,
+    /// Prize tokens the seller deposits before bidding opens
+    pub prize_amount: u64,
+    /// Bump of the prize authority PDA owning the prize vault, set by the deposit
+    pub prize_authority_bump: u8,
+    /// Whether the prize is in its vault, bids are rejected until it is
+    pub prize_deposited: bool,
+    /// Whether the seller took the prize back
+    pub prize_reclaimed: bool,
+    /// Bump of the seller bond PDA, which holds the bond apart from the treasury
+    pub seller_bond_bump: u8,
+}
+
+impl State {
+    /// Whether the account was written by this version of the program. Older accounts
+    /// have to go through `migrate_state` first
+    pub fn is_current_version(&self) -> bool {
+        self.version == CURRENT_STATE_VERSION
+    }
+
+    /// Receiver of the refunds that were not claimed within the refund window
+    pub fn unclaimed_destination(&self) -> Pubkey {
+        if self.unclaimed_beneficiary == Pubkey::default() {
+            self.authority
+        } else {
+            self.unclaimed_beneficiary
+        }
+    }
+
+    /// Makes `bidder` the highest bidder. An equal bid takes over, a lower bid is
+    /// rejected unless the auction is a Vickrey auction, where it can still set the
+    /// price paid by the winner, or a multi-unit auction, where it can still win a unit
+    pub fn record_bid(&mut self, bidder: Pubkey, amount: u64, bidder_bump: u8) -> Result<()> {
+        if amount < self.highest_bid_amount {
+            if !self.takes_lower_bids() {
+                return err!(AuctionError::BidAmountTooSmall);
+            }
+            self.second_highest_bid_amount = self.second_highest_bid_amount.max(amount);
+        } else {
+            self.second_highest_bid_amount = self.highest_bid_amount;
+            self.highest_bid_amount = amount;
+            self.highest_bidder_account = bidder;
+            self.highest_bidder_bump = bidder_bump;
+        }
+
+        self.bid_count = self
+            .bid_count
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    /// Whether a bid below the highest one still counts: it can set the Vickrey price or
+    /// win one of several units
+    pub fn takes_lower_bids(&self) -> bool {
+        self.auction_type == AuctionType::Vickrey || self.is_multi_unit()
+    }
+
+    /// Counts a contribution to a raffle. Contributions do not compete with each other,
+    /// the winner is drawn after the deadline
+    pub fn record_contribution(&mut self) -> Result<()> {
+        self.bid_count = self
+            .bid_count
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    /// Whether the losing bids are paid to the seller with the winning one, as in all-pay
+    /// auctions and raffles
+    pub fn pays_losing_bids(&self) -> bool {
+        matches!(self.auction_type, AuctionType::AllPay | AuctionType::Raffle)
+    }
+
+    /// Makes `bidder` the lowest bidder of a reverse auction. Offers above the maximum
+    /// acceptable price, or not below the lowest offer, are rejected
+    pub fn record_offer(&mut self, bidder: Pubkey, amount: u64, bidder_bump: u8) -> Result<()> {
+        if amount > self.max_acceptable_price {
+            return err!(AuctionError::OfferAboveMaximumPrice);
+        }
+        if self.lowest_bid_amount != 0 && amount >= self.lowest_bid_amount {
+            return err!(AuctionError::OfferNotLowest);
+        }
+        self.lowest_bid_amount = amount;
+        self.lowest_bidder_account = bidder;
+        self.lowest_bidder_bump = bidder_bump;
+
+        self.bid_count = self
+            .bid_count
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    /// Bidder the auction settles with: the lowest bidder of a reverse auction, the
+    /// highest bidder otherwise
+    pub fn winner(&self) -> Pubkey {
+        if self.auction_type == AuctionType::Reverse {
+            self.lowest_bidder_account
+        } else {
+            self.highest_bidder_account
+        }
+    }
+
+    /// Bump of the user bid PDA of `winner()`
+    pub fn winner_bump(&self) -> u8 {
+        if self.auction_type == AuctionType::Reverse {
+            self.lowest_bidder_bump
+        } else {
+            self.highest_bidder_bump
+        }
+    }
+
+    /// Wallet `claim_prize` sends the escrowed prize to: the receive address chosen by
+    /// the winner, or the winner itself
+    pub fn prize_receiver(&self) -> Pubkey {
+        if self.receive_address == Pubkey::default() {
+            self.winner()
+        } else {
+            self.receive_address
+        }
+    }
+
+    /// Extra seed of the state PDA. Empty for index 0, so the first auction of a seller
+    /// keeps the address `["state", initializer]`
+    pub fn index_seed(&self) -> Vec<u8> {
+        auction_index_seed(self.auction_index)
+    }
+
+    /// Items of the bundle, empty when the auction sells a single item
+    pub fn bundle(&self) -> &[Pubkey] {
+        &self.bundle_items[..self.bundle_size as usize]
+    }
+
+    /// Adds `item` to the bundle, which holds at most `MAX_BUNDLE_ITEMS` items
+    pub fn add_bundle_item(&mut self, item: Pubkey) -> Result<()> {
+        let index = self.bundle_size as usize;
+        if index >= MAX_BUNDLE_ITEMS {
+            return err!(AuctionError::BundleItemCountExceeded);
+        }
+        self.bundle_items[index] = item;
+        self.bundle_size += 1;
+        Ok(())
+    }
+
+    /// Seed of the registry page listing this auction
+    pub fn registry_page_seed(&self) -> [u8; 8] {
+        (self.sequence.saturating_sub(1) / REGISTRY_PAGE_SIZE as u64).to_le_bytes()
+    }
+
+    /// Size of the account holding this state, it grows with the metadata strings
+    pub fn space(title: &str, uri: &str) -> usize {
+        STATE_SPACE + title.len() + uri.len()
+    }
+
+    /// Lets the proxy of the previous highest bidder answer the bid that just took the
+    /// lead at `highest_bid_amount`. Each proxy bids `min_bid_increment` over the other
+    /// up to its maximum, an equal maximum goes to the earlier bidder. Returns whether
+    /// the previous highest bidder won the lead back
+    pub fn apply_proxy_bids(
+        &mut self,
+        previous_bidder: Pubkey,
+        previous_bump: u8,
+        previous_max: u64,
+        max_proxy_bid: u64,
+    ) -> Result<bool> {
+        let new_bid = self.highest_bid_amount;
+        let new_max = new_bid.max(max_proxy_bid);
+
+        if previous_max <= new_bid {
+            self.highest_proxy_max = max_proxy_bid;
+            return Ok(false);
+        }
+        if new_max > previous_max {
+            self.highest_bid_amount = previous_max
+                .checked_add(self.min_bid_increment)
+                .ok_or(AuctionError::ArithmeticOverflow)?
+                .min(new_max);
+            self.second_highest_bid_amount = previous_max;
+            self.highest_proxy_max = new_max;
+            return Ok(false);
+        }
+
+        self.highest_bid_amount = new_max
+            .checked_add(self.min_bid_increment)
+            .ok_or(AuctionError::ArithmeticOverflow)?
+            .min(previous_max);
+        self.second_highest_bid_amount = new_max;
+        self.highest_bidder_account = previous_bidder;
+        self.highest_bidder_bump = previous_bump;
+        self.highest_proxy_max = previous_max;
+        Ok(true)
+    }
+
+    /// Writes a bid to the bid history, overwriting the oldest record once the history
+    /// is full
+    pub fn push_bid_history(&mut self, bidder: Pubkey, amount: u64, timestamp: i64) -> Result<()> {
+        let index = self.bid_history_count as usize % self.bid_history.len();
+        self.bid_history[index] = BidRecord {
+            bidder,
+            amount,
+            timestamp,
+        };
+        self.bid_history_count = self
+            .bid_history_count
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    /// The most recent bids of the bid history, oldest first
+    pub fn recent_bids(&self) -> Vec<BidRecord> {
+        let capacity = self.bid_history.len();
+        let count = self.bid_history_count as usize;
+        (count.saturating_sub(capacity)..count)
+            .map(|index| self.bid_history[index % capacity])
+            .collect()
+    }
+
+    /// Whether the bids are paid in the SPL token `bid_mint` and escrowed in its token
+    /// vault, through the token instructions only
+    pub fn is_token_denominated(&self) -> bool {
+        self.bid_mint != Pubkey::default()
+    }
+
+    /// Whether the seller escrows an SPL token prize for the winner
+    pub fn has_prize_escrow(&self) -> bool {
+        self.prize_mint != Pubkey::default()
+    }
+
+    /// Whether bids wait for the seller to deposit the prize
+    pub fn awaits_prize(&self) -> bool {
+        self.has_prize_escrow() && !self.prize_deposited
+    }
+
+    /// Whether the seller can take its prize back: the auction was voided, did not meet
+    /// its reserve or took no bid
+    pub fn prize_reclaimable(&self) -> bool {
+        self.prize_deposited
+            && !self.prize_claimed
+            && !self.prize_reclaimed
+            && (self.cancelled
+                || self.reserve_not_met
+                || (self.highest_bid_amount == 0 && self.bid_count == 0))
+    }
+
+    /// Whether the auction sells shares of its item to several bidders
+    pub fn is_fractional(&self) -> bool {
+        self.total_shares > 0
+    }
+
+    /// Whether the auction sells several identical units, to its highest bids or, in a
+    /// Dutch auction, one at a time at the asking price
+    pub fn is_multi_unit(&self) -> bool {
+        self.quantity > 1
+    }
+
+    /// Sells one unit of a multi-unit Dutch auction at `now`. Selling the last unit
+    /// closes the bidding window, which lets the auction settle right away
+    pub fn sell_unit(&mut self, now: i64) -> Result<()> {
+        self.units_remaining = self
+            .units_remaining
+            .checked_sub(1)
+            .ok_or(AuctionError::Finished)?;
+        if self.units_remaining == 0 {
+            self.deadline = now;
+            self.ended_early = true;
+        }
+        Ok(())
+    }
+
+    /// Whether the price ascends in ticks on a fixed round cadence
+    pub fn is_round_based(&self) -> bool {
+        self.round_duration_secs > 0
+    }
+
+    /// Whether the open phase ends with a sealed best-and-final round among its top
+    /// bidders
+    pub fn has_final_round(&self) -> bool {
+        self.finalist_count > 0
+    }
+
+    /// Records the best-and-final offer of a finalist. The first revealed offer replaces
+    /// the result of the open phase, a later one only takes over when it is higher: a
+    /// tie goes to the finalist who revealed first
+    pub fn record_final_offer(
+        &mut self,
+        bidder: Pubkey,
+        amount: u64,
+        bidder_bump: u8,
+    ) -> Result<()> {
+        if self.finals_revealed == 0 || amount > self.highest_bid_amount {
+            self.highest_bid_amount = amount;
+            self.highest_bidder_account = bidder;
+            self.highest_bidder_bump = bidder_bump;
+        }
+        self.finals_revealed = self
+            .finals_revealed
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    /// Round of a round-based auction at `now`, counted from 0 at its start
+    pub fn current_round(&self, now: i64) -> u64 {
+        (now.saturating_sub(self.start_time).max(0) / self.round_duration_secs.max(1)) as u64
+    }
+
+    /// Price bids of `round` have to match: the start price raised by one tick per round
+    pub fn round_price(&self, round: u64) -> Result<u64> {
+        self.tick
+            .checked_mul(round)
+            .and_then(|raise| raise.checked_add(self.start_price))
+            .ok_or_else(|| error!(AuctionError::ArithmeticOverflow))
+    }
+
+    /// Accepts the price of the round at `now` with a bid of `amount`. Only the first bid
+    /// of a round at exactly its price is accepted, and the deadline moves to the end of
+    /// the next round: the auction ends once a round passes without acceptance
+    pub fn accept_round(&mut self, amount: u64, now: i64) -> Result<()> {
+        let round = self.current_round(now);
+        if self.rounds_accepted > 0 && round <= self.last_accepted_round {
+            return err!(AuctionError::RoundAlreadyAccepted);
+        }
+        if amount != self.round_price(round)? {
+            return err!(AuctionError::BidNotAtRoundPrice);
+        }
+        let rounds_to_deadline = i64::try_from(round)
+            .ok()
+            .and_then(|round| round.checked_add(2))
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        self.deadline = self
+            .round_duration_secs
+            .checked_mul(rounds_to_deadline)
+            .and_then(|elapsed| elapsed.checked_add(self.start_time))
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        self.last_accepted_round = round;
+        self.rounds_accepted = self
+            .rounds_accepted
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Charges the fee of a bid of `amount` on a penny auction. The bid has to raise the
+    /// price by exactly `min_bid_increment` and pushes the deadline back by
+    /// `extension_secs`
+    pub fn record_penny_bid(&mut self, amount: u64) -> Result<()> {
+        let price = self
+            .highest_bid_amount
+            .checked_add(self.min_bid_increment)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        if amount != price {
+            return err!(AuctionError::InvalidPennyBid);
+        }
+        self.deadline = self
+            .deadline
+            .checked_add(self.extension_secs)
+            .ok_or(AuctionError::DeadlineOverflow)?;
+        self.fees_collected = self
+            .fees_collected
+            .checked_add(self.bid_fee)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Price a winner of a multi-unit auction pays for its unit: its own `bid` when
+    /// the auction is pay-as-bid or Dutch, the `clearing_price` of the last winner
+    /// otherwise
+    pub fn unit_price(&self, bid: u64, clearing_price: u64) -> u64 {
+        if self.pay_as_bid || self.auction_type == AuctionType::Dutch {
+            bid
+        } else {
+            clearing_price
+        }
+    }
+
+    /// Lowest payment for `shares` shares of a fractional auction: their part of the
+    /// reserve price, which is the price of the whole item, rounded up
+    pub fn share_price(&self, shares: u64) -> Result<u64> {
+        let total_shares = self.total_shares.max(1) as u128;
+        let price = (self.reserve_price as u128)
+            .checked_mul(shares as u128)
+            .and_then(|price| price.checked_add(total_shares - 1))
+            .ok_or(AuctionError::ArithmeticOverflow)?
+            / total_shares;
+        u64::try_from(price).map_err(|_| error!(AuctionError::ArithmeticOverflow))
+    }
+
+    /// Allocates `shares` shares of a fractional auction to a bid of `amount`, first come
+    /// first served. Requests beyond the shares left are rejected, as are payments below
+    /// the share price
+    pub fn allocate_shares(&mut self, shares: u64, amount: u64) -> Result<()> {
+        if shares == 0 {
+            return err!(AuctionError::InvalidShareRequest);
+        }
+        let allocated = self
+            .allocated_shares
+            .checked_add(shares)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        if allocated > self.total_shares {
+            return err!(AuctionError::InsufficientSharesAvailable);
+        }
+        if amount < self.share_price(shares)? {
+            return err!(AuctionError::BidBelowAskingPrice);
+        }
+        self.allocated_shares = allocated;
+
+        self.bid_count = self
+            .bid_count
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    /// First invariant that the bids `amounts`, one per escrow of an unsettled auction,
+    /// break: the bid count has to match the escrows and the highest bid their highest
+    /// amount, or the lowest offer of a reverse auction their lowest. The highest bid of
+    /// a raffle, a drawn candle auction or a fractional auction is not the highest amount
+    pub fn bid_violation(&self, amounts: &[u64]) -> Option<HealthViolation> {
+        if self.is_settled() {
+            return None;
+        }
+        if self.bid_count as usize != amounts.len() {
+            return Some(HealthViolation::BidCountMismatch);
+        }
+        let (leading, expected) = match self.auction_type {
+            AuctionType::Reverse => (self.lowest_bid_amount, amounts.iter().copied().min()),
+            AuctionType::Raffle => return None,
+            _ if self.candle_cutoff != 0 || self.is_fractional() => return None,
+            _ => (self.highest_bid_amount, amounts.iter().copied().max()),
+        };
+        if leading != expected.unwrap_or(0) {
+            return Some(HealthViolation::HighestBidMismatch);
+        }
+        None
+    }
+
+    /// Whether the bid history of a candle auction has no room for a bid at `timestamp`.
+    /// The bids of the final window and the last bid before it have to stay in the
+    /// history until the effective end is drawn
+    pub fn candle_history_full(&self, timestamp: i64) -> bool {
+        let window_start = self.deadline.saturating_sub(self.candle_window);
+        if self.candle_window <= 0 || timestamp < window_start {
+            return false;
+        }
+        let window_bids = self
+            .recent_bids()
+            .iter()
+            .filter(|record| record.timestamp >= window_start)
+            .count();
+        window_bids + 1 >= self.bid_history.len()
+    }
+
+    /// Whether bidding on a candle auction closed at `slot`, always false until the
+    /// VRF resolved the end slot
+    pub fn candle_ended(&self, slot: u64) -> bool {
+        self.random_end_slot > 0 && slot >= self.random_end_slot
+    }
+
+    /// Whether the auction was paid out, failed its reserve or was cancelled
+    pub fn is_settled(&self) -> bool {
+        self.seller_payed || self.reserve_not_met || self.cancelled
+    }
+
+    /// Whether `signer` can settle the auction: the seller, or the key the seller
+    /// delegated settlement to
+    pub fn can_settle(&self, signer: Pubkey) -> bool {
+        signer == self.authority || self.settlement_authority == Some(signer)
+    }
+
+    /// Price the winner pays for a winning bid of `winning_bid`: the second highest
+    /// bid in a Vickrey auction, or its reserve price when the winner was the only
+    /// bidder. The winning bid in every other auction
+    pub fn price_to_pay(&self, winning_bid: u64) -> u64 {
+        if self.auction_type != AuctionType::Vickrey {
+            return winning_bid;
+        }
+        if self.second_highest_bid_amount > 0 {
+            self.second_highest_bid_amount
+        } else {
+            // the reserve stands in for the missing second bid
+            self.reserve_price.max(self.start_price).min(winning_bid)
+        }
+    }
+
+    /// Part of the seller bond the seller bond PDA still owes to the seller, the winner
+    /// or the bidders of an auction left unsettled
+    pub fn bond_outstanding(&self) -> u64 {
+        match self.dispute_status {
+            DisputeStatus::Slashed | DisputeStatus::Reclaimed => 0,
+            _ => self.seller_bond.saturating_sub(self.bond_claimed),
+        }
+    }
+
+    /// Lamports the treasury holds for someone on top of its rent: the withdrawal
+    /// penalties and the budget of a reverse auction
+    pub fn treasury_outstanding(&self) -> Result<u64> {
+        self.penalties_owed
+            .checked_add(self.budget)
+            .ok_or_else(|| error!(AuctionError::ArithmeticOverflow))
+    }
+
+    /// Lamports of `bidder`'s escrow that still belong to someone. The winning bid
+    /// leaves the escrow once the seller was paid, or as its milestones are released.
+    /// Offers of a reverse auction are never escrowed, a bid deposited into a yield vault
+    /// left the escrow
+    pub fn escrow_outstanding(&self, bidder: Pubkey, user_bid: &UserBid) -> u64 {
+        if user_bid.refunded
+            || user_bid.deposit_share > 0
+            || self.auction_type == AuctionType::Reverse
+        {
+            0
+        } else if self.seller_payed
+            && bidder == self.highest_bidder_account
+            && !self.is_multi_unit()
+        {
+            self.milestones_outstanding()
+        } else {
+            user_bid.amount
+        }
+    }
+
+    /// Part of the winning amount paid in each milestone but the last one, which also
+    /// gets the rounding remainder
+    pub fn milestone_amount(&self) -> u64 {
+        if self.milestone_count == 0 {
+            0
+        } else {
+            self.winning_amount / self.milestone_count as u64
+        }
+    }
+
+    /// Part of the winning amount still held in the winner's escrow for the milestones
+    /// that were not released
+    pub fn milestones_outstanding(&self) -> u64 {
+        if self.milestones_released >= self.milestone_count {
+            return 0;
+        }
+        let released = self.milestone_amount() * self.milestones_released as u64;
+        self.winning_amount.saturating_sub(released)
+    }
+
+    /// Lamports the bidder escrows for `user_bid`, nothing for an offer of a reverse
+    /// auction since the buyer pays it at settlement
+    pub fn escrow_required(&self, user_bid: &UserBid) -> u64 {
+        if self.auction_type == AuctionType::Reverse {
+            0
+        } else {
+            user_bid.escrowed()
+        }
+    }
+
+    /// Lamports the seller owes the highest bidder to cancel the auction,
+    /// `cancel_compensation_bps` of the highest bid
+    pub fn cancel_compensation(&self) -> Result<u64> {
+        bps_of(self.highest_bid_amount, self.cancel_compensation_bps)
+    }
+
+    /// Asking price of a Dutch auction at `now`, from its start price down to its reserve
+    /// price at the deadline, in steps of `price_step_secs` when set
+    pub fn asking_price(&self, now: i64) -> u64 {
+        compute_stepped_dutch_price(
+            self.start_price,
+            self.reserve_price,
+            self.deadline.saturating_sub(self.start_time),
+            now.saturating_sub(self.start_time),
+            self.price_step_secs,
+        )
+    }
+
+    /// Whether the auction still accepts bids at `now`. The deadline itself
+    /// already belongs to the ended auction
+    pub fn status(&self, now: i64) -> AuctionStatus {
+        if now < self.deadline {
+            AuctionStatus::Active
+        } else {
+            AuctionStatus::Ended
+        }
+    }
+}
+
+/// Extra seed of the state PDA of the auction `auction_index` of an initializer
+pub fn auction_index_seed(auction_index: u32) -> Vec<u8> {
+    if auction_index == 0 {
+        Vec::new()
+    } else {
+        auction_index.to_le_bytes().to_vec()
+    }
+}
+
+/// A bid of the bid history kept in `State`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BidRecord {
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Off-chain name of `BidRecord`, serializable with serde under the `serde` feature
+pub type BidHistoryRecord = BidRecord;
+
+/// Parameters of a new auction, the argument of `initialize` and `initialize_many`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AuctionConfig {
+    pub auction_duration: i64,
+    pub reserve_price: u64,
+    pub reserve_price_usd_cents: u64,
+    pub price_feed: Pubkey,
+    pub seller_bond: u64,
+    pub dispute_window: i64,
+    pub refund_window: i64,
+    pub unclaimed_beneficiary: Pubkey,
+    pub cancel_compensation_bps: u16,
+    pub auction_type: AuctionType,
+    pub start_price: u64,
+    pub commit_duration: i64,
+    pub withdrawal_penalty_bps: u16,
+    pub title: String,
+    pub item_hash: [u8; 32],
+    pub uri: String,
+    pub min_bid_increment: u64,
+    pub bundle_items: Vec<Pubkey>,
+    pub max_acceptable_price: u64,
+    pub price_step_secs: i64,
+    pub seller_claim_window: i64,
+    pub commit_deposit: u64,
+    pub milestone_count: u8,
+    pub condition_program: Pubkey,
+    pub condition_account: Pubkey,
+    pub candle_window: i64,
+    pub total_shares: u64,
+    pub quantity: u16,
+    pub round_duration_secs: i64,
+    pub tick: u64,
+    pub bid_fee: u64,
+    pub extension_secs: i64,
+    pub pay_as_bid: bool,
+    pub finalist_count: u8,
+    pub open_duration: i64,
+    pub final_commit_duration: i64,
+    pub bid_mint: Pubkey,
+    pub prize_mint: Pubkey,
+    pub prize_amount: u64,
+}
+
+impl AuctionConfig {
+    /// Checks every parameter, each with its own error: the duration within the bounds
+    /// of the program config, a deadline that fits in an `i64` at `now`, a commit phase
+    /// shorter than the auction, a Dutch start price above the reserve, a price step that
+    /// is not negative, a candle window within the auction, a quantity the leaderboard
+    /// can rank, rounds within the auction with a positive tick, a bid fee and increment
+    /// for penny auctions, finalists the leaderboard can rank with an open phase and a
+    /// final commit phase that end before the deadline, basis points up to 100%, metadata
+    /// and bundle within their limits and no options that exclude each other
+    pub fn validate(
+        &self,
+        min_auction_duration: i64,
+        max_auction_duration: i64,
+        now: i64,
+    ) -> Result<()> {
+        validate_auction_duration(
+            self.auction_duration,
+            min_auction_duration,
+            max_auction_duration,
+        )?;
+        if now.checked_add(self.auction_duration).is_none() {
+            return err!(AuctionError::DeadlineOverflow);
+        }
+        if self.commit_duration < 0 || self.commit_duration >= self.auction_duration {
+            return err!(AuctionError::InvalidCommitDuration);
+        }
+        if self.auction_type == AuctionType::Dutch && self.start_price < self.reserve_price {
+            return err!(AuctionError::InvalidStartPrice);
+        }
+        if self.price_step_secs < 0 {
+            return err!(AuctionError::InvalidPriceStep);
+        }
+        if self.candle_window < 0 || self.candle_window > self.auction_duration {
+            return err!(AuctionError::InvalidCandleWindow);
+        }
+        if self.quantity as usize > LEADERBOARD_SIZE {
+            return err!(AuctionError::InvalidQuantity);
+        }
+        if self.round_duration_secs < 0
+            || self.round_duration_secs > self.auction_duration
+            || (self.round_duration_secs > 0 && self.tick == 0)
+        {
+            return err!(AuctionError::InvalidRoundSchedule);
+        }
+        if self.auction_type == AuctionType::Penny
+            && (self.bid_fee == 0 || self.min_bid_increment == 0 || self.extension_secs < 0)
+        {
+            return err!(AuctionError::InvalidPennySettings);
+        }
+        let final_round_end = self.open_duration.checked_add(self.final_commit_duration);
+        if self.finalist_count > 0
+            && (self.finalist_count as usize > LEADERBOARD_SIZE
+                || self.open_duration <= 0
+                || self.final_commit_duration <= 0
+                || final_round_end.map_or(true, |end| end >= self.auction_duration))
+        {
+            return err!(AuctionError::InvalidFinalRound);
+        }
+        if self.cancel_compensation_bps as u64 > BPS_DENOMINATOR {
+            return err!(AuctionError::InvalidCompensation);
+        }
+        if self.withdrawal_penalty_bps as u64 > BPS_DENOMINATOR {
+            return err!(AuctionError::InvalidPenalty);
+        }
+        if self.title.len() > MAX_TITLE_LEN || self.uri.len() > MAX_URI_LEN {
+            return err!(AuctionError::MetadataTooLong);
+        }
+        if self.bundle_items.len() > MAX_BUNDLE_ITEMS {
+            return err!(AuctionError::BundleItemCountExceeded);
+        }
+        if (self.prize_mint == Pubkey::default()) != (self.prize_amount == 0) {
+            return err!(AuctionError::InvalidPrizeEscrow);
+        }
+        // Dutch, perpetual, reverse, raffle and penny auctions take open bids only
+        if matches!(
+            self.auction_type,
+            AuctionType::Dutch
+                | AuctionType::Perpetual
+                | AuctionType::Reverse
+                | AuctionType::Raffle
+                | AuctionType::Penny
+        ) && self.commit_duration > 0
+        {
+            return err!(AuctionError::SealedBidsNotSupported);
+        }
+        // a USD reserve is converted with a price feed, and never compared to a raffle pool
+        if self.reserve_price_usd_cents > 0
+            && (self.price_feed == Pubkey::default() || self.auction_type == AuctionType::Raffle)
+        {
+            return err!(AuctionError::UsdReserveWithoutFeed);
+        }
+        // a reverse auction caps offers with its maximum price instead of a reserve
+        if self.auction_type == AuctionType::Reverse
+            && (self.reserve_price > 0 || self.reserve_price_usd_cents > 0)
+        {
+            return err!(AuctionError::ReverseAuctionWithReserve);
+        }
+        // a commit deposit needs a commit phase
+        if self.commit_deposit > 0 && self.commit_duration == 0 {
+            return err!(AuctionError::CommitDepositWithoutCommitPhase);
+        }
+        // milestones are paid out of the escrow of an English, Vickrey or Dutch winner
+        if self.milestone_count > 0
+            && matches!(
+                self.auction_type,
+                AuctionType::AllPay
+                    | AuctionType::Perpetual
+                    | AuctionType::Reverse
+                    | AuctionType::Raffle
+            )
+        {
+            return err!(AuctionError::MilestonesNotSupported);
+        }
+        // a condition account is only read by a condition program
+        if self.condition_account != Pubkey::default()
+            && self.condition_program == Pubkey::default()
+        {
+            return err!(AuctionError::ConditionAccountWithoutProgram);
+        }
+        // a candle window draws the winner among the open bids of an English auction
+        if self.candle_window > 0
+            && (self.auction_type != AuctionType::English || self.commit_duration > 0)
+        {
+            return err!(AuctionError::CandleWindowNotSupported);
+        }
+        // shares are sold to the open bids of a plain English auction
+        if self.total_shares > 0
+            && (self.auction_type != AuctionType::English
+                || self.commit_duration > 0
+                || self.milestone_count > 0
+                || self.candle_window > 0)
+        {
+            return err!(AuctionError::FractionalNotSupported);
+        }
+        // units are sold to the open bids of a plain English or Dutch auction
+        if self.quantity > 1
+            && (!matches!(self.auction_type, AuctionType::English | AuctionType::Dutch)
+                || self.commit_duration > 0
+                || self.milestone_count > 0
+                || self.candle_window > 0
+                || self.total_shares > 0
+                || self.reserve_price_usd_cents > 0
+                || self.condition_program != Pubkey::default()
+                || !self.bundle_items.is_empty())
+        {
+            return err!(AuctionError::MultiUnitNotSupported);
+        }
+        // rounds price the open bids of a single item English auction
+        if self.round_duration_secs > 0
+            && (self.auction_type != AuctionType::English
+                || self.commit_duration > 0
+                || self.candle_window > 0
+                || self.total_shares > 0
+                || self.quantity > 1)
+        {
+            return err!(AuctionError::RoundsNotSupported);
+        }
+        // only penny bids pay a bid fee or extend the deadline
+        if self.auction_type != AuctionType::Penny && (self.bid_fee > 0 || self.extension_secs != 0)
+        {
+            return err!(AuctionError::PennyOptionsWithoutPenny);
+        }
+        // paying as bid takes more than one unit
+        if self.pay_as_bid && self.quantity <= 1 {
+            return err!(AuctionError::PayAsBidSingleUnit);
+        }
+        // a final round follows a plain single item English auction and needs finalists
+        let final_round_not_english = if self.finalist_count > 0 {
+            self.auction_type != AuctionType::English
+                || self.commit_duration > 0
+                || self.candle_window > 0
+                || self.total_shares > 0
+                || self.quantity > 1
+                || self.round_duration_secs > 0
+        } else {
+            self.open_duration != 0 || self.final_commit_duration != 0
+        };
+        if final_round_not_english {
+            return err!(AuctionError::FinalRoundNotSupported);
+        }
+        // bids in an SPL token are only taken by a plain single item English auction
+        if self.bid_mint != Pubkey::default()
+            && (self.auction_type != AuctionType::English
+                || self.commit_duration > 0
+                || self.candle_window > 0
+                || self.total_shares > 0
+                || self.quantity > 1
+                || self.round_duration_secs > 0
+                || self.finalist_count > 0
+                || self.milestone_count > 0
+                || self.reserve_price_usd_cents > 0
+                || self.condition_program != Pubkey::default())
+        {
+            return err!(AuctionError::TokenBidsNotSupported);
+        }
+        // an escrowed prize goes to a single winner, once
+        if self.prize_mint != Pubkey::default()
+            && (self.auction_type == AuctionType::Perpetual
+                || self.total_shares > 0
+                || self.quantity > 1)
+        {
+            return err!(AuctionError::PrizeEscrowNotSupported);
+        }
+        Ok(())
+    }
+}
+
+/// Bidding phase of an auction, derived from its deadline
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuctionStatus {
+    /// Bids are accepted
+    Active,
+    /// The deadline passed, the auction can be settled and refunded
+    Ended,
+}
+
+/// Pricing rule of an auction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuctionType {
+    /// The winner pays its own bid
+    English,
+    /// The winner pays the second highest bid, or the reserve price when it bid alone
+    Vickrey,
+    /// The asking price falls from `start_price` to the reserve price until the deadline
+    Dutch,
+    /// The winner pays its own bid and every losing bid goes to the seller as well
+    AllPay,
+    /// English auction that starts a new round after each sale
+    Perpetual,
+    /// Procurement auction: the seller buys from the lowest offer up to
+    /// `max_acceptable_price` and pays it at settlement
+    Reverse,
+    /// Every bid is a contribution to the pool, which goes to the seller. The winner is
+    /// drawn with a chance proportional to its contribution
+    Raffle,
+    /// Every bid pays a bid fee kept by the seller, raises the price by the bid
+    /// increment and extends the deadline. The winner pays the final price
+    Penny,
+}
+
+/// Lifecycle of the seller bond after settlement
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeStatus {
+    /// No dispute was opened
+    Idle,
+    /// The winner disputed the delivery
+    Open,
+    /// The winner confirmed the delivery
+    Confirmed,
+    /// The bond was paid to the winner
+    Slashed,
+    /// The bond was returned to the seller
+    Reclaimed,
+}
+
+/// Invariant of an auction broken, reported by `health_check`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HealthViolation {
+    /// The auction has no deadline
+    InvalidDeadline,
+    /// The treasury does not cover the outstanding penalties and budget
+    TreasuryShortfall,
+    /// A bid escrow does not cover its outstanding bid
+    EscrowShortfall,
+    /// The bid count does not match the bid escrows of the auction
+    BidCountMismatch,
+    /// The highest bid, or the lowest offer of a reverse auction, does not match the
+    /// bids of the escrows
+    HighestBidMismatch,
+    /// The seller bond PDA does not cover the outstanding seller bond
+    BondShortfall,
+}
+
+/// Keeps a ledger of what went through the treasury of an auction. Its lamports, the
+/// withdrawal penalties and the budget of a reverse auction, are held by the treasury
+/// authority PDA, which signs their transfers through the system program. Bids never go
+/// through it, and the seller bond is held by its own PDA
+#[account]
+pub struct Treasury {
+    pub total_collected: u64,
+    pub total_paid_out: u64,
+    pub bump: u8,
+}
+
+/// Bid of a bidder on an auction, seeded with `[b"user-bid", user, state]`. Its lamports
+/// are held by the `BidEscrow` of the bidder, the PDA itself only holds its rent. An
+/// outbid bid may move its lamports into a yield vault instead, they come back with
+/// `refund_with_yield`
+#[account]
+pub struct UserBid {
+    pub amount: u64,
+    pub refunded: bool,
+    /// Account that paid the rent of this PDA, it gets the rent back on close
+    pub rent_payer: Pubkey,
+    /// Highest amount the bidder's proxy raises the bid to, 0 without a proxy
+    pub max_proxy_bid: u64,
+    /// Time of the last bid placed with this user bid, 0 until its first bid
+    pub last_bid_unix_timestamp: i64,
+    /// Whether the bidder took its share of the bond of a seller who did not settle
+    pub bond_claimed: bool,
+    /// Shares of a fractional auction allocated to the bid, minted at settlement
+    pub shares: u64,
+    /// Whether the bid won a unit of a multi-unit auction, its amount is then the part
+    /// of the bid above the clearing price
+    pub unit_won: bool,
+    /// Bid fees the bidder paid to the seller of a penny auction, never refunded
+    pub fees_paid: u64,
+    /// Shares of the yield vault the escrowed lamports were deposited into, 0 while
+    /// they are in the escrow
+    pub deposit_share: u64,
+    /// Yield vault holding the escrowed lamports, the default key while they are in the
+    /// escrow
+    pub vault: Pubkey,
+}
+
+impl UserBid {
+    /// Lamports of the bidder held by its bid escrow next to the rent: the bid, or the
+    /// whole proxy maximum when it is higher
+    pub fn escrowed(&self) -> u64 {
+        self.amount.max(self.max_proxy_bid)
+    }
+}
+
+/// Escrow of the bid of a bidder on an auction, seeded with `[b"escrow", user, state]`.
+/// It holds the `escrowed()` lamports of the user bid next to its rent and nothing
+/// else, so settlement and refunds only ever drain the escrow of the bidder they pay.
+/// It is created and closed together with its user bid, whose rent payer pays its rent
+#[account]
+pub struct BidEscrow {
+    pub bump: u8,
+}
+
+/// Wallet the platform authority found funded by the seller of an auction, one per
+/// wallet and auction. It can not bid on the auction during the shill lookback of the
+/// config that follows its funding
+#[account]
+pub struct SybilCheck {
+    pub related: Pubkey,
+    /// Time the seller funded the wallet
+    pub funded_at: i64,
+    pub registered_at: i64,
+}
+
+/// Report of a suspicious auction, one per flagger and auction
+#[account]
+pub struct FraudFlag {
+    pub flagger: Pubkey,
+    pub reason: [u8; FLAG_REASON_LEN],
+    pub flagged_at: i64,
+}
+
+/// Sealed bid of a bidder, only the salted hash of the amount is stored until the reveal
+#[account]
+pub struct CommitBid {
+    pub commitment: [u8; 32],
+    pub committed_at: i64,
+    pub revealed: bool,
+    /// Deposit held next to the rent until the reveal
+    pub deposit: u64,
+}
+
+/// Allowance a bidder gave another wallet to bid on its behalf. The allowance is
+/// deposited in this PDA when it is granted, the delegate's bids are escrowed out of it
+#[account]
+pub struct DelegateAuth {
+    pub delegate: Pubkey,
+    /// Lamports the delegate can still bid
+    pub max_amount: u64,
+    /// Unix timestamp after which the delegate can no longer bid
+    pub expiry: i64,
+}
+
+/// Program-wide count of the auctions created, it orders them in the registry pages
+#[account]
+pub struct Registry {
+    pub auction_count: u64,
+}
+
+impl Registry {
+    /// Seed of the registry page the next auction is listed on
+    pub fn next_page_seed(&self) -> [u8; 8] {
+        (self.auction_count / REGISTRY_PAGE_SIZE as u64).to_le_bytes()
+    }
+
+    /// Lists `auction` on `page`, which has to be the page of the next auction, and
+    /// returns its sequence number
+    pub fn register(&mut self, page: &mut RegistryPage, auction: Pubkey) -> Result<u64> {
+        let page_index = self.auction_count / REGISTRY_PAGE_SIZE as u64;
+        let slot = (self.auction_count % REGISTRY_PAGE_SIZE as u64) as usize;
+        // a fresh page learns its index with its first entry
+        if slot == 0 {
+            page.index = page_index;
+        }
+        if page.index != page_index {
+            return err!(AuctionError::RegistryPageFull);
+        }
+
+        page.entries[slot] = RegistryEntry {
+            auction,
+            tombstoned: false,
+        };
+        page.count = slot as u8 + 1;
+        self.auction_count = self
+            .auction_count
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+
+        Ok(self.auction_count)
+    }
+}
+
+/// Auctions `index * REGISTRY_PAGE_SIZE` and up in the order they were created.
+/// Entries of closed auctions are tombstoned, the others never move
+#[account]
+pub struct RegistryPage {
+    pub index: u64,
+    pub count: u8,
+    pub entries: [RegistryEntry; REGISTRY_PAGE_SIZE],
+}
+
+impl RegistryPage {
+    /// Marks the entry of the auction with `sequence` as closed
+    pub fn tombstone(&mut self, sequence: u64) {
+        let slot = (sequence.saturating_sub(1) % REGISTRY_PAGE_SIZE as u64) as usize;
+        self.entries[slot].tombstoned = true;
+    }
+}
+
+/// An auction listed on a registry page
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct RegistryEntry {
+    pub auction: Pubkey,
+    pub tombstoned: bool,
+}
+
+/// Highest bids of an auction, apart from `State` so they can be displayed without
+/// reading the whole auction. Entries are sorted from the highest bid down
+#[account]
+pub struct Leaderboard {
+    pub entries: [LeaderboardEntry; LEADERBOARD_SIZE],
+}
+
+impl Leaderboard {
+    /// Winners of a multi-unit auction selling `quantity` units: the highest bids that
+    /// reach `reserve_price`, one unit each. A tie at the last unit goes to the bidder
+    /// who got there first. The last winner sets the clearing price
+    pub fn unit_winners(&self, quantity: u16, reserve_price: u64) -> &[LeaderboardEntry] {
+        let winners = self
+            .entries
+            .iter()
+            .take(quantity as usize)
+            .take_while(|entry| entry.rank > 0 && entry.amount >= reserve_price)
+            .count();
+        &self.entries[..winners]
+    }
+
+    /// Whether `bidder` ranks among the `finalist_count` highest bids, which the
+    /// leaderboard keeps once the open phase of an auction with a final round is over
+    pub fn is_finalist(&self, bidder: Pubkey, finalist_count: u8) -> bool {
+        self.entries
+            .iter()
+            .take(finalist_count as usize)
+            .any(|entry| entry.rank > 0 && entry.bidder == bidder)
+    }
+}
+
+/// A bidder of the leaderboard, `rank` starts at 1 and is 0 for an empty entry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct LeaderboardEntry {
+    pub rank: u8,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+/// What `refund` would give back to a bidder, the return data of `preview_refund`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct RefundPreview {
+    /// Lamports the bidder would receive, its escrowed bid and the rent of the user bid
+    /// and bid escrow when it paid it
+    pub refundable_lamports: u64,
+    pub includes_rent: bool,
+    /// Code of the `AuctionError` `refund` would fail with, `None` when it goes through
+    pub reason: Option<u32>,
+}
+
+/// Outcome of an ended auction, the return data of `get_winner`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct WinnerInfo {
+    pub winner: Pubkey,
+    /// Price the winner pays, or paid once the auction is settled
+    pub amount: u64,
+    pub settled: bool,
+}
+
+/// Build of the program, the return data of `get_version`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ProgramVersion {
+    /// Semver of the program crate
+    pub version: String,
+    /// Layout version of the `State` accounts the program reads and writes
+    pub state_schema: u8,
+    /// Cargo features the program was built with
+    pub features: Vec<String>,
+}
+
+/// Countdown of an auction by the cluster clock, the return data of
+/// `get_remaining_time`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct RemainingTime {
+    /// Seconds left before the deadline, 0 once it passed
+    pub remaining_secs: i64,
+    pub deadline: i64,
+    pub now: i64,
+}
+
+/// Lifetime statistics of a bidder across every auction of the program
+#[account]
+pub struct BidderProfile {
+    /// Auctions the bidder opened a user bid in
+    pub total_auctions_participated: u32,
+    pub total_bids_placed: u64,
+    pub total_lamports_bid: u64,
+    pub auctions_won: u32,
+    /// Auctions the bidder was refunded from without winning them, cancelled auctions
+    /// are not counted
+    pub auctions_lost: u32,
+}
+
+impl BidderProfile {
+    /// Counts a bid of `amount` that opened a new user bid
+    pub fn record_bid(&mut self, amount: u64) -> Result<()> {
+        self.total_auctions_participated = self
+            .total_auctions_participated
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        self.record_rebid(amount)
+    }
+
+    /// Counts a bid of `amount` placed again with an existing user bid
+    pub fn record_rebid(&mut self, amount: u64) -> Result<()> {
+        self.total_bids_placed = self
+            .total_bids_placed
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        self.total_lamports_bid = self
+            .total_lamports_bid
+            .checked_add(amount)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_win(&mut self) -> Result<()> {
+        self.auctions_won = self
+            .auctions_won
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_loss(&mut self) -> Result<()> {
+        self.auctions_lost = self
+            .auctions_lost
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Whether the bidder participated in enough auctions for loyalty status
+    pub fn is_loyal(&self) -> bool {
+        self.total_auctions_participated >= LOYALTY_THRESHOLD
+    }
+}
+
+/// Loyalty status of a bidder, granted once its profile counts `LOYALTY_THRESHOLD`
+/// auctions. The platform fee of the sales it wins is reduced by the loyalty discount
+/// of the config
+#[account]
+pub struct LoyaltyDiscount {
+    pub bidder: Pubkey,
+    /// Auctions the profile counted when the status was granted
+    pub auctions_participated: u32,
+    pub granted_at: i64,
+}
+
+/// Description of an auction, kept out of `State` and sized to its text
+#[account]
+pub struct DescriptionAccount {
+    pub text: Vec<u8>,
+}
+
+impl DescriptionAccount {
+    /// Size of the account holding `text`
+    pub fn space(text: &[u8]) -> usize {
+        DISCRIMINATOR_SIZE + 4 + text.len()
+    }
+}
+
+/// Final statistics of an auction, written once when `end_auction` settles it and never
+/// closed, so they outlive the auction state
+#[account]
+pub struct AuctionSummary {
+    /// Winner of the auction, the default key when the reserve was not met
+    pub winner: Pubkey,
+    /// Price paid to the seller, or to the lowest bidder of a reverse auction
+    pub final_price: u64,
+    /// Bids placed, raised bids included
+    pub total_bids: u64,
+    /// Bidders still holding a bid at settlement
+    pub total_bidders: u64,
+    pub settled_at: i64,
+    pub settled_at_slot: u64,
+    /// Time between the start of the auction and its final deadline
+    pub auction_duration: i64,
+}
+
+impl AuctionSummary {
+    /// Records the outcome of `state` once, later settlements of a perpetual auction
+    /// leave the summary of its first round untouched
+    pub fn record(&mut self, state: &State, final_price: u64, slot: u64) {
+        if self.settled_at != 0 {
+            return;
+        }
+        self.winner = if state.reserve_not_met {
+            Pubkey::default()
+        } else {
+            state.winner()
+        };
+        self.final_price = final_price;
+        self.total_bids = state.bid_history_count as u64;
+        self.total_bidders = state.bid_count as u64;
+        self.settled_at = state.settled_at;
+        self.settled_at_slot = slot;
+        self.auction_duration = state.deadline.saturating_sub(state.start_time);
+    }
+}
+
+/// Record of the sale of an auction kept for tax reporting, written once when
+/// `end_auction` pays the seller and never changed or closed afterwards
+#[account]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TaxRecord {
+    /// Party paid for the sale: the seller, or the lowest bidder of a reverse auction
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    /// Price paid by the buyer, the net proceeds of the seller plus the platform fee and
+    /// the royalties
+    pub gross_sale_amount: u64,
+    pub platform_fee_amount: u64,
+    pub royalty_amount: u64,
+    pub net_seller_proceeds: u64,
+    pub settlement_slot: u64,
+    pub settlement_unix_timestamp: i64,
+}
+
+impl TaxRecord {
+    /// Records a sale of `gross_sale_amount` from `seller` to `buyer` once, later
+    /// settlements of a perpetual auction leave the record of its first round untouched.
+    /// No royalty is deducted at settlement, the seller nets the sale less the platform
+    /// fee
+    pub fn record(
+        &mut self,
+        seller: Pubkey,
+        buyer: Pubkey,
+        gross_sale_amount: u64,
+        platform_fee_amount: u64,
+        slot: u64,
+        now: i64,
+    ) {
+        if self.settlement_unix_timestamp != 0 {
+            return;
+        }
+        self.seller = seller;
+        self.buyer = buyer;
+        self.gross_sale_amount = gross_sale_amount;
+        self.platform_fee_amount = platform_fee_amount;
+        self.royalty_amount = 0;
+        self.net_seller_proceeds = gross_sale_amount.saturating_sub(platform_fee_amount);
+        self.settlement_slot = slot;
+        self.settlement_unix_timestamp = now;
+    }
+}
+
+/// Sale history of a seller across every auction of the program. Clients rate the
+/// seller with a `SellerReputation` derived from it
+#[account]
+pub struct SellerProfile {
+    pub total_auctions_created: u32,
+    /// Auctions that paid the seller, each round of a perpetual auction counts
+    pub total_auctions_settled: u32,
+    /// Lamports paid by the winners of the settled auctions
+    pub total_revenue: u64,
+    /// Bidders still holding a bid in the auctions when they settled
+    pub total_bidders_served: u64,
+}
+
+impl SellerProfile {
+    pub fn record_auctions(&mut self, count: u32) -> Result<()> {
+        self.total_auctions_created = self
+            .total_auctions_created
+            .checked_add(count)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Counts a settled auction which paid `revenue` to the seller, with `bidders`
+    /// bids left in it
+    pub fn record_settlement(&mut self, revenue: u64, bidders: u32) -> Result<()> {
+        self.total_auctions_settled = self
+            .total_auctions_settled
+            .checked_add(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        self.total_revenue = self
+            .total_revenue
+            .checked_add(revenue)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        self.total_bidders_served = self
+            .total_bidders_served
+            .checked_add(bidders as u64)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// Reputation of a seller, never stored on chain. Clients compute it from its
+/// `SellerProfile`: `score` is the share of its created auctions that settled, in
+/// percent, or 0 for a seller without auctions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct SellerReputation {
+    pub score: u8,
+}
+
+/// Per-seller counter of auction indexes, the next auction of the seller takes
+/// `next_index`
+#[account]
+pub struct SellerCounter {
+    pub next_index: u32,
+}
+
+impl SellerCounter {
+    /// Reserves `count` indexes from `first_index`, which can not be below an index
+    /// the seller already used
+    pub fn reserve(&mut self, first_index: u32, count: u32) -> Result<()> {
+        if first_index < self.next_index {
+            return err!(AuctionError::InvalidAuctionIndex);
+        }
+        self.next_index = first_index
+            .checked_add(count)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// Program-wide parameters, its authority can update them without a redeploy
+#[account]
+pub struct Config {
+    pub authority: Pubkey,
+    pub max_auction_duration: i64,
+    pub min_auction_duration: i64,
+    pub platform_fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    pub min_bid_amount: u64,
+    /// Seconds a bidder waits before bidding again in the same auction
+    pub bid_cooldown_seconds: i64,
+    /// Reviews the fraud flags of the auctions and registers the wallets related to
+    /// sellers
+    pub platform_authority: Pubkey,
+    /// Seconds after being funded by a seller during which a related wallet can not bid
+    /// on the seller's auction
+    pub shill_lookback_secs: i64,
+    /// Part of the fees waived for bidders with loyalty status
+    pub loyalty_discount_bps: u16,
+}
+
+impl Config {
+    /// Durations have to leave room for at least one bid, fees and the loyalty discount
+    /// can not exceed 100%, the bid cooldown and the shill lookback can not be negative
+    pub fn validate(&self) -> Result<()> {
+        if self.min_auction_duration < MIN_AUCTION_DURATION_SECS
+            || self.max_auction_duration < self.min_auction_duration
+            || self.platform_fee_bps as u64 > BPS_DENOMINATOR
+            || self.bid_cooldown_seconds < 0
+            || self.shill_lookback_secs < 0
+            || self.loyalty_discount_bps as u64 > BPS_DENOMINATOR
+        {
+            return err!(AuctionError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    /// Platform fee of a sale of `amount`: `platform_fee_bps` of it, less the loyalty
+    /// discount when the buyer has loyalty status
+    pub fn platform_fee(&self, amount: u64, loyal: bool) -> Result<u64> {
+        let fee = bps_of(amount, self.platform_fee_bps)?;
+        if !loyal {
+            return Ok(fee);
+        }
+        Ok(fee - bps_of(fee, self.loyalty_discount_bps)?)
+    }
+}
+
+#[account]
+pub struct RecoveryConfig {
+    pub admin: Pubkey,
+    pub recovery_vault: Pubkey,
+    pub recovery_timeout: i64,
+}
@@ -0,0 +1,353 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, system_instruction},
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token::{self, Token, TokenAccount};
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::errors::AuctionError;
+use crate::pda::PdaHelpers;
+use crate::state::{BidEscrow, State, Treasury, UserBid};
+
+/// Checks that the bumps cached in `state` are the canonical bumps of the state,
+/// treasury, treasury authority and seller bond PDAs
+pub fn validate_all_bumps(state: &State) -> bool {
+    let (state_key, state_bump) =
+        PdaHelpers::indexed_state_pda(&state.initializer, state.auction_index);
+    let (_, treasury_bump) = PdaHelpers::treasury_pda(&state_key);
+    let (_, treasury_authority_bump) = PdaHelpers::treasury_authority_pda(&state_key);
+    let (_, seller_bond_bump) = PdaHelpers::seller_bond_pda(&state_key);
+
+    state.state_bump == state_bump
+        && state.treasury_bump == treasury_bump
+        && state.treasury_authority_bump == treasury_authority_bump
+        && state.seller_bond_bump == seller_bond_bump
+}
+
+/// Auctions last between the `min_auction_duration` and `max_auction_duration` of the config
+pub fn validate_auction_duration(
+    auction_duration: i64,
+    min_auction_duration: i64,
+    max_auction_duration: i64,
+) -> Result<()> {
+    if !(min_auction_duration..=max_auction_duration).contains(&auction_duration) {
+        return err!(AuctionError::InvalidAuctionDuration);
+    }
+    Ok(())
+}
+
+/// `bps` basis points of `amount`, rounded down
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    let share = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(AuctionError::ArithmeticOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    u64::try_from(share).map_err(|_| error!(AuctionError::ArithmeticOverflow))
+}
+
+/// Loads the user bid of `bidder` passed through the remaining accounts, after checking
+/// that it is the user bid PDA of this auction
+pub fn load_user_bid<'info>(
+    state_key: Pubkey,
+    bidder: &AccountInfo<'info>,
+    user_bid: &AccountInfo<'info>,
+) -> Result<Account<'info, UserBid>> {
+    let (expected_user_bid, _) = PdaHelpers::user_bid_pda(bidder.key, &state_key);
+    if user_bid.key() != expected_user_bid {
+        return err!(AuctionError::InvalidEscrow);
+    }
+
+    Account::<UserBid>::try_from(user_bid)
+}
+
+/// Loads the bid escrow of `bidder` passed through the remaining accounts, after
+/// checking that it is the bid escrow PDA of this auction
+pub fn load_bid_escrow<'info>(
+    state_key: Pubkey,
+    bidder: &AccountInfo<'info>,
+    escrow: &AccountInfo<'info>,
+) -> Result<Account<'info, BidEscrow>> {
+    let (expected_escrow, _) = PdaHelpers::bid_escrow_pda(bidder.key, &state_key);
+    if escrow.key() != expected_escrow {
+        return err!(AuctionError::InvalidEscrow);
+    }
+
+    Account::<BidEscrow>::try_from(escrow)
+}
+
+/// Hash of the most recent slot in the `SlotHashes` sysvar, `None` while the sysvar is
+/// empty. The sysvar is too large to deserialize on chain, its first entry follows the
+/// length of the list
+pub fn latest_slot_hash(slot_hashes: &AccountInfo) -> Result<Option<[u8; 32]>> {
+    let data = slot_hashes.try_borrow_data()?;
+    // 8 bytes of length, then the slot and the hash of each entry
+    if data.len() < 48 || data[..8] == [0u8; 8] {
+        return Ok(None);
+    }
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&data[16..48]);
+    Ok(Some(hash))
+}
+
+/// Rent exempt minimum of the treasury authority and of the seller bond PDA, which hold
+/// no data. It is never paid out before the auction is cancelled
+pub fn treasury_rent_floor() -> Result<u64> {
+    Ok(Rent::get()?.minimum_balance(0))
+}
+
+/// Lamports held by the treasury authority, or the seller bond PDA, on top of its rent
+/// exempt minimum
+pub fn treasury_available_lamports(treasury_authority: &AccountInfo) -> Result<u64> {
+    Ok(treasury_authority
+        .lamports()
+        .saturating_sub(treasury_rent_floor()?))
+}
+
+/// Checks that paying `amount` out of a treasury holding `treasury_lamports` leaves
+/// at least `min_rent_balance`, otherwise the treasury could be garbage collected
+/// while refunds are still pending
+pub fn check_treasury_withdrawal(
+    treasury_lamports: u64,
+    amount: u64,
+    min_rent_balance: u64,
+) -> Result<()> {
+    match treasury_lamports.checked_sub(amount) {
+        Some(remaining) if remaining >= min_rent_balance => Ok(()),
+        _ => err!(AuctionError::TreasuryInsufficientFunds),
+    }
+}
+
+/// Moves `amount` lamports from a signing wallet into `to` through the system
+/// program's `transfer` instruction.
+///
+/// # Arguments
+///
+/// * `from` - The wallet funding the transfer, it must sign the transaction
+/// * `to` - The treasury or bid escrow receiving the lamports
+/// * `system_program` - The system program, which debits `from`
+/// * `amount` - the amount of lamport that is sent from `from` to `to`
+///
+pub fn deposit_lamports<'info>(
+    from: &Signer<'info>,
+    to: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: from.to_account_info(),
+                to,
+            },
+        ),
+        amount,
+    )
+}
+
+/// Pays `amount` lamports out of a bid escrow, or any other account owned by the
+/// program, so its lamports are moved directly, without going through the system
+/// program.
+///
+/// # Arguments
+///
+/// * `escrow` - The `BidEscrow` PDA holding the bid
+/// * `destination_wallet` - The account credited with `amount`
+/// * `amount` - the amount of lamport that is sent from `escrow` to `destination_wallet`
+///
+pub fn transfer_from_escrow(
+    escrow: &AccountInfo,
+    destination_wallet: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    let rent_floor = Rent::get()?.minimum_balance(escrow.data_len());
+    if escrow.lamports().saturating_sub(rent_floor) < amount {
+        return err!(AuctionError::WouldBreakRentExemption);
+    }
+
+    **escrow.try_borrow_mut_lamports()? -= amount;
+    **destination_wallet.try_borrow_mut_lamports()? = destination_wallet
+        .lamports()
+        .checked_add(amount)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Pays a sale of `amount` lamports out of a bid escrow: `platform_fee` goes to the fee
+/// recipient of the config and the rest to the beneficiary.
+///
+/// # Arguments
+///
+/// * `escrow` - The `BidEscrow` PDA holding the bid
+/// * `beneficiary` - The account credited with the proceeds of the sale
+/// * `fee_recipient` - The account credited with the platform fee
+/// * `amount` - the amount of lamport of the sale, the platform fee included
+/// * `platform_fee` - the part of `amount` that goes to `fee_recipient`
+///
+pub fn pay_sale_from_escrow(
+    escrow: &AccountInfo,
+    beneficiary: &AccountInfo,
+    fee_recipient: &AccountInfo,
+    amount: u64,
+    platform_fee: u64,
+) -> Result<()> {
+    let proceeds = amount
+        .checked_sub(platform_fee)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+    if platform_fee > 0 {
+        transfer_from_escrow(escrow, fee_recipient, platform_fee)?;
+    }
+    if proceeds > 0 {
+        transfer_from_escrow(escrow, beneficiary, proceeds)?;
+    }
+
+    Ok(())
+}
+
+//
+/// A small utility function that allows us to transfer funds out of the Treasury.
+/// The lamports are held by the treasury authority, a system account, so they go
+/// through the system program's `transfer` instruction signed with the authority
+/// seeds, and the payout is recorded in `total_paid_out`. The authority keeps its
+/// rent exempt minimum, see `treasury_rent_floor`.
+///
+/// # Arguments
+///
+/// * `treasury` - The treasury of the auction, its ledger
+/// * `treasury_authority` - The treasury authority PDA of the auction, holding its lamports
+/// * `destination_wallet` - The public key of the destination address (where to send funds)
+/// * `system_program` - The system program, which debits `treasury_authority`
+/// * `state_key` - The state of the auction, which seeds the treasury authority
+/// * `authority_bump` - The bump of the treasury authority
+/// * `amount` - the amount of lamport that is sent from the treasury to `destination_wallet`
+///
+pub fn transfer_from_treasury<'info>(
+    treasury: &mut Account<'info, Treasury>,
+    treasury_authority: &AccountInfo<'info>,
+    destination_wallet: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    state_key: Pubkey,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    check_treasury_withdrawal(
+        treasury_authority.lamports(),
+        amount,
+        treasury_rent_floor()?,
+    )?;
+
+    transfer_from_treasury_authority(
+        treasury_authority,
+        destination_wallet,
+        system_program,
+        state_key,
+        authority_bump,
+        amount,
+    )?;
+
+    treasury.total_paid_out = treasury
+        .total_paid_out
+        .checked_add(amount)
+        .ok_or(AuctionError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Moves `amount` lamports out of the treasury authority of the auction `state_key`,
+/// signing the system transfer with the authority seeds. Nothing is checked, the
+/// system program rejects a transfer the authority can not cover
+pub(crate) fn transfer_from_treasury_authority<'info>(
+    treasury_authority: &AccountInfo<'info>,
+    destination_wallet: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    state_key: Pubkey,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    invoke_signed(
+        &system_instruction::transfer(treasury_authority.key, destination_wallet.key, amount),
+        &[
+            treasury_authority.clone(),
+            destination_wallet.clone(),
+            system_program.clone(),
+        ],
+        &[&[b"treasury-auth", state_key.as_ref(), &[authority_bump]]],
+    )
+    .map_err(Into::into)
+}
+
+/// Moves `amount` lamports of the seller bond out of the seller bond PDA of the auction
+/// `state_key`, signing the system transfer with its seeds. Nothing is checked, the
+/// system program rejects a transfer the bond can not cover
+pub(crate) fn transfer_from_seller_bond<'info>(
+    seller_bond: &AccountInfo<'info>,
+    destination_wallet: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    state_key: Pubkey,
+    bond_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    invoke_signed(
+        &system_instruction::transfer(seller_bond.key, destination_wallet.key, amount),
+        &[
+            seller_bond.clone(),
+            destination_wallet.clone(),
+            system_program.clone(),
+        ],
+        &[&[b"bond", state_key.as_ref(), &[bond_bump]]],
+    )
+    .map_err(Into::into)
+}
+
+/// Moves `amount` tokens out of the token vault of the auction `state_key`, the
+/// associated token account of its treasury authority, which signs the transfer
+pub(crate) fn transfer_from_token_vault<'info>(
+    vault: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    treasury_authority: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    state_key: Pubkey,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Transfer {
+                from: vault.to_account_info(),
+                to: destination.to_account_info(),
+                authority: treasury_authority.clone(),
+            },
+            &[&[b"treasury-auth", state_key.as_ref(), &[authority_bump]]],
+        ),
+        amount,
+    )
+}
+
+/// Moves `amount` tokens out of the prize vault of the auction `state_key`, the
+/// associated token account of its prize authority, which signs the transfer
+pub(crate) fn transfer_from_prize_vault<'info>(
+    prize_vault: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    prize_authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    state_key: Pubkey,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            token::Transfer {
+                from: prize_vault.clone(),
+                to: destination.clone(),
+                authority: prize_authority.clone(),
+            },
+            &[&[b"prize", state_key.as_ref(), &[authority_bump]]],
+        ),
+        amount,
+    )
+}
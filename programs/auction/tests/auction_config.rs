@@ -0,0 +1,634 @@
+use anchor_lang::prelude::Pubkey;
+use auction::constants::{LEADERBOARD_SIZE, MAX_BUNDLE_ITEMS, MAX_TITLE_LEN, MAX_URI_LEN};
+use auction::errors::AuctionError;
+use auction::{AuctionConfig, AuctionType};
+use proptest::prelude::*;
+
+const MIN_DURATION: i64 = 60;
+const MAX_DURATION: i64 = 30 * 24 * 60 * 60;
+const NOW: i64 = 1_700_000_000;
+
+fn valid_config() -> AuctionConfig {
+    AuctionConfig {
+        auction_duration: 3_600,
+        reserve_price: 1_000,
+        reserve_price_usd_cents: 0,
+        price_feed: Pubkey::default(),
+        seller_bond: 0,
+        dispute_window: 0,
+        refund_window: 0,
+        unclaimed_beneficiary: Pubkey::default(),
+        cancel_compensation_bps: 500,
+        auction_type: AuctionType::English,
+        start_price: 0,
+        commit_duration: 0,
+        withdrawal_penalty_bps: 100,
+        title: "Item".to_string(),
+        item_hash: [0; 32],
+        uri: "https://example.com/item.json".to_string(),
+        min_bid_increment: 0,
+        bundle_items: Vec::new(),
+        max_acceptable_price: 0,
+        price_step_secs: 0,
+        seller_claim_window: 0,
+        commit_deposit: 0,
+        milestone_count: 0,
+        condition_program: Pubkey::default(),
+        condition_account: Pubkey::default(),
+        candle_window: 0,
+        total_shares: 0,
+        quantity: 0,
+        round_duration_secs: 0,
+        tick: 0,
+        bid_fee: 0,
+        extension_secs: 0,
+        pay_as_bid: false,
+        finalist_count: 0,
+        open_duration: 0,
+        final_commit_duration: 0,
+        bid_mint: Pubkey::default(),
+        prize_mint: Pubkey::default(),
+        prize_amount: 0,
+    }
+}
+
+fn validate(config: &AuctionConfig, now: i64) -> Result<(), anchor_lang::error::Error> {
+    config.validate(MIN_DURATION, MAX_DURATION, now)
+}
+
+#[test]
+fn valid_config_passes() {
+    assert!(validate(&valid_config(), NOW).is_ok());
+}
+
+#[test]
+fn deadline_overflow_is_rejected() {
+    let config = AuctionConfig {
+        auction_duration: i64::MAX,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        config.validate(MIN_DURATION, i64::MAX, NOW).unwrap_err(),
+        AuctionError::DeadlineOverflow.into()
+    );
+}
+
+#[test]
+fn dutch_auction_can_not_take_sealed_bids() {
+    let config = AuctionConfig {
+        auction_type: AuctionType::Dutch,
+        start_price: 2_000,
+        commit_duration: 60,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::SealedBidsNotSupported.into()
+    );
+}
+
+#[test]
+fn usd_reserve_needs_a_price_feed() {
+    let without_feed = AuctionConfig {
+        reserve_price_usd_cents: 10_000,
+        ..valid_config()
+    };
+    let raffle = AuctionConfig {
+        auction_type: AuctionType::Raffle,
+        reserve_price_usd_cents: 10_000,
+        price_feed: Pubkey::new_unique(),
+        ..valid_config()
+    };
+
+    for config in [without_feed, raffle] {
+        assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::UsdReserveWithoutFeed.into()
+        );
+    }
+}
+
+#[test]
+fn reverse_auction_can_not_have_a_reserve() {
+    let config = AuctionConfig {
+        auction_type: AuctionType::Reverse,
+        max_acceptable_price: 5_000,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::ReverseAuctionWithReserve.into()
+    );
+}
+
+#[test]
+fn commit_deposit_needs_a_commit_phase() {
+    let config = AuctionConfig {
+        commit_deposit: 1_000,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::CommitDepositWithoutCommitPhase.into()
+    );
+}
+
+#[test]
+fn all_pay_auction_can_not_have_milestones() {
+    let config = AuctionConfig {
+        auction_type: AuctionType::AllPay,
+        milestone_count: 3,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::MilestonesNotSupported.into()
+    );
+}
+
+#[test]
+fn condition_account_needs_a_condition_program() {
+    let config = AuctionConfig {
+        condition_account: Pubkey::new_unique(),
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::ConditionAccountWithoutProgram.into()
+    );
+}
+
+#[test]
+fn candle_window_needs_an_open_english_auction() {
+    let vickrey_candle = AuctionConfig {
+        auction_type: AuctionType::Vickrey,
+        candle_window: 600,
+        ..valid_config()
+    };
+    let sealed_candle = AuctionConfig {
+        commit_duration: 600,
+        candle_window: 600,
+        ..valid_config()
+    };
+
+    for config in [vickrey_candle, sealed_candle] {
+        assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::CandleWindowNotSupported.into()
+        );
+    }
+}
+
+#[test]
+fn fractional_auction_needs_a_plain_english_auction() {
+    let config = AuctionConfig {
+        commit_duration: 600,
+        total_shares: 100,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::FractionalNotSupported.into()
+    );
+}
+
+#[test]
+fn multi_unit_auction_needs_a_plain_english_or_dutch_auction() {
+    let vickrey_units = AuctionConfig {
+        auction_type: AuctionType::Vickrey,
+        quantity: 10,
+        ..valid_config()
+    };
+    let fractional_units = AuctionConfig {
+        total_shares: 100,
+        quantity: 10,
+        ..valid_config()
+    };
+
+    for config in [vickrey_units, fractional_units] {
+        assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::MultiUnitNotSupported.into()
+        );
+    }
+}
+
+#[test]
+fn rounds_need_a_single_item_english_auction() {
+    let config = AuctionConfig {
+        auction_type: AuctionType::Dutch,
+        start_price: 2_000,
+        round_duration_secs: 60,
+        tick: 100,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::RoundsNotSupported.into()
+    );
+}
+
+#[test]
+fn bid_fee_needs_a_penny_auction() {
+    let config = AuctionConfig {
+        bid_fee: 1_000,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::PennyOptionsWithoutPenny.into()
+    );
+}
+
+#[test]
+fn pay_as_bid_needs_several_units() {
+    let config = AuctionConfig {
+        pay_as_bid: true,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::PayAsBidSingleUnit.into()
+    );
+}
+
+#[test]
+fn final_round_needs_finalists_and_an_english_auction() {
+    let vickrey_final_round = AuctionConfig {
+        auction_type: AuctionType::Vickrey,
+        finalist_count: 3,
+        open_duration: 1_800,
+        final_commit_duration: 600,
+        ..valid_config()
+    };
+    let without_finalists = AuctionConfig {
+        open_duration: 1_800,
+        final_commit_duration: 600,
+        ..valid_config()
+    };
+
+    for config in [vickrey_final_round, without_finalists] {
+        assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::FinalRoundNotSupported.into()
+        );
+    }
+}
+
+#[test]
+fn token_bids_need_a_plain_english_auction() {
+    let config = AuctionConfig {
+        auction_type: AuctionType::Vickrey,
+        bid_mint: Pubkey::new_unique(),
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::TokenBidsNotSupported.into()
+    );
+}
+
+#[test]
+fn prize_escrow_needs_a_single_winner() {
+    let config = AuctionConfig {
+        auction_type: AuctionType::Perpetual,
+        prize_mint: Pubkey::new_unique(),
+        prize_amount: 1,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::PrizeEscrowNotSupported.into()
+    );
+}
+
+#[test]
+fn prize_escrow_needs_a_mint_and_an_amount() {
+    let prize = AuctionConfig {
+        prize_mint: Pubkey::new_unique(),
+        prize_amount: 1,
+        ..valid_config()
+    };
+    let without_amount = AuctionConfig {
+        prize_mint: Pubkey::new_unique(),
+        ..valid_config()
+    };
+    let without_mint = AuctionConfig {
+        prize_amount: 1,
+        ..valid_config()
+    };
+
+    validate(&prize, NOW).unwrap();
+    assert_eq!(
+        validate(&without_amount, NOW).unwrap_err(),
+        AuctionError::InvalidPrizeEscrow.into()
+    );
+    assert_eq!(
+        validate(&without_mint, NOW).unwrap_err(),
+        AuctionError::InvalidPrizeEscrow.into()
+    );
+}
+
+#[test]
+fn candle_window_must_fit_in_the_auction() {
+    let whole_auction = AuctionConfig {
+        candle_window: 3_600,
+        ..valid_config()
+    };
+    let too_long = AuctionConfig {
+        candle_window: 3_601,
+        ..valid_config()
+    };
+    let negative = AuctionConfig {
+        candle_window: -1,
+        ..valid_config()
+    };
+
+    validate(&whole_auction, NOW).unwrap();
+    assert_eq!(
+        validate(&too_long, NOW).unwrap_err(),
+        AuctionError::InvalidCandleWindow.into()
+    );
+    assert_eq!(
+        validate(&negative, NOW).unwrap_err(),
+        AuctionError::InvalidCandleWindow.into()
+    );
+}
+
+#[test]
+fn quantity_must_fit_in_the_leaderboard() {
+    let every_entry = AuctionConfig {
+        quantity: LEADERBOARD_SIZE as u16,
+        ..valid_config()
+    };
+    let too_many = AuctionConfig {
+        quantity: LEADERBOARD_SIZE as u16 + 1,
+        ..valid_config()
+    };
+    let dutch_units = AuctionConfig {
+        auction_type: AuctionType::Dutch,
+        start_price: 2_000,
+        quantity: 3,
+        ..valid_config()
+    };
+
+    validate(&every_entry, NOW).unwrap();
+    validate(&dutch_units, NOW).unwrap();
+    assert_eq!(
+        validate(&too_many, NOW).unwrap_err(),
+        AuctionError::InvalidQuantity.into()
+    );
+}
+
+#[test]
+fn rounds_need_a_tick_and_fit_in_the_auction() {
+    let rounds = AuctionConfig {
+        round_duration_secs: 600,
+        tick: 100,
+        ..valid_config()
+    };
+    let without_tick = AuctionConfig {
+        tick: 0,
+        ..rounds.clone()
+    };
+    let too_long = AuctionConfig {
+        round_duration_secs: 3_601,
+        ..rounds.clone()
+    };
+    let negative = AuctionConfig {
+        round_duration_secs: -1,
+        ..rounds.clone()
+    };
+
+    validate(&rounds, NOW).unwrap();
+    for config in [without_tick, too_long, negative] {
+        assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::InvalidRoundSchedule.into()
+        );
+    }
+}
+
+#[test]
+fn final_rounds_need_finalists_and_phases_before_the_deadline() {
+    let final_round = AuctionConfig {
+        finalist_count: 3,
+        open_duration: 1_800,
+        final_commit_duration: 600,
+        ..valid_config()
+    };
+    let whole_leaderboard = AuctionConfig {
+        finalist_count: LEADERBOARD_SIZE as u8,
+        ..final_round.clone()
+    };
+    let too_many_finalists = AuctionConfig {
+        finalist_count: LEADERBOARD_SIZE as u8 + 1,
+        ..final_round.clone()
+    };
+    let without_open_phase = AuctionConfig {
+        open_duration: 0,
+        ..final_round.clone()
+    };
+    let without_commit_phase = AuctionConfig {
+        final_commit_duration: 0,
+        ..final_round.clone()
+    };
+    let no_reveal_phase = AuctionConfig {
+        final_commit_duration: 1_800,
+        ..final_round.clone()
+    };
+    let overflowing = AuctionConfig {
+        open_duration: i64::MAX,
+        ..final_round.clone()
+    };
+
+    validate(&final_round, NOW).unwrap();
+    validate(&whole_leaderboard, NOW).unwrap();
+    for config in [
+        too_many_finalists,
+        without_open_phase,
+        without_commit_phase,
+        no_reveal_phase,
+        overflowing,
+    ] {
+        assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::InvalidFinalRound.into()
+        );
+    }
+}
+
+#[test]
+fn penny_auctions_need_a_fee_and_an_increment() {
+    let penny = AuctionConfig {
+        auction_type: AuctionType::Penny,
+        bid_fee: 1_000,
+        min_bid_increment: 10,
+        extension_secs: 30,
+        ..valid_config()
+    };
+    let without_fee = AuctionConfig {
+        bid_fee: 0,
+        ..penny.clone()
+    };
+    let without_increment = AuctionConfig {
+        min_bid_increment: 0,
+        ..penny.clone()
+    };
+    let negative_extension = AuctionConfig {
+        extension_secs: -1,
+        ..penny.clone()
+    };
+
+    validate(&penny, NOW).unwrap();
+    for config in [without_fee, without_increment, negative_extension] {
+        assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::InvalidPennySettings.into()
+        );
+    }
+}
+
+#[test]
+fn negative_price_step_is_rejected() {
+    let config = AuctionConfig {
+        auction_type: AuctionType::Dutch,
+        start_price: 2_000,
+        price_step_secs: -1,
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::InvalidPriceStep.into()
+    );
+}
+
+#[test]
+fn bundle_up_to_the_maximum_passes() {
+    let config = AuctionConfig {
+        bundle_items: vec![Pubkey::new_unique(); MAX_BUNDLE_ITEMS],
+        ..valid_config()
+    };
+
+    assert!(validate(&config, NOW).is_ok());
+}
+
+#[test]
+fn oversized_bundle_is_rejected() {
+    let config = AuctionConfig {
+        bundle_items: vec![Pubkey::new_unique(); MAX_BUNDLE_ITEMS + 1],
+        ..valid_config()
+    };
+
+    assert_eq!(
+        validate(&config, NOW).unwrap_err(),
+        AuctionError::BundleItemCountExceeded.into()
+    );
+}
+
+proptest! {
+    #[test]
+    fn duration_out_of_bounds_is_rejected(
+        auction_duration in prop_oneof![i64::MIN..MIN_DURATION, (MAX_DURATION + 1)..=i64::MAX],
+    ) {
+        let config = AuctionConfig { auction_duration, ..valid_config() };
+
+        prop_assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::InvalidAuctionDuration.into()
+        );
+    }
+
+    #[test]
+    fn commit_phase_outside_the_auction_is_rejected(
+        commit_duration in prop_oneof![i64::MIN..0, 3_600..=i64::MAX],
+    ) {
+        let config = AuctionConfig { commit_duration, ..valid_config() };
+
+        prop_assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::InvalidCommitDuration.into()
+        );
+    }
+
+    #[test]
+    fn dutch_start_price_below_the_reserve_is_rejected(
+        reserve_price in 1..u64::MAX,
+        shortfall in 1..u64::MAX,
+    ) {
+        let config = AuctionConfig {
+            auction_type: AuctionType::Dutch,
+            reserve_price,
+            start_price: reserve_price.saturating_sub(shortfall),
+            ..valid_config()
+        };
+
+        prop_assert_eq!(
+            validate(&config, NOW).unwrap_err(),
+            AuctionError::InvalidStartPrice.into()
+        );
+    }
+
+    #[test]
+    fn bps_above_100_percent_are_rejected(bps in 10_001..=u16::MAX) {
+        let compensation = AuctionConfig { cancel_compensation_bps: bps, ..valid_config() };
+        let penalty = AuctionConfig { withdrawal_penalty_bps: bps, ..valid_config() };
+
+        prop_assert_eq!(
+            validate(&compensation, NOW).unwrap_err(),
+            AuctionError::InvalidCompensation.into()
+        );
+        prop_assert_eq!(
+            validate(&penalty, NOW).unwrap_err(),
+            AuctionError::InvalidPenalty.into()
+        );
+    }
+
+    #[test]
+    fn long_metadata_is_rejected(
+        title_len in (MAX_TITLE_LEN + 1)..(MAX_TITLE_LEN * 4),
+        uri_len in (MAX_URI_LEN + 1)..(MAX_URI_LEN * 4),
+    ) {
+        let long_title = AuctionConfig { title: "t".repeat(title_len), ..valid_config() };
+        let long_uri = AuctionConfig { uri: "u".repeat(uri_len), ..valid_config() };
+
+        prop_assert_eq!(
+            validate(&long_title, NOW).unwrap_err(),
+            AuctionError::MetadataTooLong.into()
+        );
+        prop_assert_eq!(
+            validate(&long_uri, NOW).unwrap_err(),
+            AuctionError::MetadataTooLong.into()
+        );
+    }
+
+    #[test]
+    fn valid_configs_pass(
+        auction_duration in MIN_DURATION..=MAX_DURATION,
+        cancel_compensation_bps in 0..=10_000u16,
+        withdrawal_penalty_bps in 0..=10_000u16,
+        now in 0..=(i64::MAX - MAX_DURATION),
+    ) {
+        let config = AuctionConfig {
+            auction_duration,
+            cancel_compensation_bps,
+            withdrawal_penalty_bps,
+            ..valid_config()
+        };
+
+        prop_assert!(validate(&config, now).is_ok());
+    }
+}
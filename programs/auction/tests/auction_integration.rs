@@ -0,0 +1,2619 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, hash::hash,
+        native_token::sol_to_lamports, program::set_return_data, program_pack::Pack, sysvar,
+    },
+    system_program, AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{associated_token, token::spl_token};
+use auction::constants::{
+    AUCTION_SUMMARY_SPACE, BIDDER_PROFILE_SPACE, BID_COOLDOWN_SECONDS, BID_ESCROW_SPACE,
+    CONFIG_SPACE, CURRENT_STATE_VERSION, LEADERBOARD_SPACE, LOYALTY_DISCOUNT_BPS,
+    LOYALTY_THRESHOLD, MAX_AUCTION_DURATION_SECS, MAX_DESCRIPTION_LEN, MIN_AUCTION_DURATION_SECS,
+    REGISTRY_PAGE_SPACE, REGISTRY_SPACE, SELLER_COUNTER_SPACE, SELLER_PROFILE_SPACE,
+    SHILL_LOOKBACK_SECS, STATE_SPACE, TAX_RECORD_SPACE, TREASURY_SPACE, USER_BID_SPACE,
+};
+use auction::errors::AuctionError;
+use auction::{
+    AuctionConfig, AuctionSummary, AuctionType, BidderProfile, Config, DescriptionAccount,
+    LoyaltyDiscount, PdaHelpers, State, TaxRecord,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData, ReadableAccount, WritableAccount},
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+const INITIAL_FUNDS: u64 = 10_000_000_000;
+const AUCTION_DURATION_SECS: i64 = 60;
+
+async fn setup() -> ProgramTestContext {
+    setup_with_platform_fee(0).await
+}
+
+// The program is loaded without its program data account, so the config can not be
+// initialized through `initialize_config`. It is written directly instead
+async fn setup_with_platform_fee(platform_fee_bps: u16) -> ProgramTestContext {
+    let mut program_test = ProgramTest::new("auction", auction::id(), processor!(auction::entry));
+    program_test.add_program(
+        "condition",
+        condition_program_id(),
+        processor!(check_condition),
+    );
+    program_test.add_program(
+        "yield_vault",
+        yield_vault_program_id(),
+        processor!(yield_vault),
+    );
+    // the lamports of the vault on top of its deposits pay their yield
+    program_test.add_account(
+        yield_vault(),
+        Account {
+            lamports: sol_to_lamports(1.0),
+            data: vec![0; 8],
+            owner: yield_vault_program_id(),
+            ..Account::default()
+        },
+    );
+
+    let mut data = Vec::with_capacity(CONFIG_SPACE);
+    Config {
+        authority: Pubkey::new_unique(),
+        max_auction_duration: MAX_AUCTION_DURATION_SECS,
+        min_auction_duration: MIN_AUCTION_DURATION_SECS,
+        platform_fee_bps,
+        fee_recipient: fee_recipient(),
+        min_bid_amount: 0,
+        bid_cooldown_seconds: BID_COOLDOWN_SECONDS,
+        platform_authority: Pubkey::new_unique(),
+        shill_lookback_secs: SHILL_LOOKBACK_SECS,
+        loyalty_discount_bps: LOYALTY_DISCOUNT_BPS,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    data.resize(CONFIG_SPACE, 0);
+    program_test.add_account(
+        config_pda(),
+        Account {
+            lamports: sol_to_lamports(1.0),
+            data,
+            owner: auction::id(),
+            ..Account::default()
+        },
+    );
+
+    program_test.start_with_context().await
+}
+
+// Condition program of the conditional auctions, the condition is met when the first
+// byte of the condition account is 1
+fn check_condition(_program_id: &Pubkey, accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let met = accounts[0].try_borrow_data()?[0];
+    set_return_data(&[met]);
+    Ok(())
+}
+
+fn condition_program_id() -> Pubkey {
+    Pubkey::new_from_array([7; 32])
+}
+
+fn vault_instruction(name: &str) -> Vec<u8> {
+    hash(format!("global:{}", name).as_bytes()).to_bytes()[..8].to_vec()
+}
+
+// Yield vault of the bids deposited with `deposit_to_vault`. Its account holds the sum
+// of the deposits, each lamport deposited is a share, and the vault is worth 5% more
+// than its deposits
+fn yield_vault(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let vault = &accounts[0];
+    let deposits = u64::from_le_bytes(vault.try_borrow_data()?[..8].try_into().unwrap());
+    let value = deposits * 105 / 100;
+    let (instruction, args) = data.split_at(8);
+    if instruction == vault_instruction("deposit") {
+        let amount = u64::from_le_bytes(args.try_into().unwrap());
+        vault.try_borrow_mut_data()?[..8].copy_from_slice(&(deposits + amount).to_le_bytes());
+        set_return_data(&amount.to_le_bytes());
+    } else if instruction == vault_instruction("vault_value") {
+        set_return_data(&[value.to_le_bytes(), deposits.to_le_bytes()].concat());
+    } else {
+        let shares = u64::from_le_bytes(args.try_into().unwrap());
+        let payout = value * shares / deposits;
+        vault.try_borrow_mut_data()?[..8].copy_from_slice(&(deposits - shares).to_le_bytes());
+        **vault.try_borrow_mut_lamports()? -= payout;
+        **accounts[1].try_borrow_mut_lamports()? += payout;
+    }
+    Ok(())
+}
+
+fn yield_vault_program_id() -> Pubkey {
+    Pubkey::new_from_array([9; 32])
+}
+
+fn yield_vault() -> Pubkey {
+    Pubkey::new_from_array([0x5a; 32])
+}
+
+// Every transaction is paid by the context payer, so the balances of the auction
+// participants only move by the amounts the program transfers
+async fn send(
+    ctx: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&ctx.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    ctx.banks_client.process_transaction(tx).await
+}
+
+async fn funded_keypair(ctx: &mut ProgramTestContext) -> Keypair {
+    let keypair = Keypair::new();
+    let ix = system_instruction::transfer(&ctx.payer.pubkey(), &keypair.pubkey(), INITIAL_FUNDS);
+    send(ctx, ix, &[]).await.unwrap();
+    keypair
+}
+
+async fn balance(ctx: &mut ProgramTestContext, account: Pubkey) -> u64 {
+    ctx.banks_client.get_balance(account).await.unwrap()
+}
+
+async fn rent(ctx: &mut ProgramTestContext, space: usize) -> u64 {
+    ctx.banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(space)
+}
+
+async fn fetch_state(ctx: &mut ProgramTestContext, state: Pubkey) -> State {
+    let account = ctx.banks_client.get_account(state).await.unwrap().unwrap();
+    State::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+// Moves the clock to the auction deadline, which already belongs to the ended auction
+async fn warp_to_deadline(ctx: &mut ProgramTestContext, state: Pubkey) {
+    let deadline = fetch_state(ctx, state).await.deadline;
+    let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = deadline;
+    ctx.set_sysvar(&clock);
+}
+
+fn assert_auction_error(result: Result<(), BanksClientError>, error: AuctionError) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+    );
+}
+
+fn state_pda(initializer: &Pubkey) -> Pubkey {
+    PdaHelpers::state_pda(initializer).0
+}
+
+fn config_pda() -> Pubkey {
+    PdaHelpers::config_pda().0
+}
+
+// Wallet of the config receiving the platform fees
+fn fee_recipient() -> Pubkey {
+    Pubkey::new_from_array([0xfe; 32])
+}
+
+fn treasury_pda(state: &Pubkey) -> Pubkey {
+    PdaHelpers::treasury_pda(state).0
+}
+
+fn treasury_authority_pda(state: &Pubkey) -> Pubkey {
+    PdaHelpers::treasury_authority_pda(state).0
+}
+
+fn seller_bond_pda(state: &Pubkey) -> Pubkey {
+    PdaHelpers::seller_bond_pda(state).0
+}
+
+fn leaderboard_pda(state: &Pubkey) -> Pubkey {
+    PdaHelpers::leaderboard_pda(state).0
+}
+
+fn description_pda(state: &Pubkey) -> Pubkey {
+    PdaHelpers::description_pda(state).0
+}
+
+fn summary_pda(state: &Pubkey) -> Pubkey {
+    PdaHelpers::summary_pda(state).0
+}
+
+fn tax_record_pda(state: &Pubkey) -> Pubkey {
+    PdaHelpers::tax_record_pda(state).0
+}
+
+fn sybil_check_pda(related: &Pubkey, state: &Pubkey) -> Pubkey {
+    PdaHelpers::sybil_check_pda(related, state).0
+}
+
+fn profile_pda(bidder: &Pubkey) -> Pubkey {
+    PdaHelpers::profile_pda(bidder).0
+}
+
+fn loyalty_pda(bidder: &Pubkey) -> Pubkey {
+    PdaHelpers::loyalty_pda(bidder).0
+}
+
+fn user_bid_pda(user: &Pubkey, state: &Pubkey) -> Pubkey {
+    PdaHelpers::user_bid_pda(user, state).0
+}
+
+fn bid_escrow_pda(user: &Pubkey, state: &Pubkey) -> Pubkey {
+    PdaHelpers::bid_escrow_pda(user, state).0
+}
+
+fn registry_pda() -> Pubkey {
+    PdaHelpers::registry_pda().0
+}
+
+// every test runs against a fresh bank, its few auctions all fit on the first page
+fn registry_page_pda(page: u64) -> Pubkey {
+    PdaHelpers::registry_page_pda(page).0
+}
+
+fn seller_counter_pda(initializer: &Pubkey) -> Pubkey {
+    PdaHelpers::seller_counter_pda(initializer).0
+}
+
+fn seller_profile_pda(seller: &Pubkey) -> Pubkey {
+    PdaHelpers::seller_profile_pda(seller).0
+}
+
+fn initialize_ix(initializer: &Pubkey) -> Instruction {
+    initialize_with_config_ix(initializer, auction_config())
+}
+
+fn initialize_with_config_ix(initializer: &Pubkey, auction_config: AuctionConfig) -> Instruction {
+    let state = state_pda(initializer);
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::Initialize {
+            state,
+            initializer: *initializer,
+            seller_counter: seller_counter_pda(initializer),
+            seller_profile: seller_profile_pda(initializer),
+            treasury: treasury_pda(&state),
+            treasury_authority: treasury_authority_pda(&state),
+            seller_bond: seller_bond_pda(&state),
+            leaderboard: leaderboard_pda(&state),
+            config: config_pda(),
+            registry: registry_pda(),
+            registry_page: registry_page_pda(0),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::Initialize {
+            auction_index: 0,
+            auction_config,
+        }
+        .data(),
+    }
+}
+
+// English auction of `AUCTION_DURATION_SECS` seconds in lamports, without any option
+fn auction_config() -> AuctionConfig {
+    AuctionConfig {
+        auction_duration: AUCTION_DURATION_SECS,
+        reserve_price: 0,
+        reserve_price_usd_cents: 0,
+        price_feed: Pubkey::default(),
+        seller_bond: 0,
+        dispute_window: 0,
+        refund_window: 0,
+        unclaimed_beneficiary: Pubkey::default(),
+        cancel_compensation_bps: 0,
+        auction_type: AuctionType::English,
+        start_price: 0,
+        commit_duration: 0,
+        withdrawal_penalty_bps: 0,
+        title: String::new(),
+        item_hash: [0; 32],
+        uri: String::new(),
+        min_bid_increment: 0,
+        bundle_items: Vec::new(),
+        max_acceptable_price: 0,
+        price_step_secs: 0,
+        seller_claim_window: 0,
+        commit_deposit: 0,
+        milestone_count: 0,
+        condition_program: Pubkey::default(),
+        condition_account: Pubkey::default(),
+        candle_window: 0,
+        total_shares: 0,
+        quantity: 0,
+        round_duration_secs: 0,
+        tick: 0,
+        bid_fee: 0,
+        extension_secs: 0,
+        pay_as_bid: false,
+        finalist_count: 0,
+        open_duration: 0,
+        final_commit_duration: 0,
+        bid_mint: Pubkey::default(),
+        prize_mint: Pubkey::default(),
+        prize_amount: 0,
+    }
+}
+
+fn bid_ix(state: &Pubkey, user: &Pubkey, amount: f64) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::Bid {
+            state: *state,
+            user: *user,
+            payer: *user,
+            user_bid: user_bid_pda(user, state),
+            bid_escrow: bid_escrow_pda(user, state),
+            leaderboard: leaderboard_pda(state),
+            profile: profile_pda(user),
+            config: config_pda(),
+            sybil_check: sybil_check_pda(user, state),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::Bid { amount }.data(),
+    }
+}
+
+// Buys a unit of a Dutch auction at its asking price, with the accounts of a bid
+fn accept_price_ix(state: &Pubkey, user: &Pubkey) -> Instruction {
+    Instruction {
+        data: auction::instruction::AcceptPrice {}.data(),
+        ..bid_ix(state, user, 0.0)
+    }
+}
+
+// Anyone grants the status, the context payer pays its rent
+fn grant_loyalty_status_ix(user: &Pubkey, payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::GrantLoyaltyStatus {
+            user: *user,
+            profile: profile_pda(user),
+            loyalty_discount: loyalty_pda(user),
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::GrantLoyaltyStatus {}.data(),
+    }
+}
+
+fn end_auction_ix(state: &Pubkey, initializer: &Pubkey, winner: &Pubkey) -> Instruction {
+    // auctions without a condition take any account as condition program and account
+    conditional_end_auction_ix(
+        state,
+        initializer,
+        winner,
+        &system_program::ID,
+        &system_program::ID,
+    )
+}
+
+fn conditional_end_auction_ix(
+    state: &Pubkey,
+    initializer: &Pubkey,
+    winner: &Pubkey,
+    condition_program: &Pubkey,
+    condition_account: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::EndAuction {
+            state: *state,
+            authority: *initializer,
+            treasury: treasury_pda(state),
+            treasury_authority: treasury_authority_pda(state),
+            beneficiary: *initializer,
+            user_bid: user_bid_pda(winner, state),
+            bid_escrow: bid_escrow_pda(winner, state),
+            leaderboard: leaderboard_pda(state),
+            highest_bidder: *winner,
+            winner_profile: profile_pda(winner),
+            seller_profile: seller_profile_pda(initializer),
+            summary: summary_pda(state),
+            tax_record: tax_record_pda(state),
+            config: config_pda(),
+            fee_recipient: fee_recipient(),
+            winner_loyalty: loyalty_pda(winner),
+            price_feed: system_program::ID,
+            condition_program: *condition_program,
+            condition_account: *condition_account,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::EndAuction {}.data(),
+    }
+}
+
+fn refund_ix(state: &Pubkey, user: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::Refund {
+            state: *state,
+            user: *user,
+            user_bid: user_bid_pda(user, state),
+            bid_escrow: bid_escrow_pda(user, state),
+            profile: profile_pda(user),
+            rent_payer: *user,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::Refund {}.data(),
+    }
+}
+
+fn add_description_ix(state: &Pubkey, initializer: &Pubkey, text: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::AddDescription {
+            state: *state,
+            description: description_pda(state),
+            initializer: *initializer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::AddDescription { text }.data(),
+    }
+}
+
+fn update_description_ix(state: &Pubkey, initializer: &Pubkey, text: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::UpdateDescription {
+            state: *state,
+            description: description_pda(state),
+            initializer: *initializer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::UpdateDescription { text }.data(),
+    }
+}
+
+fn bid_token_ix(
+    state: &Pubkey,
+    user: &Pubkey,
+    bid_mint: &Pubkey,
+    bidder_token: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::BidToken {
+            state: *state,
+            user: *user,
+            user_bid: user_bid_pda(user, state),
+            leaderboard: leaderboard_pda(state),
+            profile: profile_pda(user),
+            config: config_pda(),
+            sybil_check: sybil_check_pda(user, state),
+            bid_mint: *bid_mint,
+            bidder_token: *bidder_token,
+            treasury_authority: treasury_authority_pda(state),
+            vault: PdaHelpers::token_vault_address(state, bid_mint),
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::BidToken { amount }.data(),
+    }
+}
+
+fn end_auction_token_ix(
+    state: &Pubkey,
+    initializer: &Pubkey,
+    winner: &Pubkey,
+    bid_mint: &Pubkey,
+    beneficiary_token: &Pubkey,
+    fee_recipient_token: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::EndAuctionToken {
+            state: *state,
+            authority: *initializer,
+            user_bid: user_bid_pda(winner, state),
+            bid_mint: *bid_mint,
+            treasury_authority: treasury_authority_pda(state),
+            vault: PdaHelpers::token_vault_address(state, bid_mint),
+            beneficiary_token: *beneficiary_token,
+            config: config_pda(),
+            fee_recipient_token: *fee_recipient_token,
+            winner_loyalty: loyalty_pda(winner),
+            winner_profile: profile_pda(winner),
+            seller_profile: seller_profile_pda(initializer),
+            summary: summary_pda(state),
+            tax_record: tax_record_pda(state),
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::EndAuctionToken {}.data(),
+    }
+}
+
+fn refund_token_ix(
+    state: &Pubkey,
+    user: &Pubkey,
+    bid_mint: &Pubkey,
+    bidder_token: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::RefundToken {
+            state: *state,
+            user: *user,
+            user_bid: user_bid_pda(user, state),
+            profile: profile_pda(user),
+            bid_mint: *bid_mint,
+            treasury_authority: treasury_authority_pda(state),
+            vault: PdaHelpers::token_vault_address(state, bid_mint),
+            bidder_token: *bidder_token,
+            rent_payer: *user,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: auction::instruction::RefundToken {}.data(),
+    }
+}
+
+fn deposit_prize_ix(
+    state: &Pubkey,
+    initializer: &Pubkey,
+    prize_mint: &Pubkey,
+    seller_token: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::DepositPrize {
+            state: *state,
+            initializer: *initializer,
+            prize_mint: *prize_mint,
+            seller_token: *seller_token,
+            prize_authority: PdaHelpers::prize_authority_pda(state).0,
+            prize_vault: PdaHelpers::prize_vault_address(state, prize_mint),
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::DepositPrize {}.data(),
+    }
+}
+
+fn reclaim_prize_ix(
+    state: &Pubkey,
+    initializer: &Pubkey,
+    prize_mint: &Pubkey,
+    seller_token: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::ReclaimPrize {
+            state: *state,
+            initializer: *initializer,
+            prize_mint: *prize_mint,
+            prize_authority: PdaHelpers::prize_authority_pda(state).0,
+            prize_vault: PdaHelpers::prize_vault_address(state, prize_mint),
+            seller_token: *seller_token,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: auction::instruction::ReclaimPrize {}.data(),
+    }
+}
+
+// The prize accounts follow the accounts of `claim_prize` when the auction escrows a prize
+fn claim_prize_ix(
+    state: &Pubkey,
+    winner: &Pubkey,
+    prize_mint: &Pubkey,
+    winner_token: &Pubkey,
+) -> Instruction {
+    let mut accounts = auction::accounts::ClaimPrize {
+        state: *state,
+        winner: *winner,
+        user_bid: user_bid_pda(winner, state),
+        bid_escrow: bid_escrow_pda(winner, state),
+        receiver: *winner,
+        rent_payer: *winner,
+    }
+    .to_account_metas(None);
+    accounts.extend([
+        AccountMeta::new_readonly(PdaHelpers::prize_authority_pda(state).0, false),
+        AccountMeta::new(PdaHelpers::prize_vault_address(state, prize_mint), false),
+        AccountMeta::new(*winner_token, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+    Instruction {
+        program_id: auction::id(),
+        accounts,
+        data: auction::instruction::ClaimPrize {
+            delivery_note_hash: None,
+        }
+        .data(),
+    }
+}
+
+// `(bidder, user_bid, bid_escrow)` remaining accounts of the escrows of `bidders`
+fn escrow_metas(state: &Pubkey, bidders: &[&Keypair]) -> Vec<AccountMeta> {
+    let mut accounts = Vec::new();
+    for bidder in bidders {
+        accounts.push(AccountMeta::new_readonly(bidder.pubkey(), false));
+        accounts.push(AccountMeta::new(
+            user_bid_pda(&bidder.pubkey(), state),
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            bid_escrow_pda(&bidder.pubkey(), state),
+            false,
+        ));
+    }
+    accounts
+}
+
+fn release_milestone_ix(state: &Pubkey, initializer: &Pubkey, winner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::ReleaseMilestone {
+            state: *state,
+            winner: *winner,
+            bid_escrow: bid_escrow_pda(winner, state),
+            beneficiary: *initializer,
+            config: config_pda(),
+            fee_recipient: fee_recipient(),
+            winner_loyalty: loyalty_pda(winner),
+        }
+        .to_account_metas(None),
+        data: auction::instruction::ReleaseMilestone {}.data(),
+    }
+}
+
+fn claim_final_milestone_ix(state: &Pubkey, initializer: &Pubkey, winner: &Pubkey) -> Instruction {
+    Instruction {
+        data: auction::instruction::ClaimFinalMilestone {}.data(),
+        ..release_milestone_ix(state, initializer, winner)
+    }
+}
+
+// The winners are passed in leaderboard order
+fn settle_multi_unit_ix(state: &Pubkey, initializer: &Pubkey, winners: &[&Keypair]) -> Instruction {
+    let mut accounts = auction::accounts::SettleMultiUnit {
+        state: *state,
+        authority: *initializer,
+        beneficiary: *initializer,
+        leaderboard: leaderboard_pda(state),
+        config: config_pda(),
+        fee_recipient: fee_recipient(),
+    }
+    .to_account_metas(None);
+    accounts.extend(escrow_metas(state, winners));
+    Instruction {
+        program_id: auction::id(),
+        accounts,
+        data: auction::instruction::SettleMultiUnit {}.data(),
+    }
+}
+
+fn share_mint_pda(state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"share-mint", state.as_ref()], &auction::id()).0
+}
+
+fn fractional_bid_ix(state: &Pubkey, user: &Pubkey, amount: f64, shares: u64) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::FractionalBid {
+            state: *state,
+            user: *user,
+            user_bid: user_bid_pda(user, state),
+            bid_escrow: bid_escrow_pda(user, state),
+            share_mint: share_mint_pda(state),
+            profile: profile_pda(user),
+            config: config_pda(),
+            sybil_check: sybil_check_pda(user, state),
+            system_program: system_program::ID,
+            token_program: spl_token::id(),
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::FractionalBid {
+            amount: sol_to_lamports(amount),
+            shares_requested: shares,
+        }
+        .data(),
+    }
+}
+
+// Every bid comes with the share account of its bidder
+fn settle_fractional_ix(
+    state: &Pubkey,
+    initializer: &Pubkey,
+    bids: &[(&Keypair, Pubkey)],
+) -> Instruction {
+    let mut accounts = auction::accounts::SettleFractional {
+        state: *state,
+        authority: *initializer,
+        beneficiary: *initializer,
+        share_mint: share_mint_pda(state),
+        config: config_pda(),
+        fee_recipient: fee_recipient(),
+        token_program: spl_token::id(),
+    }
+    .to_account_metas(None);
+    for (bidder, share_account) in bids {
+        accounts.push(AccountMeta::new_readonly(bidder.pubkey(), false));
+        accounts.push(AccountMeta::new(
+            user_bid_pda(&bidder.pubkey(), state),
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            bid_escrow_pda(&bidder.pubkey(), state),
+            false,
+        ));
+        accounts.push(AccountMeta::new(*share_account, false));
+    }
+    Instruction {
+        program_id: auction::id(),
+        accounts,
+        data: auction::instruction::SettleFractional {}.data(),
+    }
+}
+
+fn collect_bids_ix(state: &Pubkey, initializer: &Pubkey, losers: &[&Keypair]) -> Instruction {
+    let mut accounts = auction::accounts::CollectBids {
+        state: *state,
+        authority: *initializer,
+        beneficiary: *initializer,
+        config: config_pda(),
+        fee_recipient: fee_recipient(),
+    }
+    .to_account_metas(None);
+    accounts.extend(escrow_metas(state, losers));
+    Instruction {
+        program_id: auction::id(),
+        accounts,
+        data: auction::instruction::CollectBids {}.data(),
+    }
+}
+
+// Every bidder of the raffle has to be passed, sorted by key
+fn draw_winner_ix(state: &Pubkey, initializer: &Pubkey, bidders: &[&Keypair]) -> Instruction {
+    let mut bidders = bidders.to_vec();
+    bidders.sort_by_key(|bidder| bidder.pubkey());
+    let mut accounts = auction::accounts::DrawWinner {
+        state: *state,
+        authority: *initializer,
+        slot_hashes: sysvar::slot_hashes::ID,
+    }
+    .to_account_metas(None);
+    for bidder in bidders {
+        accounts.push(AccountMeta::new_readonly(bidder.pubkey(), false));
+        accounts.push(AccountMeta::new_readonly(
+            user_bid_pda(&bidder.pubkey(), state),
+            false,
+        ));
+    }
+    Instruction {
+        program_id: auction::id(),
+        accounts,
+        data: auction::instruction::DrawWinner {}.data(),
+    }
+}
+
+fn deposit_to_vault_ix(state: &Pubkey, user: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::DepositToVault {
+            state: *state,
+            user: *user,
+            user_bid: user_bid_pda(user, state),
+            bid_escrow: bid_escrow_pda(user, state),
+            vault_program: yield_vault_program_id(),
+            vault: yield_vault(),
+        }
+        .to_account_metas(None),
+        data: auction::instruction::DepositToVault {}.data(),
+    }
+}
+
+fn refund_with_yield_ix(state: &Pubkey, user: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::RefundWithYield {
+            state: *state,
+            user: *user,
+            user_bid: user_bid_pda(user, state),
+            bid_escrow: bid_escrow_pda(user, state),
+            profile: profile_pda(user),
+            rent_payer: *user,
+            vault_program: yield_vault_program_id(),
+            vault: yield_vault(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::RefundWithYield {}.data(),
+    }
+}
+
+fn verify_auction_ix(state: &Pubkey, bidders: &[&Keypair]) -> Instruction {
+    let mut accounts = auction::accounts::VerifyAuction {
+        state: *state,
+        treasury: treasury_pda(state),
+        treasury_authority: treasury_authority_pda(state),
+        seller_bond: seller_bond_pda(state),
+    }
+    .to_account_metas(None);
+    for bidder in bidders {
+        accounts.push(AccountMeta::new_readonly(bidder.pubkey(), false));
+        accounts.push(AccountMeta::new_readonly(
+            user_bid_pda(&bidder.pubkey(), state),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(
+            bid_escrow_pda(&bidder.pubkey(), state),
+            false,
+        ));
+    }
+    Instruction {
+        program_id: auction::id(),
+        accounts,
+        data: auction::instruction::VerifyAuction {}.data(),
+    }
+}
+
+fn health_check_ix(state: &Pubkey, bidders: &[&Keypair]) -> Instruction {
+    let mut bidders: Vec<Pubkey> = bidders.iter().map(|bidder| bidder.pubkey()).collect();
+    bidders.sort();
+    let mut accounts = auction::accounts::VerifyAuction {
+        state: *state,
+        treasury: treasury_pda(state),
+        treasury_authority: treasury_authority_pda(state),
+        seller_bond: seller_bond_pda(state),
+    }
+    .to_account_metas(None);
+    for bidder in bidders {
+        accounts.push(AccountMeta::new_readonly(bidder, false));
+        accounts.push(AccountMeta::new_readonly(
+            user_bid_pda(&bidder, state),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(
+            bid_escrow_pda(&bidder, state),
+            false,
+        ));
+    }
+    Instruction {
+        program_id: auction::id(),
+        accounts,
+        data: auction::instruction::HealthCheck {}.data(),
+    }
+}
+
+fn migrate_state_ix(state: &Pubkey, initializer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::MigrateState {
+            state: *state,
+            initializer: *initializer,
+            leaderboard: leaderboard_pda(state),
+            treasury: treasury_pda(state),
+            treasury_authority: treasury_authority_pda(state),
+            seller_bond: seller_bond_pda(state),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::MigrateState {}.data(),
+    }
+}
+
+fn migrate_bid_escrow_ix(state: &Pubkey, bidder: &Pubkey, payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: auction::id(),
+        accounts: auction::accounts::MigrateBidEscrow {
+            state: *state,
+            bidder: *bidder,
+            user_bid: user_bid_pda(bidder, state),
+            bid_escrow: bid_escrow_pda(bidder, state),
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: auction::instruction::MigrateBidEscrow {}.data(),
+    }
+}
+
+// Moves the bid of `bidder` back into its user bid and removes its bid escrow, as the
+// program left bids before bid escrows
+async fn write_legacy_bid(ctx: &mut ProgramTestContext, state: &Pubkey, bidder: &Pubkey) {
+    let user_bid = user_bid_pda(bidder, state);
+    let bid_escrow = bid_escrow_pda(bidder, state);
+    let held = balance(ctx, bid_escrow).await - rent(ctx, BID_ESCROW_SPACE).await;
+
+    let mut account: AccountSharedData = ctx
+        .banks_client
+        .get_account(user_bid)
+        .await
+        .unwrap()
+        .unwrap()
+        .into();
+    account.set_lamports(account.lamports() + held);
+    ctx.set_account(&user_bid, &account);
+    ctx.set_account(&bid_escrow, &AccountSharedData::default());
+}
+
+// Rewrites the state as the previous program version left it, `data_len` bytes long
+async fn write_legacy_state(ctx: &mut ProgramTestContext, state: Pubkey, data_len: usize) {
+    let mut legacy = fetch_state(ctx, state).await;
+    legacy.version = 0;
+    let mut data = Vec::new();
+    legacy.try_serialize(&mut data).unwrap();
+    data.resize(data_len, 0);
+
+    let mut account: AccountSharedData = ctx
+        .banks_client
+        .get_account(state)
+        .await
+        .unwrap()
+        .unwrap()
+        .into();
+    account.set_data(data);
+    ctx.set_account(&state, &account);
+}
+
+// Replaces the state account data with `auction`, keeping the account size
+async fn overwrite_state(ctx: &mut ProgramTestContext, state: Pubkey, auction: &State) {
+    let mut account: AccountSharedData = ctx
+        .banks_client
+        .get_account(state)
+        .await
+        .unwrap()
+        .unwrap()
+        .into();
+    let mut data = Vec::new();
+    auction.try_serialize(&mut data).unwrap();
+    data.resize(account.data().len(), 0);
+    account.set_data(data);
+    ctx.set_account(&state, &account);
+}
+
+// Makes the auction settle only when the test condition program agrees, which it does
+// when `met`. The condition account is rewritten on every call
+async fn set_condition(
+    ctx: &mut ProgramTestContext,
+    state: Pubkey,
+    condition_account: Pubkey,
+    met: bool,
+) {
+    let mut auction = fetch_state(ctx, state).await;
+    auction.condition_program = condition_program_id();
+    auction.condition_account = condition_account;
+    overwrite_state(ctx, state, &auction).await;
+
+    let condition = Account {
+        lamports: sol_to_lamports(1.0),
+        data: vec![met as u8],
+        owner: condition_program_id(),
+        ..Account::default()
+    };
+    ctx.set_account(&condition_account, &condition.into());
+}
+
+// Overwrites the count of auctions the profile of `bidder` took part in
+async fn set_participations(ctx: &mut ProgramTestContext, bidder: &Pubkey, count: u32) {
+    let profile = profile_pda(bidder);
+    let mut account: AccountSharedData = ctx
+        .banks_client
+        .get_account(profile)
+        .await
+        .unwrap()
+        .unwrap()
+        .into();
+    let mut stats = BidderProfile::try_deserialize(&mut account.data()).unwrap();
+    stats.total_auctions_participated = count;
+    let mut data = Vec::new();
+    stats.try_serialize(&mut data).unwrap();
+    account.set_data(data);
+    ctx.set_account(&profile, &account);
+}
+
+// Creates an auction and checks what the initializer paid for it
+async fn initialize_auction(ctx: &mut ProgramTestContext, initializer: &Keypair) -> Pubkey {
+    let state = state_pda(&initializer.pubkey());
+    let treasury = treasury_pda(&state);
+    let treasury_authority = treasury_authority_pda(&state);
+    let state_rent = rent(ctx, STATE_SPACE).await;
+    let treasury_rent = rent(ctx, TREASURY_SPACE).await;
+    // the treasury authority holds no data, only lamports
+    let treasury_authority_rent = rent(ctx, 0).await;
+    let leaderboard_rent = rent(ctx, LEADERBOARD_SPACE).await;
+    let seller_counter_rent = rent(ctx, SELLER_COUNTER_SPACE).await;
+    let seller_profile_rent = rent(ctx, SELLER_PROFILE_SPACE).await;
+    // the first auction of the bank also creates the registry and its first page
+    let registry_rent = rent(ctx, REGISTRY_SPACE).await + rent(ctx, REGISTRY_PAGE_SPACE).await;
+
+    send(ctx, initialize_ix(&initializer.pubkey()), &[initializer])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        balance(ctx, initializer.pubkey()).await,
+        INITIAL_FUNDS
+            - state_rent
+            - treasury_rent
+            - treasury_authority_rent
+            - leaderboard_rent
+            - seller_counter_rent
+            - seller_profile_rent
+            - registry_rent
+    );
+    assert_eq!(balance(ctx, state).await, state_rent);
+    assert_eq!(balance(ctx, treasury).await, treasury_rent);
+    assert_eq!(
+        balance(ctx, treasury_authority).await,
+        treasury_authority_rent
+    );
+    state
+}
+
+// Creates a mint without decimals whose mint authority is the context payer
+async fn create_mint(ctx: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let ix = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &mint.pubkey(),
+        rent(ctx, spl_token::state::Mint::LEN).await,
+        spl_token::state::Mint::LEN as u64,
+        &spl_token::id(),
+    );
+    send(ctx, ix, &[&mint]).await.unwrap();
+    let ix = spl_token::instruction::initialize_mint(
+        &spl_token::id(),
+        &mint.pubkey(),
+        &ctx.payer.pubkey(),
+        None,
+        0,
+    )
+    .unwrap();
+    send(ctx, ix, &[]).await.unwrap();
+    mint.pubkey()
+}
+
+// Creates a token account of `mint` for `owner`, holding `amount` freshly minted tokens
+async fn create_token_account(
+    ctx: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let account = Keypair::new();
+    let ix = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &account.pubkey(),
+        rent(ctx, spl_token::state::Account::LEN).await,
+        spl_token::state::Account::LEN as u64,
+        &spl_token::id(),
+    );
+    send(ctx, ix, &[&account]).await.unwrap();
+    let ix = spl_token::instruction::initialize_account(
+        &spl_token::id(),
+        &account.pubkey(),
+        mint,
+        owner,
+    )
+    .unwrap();
+    send(ctx, ix, &[]).await.unwrap();
+    if amount > 0 {
+        let ix = spl_token::instruction::mint_to(
+            &spl_token::id(),
+            mint,
+            &account.pubkey(),
+            &ctx.payer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        send(ctx, ix, &[]).await.unwrap();
+    }
+    account.pubkey()
+}
+
+async fn token_balance(ctx: &mut ProgramTestContext, account: Pubkey) -> u64 {
+    let account = ctx
+        .banks_client
+        .get_account(account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
+
+// Places a bid and checks that it is escrowed in the bid escrow PDA
+async fn place_bid(ctx: &mut ProgramTestContext, state: &Pubkey, bidder: &Keypair, amount: f64) {
+    let user_bid = user_bid_pda(&bidder.pubkey(), state);
+    let bid_escrow = bid_escrow_pda(&bidder.pubkey(), state);
+    let user_bid_rent = rent(ctx, USER_BID_SPACE).await;
+    let escrow_rent = rent(ctx, BID_ESCROW_SPACE).await;
+    // the first bid of a bidder also opens its profile
+    let profile_rent = match balance(ctx, profile_pda(&bidder.pubkey())).await {
+        0 => rent(ctx, BIDDER_PROFILE_SPACE).await,
+        _ => 0,
+    };
+    let bidder_balance = balance(ctx, bidder.pubkey()).await;
+
+    send(ctx, bid_ix(state, &bidder.pubkey(), amount), &[bidder])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        balance(ctx, bidder.pubkey()).await,
+        bidder_balance - user_bid_rent - escrow_rent - profile_rent - sol_to_lamports(amount)
+    );
+    assert_eq!(balance(ctx, user_bid).await, user_bid_rent);
+    assert_eq!(
+        balance(ctx, bid_escrow).await,
+        escrow_rent + sol_to_lamports(amount)
+    );
+}
+
+// Settles the auction and checks that only the winner's escrow paid the seller, who
+// also paid the rent of the summary and the tax record
+async fn end_auction(
+    ctx: &mut ProgramTestContext,
+    state: &Pubkey,
+    initializer: &Keypair,
+    winner: &Keypair,
+    winning_amount: f64,
+) {
+    let bid_escrow = bid_escrow_pda(&winner.pubkey(), state);
+    let seller_balance = balance(ctx, initializer.pubkey()).await;
+    let escrow_balance = balance(ctx, bid_escrow).await;
+    let treasury_balance = balance(ctx, treasury_authority_pda(state)).await;
+    let records_rent = rent(ctx, AUCTION_SUMMARY_SPACE).await + rent(ctx, TAX_RECORD_SPACE).await;
+
+    send(
+        ctx,
+        end_auction_ix(state, &initializer.pubkey(), &winner.pubkey()),
+        &[initializer],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        balance(ctx, initializer.pubkey()).await,
+        seller_balance + sol_to_lamports(winning_amount) - records_rent
+    );
+    assert_eq!(
+        balance(ctx, bid_escrow).await,
+        escrow_balance - sol_to_lamports(winning_amount)
+    );
+    assert_eq!(
+        balance(ctx, treasury_authority_pda(state)).await,
+        treasury_balance
+    );
+    assert!(fetch_state(ctx, *state).await.seller_payed);
+}
+
+// Refunds a bidder and checks that the whole escrow and the user bid rent went back to it
+async fn refund(ctx: &mut ProgramTestContext, state: &Pubkey, bidder: &Keypair) {
+    let user_bid = user_bid_pda(&bidder.pubkey(), state);
+    let bid_escrow = bid_escrow_pda(&bidder.pubkey(), state);
+    let bidder_balance = balance(ctx, bidder.pubkey()).await;
+    let escrow_balance = balance(ctx, user_bid).await + balance(ctx, bid_escrow).await;
+
+    send(ctx, refund_ix(state, &bidder.pubkey()), &[bidder])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        balance(ctx, bidder.pubkey()).await,
+        bidder_balance + escrow_balance
+    );
+    assert_eq!(balance(ctx, user_bid).await, 0);
+    assert_eq!(balance(ctx, bid_escrow).await, 0);
+}
+
+#[tokio::test]
+async fn single_bidder_gets_back_the_user_bid_rent() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let winner = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &winner, 1.0).await;
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &winner, 1.0).await;
+    refund(&mut ctx, &state, &winner).await;
+
+    let profile_rent = rent(&mut ctx, BIDDER_PROFILE_SPACE).await;
+    assert_eq!(
+        balance(&mut ctx, winner.pubkey()).await,
+        INITIAL_FUNDS - profile_rent - sol_to_lamports(1.0)
+    );
+}
+
+#[tokio::test]
+async fn every_loser_is_refunded_in_full() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let mut bidders = Vec::new();
+    for _ in 0..3 {
+        bidders.push(funded_keypair(&mut ctx).await);
+    }
+    let amounts = [1.0, 1.2, 1.3];
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    for (bidder, amount) in bidders.iter().zip(amounts) {
+        place_bid(&mut ctx, &state, bidder, amount).await;
+    }
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &bidders[2], 1.3).await;
+
+    // the profiles outlive the auction, their rent is the only thing not refunded
+    let profile_rent = rent(&mut ctx, BIDDER_PROFILE_SPACE).await;
+    for loser in &bidders[..2] {
+        refund(&mut ctx, &state, loser).await;
+        assert_eq!(
+            balance(&mut ctx, loser.pubkey()).await,
+            INITIAL_FUNDS - profile_rent
+        );
+    }
+    refund(&mut ctx, &state, &bidders[2]).await;
+    assert_eq!(
+        balance(&mut ctx, bidders[2].pubkey()).await,
+        INITIAL_FUNDS - profile_rent - sol_to_lamports(1.3)
+    );
+}
+
+#[tokio::test]
+async fn refunds_count_down_the_bids_of_the_auction() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let mut bidders = Vec::new();
+    for _ in 0..2 {
+        bidders.push(funded_keypair(&mut ctx).await);
+    }
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &bidders[0], 1.0).await;
+    place_bid(&mut ctx, &state, &bidders[1], 1.2).await;
+    assert_eq!(fetch_state(&mut ctx, state).await.bid_count, 2);
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &bidders[1], 1.2).await;
+
+    refund(&mut ctx, &state, &bidders[0]).await;
+    assert_eq!(fetch_state(&mut ctx, state).await.bid_count, 1);
+    refund(&mut ctx, &state, &bidders[1]).await;
+    assert_eq!(fetch_state(&mut ctx, state).await.bid_count, 0);
+}
+
+// Lamports held by `accounts`. Transactions are paid by the context payer, so the sum
+// over every account an auction touches only moves when lamports leak or are created
+async fn total_lamports(ctx: &mut ProgramTestContext, accounts: &[Pubkey]) -> u64 {
+    let mut total = 0;
+    for account in accounts {
+        total += balance(ctx, *account).await;
+    }
+    total
+}
+
+#[tokio::test]
+async fn lamports_are_conserved_through_the_lifecycle() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let mut bidders = Vec::new();
+    for _ in 0..3 {
+        bidders.push(funded_keypair(&mut ctx).await);
+    }
+    let amounts = [1.0, 1.2, 1.3];
+
+    let state = state_pda(&initializer.pubkey());
+    let mut accounts = vec![
+        initializer.pubkey(),
+        state,
+        treasury_pda(&state),
+        treasury_authority_pda(&state),
+        // the rent of the records created along the way stays with them
+        leaderboard_pda(&state),
+        seller_counter_pda(&initializer.pubkey()),
+        seller_profile_pda(&initializer.pubkey()),
+        registry_pda(),
+        registry_page_pda(0),
+        summary_pda(&state),
+        tax_record_pda(&state),
+    ];
+    for bidder in &bidders {
+        accounts.push(bidder.pubkey());
+        accounts.push(user_bid_pda(&bidder.pubkey(), &state));
+        accounts.push(bid_escrow_pda(&bidder.pubkey(), &state));
+        accounts.push(profile_pda(&bidder.pubkey()));
+    }
+    let total = total_lamports(&mut ctx, &accounts).await;
+
+    initialize_auction(&mut ctx, &initializer).await;
+    assert_eq!(total_lamports(&mut ctx, &accounts).await, total);
+
+    for (bidder, amount) in bidders.iter().zip(amounts) {
+        place_bid(&mut ctx, &state, bidder, amount).await;
+        assert_eq!(total_lamports(&mut ctx, &accounts).await, total);
+    }
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &bidders[2], 1.3).await;
+    assert_eq!(total_lamports(&mut ctx, &accounts).await, total);
+
+    for bidder in &bidders {
+        refund(&mut ctx, &state, bidder).await;
+        assert_eq!(total_lamports(&mut ctx, &accounts).await, total);
+    }
+}
+
+#[tokio::test]
+async fn summary_records_the_settled_auction() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidders = [
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+    ];
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &bidders[0], 1.0).await;
+    place_bid(&mut ctx, &state, &bidders[1], 1.5).await;
+    let auction = fetch_state(&mut ctx, state).await;
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &bidders[1], 1.5).await;
+
+    let account = ctx
+        .banks_client
+        .get_account(summary_pda(&state))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), AUCTION_SUMMARY_SPACE);
+    let summary = AuctionSummary::try_deserialize(&mut account.data.as_slice()).unwrap();
+    let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    assert_eq!(summary.winner, bidders[1].pubkey());
+    assert_eq!(summary.final_price, sol_to_lamports(1.5));
+    assert_eq!(summary.total_bids, 2);
+    assert_eq!(summary.total_bidders, 2);
+    assert_eq!(summary.settled_at, clock.unix_timestamp);
+    assert_eq!(summary.settled_at_slot, clock.slot);
+    assert_eq!(
+        summary.auction_duration,
+        auction.deadline - auction.start_time
+    );
+}
+
+#[tokio::test]
+async fn tax_record_accounts_for_every_lamport_of_the_sale() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidders = [
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+    ];
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &bidders[0], 1.0).await;
+    place_bid(&mut ctx, &state, &bidders[1], 1.5).await;
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &bidders[1], 1.5).await;
+
+    let account = ctx
+        .banks_client
+        .get_account(tax_record_pda(&state))
+        .await
+        .unwrap()
+        .unwrap();
+    let record = TaxRecord::try_deserialize(&mut account.data.as_slice()).unwrap();
+    let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    assert_eq!(record.seller, initializer.pubkey());
+    assert_eq!(record.buyer, bidders[1].pubkey());
+    assert_eq!(record.gross_sale_amount, sol_to_lamports(1.5));
+    assert_eq!(
+        record.gross_sale_amount,
+        record.net_seller_proceeds + record.platform_fee_amount + record.royalty_amount
+    );
+    assert_eq!(record.settlement_slot, clock.slot);
+    assert_eq!(record.settlement_unix_timestamp, clock.unix_timestamp);
+}
+
+#[tokio::test]
+async fn bid_after_deadline_fails() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    warp_to_deadline(&mut ctx, state).await;
+
+    let result = send(&mut ctx, bid_ix(&state, &bidder.pubkey(), 1.0), &[&bidder]).await;
+
+    assert_auction_error(result, AuctionError::Finished);
+    assert_eq!(balance(&mut ctx, bidder.pubkey()).await, INITIAL_FUNDS);
+    assert_eq!(
+        balance(&mut ctx, user_bid_pda(&bidder.pubkey(), &state)).await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn end_auction_before_deadline_fails() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &bidder, 1.0).await;
+
+    let bid_escrow = bid_escrow_pda(&bidder.pubkey(), &state);
+    let seller_balance = balance(&mut ctx, initializer.pubkey()).await;
+    let escrow_balance = balance(&mut ctx, bid_escrow).await;
+
+    let result = send(
+        &mut ctx,
+        end_auction_ix(&state, &initializer.pubkey(), &bidder.pubkey()),
+        &[&initializer],
+    )
+    .await;
+
+    assert_auction_error(result, AuctionError::StillActive);
+    assert_eq!(
+        balance(&mut ctx, initializer.pubkey()).await,
+        seller_balance
+    );
+    assert_eq!(balance(&mut ctx, bid_escrow).await, escrow_balance);
+    assert!(!fetch_state(&mut ctx, state).await.seller_payed);
+}
+
+#[tokio::test]
+async fn double_end_auction_fails() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &bidder, 1.0).await;
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &bidder, 1.0).await;
+
+    let bid_escrow = bid_escrow_pda(&bidder.pubkey(), &state);
+    let seller_balance = balance(&mut ctx, initializer.pubkey()).await;
+    let escrow_balance = balance(&mut ctx, bid_escrow).await;
+
+    let result = send(
+        &mut ctx,
+        end_auction_ix(&state, &initializer.pubkey(), &bidder.pubkey()),
+        &[&initializer],
+    )
+    .await;
+
+    assert_auction_error(result, AuctionError::AlreadyClaimedPrize);
+    assert_eq!(
+        balance(&mut ctx, initializer.pubkey()).await,
+        seller_balance
+    );
+    assert_eq!(balance(&mut ctx, bid_escrow).await, escrow_balance);
+}
+
+#[tokio::test]
+async fn verify_auction_holds_through_settlement() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let loser = funded_keypair(&mut ctx).await;
+    let winner = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &loser, 1.0).await;
+    place_bid(&mut ctx, &state, &winner, 1.5).await;
+
+    send(&mut ctx, verify_auction_ix(&state, &[&loser, &winner]), &[])
+        .await
+        .unwrap();
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &winner, 1.5).await;
+
+    send(&mut ctx, verify_auction_ix(&state, &[&loser, &winner]), &[])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn verify_auction_detects_a_drained_escrow() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let loser = funded_keypair(&mut ctx).await;
+    let winner = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &loser, 1.0).await;
+    place_bid(&mut ctx, &state, &winner, 1.5).await;
+
+    // corrupt the loser's escrow so it no longer covers its bid
+    let bid_escrow = bid_escrow_pda(&loser.pubkey(), &state);
+    let mut escrow: AccountSharedData = ctx
+        .banks_client
+        .get_account(bid_escrow)
+        .await
+        .unwrap()
+        .unwrap()
+        .into();
+    escrow.set_lamports(escrow.lamports() - 1);
+    ctx.set_account(&bid_escrow, &escrow);
+
+    // the healthy escrow alone still passes
+    send(&mut ctx, verify_auction_ix(&state, &[&winner]), &[])
+        .await
+        .unwrap();
+
+    let result = send(&mut ctx, verify_auction_ix(&state, &[&loser, &winner]), &[]).await;
+    assert_auction_error(result, AuctionError::InvariantViolated);
+}
+
+#[tokio::test]
+async fn health_check_needs_every_escrow_and_a_matching_highest_bid() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let loser = funded_keypair(&mut ctx).await;
+    let winner = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &loser, 1.0).await;
+    place_bid(&mut ctx, &state, &winner, 1.5).await;
+
+    send(&mut ctx, health_check_ix(&state, &[&loser, &winner]), &[])
+        .await
+        .unwrap();
+
+    // a missing escrow leaves a bid unaccounted for
+    let result = send(&mut ctx, health_check_ix(&state, &[&winner]), &[]).await;
+    assert_auction_error(result, AuctionError::InvariantViolated);
+
+    // corrupt the highest bid so no escrow holds it
+    let mut auction = fetch_state(&mut ctx, state).await;
+    auction.highest_bid_amount = sol_to_lamports(2.0);
+    overwrite_state(&mut ctx, state, &auction).await;
+    let result = send(&mut ctx, health_check_ix(&state, &[&loser, &winner]), &[]).await;
+    assert_auction_error(result, AuctionError::InvariantViolated);
+}
+
+#[tokio::test]
+async fn stale_state_is_migrated_before_use() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+
+    write_legacy_state(&mut ctx, state, STATE_SPACE).await;
+    let result = send(&mut ctx, bid_ix(&state, &bidder.pubkey(), 1.0), &[&bidder]).await;
+    assert_auction_error(result, AuctionError::StateMigrationRequired);
+
+    // accounts of the previous layouts are shorter than `STATE_SPACE`
+    let mut serialized = Vec::new();
+    fetch_state(&mut ctx, state)
+        .await
+        .try_serialize(&mut serialized)
+        .unwrap();
+    write_legacy_state(&mut ctx, state, serialized.len() - 1).await;
+
+    let result = send(
+        &mut ctx,
+        migrate_state_ix(&state, &bidder.pubkey()),
+        &[&bidder],
+    )
+    .await;
+    assert_auction_error(result, AuctionError::Unauthorized);
+
+    send(
+        &mut ctx,
+        migrate_state_ix(&state, &initializer.pubkey()),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+
+    let account = ctx.banks_client.get_account(state).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), STATE_SPACE);
+    assert_eq!(
+        fetch_state(&mut ctx, state).await.version,
+        CURRENT_STATE_VERSION
+    );
+
+    let result = send(
+        &mut ctx,
+        migrate_state_ix(&state, &initializer.pubkey()),
+        &[&initializer],
+    )
+    .await;
+    assert_auction_error(result, AuctionError::StateAlreadyMigrated);
+
+    place_bid(&mut ctx, &state, &bidder, 1.0).await;
+}
+
+#[tokio::test]
+async fn legacy_bid_is_migrated_to_its_bid_escrow() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let loser = funded_keypair(&mut ctx).await;
+    let winner = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &loser, 1.0).await;
+    write_legacy_bid(&mut ctx, &state, &loser.pubkey()).await;
+
+    // the bid can not be raised while its user bid still holds it
+    let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += BID_COOLDOWN_SECONDS;
+    ctx.set_sysvar(&clock);
+    let result = send(&mut ctx, bid_ix(&state, &loser.pubkey(), 2.0), &[&loser]).await;
+    assert_auction_error(result, AuctionError::BidEscrowMigrationRequired);
+
+    // anyone migrates it, here the context payer, who pays the escrow rent
+    let payer = ctx.payer.pubkey();
+    send(
+        &mut ctx,
+        migrate_bid_escrow_ix(&state, &loser.pubkey(), &payer),
+        &[],
+    )
+    .await
+    .unwrap();
+    let user_bid_rent = rent(&mut ctx, USER_BID_SPACE).await;
+    let escrow_rent = rent(&mut ctx, BID_ESCROW_SPACE).await;
+    assert_eq!(
+        balance(&mut ctx, user_bid_pda(&loser.pubkey(), &state)).await,
+        user_bid_rent
+    );
+    assert_eq!(
+        balance(&mut ctx, bid_escrow_pda(&loser.pubkey(), &state)).await,
+        escrow_rent + sol_to_lamports(1.0)
+    );
+    send(&mut ctx, verify_auction_ix(&state, &[&loser]), &[])
+        .await
+        .unwrap();
+
+    place_bid(&mut ctx, &state, &winner, 1.5).await;
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &winner, 1.5).await;
+    refund(&mut ctx, &state, &loser).await;
+}
+
+#[tokio::test]
+async fn conditional_auction_settles_once_the_condition_is_met() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let winner = funded_keypair(&mut ctx).await;
+    let condition_account = Pubkey::new_unique();
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &winner, 1.0).await;
+    set_condition(&mut ctx, state, condition_account, false).await;
+    warp_to_deadline(&mut ctx, state).await;
+
+    let end_ix = || {
+        conditional_end_auction_ix(
+            &state,
+            &initializer.pubkey(),
+            &winner.pubkey(),
+            &condition_program_id(),
+            &condition_account,
+        )
+    };
+    let result = send(&mut ctx, end_ix(), &[&initializer]).await;
+    assert_auction_error(result, AuctionError::ConditionNotMet);
+    assert!(!fetch_state(&mut ctx, state).await.seller_payed);
+
+    // the condition program has to be the one of the auction
+    let result = send(
+        &mut ctx,
+        end_auction_ix(&state, &initializer.pubkey(), &winner.pubkey()),
+        &[&initializer],
+    )
+    .await;
+    assert_auction_error(result, AuctionError::InvalidConditionProgram);
+
+    set_condition(&mut ctx, state, condition_account, true).await;
+    let seller_balance = balance(&mut ctx, initializer.pubkey()).await;
+    let records_rent =
+        rent(&mut ctx, AUCTION_SUMMARY_SPACE).await + rent(&mut ctx, TAX_RECORD_SPACE).await;
+    send(&mut ctx, end_ix(), &[&initializer]).await.unwrap();
+    assert_eq!(
+        balance(&mut ctx, initializer.pubkey()).await,
+        seller_balance + sol_to_lamports(1.0) - records_rent
+    );
+    assert!(fetch_state(&mut ctx, state).await.seller_payed);
+}
+
+#[tokio::test]
+async fn loyalty_status_needs_the_participation_threshold() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &bidder, 1.0).await;
+
+    // the bidder took part in one auction short of the threshold
+    set_participations(&mut ctx, &bidder.pubkey(), LOYALTY_THRESHOLD - 1).await;
+
+    let payer = ctx.payer.pubkey();
+    let result = send(
+        &mut ctx,
+        grant_loyalty_status_ix(&bidder.pubkey(), &payer),
+        &[],
+    )
+    .await;
+    assert_auction_error(result, AuctionError::LoyaltyThresholdNotReached);
+
+    // bidding on another auction reaches it
+    let other_seller = funded_keypair(&mut ctx).await;
+    let other_state = initialize_auction(&mut ctx, &other_seller).await;
+    place_bid(&mut ctx, &other_state, &bidder, 1.0).await;
+    send(
+        &mut ctx,
+        grant_loyalty_status_ix(&bidder.pubkey(), &payer),
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let account = ctx
+        .banks_client
+        .get_account(loyalty_pda(&bidder.pubkey()))
+        .await
+        .unwrap()
+        .unwrap();
+    let loyalty = LoyaltyDiscount::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(loyalty.bidder, bidder.pubkey());
+    assert_eq!(loyalty.auctions_participated, LOYALTY_THRESHOLD);
+}
+
+// The platform fee comes out of the proceeds of the seller, a winner with loyalty
+// status is charged the discounted fee
+#[tokio::test]
+async fn platform_fee_is_discounted_for_loyal_winners() {
+    let platform_fee_bps = 500;
+    let mut ctx = setup_with_platform_fee(platform_fee_bps).await;
+    let payer = ctx.payer.pubkey();
+    let full_fee = sol_to_lamports(1.0) * platform_fee_bps as u64 / 10_000;
+    let discounted_fee = full_fee - full_fee * LOYALTY_DISCOUNT_BPS as u64 / 10_000;
+
+    for loyal in [false, true] {
+        let initializer = funded_keypair(&mut ctx).await;
+        let bidder = funded_keypair(&mut ctx).await;
+        let state = initialize_auction(&mut ctx, &initializer).await;
+        place_bid(&mut ctx, &state, &bidder, 1.0).await;
+        if loyal {
+            set_participations(&mut ctx, &bidder.pubkey(), LOYALTY_THRESHOLD).await;
+            send(
+                &mut ctx,
+                grant_loyalty_status_ix(&bidder.pubkey(), &payer),
+                &[],
+            )
+            .await
+            .unwrap();
+        }
+        let fee = if loyal { discounted_fee } else { full_fee };
+
+        warp_to_deadline(&mut ctx, state).await;
+        let seller_balance = balance(&mut ctx, initializer.pubkey()).await;
+        let recipient_balance = balance(&mut ctx, fee_recipient()).await;
+        let records_rent =
+            rent(&mut ctx, AUCTION_SUMMARY_SPACE).await + rent(&mut ctx, TAX_RECORD_SPACE).await;
+        send(
+            &mut ctx,
+            end_auction_ix(&state, &initializer.pubkey(), &bidder.pubkey()),
+            &[&initializer],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            balance(&mut ctx, fee_recipient()).await,
+            recipient_balance + fee
+        );
+        assert_eq!(
+            balance(&mut ctx, initializer.pubkey()).await,
+            seller_balance + sol_to_lamports(1.0) - fee - records_rent
+        );
+        let account = ctx
+            .banks_client
+            .get_account(tax_record_pda(&state))
+            .await
+            .unwrap()
+            .unwrap();
+        let record = TaxRecord::try_deserialize(&mut account.data.as_slice()).unwrap();
+        assert_eq!(record.platform_fee_amount, fee);
+        assert_eq!(record.net_seller_proceeds, sol_to_lamports(1.0) - fee);
+    }
+}
+
+// Initializes an auction with `auction_config`, as `initialize_auction` does without
+// checking what the initializer paid
+async fn initialize_with_config(
+    ctx: &mut ProgramTestContext,
+    initializer: &Keypair,
+    auction_config: AuctionConfig,
+) -> Pubkey {
+    send(
+        ctx,
+        initialize_with_config_ix(&initializer.pubkey(), auction_config),
+        &[initializer],
+    )
+    .await
+    .unwrap();
+    state_pda(&initializer.pubkey())
+}
+
+#[tokio::test]
+async fn platform_fee_is_charged_on_every_milestone() {
+    let platform_fee_bps = 500;
+    let mut ctx = setup_with_platform_fee(platform_fee_bps).await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+    let config = AuctionConfig {
+        milestone_count: 3,
+        ..auction_config()
+    };
+    let state = initialize_with_config(&mut ctx, &initializer, config).await;
+    place_bid(&mut ctx, &state, &bidder, 1.0).await;
+
+    warp_to_deadline(&mut ctx, state).await;
+    let recipient_balance = balance(&mut ctx, fee_recipient()).await;
+    send(
+        &mut ctx,
+        end_auction_ix(&state, &initializer.pubkey(), &bidder.pubkey()),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    // the winning bid stays in the escrow until the first milestone
+    assert_eq!(balance(&mut ctx, fee_recipient()).await, recipient_balance);
+
+    let milestone = sol_to_lamports(1.0) / 3;
+    let milestone_fee = milestone * platform_fee_bps as u64 / 10_000;
+    for released in 1..3 {
+        let seller_balance = balance(&mut ctx, initializer.pubkey()).await;
+        send(
+            &mut ctx,
+            release_milestone_ix(&state, &initializer.pubkey(), &bidder.pubkey()),
+            &[&bidder],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            balance(&mut ctx, initializer.pubkey()).await,
+            seller_balance + milestone - milestone_fee
+        );
+        assert_eq!(
+            balance(&mut ctx, fee_recipient()).await,
+            recipient_balance + milestone_fee * released
+        );
+    }
+
+    // the last milestone pays the rest of the fee charged on the winning bid
+    let fee = sol_to_lamports(1.0) * platform_fee_bps as u64 / 10_000;
+    let seller_balance = balance(&mut ctx, initializer.pubkey()).await;
+    send(
+        &mut ctx,
+        claim_final_milestone_ix(&state, &initializer.pubkey(), &bidder.pubkey()),
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        balance(&mut ctx, fee_recipient()).await,
+        recipient_balance + fee
+    );
+    assert_eq!(
+        balance(&mut ctx, initializer.pubkey()).await,
+        seller_balance + (sol_to_lamports(1.0) - 2 * milestone) - (fee - 2 * milestone_fee)
+    );
+}
+
+#[tokio::test]
+async fn platform_fee_is_charged_on_every_unit() {
+    let platform_fee_bps = 500;
+    let mut ctx = setup_with_platform_fee(platform_fee_bps).await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidders = [
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+    ];
+    let config = AuctionConfig {
+        quantity: 2,
+        ..auction_config()
+    };
+    let state = initialize_with_config(&mut ctx, &initializer, config).await;
+    place_bid(&mut ctx, &state, &bidders[0], 1.0).await;
+    place_bid(&mut ctx, &state, &bidders[1], 2.0).await;
+
+    warp_to_deadline(&mut ctx, state).await;
+    let seller_balance = balance(&mut ctx, initializer.pubkey()).await;
+    let recipient_balance = balance(&mut ctx, fee_recipient()).await;
+    send(
+        &mut ctx,
+        settle_multi_unit_ix(&state, &initializer.pubkey(), &[&bidders[1], &bidders[0]]),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+
+    // both units sell at the clearing price, the lowest winning bid
+    let fee = sol_to_lamports(1.0) * platform_fee_bps as u64 / 10_000;
+    assert_eq!(
+        balance(&mut ctx, fee_recipient()).await,
+        recipient_balance + 2 * fee
+    );
+    assert_eq!(
+        balance(&mut ctx, initializer.pubkey()).await,
+        seller_balance + 2 * (sol_to_lamports(1.0) - fee)
+    );
+}
+
+#[tokio::test]
+async fn platform_fee_is_charged_on_every_fractional_bid() {
+    let platform_fee_bps = 500;
+    let mut ctx = setup_with_platform_fee(platform_fee_bps).await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidders = [
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+    ];
+    let config = AuctionConfig {
+        reserve_price: sol_to_lamports(1.0),
+        total_shares: 10,
+        ..auction_config()
+    };
+    let state = initialize_with_config(&mut ctx, &initializer, config).await;
+    for bidder in &bidders {
+        send(
+            &mut ctx,
+            fractional_bid_ix(&state, &bidder.pubkey(), 0.5, 5),
+            &[bidder],
+        )
+        .await
+        .unwrap();
+    }
+    let share_mint = share_mint_pda(&state);
+    let mut bids = Vec::new();
+    for bidder in &bidders {
+        let share_account = create_token_account(&mut ctx, &share_mint, &bidder.pubkey(), 0).await;
+        bids.push((bidder, share_account));
+    }
+
+    warp_to_deadline(&mut ctx, state).await;
+    let seller_balance = balance(&mut ctx, initializer.pubkey()).await;
+    let recipient_balance = balance(&mut ctx, fee_recipient()).await;
+    send(
+        &mut ctx,
+        settle_fractional_ix(&state, &initializer.pubkey(), &bids),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+
+    let fee = sol_to_lamports(0.5) * platform_fee_bps as u64 / 10_000;
+    assert_eq!(
+        balance(&mut ctx, fee_recipient()).await,
+        recipient_balance + 2 * fee
+    );
+    assert_eq!(
+        balance(&mut ctx, initializer.pubkey()).await,
+        seller_balance + 2 * (sol_to_lamports(0.5) - fee)
+    );
+    for (_, share_account) in bids {
+        assert_eq!(token_balance(&mut ctx, share_account).await, 5);
+    }
+}
+
+#[tokio::test]
+async fn platform_fee_of_a_token_auction_is_charged_in_its_bid_mint() {
+    let platform_fee_bps = 500;
+    let mut ctx = setup_with_platform_fee(platform_fee_bps).await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+    let bid_mint = create_mint(&mut ctx).await;
+    let seller_token = create_token_account(&mut ctx, &bid_mint, &initializer.pubkey(), 0).await;
+    let fee_recipient_token = create_token_account(&mut ctx, &bid_mint, &fee_recipient(), 0).await;
+    let bidder_token = create_token_account(&mut ctx, &bid_mint, &bidder.pubkey(), 1_000).await;
+    let config = AuctionConfig {
+        bid_mint,
+        ..auction_config()
+    };
+    let state = initialize_with_config(&mut ctx, &initializer, config).await;
+    send(
+        &mut ctx,
+        bid_token_ix(&state, &bidder.pubkey(), &bid_mint, &bidder_token, 1_000),
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+
+    warp_to_deadline(&mut ctx, state).await;
+    send(
+        &mut ctx,
+        end_auction_token_ix(
+            &state,
+            &initializer.pubkey(),
+            &bidder.pubkey(),
+            &bid_mint,
+            &seller_token,
+            &fee_recipient_token,
+        ),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(token_balance(&mut ctx, fee_recipient_token).await, 50);
+    assert_eq!(token_balance(&mut ctx, seller_token).await, 950);
+    let account = ctx
+        .banks_client
+        .get_account(tax_record_pda(&state))
+        .await
+        .unwrap()
+        .unwrap();
+    let record = TaxRecord::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(record.platform_fee_amount, 50);
+}
+
+#[tokio::test]
+async fn platform_fee_is_charged_on_the_losing_bids_of_an_all_pay_auction() {
+    let platform_fee_bps = 500;
+    let mut ctx = setup_with_platform_fee(platform_fee_bps).await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidders = [
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+    ];
+    let config = AuctionConfig {
+        auction_type: AuctionType::AllPay,
+        ..auction_config()
+    };
+    let state = initialize_with_config(&mut ctx, &initializer, config).await;
+    for (bidder, amount) in bidders.iter().zip([0.5, 1.0, 2.0]) {
+        place_bid(&mut ctx, &state, bidder, amount).await;
+    }
+
+    // the settlement collects the first losing bid, `collect_bids` the other one
+    warp_to_deadline(&mut ctx, state).await;
+    let recipient_balance = balance(&mut ctx, fee_recipient()).await;
+    let mut ix = end_auction_ix(&state, &initializer.pubkey(), &bidders[2].pubkey());
+    ix.accounts.extend(escrow_metas(&state, &[&bidders[1]]));
+    send(&mut ctx, ix, &[&initializer]).await.unwrap();
+    let fee = sol_to_lamports(3.0) * platform_fee_bps as u64 / 10_000;
+    assert_eq!(
+        balance(&mut ctx, fee_recipient()).await,
+        recipient_balance + fee
+    );
+
+    let seller_balance = balance(&mut ctx, initializer.pubkey()).await;
+    send(
+        &mut ctx,
+        collect_bids_ix(&state, &initializer.pubkey(), &[&bidders[0]]),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    let losing_fee = sol_to_lamports(0.5) * platform_fee_bps as u64 / 10_000;
+    assert_eq!(
+        balance(&mut ctx, fee_recipient()).await,
+        recipient_balance + fee + losing_fee
+    );
+    assert_eq!(
+        balance(&mut ctx, initializer.pubkey()).await,
+        seller_balance + sol_to_lamports(0.5) - losing_fee
+    );
+}
+
+#[tokio::test]
+async fn platform_fee_is_charged_on_the_whole_pool_of_a_raffle() {
+    let platform_fee_bps = 500;
+    let mut ctx = setup_with_platform_fee(platform_fee_bps).await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidders = [
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+    ];
+    let config = AuctionConfig {
+        auction_type: AuctionType::Raffle,
+        ..auction_config()
+    };
+    let state = initialize_with_config(&mut ctx, &initializer, config).await;
+    place_bid(&mut ctx, &state, &bidders[0], 1.0).await;
+    place_bid(&mut ctx, &state, &bidders[1], 2.0).await;
+
+    // the draw needs a slot hash, the clock is moved back to the deadline after the warp
+    ctx.warp_to_slot(100).unwrap();
+    warp_to_deadline(&mut ctx, state).await;
+    send(
+        &mut ctx,
+        draw_winner_ix(&state, &initializer.pubkey(), &[&bidders[0], &bidders[1]]),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    let winner = fetch_state(&mut ctx, state).await.highest_bidder_account;
+    let loser = if winner == bidders[0].pubkey() {
+        &bidders[1]
+    } else {
+        &bidders[0]
+    };
+
+    // whoever wins, the winning and the losing contributions are both charged the fee
+    let recipient_balance = balance(&mut ctx, fee_recipient()).await;
+    let mut ix = end_auction_ix(&state, &initializer.pubkey(), &winner);
+    ix.accounts.extend(escrow_metas(&state, &[loser]));
+    send(&mut ctx, ix, &[&initializer]).await.unwrap();
+    let fee = sol_to_lamports(3.0) * platform_fee_bps as u64 / 10_000;
+    assert_eq!(
+        balance(&mut ctx, fee_recipient()).await,
+        recipient_balance + fee
+    );
+}
+
+// The description is kept out of the state, in an account sized to its text
+#[tokio::test]
+async fn description_holds_up_to_1024_bytes_until_the_first_bid() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    let description = description_pda(&state);
+
+    let too_long = send(
+        &mut ctx,
+        add_description_ix(
+            &state,
+            &initializer.pubkey(),
+            vec![b'a'; MAX_DESCRIPTION_LEN + 1],
+        ),
+        &[&initializer],
+    )
+    .await;
+    assert_auction_error(too_long, AuctionError::DescriptionTooLong);
+    assert!(!fetch_state(&mut ctx, state).await.has_description);
+
+    send(
+        &mut ctx,
+        add_description_ix(&state, &initializer.pubkey(), b"short".to_vec()),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    assert!(fetch_state(&mut ctx, state).await.has_description);
+    assert_eq!(
+        balance(&mut ctx, description).await,
+        rent(&mut ctx, DescriptionAccount::space(b"short")).await
+    );
+
+    // the account grows to the longest description allowed, and no further
+    let text = vec![b'a'; MAX_DESCRIPTION_LEN];
+    send(
+        &mut ctx,
+        update_description_ix(&state, &initializer.pubkey(), text.clone()),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    let account = ctx
+        .banks_client
+        .get_account(description)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), DescriptionAccount::space(&text));
+    assert_eq!(
+        DescriptionAccount::try_deserialize(&mut account.data.as_slice())
+            .unwrap()
+            .text,
+        text
+    );
+    let too_long = send(
+        &mut ctx,
+        update_description_ix(
+            &state,
+            &initializer.pubkey(),
+            vec![b'a'; MAX_DESCRIPTION_LEN + 1],
+        ),
+        &[&initializer],
+    )
+    .await;
+    assert_auction_error(too_long, AuctionError::DescriptionTooLong);
+
+    // bidders joined for the item as it was described
+    place_bid(&mut ctx, &state, &bidder, 1.0).await;
+    let after_bid = send(
+        &mut ctx,
+        update_description_ix(&state, &initializer.pubkey(), b"changed".to_vec()),
+        &[&initializer],
+    )
+    .await;
+    assert_auction_error(after_bid, AuctionError::CannotChangeAfterBids);
+}
+
+#[tokio::test]
+async fn token_auction_settles_and_refunds_in_its_bid_mint() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let mut bidders = Vec::new();
+    for _ in 0..3 {
+        bidders.push(funded_keypair(&mut ctx).await);
+    }
+    let bid_mint = create_mint(&mut ctx).await;
+    let seller_token = create_token_account(&mut ctx, &bid_mint, &initializer.pubkey(), 0).await;
+    let fee_recipient_token = create_token_account(&mut ctx, &bid_mint, &fee_recipient(), 0).await;
+    let mut bidder_tokens = Vec::new();
+    for bidder in &bidders {
+        bidder_tokens
+            .push(create_token_account(&mut ctx, &bid_mint, &bidder.pubkey(), 1_000).await);
+    }
+    let amounts = [100, 120, 130];
+
+    let state = state_pda(&initializer.pubkey());
+    let config = AuctionConfig {
+        bid_mint,
+        ..auction_config()
+    };
+    send(
+        &mut ctx,
+        initialize_with_config_ix(&initializer.pubkey(), config),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(fetch_state(&mut ctx, state).await.bid_mint, bid_mint);
+
+    // the auction only takes bids in its mint
+    let lamport_bid = send(
+        &mut ctx,
+        bid_ix(&state, &bidders[0].pubkey(), 1.0),
+        &[&bidders[0]],
+    )
+    .await;
+    assert_auction_error(lamport_bid, AuctionError::TokenInstructionRequired);
+
+    for ((bidder, bidder_token), amount) in bidders.iter().zip(&bidder_tokens).zip(amounts) {
+        send(
+            &mut ctx,
+            bid_token_ix(&state, &bidder.pubkey(), &bid_mint, bidder_token, amount),
+            &[bidder],
+        )
+        .await
+        .unwrap();
+        assert_eq!(token_balance(&mut ctx, *bidder_token).await, 1_000 - amount);
+    }
+    let vault = PdaHelpers::token_vault_address(&state, &bid_mint);
+    assert_eq!(token_balance(&mut ctx, vault).await, 350);
+
+    warp_to_deadline(&mut ctx, state).await;
+    send(
+        &mut ctx,
+        end_auction_token_ix(
+            &state,
+            &initializer.pubkey(),
+            &bidders[2].pubkey(),
+            &bid_mint,
+            &seller_token,
+            &fee_recipient_token,
+        ),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut ctx, seller_token).await, 130);
+    let auction = fetch_state(&mut ctx, state).await;
+    assert!(auction.seller_payed);
+    assert_eq!(auction.winning_amount, 130);
+
+    // the losers get their tokens back, the winner only the rent of its user bid
+    for (bidder, bidder_token) in bidders.iter().zip(&bidder_tokens) {
+        send(
+            &mut ctx,
+            refund_token_ix(&state, &bidder.pubkey(), &bid_mint, bidder_token),
+            &[bidder],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            balance(&mut ctx, user_bid_pda(&bidder.pubkey(), &state)).await,
+            0
+        );
+    }
+    assert_eq!(token_balance(&mut ctx, bidder_tokens[0]).await, 1_000);
+    assert_eq!(token_balance(&mut ctx, bidder_tokens[1]).await, 1_000);
+    assert_eq!(token_balance(&mut ctx, bidder_tokens[2]).await, 870);
+    assert_eq!(token_balance(&mut ctx, vault).await, 0);
+}
+
+// Initializes an auction escrowing `prize_amount` tokens of a fresh mint, held by the seller
+async fn initialize_prize_auction(
+    ctx: &mut ProgramTestContext,
+    initializer: &Keypair,
+    reserve_price: u64,
+    prize_amount: u64,
+) -> (Pubkey, Pubkey, Pubkey) {
+    let prize_mint = create_mint(ctx).await;
+    let seller_token =
+        create_token_account(ctx, &prize_mint, &initializer.pubkey(), prize_amount).await;
+    let config = AuctionConfig {
+        reserve_price,
+        prize_mint,
+        prize_amount,
+        ..auction_config()
+    };
+    send(
+        ctx,
+        initialize_with_config_ix(&initializer.pubkey(), config),
+        &[initializer],
+    )
+    .await
+    .unwrap();
+    (state_pda(&initializer.pubkey()), prize_mint, seller_token)
+}
+
+#[tokio::test]
+async fn escrowed_prize_opens_bidding_and_goes_to_the_winner() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+    let (state, prize_mint, seller_token) =
+        initialize_prize_auction(&mut ctx, &initializer, 0, 5).await;
+    let winner_token = create_token_account(&mut ctx, &prize_mint, &bidder.pubkey(), 0).await;
+    let prize_vault = PdaHelpers::prize_vault_address(&state, &prize_mint);
+
+    // bids wait for the prize
+    let early_bid = send(&mut ctx, bid_ix(&state, &bidder.pubkey(), 1.0), &[&bidder]).await;
+    assert_auction_error(early_bid, AuctionError::PrizeNotDeposited);
+
+    send(
+        &mut ctx,
+        deposit_prize_ix(&state, &initializer.pubkey(), &prize_mint, &seller_token),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut ctx, seller_token).await, 0);
+    assert_eq!(token_balance(&mut ctx, prize_vault).await, 5);
+    let second_deposit = send(
+        &mut ctx,
+        deposit_prize_ix(&state, &initializer.pubkey(), &prize_mint, &seller_token),
+        &[&initializer],
+    )
+    .await;
+    assert_auction_error(second_deposit, AuctionError::PrizeAlreadyDeposited);
+
+    place_bid(&mut ctx, &state, &bidder, 1.0).await;
+    // the prize is promised to the bidders once they joined
+    let reclaim = send(
+        &mut ctx,
+        reclaim_prize_ix(&state, &initializer.pubkey(), &prize_mint, &seller_token),
+        &[&initializer],
+    )
+    .await;
+    assert_auction_error(reclaim, AuctionError::PrizeNotReclaimable);
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &bidder, 1.0).await;
+    send(
+        &mut ctx,
+        claim_prize_ix(&state, &bidder.pubkey(), &prize_mint, &winner_token),
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut ctx, winner_token).await, 5);
+    assert_eq!(token_balance(&mut ctx, prize_vault).await, 0);
+    assert!(fetch_state(&mut ctx, state).await.prize_claimed);
+}
+
+#[tokio::test]
+async fn seller_reclaims_the_prize_when_the_reserve_is_not_met() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidder = funded_keypair(&mut ctx).await;
+    let (state, prize_mint, seller_token) =
+        initialize_prize_auction(&mut ctx, &initializer, sol_to_lamports(2.0), 5).await;
+    let prize_vault = PdaHelpers::prize_vault_address(&state, &prize_mint);
+
+    send(
+        &mut ctx,
+        deposit_prize_ix(&state, &initializer.pubkey(), &prize_mint, &seller_token),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    place_bid(&mut ctx, &state, &bidder, 1.0).await;
+
+    warp_to_deadline(&mut ctx, state).await;
+    send(
+        &mut ctx,
+        end_auction_ix(&state, &initializer.pubkey(), &bidder.pubkey()),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    assert!(fetch_state(&mut ctx, state).await.reserve_not_met);
+
+    send(
+        &mut ctx,
+        reclaim_prize_ix(&state, &initializer.pubkey(), &prize_mint, &seller_token),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut ctx, seller_token).await, 5);
+    assert_eq!(token_balance(&mut ctx, prize_vault).await, 0);
+    let auction = fetch_state(&mut ctx, state).await;
+    assert!(auction.prize_reclaimed && !auction.prize_deposited);
+}
+
+#[tokio::test]
+async fn multi_unit_dutch_auction_sells_one_unit_per_buyer() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let buyers = [
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+    ];
+    let state = state_pda(&initializer.pubkey());
+    let config = AuctionConfig {
+        auction_type: AuctionType::Dutch,
+        start_price: sol_to_lamports(1.0),
+        reserve_price: sol_to_lamports(1.0),
+        quantity: 2,
+        ..auction_config()
+    };
+    send(
+        &mut ctx,
+        initialize_with_config_ix(&initializer.pubkey(), config),
+        &[&initializer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(fetch_state(&mut ctx, state).await.units_remaining, 2);
+
+    send(
+        &mut ctx,
+        accept_price_ix(&state, &buyers[0].pubkey()),
+        &[&buyers[0]],
+    )
+    .await
+    .unwrap();
+    let result = send(
+        &mut ctx,
+        accept_price_ix(&state, &buyers[0].pubkey()),
+        &[&buyers[0]],
+    )
+    .await;
+    assert_auction_error(result, AuctionError::UnitAlreadyBought);
+    assert_eq!(fetch_state(&mut ctx, state).await.units_remaining, 1);
+
+    // the last unit closes the sale before the deadline
+    send(
+        &mut ctx,
+        accept_price_ix(&state, &buyers[1].pubkey()),
+        &[&buyers[1]],
+    )
+    .await
+    .unwrap();
+    let auction = fetch_state(&mut ctx, state).await;
+    assert_eq!(auction.units_remaining, 0);
+    assert!(auction.ended_early);
+    let result = send(
+        &mut ctx,
+        accept_price_ix(&state, &buyers[2].pubkey()),
+        &[&buyers[2]],
+    )
+    .await;
+    assert_auction_error(result, AuctionError::Finished);
+}
+
+#[tokio::test]
+async fn outbid_bid_is_refunded_with_the_yield_of_its_vault() {
+    let mut ctx = setup().await;
+    let initializer = funded_keypair(&mut ctx).await;
+    let bidders = [
+        funded_keypair(&mut ctx).await,
+        funded_keypair(&mut ctx).await,
+    ];
+    let state = initialize_auction(&mut ctx, &initializer).await;
+    place_bid(&mut ctx, &state, &bidders[0], 1.0).await;
+    place_bid(&mut ctx, &state, &bidders[1], 2.0).await;
+
+    // the highest bid stays in its escrow
+    let result = send(
+        &mut ctx,
+        deposit_to_vault_ix(&state, &bidders[1].pubkey()),
+        &[&bidders[1]],
+    )
+    .await;
+    assert_auction_error(result, AuctionError::HighestBidderCannotWithdraw);
+
+    let user_bid = user_bid_pda(&bidders[0].pubkey(), &state);
+    let bid_escrow = bid_escrow_pda(&bidders[0].pubkey(), &state);
+    let user_bid_rent = rent(&mut ctx, USER_BID_SPACE).await;
+    let escrow_rent = rent(&mut ctx, BID_ESCROW_SPACE).await;
+    let vault_balance = balance(&mut ctx, yield_vault()).await;
+    send(
+        &mut ctx,
+        deposit_to_vault_ix(&state, &bidders[0].pubkey()),
+        &[&bidders[0]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(balance(&mut ctx, bid_escrow).await, escrow_rent);
+    assert_eq!(
+        balance(&mut ctx, yield_vault()).await,
+        vault_balance + sol_to_lamports(1.0)
+    );
+    // a deposited bid can not be raised, nor refunded from its escrow
+    let result = send(
+        &mut ctx,
+        bid_ix(&state, &bidders[0].pubkey(), 3.0),
+        &[&bidders[0]],
+    )
+    .await;
+    assert_auction_error(result, AuctionError::BidInVault);
+
+    warp_to_deadline(&mut ctx, state).await;
+    end_auction(&mut ctx, &state, &initializer, &bidders[1], 2.0).await;
+    let result = send(
+        &mut ctx,
+        refund_ix(&state, &bidders[0].pubkey()),
+        &[&bidders[0]],
+    )
+    .await;
+    assert_auction_error(result, AuctionError::BidInVault);
+
+    // the vault pays the bid back with its 5% yield, the user bid and escrow their rent
+    let bidder_balance = balance(&mut ctx, bidders[0].pubkey()).await;
+    send(
+        &mut ctx,
+        refund_with_yield_ix(&state, &bidders[0].pubkey()),
+        &[&bidders[0]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        balance(&mut ctx, bidders[0].pubkey()).await,
+        bidder_balance + sol_to_lamports(1.0) * 105 / 100 + user_bid_rent + escrow_rent
+    );
+    assert_eq!(balance(&mut ctx, user_bid).await, 0);
+    assert_eq!(balance(&mut ctx, bid_escrow).await, 0);
+    assert_eq!(
+        balance(&mut ctx, yield_vault()).await,
+        vault_balance - sol_to_lamports(1.0) * 5 / 100
+    );
+}
@@ -0,0 +1,525 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use auction::constants::LEADERBOARD_SIZE;
+use auction::constants::{MAX_AUCTION_DURATION_SECS, MIN_AUCTION_DURATION_SECS, STATE_SPACE};
+use auction::errors::AuctionError;
+use auction::{
+    process_bid, process_fractional_bid, update_leaderboard, validate_auction_duration,
+    AuctionType, HealthViolation, Leaderboard, LeaderboardEntry, State,
+};
+use proptest::prelude::*;
+
+// A freshly initialized auction, before any bid. Zeroed account data decodes to
+// the default value of every field
+fn empty_state() -> State {
+    State::try_deserialize_unchecked(&mut [0u8; STATE_SPACE].as_slice()).unwrap()
+}
+
+#[test]
+fn seller_and_highest_bidder_can_not_bid_trivially() {
+    let mut state = empty_state();
+    state.deadline = i64::MAX;
+    let seller = Pubkey::new_unique();
+    state.initializer = seller;
+    let bidder = Pubkey::new_unique();
+
+    let seller_bid = process_bid(&mut state, seller, 0, 1, 0);
+    assert_eq!(
+        seller_bid.unwrap_err(),
+        AuctionError::SellerCannotBid.into()
+    );
+
+    process_bid(&mut state, bidder, 0, 1, 0).unwrap();
+    let repeated_bid = process_bid(&mut state, bidder, 0, 1, 0);
+    assert_eq!(
+        repeated_bid.unwrap_err(),
+        AuctionError::AlreadyHighestBidder.into()
+    );
+
+    // raising its own bid is still allowed
+    process_bid(&mut state, bidder, 0, 2, 0).unwrap();
+    assert_eq!(state.highest_bid_amount, 2);
+}
+
+#[test]
+fn candle_history_keeps_the_last_bid_before_the_window() {
+    let mut state = empty_state();
+    state.deadline = 1_000;
+    state.candle_window = 100;
+
+    // bids before the window are never limited
+    for timestamp in 0..20 {
+        let amount = state.highest_bid_amount + 1;
+        process_bid(&mut state, Pubkey::new_unique(), 0, amount, timestamp).unwrap();
+    }
+    // the window takes all but one record of the history
+    for timestamp in 900..909 {
+        let amount = state.highest_bid_amount + 1;
+        process_bid(&mut state, Pubkey::new_unique(), 0, amount, timestamp).unwrap();
+    }
+    let amount = state.highest_bid_amount + 1;
+    let full = process_bid(&mut state, Pubkey::new_unique(), 0, amount, 909);
+    assert_eq!(full.unwrap_err(), AuctionError::CandleHistoryFull.into());
+
+    assert_eq!(state.recent_bids()[0].timestamp, 19);
+}
+
+#[test]
+fn fractional_shares_go_first_come_first_served() {
+    let mut state = empty_state();
+    state.deadline = i64::MAX;
+    state.total_shares = 100;
+    state.reserve_price = 1_000;
+
+    let regular_bid = process_bid(&mut state, Pubkey::new_unique(), 0, 1_000, 0);
+    assert_eq!(
+        regular_bid.unwrap_err(),
+        AuctionError::FractionalBidsOnly.into()
+    );
+
+    // 20 shares are a fifth of the reserve price
+    let underpaid = process_fractional_bid(&mut state, Pubkey::new_unique(), 199, 20, 0);
+    assert_eq!(
+        underpaid.unwrap_err(),
+        AuctionError::BidBelowAskingPrice.into()
+    );
+    for _ in 0..5 {
+        process_fractional_bid(&mut state, Pubkey::new_unique(), 200, 20, 0).unwrap();
+    }
+    assert_eq!(state.allocated_shares, 100);
+    assert_eq!(state.bid_count, 5);
+
+    let sold_out = process_fractional_bid(&mut state, Pubkey::new_unique(), 1_000, 1, 0);
+    assert_eq!(
+        sold_out.unwrap_err(),
+        AuctionError::InsufficientSharesAvailable.into()
+    );
+}
+
+#[test]
+fn share_price_rounds_up() {
+    let mut state = empty_state();
+    state.total_shares = 3;
+    state.reserve_price = 1_000;
+
+    assert_eq!(state.share_price(1).unwrap(), 334);
+    assert_eq!(state.share_price(3).unwrap(), 1_000);
+    assert_eq!(state.share_price(0).unwrap(), 0);
+}
+
+#[test]
+fn bid_violation_compares_the_state_with_the_escrows() {
+    let mut state = empty_state();
+    state.deadline = i64::MAX;
+    process_bid(&mut state, Pubkey::new_unique(), 0, 100, 0).unwrap();
+    process_bid(&mut state, Pubkey::new_unique(), 0, 300, 0).unwrap();
+
+    assert_eq!(state.bid_violation(&[100, 300]), None);
+    assert_eq!(
+        state.bid_violation(&[300]),
+        Some(HealthViolation::BidCountMismatch)
+    );
+    assert_eq!(
+        state.bid_violation(&[100, 200]),
+        Some(HealthViolation::HighestBidMismatch)
+    );
+
+    // refunds after settlement no longer have to add up
+    state.seller_payed = true;
+    assert_eq!(state.bid_violation(&[]), None);
+}
+
+#[test]
+fn proxy_answers_a_bid_up_to_its_maximum() {
+    let mut state = empty_state();
+    state.min_bid_increment = 10;
+    let proxy_bidder = Pubkey::new_unique();
+
+    // the proxy bidder leads at 100 with a maximum of 500
+    state.highest_bid_amount = 100;
+    state.highest_bidder_account = proxy_bidder;
+    state.highest_proxy_max = 500;
+
+    // a bid of 200 is answered with 210
+    state.highest_bid_amount = 200;
+    state.highest_bidder_account = Pubkey::new_unique();
+    assert!(state.apply_proxy_bids(proxy_bidder, 7, 500, 0).unwrap());
+    assert_eq!(state.highest_bidder_account, proxy_bidder);
+    assert_eq!(state.highest_bidder_bump, 7);
+    assert_eq!(state.highest_bid_amount, 210);
+    assert_eq!(state.second_highest_bid_amount, 200);
+
+    // a bid of 495 is answered with the maximum only
+    state.highest_bid_amount = 495;
+    assert!(state.apply_proxy_bids(proxy_bidder, 7, 500, 0).unwrap());
+    assert_eq!(state.highest_bid_amount, 500);
+
+    // a competing proxy with a higher maximum wins just above the first one
+    let other = Pubkey::new_unique();
+    state.highest_bid_amount = 300;
+    state.highest_bidder_account = other;
+    assert!(!state.apply_proxy_bids(proxy_bidder, 7, 500, 800).unwrap());
+    assert_eq!(state.highest_bidder_account, other);
+    assert_eq!(state.highest_bid_amount, 510);
+    assert_eq!(state.highest_proxy_max, 800);
+
+    // a bid above the proxy maximum wins as submitted
+    state.highest_bid_amount = 900;
+    state.highest_bidder_account = proxy_bidder;
+    assert!(!state.apply_proxy_bids(other, 0, 800, 0).unwrap());
+    assert_eq!(state.highest_bid_amount, 900);
+    assert_eq!(state.highest_proxy_max, 0);
+}
+
+#[test]
+fn leaderboard_keeps_the_ten_highest_bidders_sorted() {
+    let mut leaderboard = Leaderboard {
+        entries: [LeaderboardEntry::default(); LEADERBOARD_SIZE],
+    };
+    let amounts = [50, 20, 90, 10, 70, 30, 100, 60, 5, 80, 40, 95, 15, 65, 25];
+    let bidders: Vec<Pubkey> = amounts.iter().map(|_| Pubkey::new_unique()).collect();
+
+    for (bidder, amount) in bidders.iter().zip(amounts) {
+        update_leaderboard(&mut leaderboard, *bidder, amount);
+    }
+
+    let mut expected: Vec<(Pubkey, u64)> = bidders.iter().copied().zip(amounts).collect();
+    expected.sort_by(|a, b| b.1.cmp(&a.1));
+    let ranked: Vec<(u8, Pubkey, u64)> = leaderboard
+        .entries
+        .iter()
+        .map(|entry| (entry.rank, entry.bidder, entry.amount))
+        .collect();
+    let expected: Vec<(u8, Pubkey, u64)> = expected[..LEADERBOARD_SIZE]
+        .iter()
+        .enumerate()
+        .map(|(index, (bidder, amount))| (index as u8 + 1, *bidder, *amount))
+        .collect();
+    assert_eq!(ranked, expected);
+
+    // a bidder raising its bid moves up instead of taking a second entry
+    update_leaderboard(&mut leaderboard, bidders[0], 99);
+    assert_eq!(leaderboard.entries[1].bidder, bidders[0]);
+    assert_eq!(leaderboard.entries[1].amount, 99);
+    assert_eq!(
+        leaderboard
+            .entries
+            .iter()
+            .filter(|entry| entry.bidder == bidders[0])
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn unit_winners_stop_at_the_bids_placed() {
+    let mut leaderboard = Leaderboard {
+        entries: [LeaderboardEntry::default(); LEADERBOARD_SIZE],
+    };
+    let bidders: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    for (bidder, amount) in bidders.iter().zip([30, 50, 40]) {
+        update_leaderboard(&mut leaderboard, *bidder, amount);
+    }
+
+    // three bids for ten units all win, at the lowest of them
+    let winners = leaderboard.unit_winners(10, 0);
+    let winning: Vec<(Pubkey, u64)> = winners
+        .iter()
+        .map(|entry| (entry.bidder, entry.amount))
+        .collect();
+    assert_eq!(
+        winning,
+        vec![(bidders[1], 50), (bidders[2], 40), (bidders[0], 30)]
+    );
+    assert_eq!(winners.last().unwrap().amount, 30);
+    // bids below the reserve win nothing
+    assert_eq!(leaderboard.unit_winners(10, 35).len(), 2);
+    assert!(leaderboard.unit_winners(10, 60).is_empty());
+}
+
+#[test]
+fn unit_winners_break_ties_at_the_cutoff_by_arrival() {
+    let mut leaderboard = Leaderboard {
+        entries: [LeaderboardEntry::default(); LEADERBOARD_SIZE],
+    };
+    let bidders: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    for (bidder, amount) in bidders.iter().zip([100, 70, 70, 70]) {
+        update_leaderboard(&mut leaderboard, *bidder, amount);
+    }
+
+    // two units: the first bid of 70 takes the second one, the later ones lose it
+    let winners = leaderboard.unit_winners(2, 0);
+    assert_eq!(winners.len(), 2);
+    assert_eq!(winners[0].bidder, bidders[0]);
+    assert_eq!(winners[1].bidder, bidders[1]);
+    assert_eq!(winners[1].amount, 70);
+}
+
+#[test]
+fn unit_winners_take_in_a_late_top_up_behind_earlier_ties() {
+    let mut leaderboard = Leaderboard {
+        entries: [LeaderboardEntry::default(); LEADERBOARD_SIZE],
+    };
+    let bidders: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    for (bidder, amount) in bidders.iter().zip([100, 80, 60, 70]) {
+        update_leaderboard(&mut leaderboard, *bidder, amount);
+    }
+
+    // topping up to 80 moves the third bidder into the winning set, but it got there
+    // after the second bidder, who keeps the first place of the tie
+    update_leaderboard(&mut leaderboard, bidders[2], 80);
+    let winners: Vec<Pubkey> = leaderboard
+        .unit_winners(3, 0)
+        .iter()
+        .map(|entry| entry.bidder)
+        .collect();
+    assert_eq!(winners, vec![bidders[0], bidders[1], bidders[2]]);
+    assert_eq!(leaderboard.unit_winners(2, 0)[1].bidder, bidders[1]);
+}
+
+#[test]
+fn pay_as_bid_winners_pay_their_own_bids() {
+    let mut state = empty_state();
+    state.quantity = 3;
+
+    assert_eq!(state.unit_price(100, 60), 60);
+    state.pay_as_bid = true;
+    assert_eq!(state.unit_price(100, 60), 100);
+    assert_eq!(state.unit_price(60, 60), 60);
+}
+
+#[test]
+fn the_highest_final_offer_wins_the_final_round() {
+    let mut state = empty_state();
+    state.finalist_count = 3;
+    let bidders: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    let mut leaderboard = Leaderboard {
+        entries: [LeaderboardEntry::default(); LEADERBOARD_SIZE],
+    };
+    for (bidder, amount) in bidders.iter().zip([100, 90, 80, 80]) {
+        update_leaderboard(&mut leaderboard, *bidder, amount);
+    }
+    state.highest_bidder_account = bidders[0];
+    state.highest_bid_amount = 100;
+
+    assert!(bidders[..3]
+        .iter()
+        .all(|bidder| leaderboard.is_finalist(*bidder, state.finalist_count)));
+    // a tie at the last finalist goes to the bidder who got there first
+    assert!(!leaderboard.is_finalist(bidders[3], state.finalist_count));
+
+    // the open leader never reveals, the first revealed offer replaces it even when lower
+    state.record_final_offer(bidders[2], 95, 2).unwrap();
+    assert_eq!(state.highest_bidder_account, bidders[2]);
+    assert_eq!(state.highest_bid_amount, 95);
+    assert_eq!(state.highest_bidder_bump, 2);
+    // a tie goes to the finalist who revealed first
+    state.record_final_offer(bidders[1], 95, 1).unwrap();
+    assert_eq!(state.highest_bidder_account, bidders[2]);
+    assert_eq!(state.finals_revealed, 2);
+}
+
+#[test]
+fn multi_unit_auctions_accept_lower_bids() {
+    let mut state = empty_state();
+    state.deadline = i64::MAX;
+    state.quantity = 3;
+    let bidders: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+
+    process_bid(&mut state, bidders[0], 0, 100, 0).unwrap();
+    process_bid(&mut state, bidders[1], 0, 60, 0).unwrap();
+    assert_eq!(state.highest_bidder_account, bidders[0]);
+    assert_eq!(state.highest_bid_amount, 100);
+
+    state.quantity = 1;
+    assert_eq!(
+        process_bid(&mut state, bidders[1], 0, 80, 0).unwrap_err(),
+        AuctionError::BidAmountTooSmall.into()
+    );
+}
+
+#[test]
+fn dutch_units_sell_at_falling_prices_until_sold_out() {
+    let mut state = empty_state();
+    state.auction_type = AuctionType::Dutch;
+    state.start_price = 300;
+    state.reserve_price = 100;
+    state.deadline = 20;
+    state.quantity = 3;
+    state.units_remaining = 3;
+    let buyers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+
+    for (buyer, now) in buyers.iter().zip([0, 5, 10]) {
+        let price = state.asking_price(now);
+        process_bid(&mut state, *buyer, 0, price, now).unwrap();
+        state.sell_unit(now).unwrap();
+    }
+    assert_eq!(state.bid_count, 3);
+    assert_eq!(state.highest_bidder_account, buyers[0]);
+    // the last unit closed the bidding window
+    assert_eq!(state.units_remaining, 0);
+    assert_eq!(state.deadline, 10);
+    assert!(state.ended_early);
+    assert_eq!(
+        state.sell_unit(10).unwrap_err(),
+        AuctionError::Finished.into()
+    );
+
+    // every buyer pays the price it took, whatever the lowest one
+    assert_eq!(state.unit_price(250, 200), 250);
+}
+
+#[test]
+fn penny_bids_raise_the_price_by_the_increment_and_extend_the_deadline() {
+    let mut state = empty_state();
+    state.auction_type = AuctionType::Penny;
+    state.deadline = 100;
+    state.min_bid_increment = 10;
+    state.bid_fee = 3;
+    state.extension_secs = 20;
+    let bidders: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+
+    assert_eq!(
+        process_bid(&mut state, bidders[0], 0, 20, 0).unwrap_err(),
+        AuctionError::InvalidPennyBid.into()
+    );
+    process_bid(&mut state, bidders[0], 0, 10, 0).unwrap();
+    process_bid(&mut state, bidders[1], 0, 20, 110).unwrap();
+    assert_eq!(state.highest_bidder_account, bidders[1]);
+    assert_eq!(state.deadline, 140);
+    assert_eq!(state.fees_collected, 6);
+}
+
+#[test]
+fn penny_extensions_stop_at_the_largest_deadline() {
+    let mut state = empty_state();
+    state.auction_type = AuctionType::Penny;
+    state.min_bid_increment = 1;
+    state.extension_secs = 10;
+    state.deadline = i64::MAX - 15;
+    let bidders: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+
+    process_bid(&mut state, bidders[0], 0, 1, 0).unwrap();
+    assert_eq!(state.deadline, i64::MAX - 5);
+    // the next extension would move the deadline past the largest timestamp
+    assert_eq!(
+        process_bid(&mut state, bidders[1], 0, 2, 0).unwrap_err(),
+        AuctionError::DeadlineOverflow.into()
+    );
+    assert_eq!(state.highest_bidder_account, bidders[0]);
+    assert_eq!(state.deadline, i64::MAX - 5);
+}
+
+#[test]
+fn rounds_take_one_bid_at_the_tick_price() {
+    let mut state = empty_state();
+    state.deadline = 1_000;
+    state.start_price = 100;
+    state.round_duration_secs = 10;
+    state.tick = 5;
+    let bidders: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+
+    // nobody accepted the first two rounds, the deadline stays
+    assert_eq!(
+        process_bid(&mut state, bidders[0], 0, 100, 25).unwrap_err(),
+        AuctionError::BidNotAtRoundPrice.into()
+    );
+    process_bid(&mut state, bidders[0], 0, 110, 25).unwrap();
+    assert_eq!(state.deadline, 40);
+    assert_eq!(
+        process_bid(&mut state, bidders[1], 0, 110, 29).unwrap_err(),
+        AuctionError::RoundAlreadyAccepted.into()
+    );
+
+    process_bid(&mut state, bidders[1], 0, 115, 30).unwrap();
+    assert_eq!(state.highest_bidder_account, bidders[1]);
+    assert_eq!(state.deadline, 50);
+    assert_eq!(state.rounds_accepted, 2);
+
+    // the fifth round passed without acceptance, the auction ended with it
+    assert_eq!(
+        process_bid(&mut state, bidders[0], 0, 125, 50).unwrap_err(),
+        AuctionError::Finished.into()
+    );
+}
+
+prop_compose! {
+    // Mixes the boundaries, repeated amounts from a small range and arbitrary amounts,
+    // so sequences contain both accepted and rejected bids
+    fn valid_bid_sequence()(
+        bids in prop::collection::vec(
+            prop_oneof![Just(0u64), Just(u64::MAX), 0u64..16, any::<u64>()],
+            0..32,
+        )
+    ) -> Vec<u64> {
+        bids
+    }
+}
+
+proptest! {
+    #[test]
+    fn highest_bid_is_the_highest_accepted_bid(bids in valid_bid_sequence()) {
+        let mut state = empty_state();
+        let mut accepted = Vec::new();
+
+        for (bidder_index, amount) in bids.iter().enumerate() {
+            let bidder = Pubkey::new_unique();
+            let is_valid = *amount >= state.highest_bid_amount;
+
+            let result = state.record_bid(bidder, *amount, bidder_index as u8);
+
+            prop_assert_eq!(result.is_ok(), is_valid);
+            if is_valid {
+                accepted.push(*amount);
+                prop_assert_eq!(state.highest_bidder_account, bidder);
+            }
+        }
+
+        prop_assert_eq!(state.highest_bid_amount, accepted.iter().copied().max().unwrap_or(0));
+        // the highest bid of the sequence is never rejected
+        prop_assert_eq!(state.highest_bid_amount, bids.iter().copied().max().unwrap_or(0));
+    }
+
+    #[test]
+    fn bid_history_keeps_the_most_recent_bids_in_order(bid_total in 0usize..32) {
+        let mut state = empty_state();
+        state.deadline = i64::MAX;
+        let mut placed = Vec::new();
+
+        for timestamp in 0..bid_total as i64 {
+            let bidder = Pubkey::new_unique();
+            let amount = state.highest_bid_amount + 1;
+            process_bid(&mut state, bidder, 0, amount, timestamp).unwrap();
+            placed.push((bidder, amount, timestamp));
+        }
+
+        let recent: Vec<_> = state
+            .recent_bids()
+            .iter()
+            .map(|record| (record.bidder, record.amount, record.timestamp))
+            .collect();
+        prop_assert_eq!(state.bid_history_count as usize, bid_total);
+        prop_assert_eq!(recent, placed[bid_total.saturating_sub(10)..].to_vec());
+    }
+
+    #[test]
+    fn only_bounded_positive_durations_are_accepted(
+        auction_duration in prop_oneof![
+            -1i64..=MAX_AUCTION_DURATION_SECS + 1,
+            -1i64..=i64::MAX,
+            Just(i64::MAX),
+        ]
+    ) {
+        let is_valid = auction_duration > 0
+            && (MIN_AUCTION_DURATION_SECS..=MAX_AUCTION_DURATION_SECS).contains(&auction_duration);
+
+        prop_assert_eq!(
+            validate_auction_duration(
+                auction_duration,
+                MIN_AUCTION_DURATION_SECS,
+                MAX_AUCTION_DURATION_SECS,
+            )
+            .is_ok(),
+            is_valid
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::Pubkey;
+use auction::candle::{candle_cutoff, candle_end_slot, candle_winner, seconds_to_slots};
+use auction::state::BidRecord;
+
+fn randomness(value: u64) -> [u8; 32] {
+    let mut randomness = [0u8; 32];
+    randomness[..8].copy_from_slice(&value.to_le_bytes());
+    randomness
+}
+
+#[test]
+fn end_slot_is_within_the_window() {
+    assert_eq!(candle_end_slot(randomness(0), 100, 50), 101);
+    assert_eq!(candle_end_slot(randomness(49), 100, 50), 150);
+    assert_eq!(candle_end_slot(randomness(50), 100, 50), 101);
+    assert_eq!(
+        candle_end_slot(randomness(u64::MAX), 100, 50),
+        101 + u64::MAX % 50
+    );
+}
+
+#[test]
+fn empty_window_ends_at_the_next_slot() {
+    assert_eq!(candle_end_slot(randomness(12345), 100, 0), 101);
+}
+
+#[test]
+fn end_slot_saturates() {
+    assert_eq!(candle_end_slot(randomness(7), u64::MAX, 50), u64::MAX);
+}
+
+#[test]
+fn seconds_are_converted_to_slots() {
+    assert_eq!(seconds_to_slots(10, 400), 25);
+    assert_eq!(seconds_to_slots(0, 400), 0);
+    assert_eq!(seconds_to_slots(-10, 400), 0);
+    assert_eq!(seconds_to_slots(10, 0), 0);
+}
+
+#[test]
+fn cutoff_falls_within_the_final_window() {
+    // a 60 second window before a deadline at 1_000
+    assert_eq!(candle_cutoff(randomness(0), 1_000, 60), 941);
+    assert_eq!(candle_cutoff(randomness(59), 1_000, 60), 1_000);
+    assert_eq!(candle_cutoff(randomness(60), 1_000, 60), 941);
+    assert_eq!(candle_cutoff(randomness(100), 1_000, 60), 981);
+}
+
+#[test]
+fn empty_window_ends_at_the_deadline() {
+    assert_eq!(candle_cutoff(randomness(12345), 1_000, 0), 1_000);
+}
+
+#[test]
+fn winner_is_the_last_bid_before_the_cutoff() {
+    let bid = |amount: u64, timestamp: i64| BidRecord {
+        bidder: Pubkey::new_unique(),
+        amount,
+        timestamp,
+    };
+    let history = [bid(100, 900), bid(200, 950), bid(300, 980), bid(400, 999)];
+
+    assert_eq!(candle_winner(&history, 941), Some(history[0]));
+    assert_eq!(candle_winner(&history, 981), Some(history[2]));
+    // a bid placed at the cutoff came too late
+    assert_eq!(candle_winner(&history, 980), Some(history[1]));
+    assert_eq!(candle_winner(&history, 1_000), Some(history[3]));
+    assert_eq!(candle_winner(&history, 900), None);
+    assert_eq!(candle_winner(&[], 1_000), None);
+}
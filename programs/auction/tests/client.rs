@@ -0,0 +1,104 @@
+#![cfg(feature = "client")]
+
+use anchor_lang::prelude::Pubkey;
+use auction::client::AuctionInstructions;
+use auction::PdaHelpers;
+
+#[test]
+fn first_auction_keeps_the_unindexed_state_address() {
+    let initializer = Pubkey::new_unique();
+
+    let (state, bump) = PdaHelpers::state_pda(&initializer);
+    assert_eq!(
+        (state, bump),
+        Pubkey::find_program_address(&[b"state", initializer.as_ref()], &auction::id())
+    );
+    assert_ne!(PdaHelpers::indexed_state_pda(&initializer, 1).0, state);
+}
+
+#[test]
+fn bid_marks_the_bidder_as_signer_of_its_escrow() {
+    let state = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+
+    let instruction = AuctionInstructions::bid(&state, &user, 1.5);
+    assert_eq!(instruction.program_id, auction::id());
+
+    let meta = |key: Pubkey| {
+        instruction
+            .accounts
+            .iter()
+            .find(|meta| meta.pubkey == key)
+            .unwrap()
+            .clone()
+    };
+    assert!(meta(state).is_writable && !meta(state).is_signer);
+    assert!(meta(user).is_writable && meta(user).is_signer);
+    let user_bid = meta(PdaHelpers::user_bid_pda(&user, &state).0);
+    assert!(user_bid.is_writable && !user_bid.is_signer);
+    let bid_escrow = meta(PdaHelpers::bid_escrow_pda(&user, &state).0);
+    assert!(bid_escrow.is_writable && !bid_escrow.is_signer);
+}
+
+#[test]
+fn initialize_derives_the_accounts_of_the_indexed_auction() {
+    let initializer = Pubkey::new_unique();
+    let (state, _) = PdaHelpers::indexed_state_pda(&initializer, 2);
+
+    let instruction = AuctionInstructions::initialize(
+        &initializer,
+        2,
+        0,
+        auction::AuctionConfig {
+            auction_duration: 3_600,
+            reserve_price: 0,
+            reserve_price_usd_cents: 0,
+            price_feed: Pubkey::default(),
+            seller_bond: 0,
+            dispute_window: 0,
+            refund_window: 0,
+            unclaimed_beneficiary: Pubkey::default(),
+            cancel_compensation_bps: 0,
+            auction_type: auction::AuctionType::English,
+            start_price: 0,
+            commit_duration: 0,
+            withdrawal_penalty_bps: 0,
+            title: String::new(),
+            item_hash: [0; 32],
+            uri: String::new(),
+            min_bid_increment: 0,
+            bundle_items: Vec::new(),
+            max_acceptable_price: 0,
+            price_step_secs: 0,
+            seller_claim_window: 0,
+            commit_deposit: 0,
+            milestone_count: 0,
+            condition_program: Pubkey::default(),
+            condition_account: Pubkey::default(),
+            candle_window: 0,
+            total_shares: 0,
+            quantity: 0,
+            round_duration_secs: 0,
+            tick: 0,
+            bid_fee: 0,
+            extension_secs: 0,
+            pay_as_bid: false,
+            finalist_count: 0,
+            open_duration: 0,
+            final_commit_duration: 0,
+            bid_mint: Pubkey::default(),
+            prize_mint: Pubkey::default(),
+            prize_amount: 0,
+        },
+    );
+
+    let keys: Vec<Pubkey> = instruction
+        .accounts
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect();
+    assert_eq!(keys[0], state);
+    assert!(keys.contains(&PdaHelpers::treasury_pda(&state).0));
+    assert!(keys.contains(&PdaHelpers::treasury_authority_pda(&state).0));
+    assert!(keys.contains(&PdaHelpers::leaderboard_pda(&state).0));
+}
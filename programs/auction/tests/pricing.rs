@@ -0,0 +1,162 @@
+use auction::pricing::{compute_dutch_price, compute_stepped_dutch_price};
+use proptest::prelude::*;
+
+const START: u64 = 10_000;
+const RESERVE: u64 = 1_000;
+const DURATION: i64 = 100;
+
+#[test]
+fn price_starts_at_the_start_price() {
+    assert_eq!(compute_dutch_price(START, RESERVE, DURATION, 0), START);
+}
+
+#[test]
+fn price_before_the_start_is_the_start_price() {
+    assert_eq!(compute_dutch_price(START, RESERVE, DURATION, -1), START);
+    assert_eq!(
+        compute_dutch_price(START, RESERVE, DURATION, i64::MIN),
+        START
+    );
+}
+
+#[test]
+fn price_falls_linearly() {
+    assert_eq!(compute_dutch_price(START, RESERVE, DURATION, 1), 9_910);
+    assert_eq!(compute_dutch_price(START, RESERVE, DURATION, 50), 5_500);
+    assert_eq!(compute_dutch_price(START, RESERVE, DURATION, 99), 1_090);
+}
+
+#[test]
+fn price_ends_at_the_reserve_price() {
+    assert_eq!(
+        compute_dutch_price(START, RESERVE, DURATION, DURATION),
+        RESERVE
+    );
+    assert_eq!(
+        compute_dutch_price(START, RESERVE, DURATION, DURATION + 1),
+        RESERVE
+    );
+    assert_eq!(
+        compute_dutch_price(START, RESERVE, DURATION, i64::MAX),
+        RESERVE
+    );
+}
+
+#[test]
+fn price_rounds_towards_the_start_price() {
+    // a drop of 9_000 * 1 / 7 = 1_285.71 lamports
+    assert_eq!(compute_dutch_price(START, RESERVE, 7, 1), 8_715);
+}
+
+#[test]
+fn start_price_not_above_the_reserve_is_the_reserve() {
+    assert_eq!(compute_dutch_price(RESERVE, RESERVE, DURATION, 0), RESERVE);
+    assert_eq!(
+        compute_dutch_price(RESERVE - 1, RESERVE, DURATION, 0),
+        RESERVE
+    );
+}
+
+#[test]
+fn empty_duration_is_the_reserve() {
+    assert_eq!(compute_dutch_price(START, RESERVE, 0, 0), RESERVE);
+    assert_eq!(compute_dutch_price(START, RESERVE, -1, 0), RESERVE);
+}
+
+#[test]
+fn extreme_prices_do_not_overflow() {
+    assert_eq!(compute_dutch_price(u64::MAX, 0, i64::MAX, 0), u64::MAX);
+    assert_eq!(
+        compute_dutch_price(u64::MAX, 0, 2, 1),
+        u64::MAX - u64::MAX / 2
+    );
+}
+
+#[test]
+fn stepped_price_holds_until_the_next_step() {
+    const STEP: i64 = 25;
+    let price = |elapsed| compute_stepped_dutch_price(START, RESERVE, DURATION, elapsed, STEP);
+
+    assert_eq!(price(0), START);
+    assert_eq!(price(24), START);
+    assert_eq!(price(25), 7_750);
+    assert_eq!(price(49), 7_750);
+    assert_eq!(price(50), 5_500);
+    assert_eq!(price(99), 3_250);
+}
+
+#[test]
+fn stepped_price_ends_at_the_reserve_price() {
+    assert_eq!(
+        compute_stepped_dutch_price(START, RESERVE, DURATION, DURATION, 30),
+        RESERVE
+    );
+    // the last step before the deadline still sells above the reserve
+    assert_eq!(
+        compute_stepped_dutch_price(START, RESERVE, DURATION, DURATION - 1, 30),
+        1_900
+    );
+}
+
+#[test]
+fn zero_step_falls_linearly() {
+    for elapsed in [-1, 0, 1, 50, 99, DURATION] {
+        assert_eq!(
+            compute_stepped_dutch_price(START, RESERVE, DURATION, elapsed, 0),
+            compute_dutch_price(START, RESERVE, DURATION, elapsed)
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn price_stays_between_the_reserve_and_the_start_price(
+        start_price in any::<u64>(),
+        reserve_price in any::<u64>(),
+        duration in any::<i64>(),
+        elapsed in any::<i64>(),
+    ) {
+        let price = compute_dutch_price(start_price, reserve_price, duration, elapsed);
+
+        prop_assert!(price >= reserve_price);
+        prop_assert!(price <= start_price.max(reserve_price));
+    }
+
+    #[test]
+    fn price_never_increases(
+        start_price in any::<u64>(),
+        reserve_price in any::<u64>(),
+        duration in 1i64..=i64::MAX,
+        elapsed in any::<i64>(),
+        later in 0i64..=i64::MAX,
+    ) {
+        let price = compute_dutch_price(start_price, reserve_price, duration, elapsed);
+        let later_price =
+            compute_dutch_price(start_price, reserve_price, duration, elapsed.saturating_add(later));
+
+        prop_assert!(later_price <= price);
+    }
+
+    #[test]
+    fn stepped_price_never_increases_and_stays_above_the_linear_price(
+        start_price in any::<u64>(),
+        reserve_price in any::<u64>(),
+        duration in 1i64..=i64::MAX,
+        elapsed in any::<i64>(),
+        later in 0i64..=i64::MAX,
+        step in 0i64..=i64::MAX,
+    ) {
+        let price =
+            compute_stepped_dutch_price(start_price, reserve_price, duration, elapsed, step);
+        let later_price = compute_stepped_dutch_price(
+            start_price,
+            reserve_price,
+            duration,
+            elapsed.saturating_add(later),
+            step,
+        );
+
+        prop_assert!(later_price <= price);
+        prop_assert!(price >= compute_dutch_price(start_price, reserve_price, duration, elapsed));
+    }
+}
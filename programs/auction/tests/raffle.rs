@@ -0,0 +1,46 @@
+use auction::raffle::raffle_winner;
+
+fn randomness(value: u128) -> [u8; 32] {
+    let mut randomness = [0u8; 32];
+    randomness[..16].copy_from_slice(&value.to_le_bytes());
+    randomness
+}
+
+#[test]
+fn ticket_picks_the_range_it_falls_in() {
+    // tickets 0..100 belong to the first bidder, 100..300 to the second
+    let totals = [100, 200];
+
+    assert_eq!(raffle_winner(randomness(0), &totals), Some(0));
+    assert_eq!(raffle_winner(randomness(99), &totals), Some(0));
+    assert_eq!(raffle_winner(randomness(100), &totals), Some(1));
+    assert_eq!(raffle_winner(randomness(299), &totals), Some(1));
+    // the ticket wraps around the pool
+    assert_eq!(raffle_winner(randomness(300), &totals), Some(0));
+    assert_eq!(raffle_winner(randomness(400), &totals), Some(1));
+}
+
+#[test]
+fn empty_contributions_never_win() {
+    let totals = [0, 50, 0, 50];
+
+    assert_eq!(raffle_winner(randomness(0), &totals), Some(1));
+    assert_eq!(raffle_winner(randomness(49), &totals), Some(1));
+    assert_eq!(raffle_winner(randomness(50), &totals), Some(3));
+}
+
+#[test]
+fn empty_pool_has_no_winner() {
+    assert_eq!(raffle_winner(randomness(7), &[]), None);
+    assert_eq!(raffle_winner(randomness(7), &[0, 0]), None);
+}
+
+#[test]
+fn same_randomness_draws_the_same_winner() {
+    let totals = [5, 17, 3, 42, 8];
+    let drawn = raffle_winner(randomness(123_456_789), &totals);
+
+    for _ in 0..3 {
+        assert_eq!(raffle_winner(randomness(123_456_789), &totals), drawn);
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::Rent;
+use auction::check_treasury_withdrawal;
+use auction::errors::AuctionError;
+use proptest::prelude::*;
+
+fn min_rent_balance() -> u64 {
+    Rent::default().minimum_balance(8)
+}
+
+#[test]
+fn withdrawal_down_to_the_rent_floor_is_allowed() {
+    let treasury_lamports = min_rent_balance() + 1_000;
+
+    assert!(check_treasury_withdrawal(treasury_lamports, 0, min_rent_balance()).is_ok());
+    assert!(check_treasury_withdrawal(treasury_lamports, 1_000, min_rent_balance()).is_ok());
+}
+
+#[test]
+fn withdrawal_below_the_rent_floor_is_rejected() {
+    let treasury_lamports = min_rent_balance() + 1_000;
+
+    assert_eq!(
+        check_treasury_withdrawal(treasury_lamports, 1_001, min_rent_balance()).unwrap_err(),
+        AuctionError::TreasuryInsufficientFunds.into()
+    );
+    // more than the treasury holds
+    assert_eq!(
+        check_treasury_withdrawal(treasury_lamports, u64::MAX, min_rent_balance()).unwrap_err(),
+        AuctionError::TreasuryInsufficientFunds.into()
+    );
+}
+
+proptest! {
+    #[test]
+    fn withdrawal_never_leaves_less_than_the_rent_floor(
+        treasury_lamports in any::<u64>(),
+        amount in any::<u64>(),
+    ) {
+        let result = check_treasury_withdrawal(treasury_lamports, amount, min_rent_balance());
+
+        let remaining = treasury_lamports.checked_sub(amount);
+        prop_assert_eq!(result.is_ok(), remaining.map_or(false, |r| r >= min_rent_balance()));
+    }
+}
@@ -0,0 +1,79 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, endAuction, fundAccount, initializeAuction, placeBid, settlementRent } from "./utils";
+
+describe("accept bid early", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    for (let bidder of bidders) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("Seller accepts the highest bid before the deadline", async () => {
+    // long enough for the auction to never reach its deadline during the test
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 3600 });
+
+    try {
+      await program.methods
+        .acceptBidEarly(bidders[0].publicKey, new anchor.BN(0))
+        .accounts({ state, initializer: initializer.publicKey })
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("NoBids");
+    }
+
+    await placeBid(program, state, bidders[0], 1.0);
+    const observed = await program.account.state.fetch(state);
+
+    // a higher bid lands before the seller's acceptance
+    await placeBid(program, state, bidders[1], 1.5);
+
+    try {
+      await program.methods
+        .acceptBidEarly(observed.highestBidderAccount, observed.highestBidAmount)
+        .accounts({ state, initializer: initializer.publicKey })
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("HighestBidChanged");
+    }
+
+    await program.methods
+      .acceptBidEarly(bidders[1].publicKey, new anchor.BN(convertSolToLamports(1.5)))
+      .accounts({ state, initializer: initializer.publicKey })
+      .signers([initializer])
+      .rpc();
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.endedEarly).equal(true);
+
+    // bidding is closed and the seller can settle right away
+    try {
+      await placeBid(program, state, bidders[2], 2.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Finished");
+    }
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    await endAuction(program, state, initializer);
+
+    // the seller paid the rent of the summary and the tax record
+    const recordsRent = await settlementRent(provider, state, program.programId);
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance + convertSolToLamports(1.5) - recordsRent);
+  });
+});
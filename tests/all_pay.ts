@@ -0,0 +1,132 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, feeAccounts, feeRecipientAccounts, findBidEscrowPda, findLeaderboardPda, findProfilePda, findSellerProfilePda, findSummaryPda, findTaxRecordPda, findTreasuryAuthorityPda, findTreasuryPda, findUserBidPda, fundAccount, initializeAuction, placeBid, refund, settlementRent } from "./utils";
+
+describe("all-pay auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ];
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    for (const bidder of bidders) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("Seller receives every bid and losers only get their rent back", async () => {
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 5,
+      auctionType: { allPay: {} },
+    });
+
+    await placeBid(program, state, bidders[0], 1.0);
+    await placeBid(program, state, bidders[1], 1.5);
+    await placeBid(program, state, bidders[2], 2.0);
+
+    try {
+      await program.methods
+        .withdrawOutbid()
+        .accounts({
+          state,
+          user: bidders[0].publicKey,
+          userBid: await findUserBidPda(bidders[0].publicKey, state, program.programId),
+          bidEscrow: await findBidEscrowPda(bidders[0].publicKey, state, program.programId),
+          rentPayer: bidders[0].publicKey,
+        })
+        .signers([bidders[0]])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("WithdrawalNotAllowed");
+    }
+
+    await delay(6000);
+
+    const escrowAccounts = async (bidder: anchor.web3.Keypair) => [
+      { pubkey: bidder.publicKey, isWritable: false, isSigner: false },
+      { pubkey: await findUserBidPda(bidder.publicKey, state, program.programId), isWritable: true, isSigner: false },
+      { pubkey: await findBidEscrowPda(bidder.publicKey, state, program.programId), isWritable: true, isSigner: false },
+    ];
+
+    // the settlement and refund events tell an all-pay auction apart from the others
+    let settled = null;
+    let refunded = null;
+    const settledListener = program.addEventListener("AuctionSettled", (event) => { settled = event; });
+    const refundedListener = program.addEventListener("BidRefunded", (event) => { refunded = event; });
+
+    const sellerBefore = await provider.connection.getBalance(initializer.publicKey);
+    await program.methods
+      .endAuction()
+      .accounts({
+        state,
+        authority: initializer.publicKey,
+        treasury: await findTreasuryPda(state, program.programId),
+        treasuryAuthority: await findTreasuryAuthorityPda(state, program.programId),
+        summary: await findSummaryPda(state, program.programId),
+        taxRecord: await findTaxRecordPda(state, program.programId),
+        userBid: await findUserBidPda(bidders[2].publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(bidders[2].publicKey, state, program.programId),
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        beneficiary: initializer.publicKey,
+        highestBidder: bidders[2].publicKey,
+        winnerProfile: await findProfilePda(bidders[2].publicKey, program.programId),
+        sellerProfile: await findSellerProfilePda(initializer.publicKey, program.programId),
+        ...await feeAccounts(program, bidders[2].publicKey),
+        priceFeed: anchor.web3.SystemProgram.programId,
+        conditionProgram: anchor.web3.SystemProgram.programId,
+        conditionAccount: anchor.web3.SystemProgram.programId,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(await escrowAccounts(bidders[0]))
+      .signers([initializer])
+      .rpc();
+    // the seller paid the rent of the summary and the tax record
+    const recordsRent = await settlementRent(provider, state, program.programId);
+    expect(await provider.connection.getBalance(initializer.publicKey) - sellerBefore)
+      .equal(convertSolToLamports(3.0) - recordsRent);
+
+    // the second loser was left out of the settlement, its bid is still owed to the seller
+    try {
+      await refund(program, state, bidders[1]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AllPayNoRefund");
+    }
+
+    await program.methods
+      .collectBids()
+      .accounts({ state, authority: initializer.publicKey, beneficiary: initializer.publicKey, ...await feeRecipientAccounts(program) })
+      .remainingAccounts(await escrowAccounts(bidders[1]))
+      .signers([initializer])
+      .rpc();
+    expect(await provider.connection.getBalance(initializer.publicKey) - sellerBefore)
+      .equal(convertSolToLamports(4.5) - recordsRent);
+
+    // a loser closes its escrow without getting the bid back
+    const loserBefore = await provider.connection.getBalance(bidders[0].publicKey);
+    await refund(program, state, bidders[0]);
+    const loserAfter = await provider.connection.getBalance(bidders[0].publicKey);
+    expect(loserAfter - loserBefore).lessThan(convertSolToLamports(0.01));
+
+    await delay(1000);
+    await program.removeEventListener(settledListener);
+    await program.removeEventListener(refundedListener);
+    expect(settled.auctionType).deep.equal({ allPay: {} });
+    expect(settled.winner.toBase58()).equal(bidders[2].publicKey.toBase58());
+    expect(settled.amount.toNumber()).equal(convertSolToLamports(2.0));
+    expect(refunded.auctionType).deep.equal({ allPay: {} });
+    expect(refunded.bidder.toBase58()).equal(bidders[0].publicKey.toBase58());
+    expect(refunded.amount.toNumber()).equal(0);
+  });
+});
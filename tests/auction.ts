@@ -3,6 +3,7 @@ import { Program } from "@project-serum/anchor";
 import { PublicKey } from "@solana/web3.js";
 import { Auction } from "../target/types/auction";
 import { expect } from "chai"
+import { auctionConfig, convertSolToLamports, delay, ensureConfig, feeAccounts, findBidEscrowPda, findConfigPda, findLeaderboardPda, findProfilePda, findSellerBondPda, findSellerCounterPda, findSellerProfilePda, findSummaryPda, findSybilCheckPda, findTaxRecordPda, findTreasuryAuthorityPda, findTreasuryPda, fundAccount, placeBid, registryAccounts, rentOf, settlementRent } from "./utils";
 const { SystemProgram } = anchor.web3;
 
 describe("auction", () => {
@@ -14,6 +15,10 @@ describe("auction", () => {
 
   let treasury: PublicKey = null;
   let state: PublicKey = null;
+  let stateBump: number = null;
+  let treasuryBump: number = null;
+  let treasuryAuthority: PublicKey = null;
+  let treasuryAuthorityBump: number = null;
 
   // The Accounts to create.
   const initializer = anchor.web3.Keypair.generate();
@@ -30,6 +35,16 @@ describe("auction", () => {
     { title: "Loser claims refund for 2nd time - should fail", account: bidders[0].account, errorCode: "AccountNotInitialized" },
   ];
 
+  // Rent of the profile the first bid of a bidder opened, it is never refunded
+  const profileRent = async (bidder: PublicKey): Promise<number> =>
+    rentOf(provider, await findProfilePda(bidder, program.programId));
+
+  // Rent exempt minimum of a bid escrow PDA, it holds the bid on top of it
+  const escrowRent = async (bidEscrowPda: PublicKey): Promise<number> => {
+    const account = await provider.connection.getAccountInfo(bidEscrowPda);
+    return provider.connection.getMinimumBalanceForRentExemption(account.data.length);
+  }
+
   before(async () => {
     await fundAccount(provider, initializer.publicKey, initialFunds);
     await fundAccount(provider, thief.publicKey, initialFunds);
@@ -45,6 +60,7 @@ describe("auction", () => {
     );
 
     state = _state_pda;
+    stateBump = _state_nonce;
 
     const [_pda, _nonce] = await PublicKey.findProgramAddress(
       [Buffer.from("treasury"), state.toBytes()],
@@ -52,6 +68,16 @@ describe("auction", () => {
     );
 
     treasury = _pda;
+    treasuryBump = _nonce;
+
+    // Get the PDA holding the lamports of the treasury.
+    const [_authority_pda, _authority_nonce] = await PublicKey.findProgramAddress(
+      [Buffer.from("treasury-auth"), state.toBytes()],
+      program.programId
+    );
+
+    treasuryAuthority = _authority_pda;
+    treasuryAuthorityBump = _authority_nonce;
 
   })
 
@@ -60,11 +86,18 @@ describe("auction", () => {
     const auctionDurationInSecs = new anchor.BN(3);
 
     await program.methods
-      .initialize(auctionDurationInSecs)
+      .initialize(0, { ...auctionConfig(), auctionDuration: auctionDurationInSecs })
       .accounts({
         state: state,
         initializer: initializer.publicKey,
+        sellerCounter: await findSellerCounterPda(initializer.publicKey, program.programId),
+        sellerProfile: await findSellerProfilePda(initializer.publicKey, program.programId),
+        ...await registryAccounts(program),
         treasury: treasury,
+        treasuryAuthority: treasuryAuthority,
+        sellerBond: await findSellerBondPda(state, program.programId),
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        config: await ensureConfig(program),
         systemProgram: SystemProgram.programId,
       })
       .signers([initializer])
@@ -76,26 +109,41 @@ describe("auction", () => {
     // console.log("Auction Initialized!\nDeadline: ", currentDeadline);
 
     expect(currentDeadline).greaterThanOrEqual((new Date()).getTime() / 1000 + Number(auctionDurationInSecs) - 5);
+    expect(auction.stateBump).equal(stateBump);
+    expect(auction.treasuryBump).equal(treasuryBump);
+    expect(auction.treasuryAuthorityBump).equal(treasuryAuthorityBump);
+    // the treasury authority only holds its rent until a penalty comes in, and the seller
+    // bond PDA stays empty without a bond
+    expect(await provider.connection.getBalance(await findSellerBondPda(state, program.programId))).equal(0);
+    expect(await provider.connection.getBalance(treasuryAuthority)).equal(await rentOf(provider, treasuryAuthority));
+
+    const ledger = await program.account.treasury.fetch(treasury);
+    expect(ledger.bump).equal(treasuryBump);
+    expect(Number(ledger.totalCollected)).equal(0);
   });
 
 
   for (let bidder of bidders) {
-    let treasuryBalance: number;
     it("Place a bid", async () => {
-      treasuryBalance = await provider.connection.getBalance(treasury);
       // Get the PDA that is assigned to user bid.
       const [userBidPda, _nonce] = await PublicKey.findProgramAddress(
         [Buffer.from("user-bid"), bidder.account.publicKey.toBytes(), state.toBytes()],
         program.programId
       );
+      const bidEscrowPda = await findBidEscrowPda(bidder.account.publicKey, state, program.programId);
 
       await program.methods
         .bid(bidder.amount)
         .accounts({
           state: state,
           user: bidder.account.publicKey,
-          treasury: treasury,
+          payer: bidder.account.publicKey,
           userBid: userBidPda,
+          bidEscrow: bidEscrowPda,
+          leaderboard: await findLeaderboardPda(state, program.programId),
+          sybilCheck: await findSybilCheckPda(bidder.account.publicKey, state, program.programId),
+          profile: await findProfilePda(bidder.account.publicKey, program.programId),
+          config: await findConfigPda(program.programId),
           systemProgram: SystemProgram.programId,
         })
         .signers([bidder.account])
@@ -106,23 +154,27 @@ describe("auction", () => {
       const highestBidNum = Number(auction.highestBidAmount);
       // console.log("Bid placed!\nHighest Bid: ", highestBidNum);
 
-      const updatedTreasuryBalance = await provider.connection.getBalance(treasury);
+      const escrowBalance = await provider.connection.getBalance(bidEscrowPda);
 
       // Highest bid number is in lamports
       expect(highestBidNum).equal(convertSolToLamports(bidder.amount));
       expect(auction.highestBidderAccount.toString()).equal(bidder.account.publicKey.toString());
       expect(auction.highestBidderAccount.toString()).equal(bidder.account.publicKey.toString());
-      expect(updatedTreasuryBalance).equal(treasuryBalance + convertSolToLamports(bidder.amount));
+      expect(escrowBalance).equal(await escrowRent(bidEscrowPda) + convertSolToLamports(bidder.amount));
+
+      const userBid = await program.account.userBid.fetch(userBidPda);
+      expect(Number(userBid.amount)).equal(convertSolToLamports(bidder.amount));
+      expect(userBid.refunded).equal(false);
     });
   }
 
   it("User wants to place a bid smaller than highest bid - should fail", async () => {
-    const treasuryBalance = await provider.connection.getBalance(treasury);
     // Get the PDA that is assigned to user bid.
     const [userBidPda, _nonce] = await PublicKey.findProgramAddress(
       [Buffer.from("user-bid"), thief.publicKey.toBytes(), state.toBytes()],
       program.programId
     );
+    const bidEscrowPda = await findBidEscrowPda(thief.publicKey, state, program.programId);
 
     try {
       await program.methods
@@ -130,8 +182,13 @@ describe("auction", () => {
         .accounts({
           state: state,
           user: thief.publicKey,
-          treasury: treasury,
+          payer: thief.publicKey,
           userBid: userBidPda,
+          bidEscrow: bidEscrowPda,
+          leaderboard: await findLeaderboardPda(state, program.programId),
+          sybilCheck: await findSybilCheckPda(thief.publicKey, state, program.programId),
+          profile: await findProfilePda(thief.publicKey, program.programId),
+          config: await findConfigPda(program.programId),
           systemProgram: SystemProgram.programId,
         })
         .signers([thief])
@@ -141,30 +198,53 @@ describe("auction", () => {
       expect(error.error.errorCode.code).equal("BidAmountTooSmall");
     }
 
-    const updatedTreasuryBalance = await provider.connection.getBalance(treasury)
     const thiefBalance = await provider.connection.getBalance(thief.publicKey);
 
     expect(thiefBalance).equal(initialFunds);
-    expect(updatedTreasuryBalance).equal(treasuryBalance);
+    expect(await provider.connection.getAccountInfo(userBidPda)).equal(null);
+    expect(await provider.connection.getAccountInfo(bidEscrowPda)).equal(null);
+  });
+
+  it("Seller wants to bid on its own auction - should fail", async () => {
+    try {
+      await placeBid(program, state, initializer, 2.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("SellerCannotBid");
+    }
   });
 
   it("End auction before deadline - should fail", async () => {
-    const treasuryBalance = await provider.connection.getBalance(treasury);
     const auctionState = await program.account.state.fetch(state);
     // Get the PDA that is assigned to user bid.
     const [userBidPda, _nonce] = await PublicKey.findProgramAddress(
       [Buffer.from("user-bid"), auctionState.highestBidderAccount.toBytes(), state.toBytes()],
       program.programId
     );
+    const bidEscrowPda = await findBidEscrowPda(auctionState.highestBidderAccount, state, program.programId);
+    const escrowBalance = await provider.connection.getBalance(bidEscrowPda);
 
     try {
       await program.methods
         .endAuction()
         .accounts({
           state: state,
-          initializer: initializer.publicKey,
-          treasury: treasury,
+          authority: initializer.publicKey,
+          treasury: await findTreasuryPda(state, program.programId),
+          treasuryAuthority: await findTreasuryAuthorityPda(state, program.programId),
+          summary: await findSummaryPda(state, program.programId),
+          taxRecord: await findTaxRecordPda(state, program.programId),
           userBid: userBidPda,
+          bidEscrow: bidEscrowPda,
+          leaderboard: await findLeaderboardPda(state, program.programId),
+          beneficiary: initializer.publicKey,
+          highestBidder: auctionState.highestBidderAccount,
+          winnerProfile: await findProfilePda(auctionState.highestBidderAccount, program.programId),
+          sellerProfile: await findSellerProfilePda(auctionState.initializer, program.programId),
+          ...await feeAccounts(program, auctionState.highestBidderAccount),
+          priceFeed: SystemProgram.programId,
+          conditionProgram: SystemProgram.programId,
+          conditionAccount: SystemProgram.programId,
           systemProgram: SystemProgram.programId,
         })
         .signers([initializer])
@@ -174,21 +254,22 @@ describe("auction", () => {
       expect(error.error.errorCode.code).equal("StillActive");
     }
 
-    const updatedTreasuryBalance = await provider.connection.getBalance(treasury)
+    const updatedEscrowBalance = await provider.connection.getBalance(bidEscrowPda);
     const auction = await program.account.state.fetch(state);
 
     expect(auction.sellerPayed).equal(false);
-    expect(updatedTreasuryBalance).equal(treasuryBalance);
+    expect(updatedEscrowBalance).equal(escrowBalance);
   });
 
   it("Not seller claims prize - should fail", async () => {
-    const treasuryBalance = await provider.connection.getBalance(treasury);
     const auctionState = await program.account.state.fetch(state);
     // Get the PDA that is assigned to user bid.
     const [userBidPda, _nonce] = await PublicKey.findProgramAddress(
       [Buffer.from("user-bid"), auctionState.highestBidderAccount.toBytes(), state.toBytes()],
       program.programId
     );
+    const bidEscrowPda = await findBidEscrowPda(auctionState.highestBidderAccount, state, program.programId);
+    const escrowBalance = await provider.connection.getBalance(bidEscrowPda);
 
     // wait 5secs for auction to finish
     await delay(5000);
@@ -198,27 +279,39 @@ describe("auction", () => {
         .endAuction()
         .accounts({
           state: state,
-          initializer: thief.publicKey,
-          treasury: treasury,
+          authority: thief.publicKey,
+          treasury: await findTreasuryPda(state, program.programId),
+          treasuryAuthority: await findTreasuryAuthorityPda(state, program.programId),
+          summary: await findSummaryPda(state, program.programId),
+          taxRecord: await findTaxRecordPda(state, program.programId),
           userBid: userBidPda,
+          bidEscrow: bidEscrowPda,
+          leaderboard: await findLeaderboardPda(state, program.programId),
+          beneficiary: initializer.publicKey,
+          highestBidder: auctionState.highestBidderAccount,
+          winnerProfile: await findProfilePda(auctionState.highestBidderAccount, program.programId),
+          sellerProfile: await findSellerProfilePda(auctionState.initializer, program.programId),
+          ...await feeAccounts(program, auctionState.highestBidderAccount),
+          priceFeed: SystemProgram.programId,
+          conditionProgram: SystemProgram.programId,
+          conditionAccount: SystemProgram.programId,
           systemProgram: SystemProgram.programId,
         })
         .signers([thief])
         .rpc();
       throw new Error("Should have failed!");
     } catch (error) {
-      expect(error.error.errorCode.code).equal("ConstraintHasOne");
+      expect(error.error.errorCode.code).equal("Unauthorized");
     }
 
-    const updatedTreasuryBalance = await provider.connection.getBalance(treasury)
+    const updatedEscrowBalance = await provider.connection.getBalance(bidEscrowPda);
     const auction = await program.account.state.fetch(state);
 
     expect(auction.sellerPayed).equal(false);
-    expect(updatedTreasuryBalance).equal(treasuryBalance);
+    expect(updatedEscrowBalance).equal(escrowBalance);
   });
 
   it("End auction", async () => {
-    const treasuryBalance = await provider.connection.getBalance(treasury);
     const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
 
     const auctionState = await program.account.state.fetch(state);
@@ -227,14 +320,29 @@ describe("auction", () => {
       [Buffer.from("user-bid"), auctionState.highestBidderAccount.toBytes(), state.toBytes()],
       program.programId
     );
+    const bidEscrowPda = await findBidEscrowPda(auctionState.highestBidderAccount, state, program.programId);
+    const escrowBalance = await provider.connection.getBalance(bidEscrowPda);
 
     await program.methods
       .endAuction()
       .accounts({
         state: state,
-        initializer: initializer.publicKey,
-        treasury: treasury,
+        authority: initializer.publicKey,
+        treasury: await findTreasuryPda(state, program.programId),
+        treasuryAuthority: await findTreasuryAuthorityPda(state, program.programId),
+        summary: await findSummaryPda(state, program.programId),
+        taxRecord: await findTaxRecordPda(state, program.programId),
         userBid: userBidPda,
+        bidEscrow: bidEscrowPda,
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        beneficiary: initializer.publicKey,
+        highestBidder: auctionState.highestBidderAccount,
+        winnerProfile: await findProfilePda(auctionState.highestBidderAccount, program.programId),
+        sellerProfile: await findSellerProfilePda(auctionState.initializer, program.programId),
+        ...await feeAccounts(program, auctionState.highestBidderAccount),
+        priceFeed: SystemProgram.programId,
+        conditionProgram: SystemProgram.programId,
+        conditionAccount: SystemProgram.programId,
         systemProgram: SystemProgram.programId,
       })
       .signers([initializer])
@@ -244,87 +352,112 @@ describe("auction", () => {
 
     // console.log("Bid ended!");
 
-    const updatedTreasuryBalance = await provider.connection.getBalance(treasury);
+    const updatedEscrowBalance = await provider.connection.getBalance(bidEscrowPda);
     const updatedSellerBalance = await provider.connection.getBalance(initializer.publicKey);
 
     // Highest bid number is in lamports
     expect(auction.sellerPayed).equal(true);
-    expect(updatedTreasuryBalance).equal(treasuryBalance - Number(auctionState.highestBidAmount));
-    expect(updatedSellerBalance).equal(sellerBalance + Number(auctionState.highestBidAmount));
+    expect(updatedEscrowBalance).equal(escrowBalance - Number(auctionState.highestBidAmount));
+    expect(updatedEscrowBalance).equal(await escrowRent(bidEscrowPda));
+    // the seller paid the rent of the summary and the tax record
+    const recordsRent = await settlementRent(provider, state, program.programId);
+    expect(updatedSellerBalance).equal(sellerBalance + Number(auctionState.highestBidAmount) - recordsRent);
+
+    // every lamport of the sale is accounted for in the tax record
+    const taxRecord = await program.account.taxRecord.fetch(await findTaxRecordPda(state, program.programId));
+    expect(taxRecord.seller.toBase58()).equal(initializer.publicKey.toBase58());
+    expect(taxRecord.buyer.toBase58()).equal(auctionState.highestBidderAccount.toBase58());
+    expect(taxRecord.grossSaleAmount.toNumber()).equal(Number(auctionState.highestBidAmount));
+    expect(taxRecord.grossSaleAmount.toNumber()).equal(
+      taxRecord.netSellerProceeds.toNumber() + taxRecord.platformFeeAmount.toNumber() + taxRecord.royaltyAmount.toNumber()
+    );
   });
 
 
   it("Loser claims refund", async () => {
     const loser = bidders[0];
-    const treasuryBalance = await provider.connection.getBalance(treasury);
 
     const [userBidPda, _nonce] = await PublicKey.findProgramAddress(
       [Buffer.from("user-bid"), loser.account.publicKey.toBytes(), state.toBytes()],
       program.programId
     );
+    const bidEscrowPda = await findBidEscrowPda(loser.account.publicKey, state, program.programId);
 
     await program.methods
       .refund()
       .accounts({
         state: state,
-        treasury: treasury,
         user: loser.account.publicKey,
         userBid: userBidPda,
+        bidEscrow: bidEscrowPda,
+        profile: await findProfilePda(loser.account.publicKey, program.programId),
+        rentPayer: loser.account.publicKey,
         systemProgram: SystemProgram.programId,
       })
       .signers([loser.account])
       .rpc();
 
-    const updatedTreasuryBalance = await provider.connection.getBalance(treasury);
     const updatedLoserBalance = await provider.connection.getBalance(loser.account.publicKey);
 
-    expect(updatedTreasuryBalance).equal(treasuryBalance - convertSolToLamports(loser.amount));
-    expect(updatedLoserBalance).equal(initialFunds);
+    expect(await provider.connection.getAccountInfo(userBidPda)).equal(null);
+    expect(await provider.connection.getAccountInfo(bidEscrowPda)).equal(null);
+    expect(updatedLoserBalance).equal(initialFunds - await profileRent(loser.account.publicKey));
   });
 
 
-  it("Winner claims refund | should only get the rent payed for user_bid PDA", async () => {
+  it("Winner claims refund | should only get the rent payed for user_bid and bid escrow PDAs", async () => {
     const winner = bidders[2];
 
     const [userBidPda, _nonce] = await PublicKey.findProgramAddress(
       [Buffer.from("user-bid"), winner.account.publicKey.toBytes(), state.toBytes()],
       program.programId
     );
+    const bidEscrowPda = await findBidEscrowPda(winner.account.publicKey, state, program.programId);
 
     await program.methods
       .refund()
       .accounts({
         state: state,
-        treasury: treasury,
         user: winner.account.publicKey,
         userBid: userBidPda,
+        bidEscrow: bidEscrowPda,
+        profile: await findProfilePda(winner.account.publicKey, program.programId),
+        rentPayer: winner.account.publicKey,
         systemProgram: SystemProgram.programId,
       })
       .signers([winner.account])
       .rpc();
 
     const updatedWinnerBalance = await provider.connection.getBalance(winner.account.publicKey);
-    expect(updatedWinnerBalance).equal(initialFunds - convertSolToLamports(winner.amount));
+    expect(updatedWinnerBalance).equal(initialFunds - convertSolToLamports(winner.amount) - await profileRent(winner.account.publicKey));
   });
 
 
   for (let c of failRefundCases) {
     it(c.title, async () => {
-      const treasuryBalance = await provider.connection.getBalance(treasury);
+      // the escrow of the loser that did not claim yet must stay untouched
+      const [otherUserBidPda, _otherNonce] = await PublicKey.findProgramAddress(
+        [Buffer.from("user-bid"), bidders[1].account.publicKey.toBytes(), state.toBytes()],
+        program.programId
+      );
+      const otherEscrowBalance = await provider.connection.getBalance(otherBidEscrowPda);
 
       const [userBidPda, _nonce] = await PublicKey.findProgramAddress(
         [Buffer.from("user-bid"), c.account.publicKey.toBytes(), state.toBytes()],
         program.programId
       );
+      const bidEscrowPda = await findBidEscrowPda(c.account.publicKey, state, program.programId);
 
       try {
         await program.methods
           .refund()
           .accounts({
             state: state,
-            treasury: treasury,
             user: c.account.publicKey,
             userBid: userBidPda,
+            bidEscrow: bidEscrowPda,
+            profile: await findProfilePda(c.account.publicKey, program.programId),
+            rentPayer: c.account.publicKey,
             systemProgram: SystemProgram.programId,
           })
           .signers([c.account])
@@ -334,24 +467,39 @@ describe("auction", () => {
         expect(error.error.errorCode.code).equal(c.errorCode);
       }
 
-      const updatedTreasuryBalance = await provider.connection.getBalance(treasury)
-      expect(updatedTreasuryBalance).equal(treasuryBalance);
+      expect(await provider.connection.getBalance(otherBidEscrowPda)).equal(otherEscrowBalance);
     });
   }
 
-});
+  it("Payouts of other bids do not affect a pending refund", async () => {
+    const loser = bidders[1];
+
+    const [userBidPda, _nonce] = await PublicKey.findProgramAddress(
+      [Buffer.from("user-bid"), loser.account.publicKey.toBytes(), state.toBytes()],
+      program.programId
+    );
+    const bidEscrowPda = await findBidEscrowPda(loser.account.publicKey, state, program.programId);
 
+    // the seller payout and the other refunds only debited their own escrows
+    const escrowBalance = await provider.connection.getBalance(bidEscrowPda);
+    expect(escrowBalance).equal(await escrowRent(bidEscrowPda) + convertSolToLamports(loser.amount));
 
-const fundAccount = async (provider: anchor.Provider, accountPubkey: anchor.web3.PublicKey, amount: number = 10000000000): Promise<void> => {
-  const tx = await provider.connection.requestAirdrop(accountPubkey, amount);
-  const { blockhash, lastValidBlockHeight } = await provider.connection.getLatestBlockhash();
-  await provider.connection.confirmTransaction({
-    blockhash,
-    lastValidBlockHeight,
-    signature: tx
+    await program.methods
+      .refund()
+      .accounts({
+        state: state,
+        user: loser.account.publicKey,
+        userBid: userBidPda,
+        bidEscrow: bidEscrowPda,
+        profile: await findProfilePda(loser.account.publicKey, program.programId),
+        rentPayer: loser.account.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([loser.account])
+      .rpc();
+
+    expect(await provider.connection.getBalance(loser.account.publicKey)).equal(initialFunds - await profileRent(loser.account.publicKey));
   });
-}
 
-const convertSolToLamports = (solAmount: number): number => solAmount * 10 ** 9;
+});
 
-const delay = ms => new Promise(res => setTimeout(res, ms));
\ No newline at end of file
@@ -0,0 +1,55 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, fundAccount, initializeAuction, placeBid, settlementRent } from "./utils";
+
+describe("beneficiary", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+  const dao = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  it("Proceeds go to the beneficiary and it can not change after settlement", async () => {
+    const { state } = await initializeAuction(program, initializer);
+
+    await program.methods
+      .setBeneficiary(dao.publicKey)
+      .accounts({ state, initializer: initializer.publicKey })
+      .signers([initializer])
+      .rpc();
+
+    await placeBid(program, state, bidder, 1.0);
+
+    // wait for auction to finish
+    await delay(5000);
+
+    // the seller still settles, the payout goes to the beneficiary
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    await endAuction(program, state, initializer);
+
+    expect(await provider.connection.getBalance(dao.publicKey)).equal(convertSolToLamports(1.0));
+    // the seller only paid the rent of the summary and the tax record
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance - await settlementRent(provider, state, program.programId));
+
+    try {
+      await program.methods
+        .setBeneficiary(initializer.publicKey)
+        .accounts({ state, initializer: initializer.publicKey })
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AlreadySettled");
+    }
+  });
+});
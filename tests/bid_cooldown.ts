@@ -0,0 +1,49 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, findBidEscrowPda, fundAccount, initializeAuction, placeBid, rentOf } from "./utils";
+
+describe("bid cooldown", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+  const rival = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    for (const account of [initializer, bidder, rival]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Outbid bidder bids again once its cooldown passed", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 60 });
+
+    await placeBid(program, state, bidder, 1.0);
+    const bidEscrow = await findBidEscrowPda(bidder.publicKey, state, program.programId);
+    await placeBid(program, state, rival, 1.5);
+
+    await delay(3000);
+    try {
+      await placeBid(program, state, bidder, 2.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("BidCooldownNotExpired");
+    }
+
+    await delay(3000);
+    await placeBid(program, state, bidder, 2.0);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(bidder.publicKey.toBase58());
+    expect(auction.highestBidAmount.toNumber()).equal(convertSolToLamports(2.0));
+    expect(auction.bidCount).equal(2);
+    // the raise is escrowed on top of the first bid
+    expect(await provider.connection.getBalance(bidEscrow))
+      .equal(await rentOf(provider, bidEscrow) + convertSolToLamports(2.0));
+  });
+});
@@ -0,0 +1,58 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("bid history", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = Array.from({ length: 15 }, () => anchor.web3.Keypair.generate());
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    for (const bidder of bidders) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("Keeps the 10 most recent bids in chronological order", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 3600 });
+
+    for (let i = 0; i < bidders.length; i++) {
+      await placeBid(program, state, bidders[i], 0.5 * (i + 1));
+    }
+
+    const expected = bidders.slice(5).map((bidder, i) => ({
+      bidder: bidder.publicKey.toString(),
+      amount: convertSolToLamports(0.5 * (i + 6)),
+    }));
+    const toComparable = (records) => records.map((record) => ({
+      bidder: record.bidder.toString(),
+      amount: record.amount.toNumber(),
+    }));
+
+    let queried = null;
+    const listener = program.addEventListener("BidHistoryQueried", (event) => { queried = event; });
+
+    await program.methods.getBidHistory().accounts({ state }).rpc();
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    expect(queried.auction.toString()).equal(state.toString());
+    expect(queried.bidHistoryCount).equal(15);
+    expect(toComparable(queried.bids)).deep.equal(expected);
+    for (let i = 1; i < queried.bids.length; i++) {
+      expect(queried.bids[i].timestamp.toNumber()).gte(queried.bids[i - 1].timestamp.toNumber());
+    }
+
+    // the state account holds the same records, the oldest one at index 15 % 10
+    const auction = await program.account.state.fetch(state);
+    const history = auction.bidHistory as any[];
+    expect(toComparable([...history.slice(5), ...history.slice(0, 5)])).deep.equal(expected);
+  });
+});
@@ -0,0 +1,53 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { BID_COOLDOWN_SECONDS, convertSolToLamports, delay, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("bidder balance", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+  const raiser = anchor.web3.Keypair.generate();
+  const rival = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey, convertSolToLamports(1.0));
+    await fundAccount(provider, raiser.publicKey, convertSolToLamports(1.0));
+    await fundAccount(provider, rival.publicKey);
+  });
+
+  it("Bidder must keep a minimum balance on top of its bid", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 60 });
+
+    // 0.05 SOL of minimum balance and 0.01 SOL of rent reserve are left out
+    try {
+      await placeBid(program, state, bidder, 0.95);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("BidderBalanceTooLow");
+    }
+
+    await placeBid(program, state, bidder, 0.5);
+    expect((await program.account.state.fetch(state)).highestBidAmount.toNumber())
+      .equal(convertSolToLamports(0.5));
+  });
+
+  it("A raise only needs the balance for its difference with the escrowed bid", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 60, auctionIndex: 1 });
+
+    await placeBid(program, state, raiser, 0.5);
+    await placeBid(program, state, rival, 0.6);
+    await delay((BID_COOLDOWN_SECONDS + 1) * 1000);
+
+    // 0.4 SOL more is escrowed, the whole 0.9 SOL would not leave the minimum balance
+    await placeBid(program, state, raiser, 0.9);
+    expect((await program.account.state.fetch(state)).highestBidAmount.toNumber())
+      .equal(convertSolToLamports(0.9));
+  });
+});
@@ -0,0 +1,81 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findProfilePda, fundAccount, initializeAuction, placeBid, refund } from "./utils";
+
+describe("bidder profile", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const bidder = anchor.web3.Keypair.generate();
+  const rival = anchor.web3.Keypair.generate();
+  // every initializer holds a single auction
+  const initializers = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ];
+
+  before(async () => {
+    for (const account of [bidder, rival, ...initializers]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Accumulates the statistics of a bidder across auctions", async () => {
+    const amounts = [1.0, 0.5, 2.0];
+    const states = [];
+    for (const initializer of initializers) {
+      states.push((await initializeAuction(program, initializer, { durationInSecs: 5 })).state);
+    }
+
+    const updates = [];
+    const listener = program.addEventListener("ProfileUpdated", (event) => {
+      if (event.bidder.equals(bidder.publicKey)) {
+        updates.push(event);
+      }
+    });
+
+    for (const [index, state] of states.entries()) {
+      await placeBid(program, state, bidder, amounts[index]);
+    }
+    // the rival only outbids the bidder in the second auction
+    await placeBid(program, states[1], rival, 0.8);
+
+    const profile = await findProfilePda(bidder.publicKey, program.programId);
+    let stats = await program.account.bidderProfile.fetch(profile);
+    expect(stats.totalAuctionsParticipated).equal(3);
+    expect(stats.totalBidsPlaced.toNumber()).equal(3);
+    expect(stats.totalLamportsBid.toNumber()).equal(convertSolToLamports(3.5));
+    expect(stats.auctionsWon).equal(0);
+    expect(stats.auctionsLost).equal(0);
+
+    await delay(6000);
+
+    await endAuction(program, states[0], initializers[0]);
+    await endAuction(program, states[2], initializers[2]);
+    await endAuction(program, states[1], initializers[1]);
+    await refund(program, states[1], bidder);
+    // the refund of a won auction is not a loss
+    await refund(program, states[0], bidder);
+
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    stats = await program.account.bidderProfile.fetch(profile);
+    expect(stats.totalAuctionsParticipated).equal(3);
+    expect(stats.totalBidsPlaced.toNumber()).equal(3);
+    expect(stats.totalLamportsBid.toNumber()).equal(convertSolToLamports(3.5));
+    expect(stats.auctionsWon).equal(2);
+    expect(stats.auctionsLost).equal(1);
+
+    // three bids, two wins and one loss
+    expect(updates.length).equal(6);
+    const last = updates[updates.length - 1];
+    expect(last.auctionsWon).equal(2);
+    expect(last.auctionsLost).equal(1);
+  });
+});
@@ -0,0 +1,63 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("bundle auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+  const items = Array.from({ length: 8 }, () => anchor.web3.Keypair.generate().publicKey);
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  it("Sells the items of the bundle together", async () => {
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 5,
+      bundleItems: items.slice(0, 6),
+    });
+
+    const addItem = (item: anchor.web3.PublicKey) => program.methods
+      .addBundleItem(item)
+      .accounts({ state, initializer: initializer.publicKey })
+      .signers([initializer])
+      .rpc();
+
+    await addItem(items[6]);
+    await addItem(items[7]);
+    try {
+      await addItem(anchor.web3.Keypair.generate().publicKey);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("BundleItemCountExceeded");
+    }
+
+    await placeBid(program, state, bidder, 1.0);
+    try {
+      await addItem(items[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("CannotChangeAfterBids");
+    }
+
+    await delay(6000);
+
+    let sale = null;
+    const listener = program.addEventListener("BundleSaleCompleted", (event) => { sale = event; });
+    await endAuction(program, state, initializer);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    expect(sale.items.map((item) => item.toBase58())).deep.equal(items.map((item) => item.toBase58()));
+    expect(sale.winner.toBase58()).equal(bidder.publicKey.toBase58());
+    expect(sale.totalAmount.toNumber()).equal(convertSolToLamports(1.0));
+  });
+});
@@ -0,0 +1,84 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, findAuctionRegistryPagePda, findSellerBondPda, findTreasuryAuthorityPda, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("cancel auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+
+  const cancel = async (state: anchor.web3.PublicKey, treasury: anchor.web3.PublicKey) => {
+    await program.methods
+      .cancelAuction()
+      .accounts({
+        state,
+        initializer: initializer.publicKey,
+        treasury,
+        treasuryAuthority: await findTreasuryAuthorityPda(state, program.programId),
+        sellerBond: await findSellerBondPda(state, program.programId),
+        registryPage: await findAuctionRegistryPagePda(program, state),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([initializer])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  it("Seller cancels an auction without bids and gets the rent back", async () => {
+    const sellerBond = convertSolToLamports(1.0);
+    const { state, treasury, treasuryAuthority, sellerBond: sellerBondPda } = await initializeAuction(program, initializer, { durationInSecs: 3600, sellerBond });
+
+    const stateBalance = await provider.connection.getBalance(state);
+    const treasuryBalance = await provider.connection.getBalance(treasury);
+    const treasuryAuthorityBalance = await provider.connection.getBalance(treasuryAuthority);
+    const sellerBondBalance = await provider.connection.getBalance(sellerBondPda);
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+
+    let cancelled = null;
+    const listener = program.addEventListener("AuctionCancelled", (event) => { cancelled = event; });
+
+    await cancel(state, treasury);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    // the seller also pays the transaction fee
+    const fee = 5000;
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance + stateBalance + treasuryBalance + treasuryAuthorityBalance + sellerBondBalance - fee);
+    expect(sellerBondBalance).greaterThan(sellerBond);
+    expect(await provider.connection.getAccountInfo(state)).equal(null);
+    expect(await provider.connection.getAccountInfo(treasury)).equal(null);
+    expect(await provider.connection.getBalance(treasuryAuthority)).equal(0);
+    expect(await provider.connection.getBalance(sellerBondPda)).equal(0);
+    expect(cancelled.auction.toString()).equal(state.toString());
+
+    try {
+      await placeBid(program, state, bidder, 1.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AccountNotInitialized");
+    }
+  });
+
+  it("Seller can start over but not cancel once there are bids", async () => {
+    const { state, treasury } = await initializeAuction(program, initializer, { durationInSecs: 3600 });
+
+    await placeBid(program, state, bidder, 1.0);
+
+    try {
+      await cancel(state, treasury);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AuctionHasBids");
+    }
+  });
+});
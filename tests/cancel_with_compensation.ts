@@ -0,0 +1,78 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, endAuction, fundAccount, initializeAuction, placeBid, refund } from "./utils";
+
+describe("cancel with compensation", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    for (let bidder of bidders) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("Seller cancels by compensating the highest bidder and every bid is refunded", async () => {
+    // long enough for the auction to never reach its deadline during the test, 10% compensation
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 3600, cancelCompensationBps: 1000 });
+
+    await placeBid(program, state, bidders[0], 1.0);
+    await placeBid(program, state, bidders[1], 2.0);
+
+    // only the highest bidder can receive the compensation
+    try {
+      await program.methods
+        .cancelWithCompensation()
+        .accounts({
+          state,
+          initializer: initializer.publicKey,
+          highestBidder: bidders[0].publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidHighestBidder");
+    }
+
+    const winnerBalance = await provider.connection.getBalance(bidders[1].publicKey);
+
+    await program.methods
+      .cancelWithCompensation()
+      .accounts({
+        state,
+        initializer: initializer.publicKey,
+        highestBidder: bidders[1].publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([initializer])
+      .rpc();
+
+    expect(await provider.connection.getBalance(bidders[1].publicKey)).equal(winnerBalance + convertSolToLamports(0.2));
+    expect((await program.account.state.fetch(state)).cancelled).equal(true);
+
+    // the seller can not settle a cancelled auction
+    try {
+      await endAuction(program, state, initializer);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AuctionCancelled");
+    }
+
+    // every bid, including the highest one, is refunded in full
+    for (let [i, bidder] of bidders.entries()) {
+      const balance = await provider.connection.getBalance(bidder.publicKey);
+      await refund(program, state, bidder);
+      expect(await provider.connection.getBalance(bidder.publicKey)).greaterThan(balance + convertSolToLamports(i + 1));
+    }
+  });
+});
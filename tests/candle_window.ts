@@ -0,0 +1,59 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("candle window", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const earlyBidder = anchor.web3.Keypair.generate();
+  const lateBidder = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    for (const account of [initializer, earlyBidder, lateBidder]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Settles with the last bid placed before the drawn cutoff", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 8, candleWindowInSecs: 4 });
+    await placeBid(program, state, earlyBidder, 0.5);
+    // inside the window, it only wins when the cutoff comes after it
+    await delay(5000);
+    await placeBid(program, state, lateBidder, 1.0);
+    await delay(4000);
+
+    try {
+      await endAuction(program, state, initializer);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("CandleNotResolved");
+    }
+
+    await program.methods
+      .resolveCandle()
+      .accounts({ state, slotHashes: anchor.web3.SYSVAR_SLOT_HASHES_PUBKEY })
+      .rpc();
+
+    const auction = await program.account.state.fetch(state);
+    const deadline = auction.deadline.toNumber();
+    const cutoff = auction.candleCutoff.toNumber();
+    expect(cutoff).greaterThan(deadline - 4);
+    expect(cutoff).lessThanOrEqual(deadline);
+
+    const lateBid = auction.bidHistory[1];
+    const winner = lateBid.timestamp.toNumber() < cutoff ? lateBidder : earlyBidder;
+    const amount = winner === lateBidder ? 1.0 : 0.5;
+    expect((auction.highestBidderAccount as PublicKey).toBase58()).equal(winner.publicKey.toBase58());
+
+    await endAuction(program, state, initializer);
+    expect((await program.account.state.fetch(state)).winningAmount.toNumber())
+      .equal(convertSolToLamports(amount));
+  });
+});
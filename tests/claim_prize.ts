@@ -0,0 +1,77 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { delay, endAuction, findBidEscrowPda, findUserBidPda, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("claim prize", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const loser = anchor.web3.Keypair.generate();
+  const winner = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, loser.publicKey);
+    await fundAccount(provider, winner.publicKey);
+  });
+
+  const claimPrize = async (state: anchor.web3.PublicKey, user: anchor.web3.Keypair, deliveryNoteHash: number[] | null) =>
+    program.methods
+      .claimPrize(deliveryNoteHash)
+      .accounts({
+        state,
+        winner: user.publicKey,
+        userBid: await findUserBidPda(user.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(user.publicKey, state, program.programId),
+        receiver: user.publicKey,
+        rentPayer: user.publicKey,
+      })
+      .signers([user])
+      .rpc();
+
+  it("Only the winner claims the prize, and only once", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 3 });
+    await placeBid(program, state, loser, 1.0);
+    const winnerBid = await placeBid(program, state, winner, 2.0);
+    await delay(4000);
+    await endAuction(program, state, initializer);
+
+    try {
+      await claimPrize(state, loser, null);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("NotTheWinner");
+    }
+
+    const deliveryNoteHash = Array(32).fill(7);
+    let claimed = null;
+    const listener = program.addEventListener("PrizeClaimed", (event) => { claimed = event; });
+    const winnerBefore = await provider.connection.getBalance(winner.publicKey);
+    const winnerEscrow = await findBidEscrowPda(winner.publicKey, state, program.programId);
+    const rent = await provider.connection.getBalance(winnerBid) + await provider.connection.getBalance(winnerEscrow);
+    await claimPrize(state, winner, deliveryNoteHash);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    expect(claimed.auctionType).deep.equal({ english: {} });
+    expect(claimed.winner.toBase58()).equal(winner.publicKey.toBase58());
+    expect(claimed.deliveryNoteHash).deep.equal(deliveryNoteHash);
+    expect((await program.account.state.fetch(state)).prizeClaimed).equal(true);
+    expect(await provider.connection.getAccountInfo(winnerBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(winnerEscrow)).equal(null);
+    expect(await provider.connection.getBalance(winner.publicKey)).equal(winnerBefore + rent);
+
+    // the user bid was closed by the first claim
+    try {
+      await claimPrize(state, winner, null);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AccountNotInitialized");
+    }
+  });
+});
@@ -0,0 +1,67 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { BID_COOLDOWN_SECONDS, LOYALTY_DISCOUNT_BPS, MAX_AUCTION_DURATION_SECS, SHILL_LOOKBACK_SECS, ensureConfig, fundAccount, initializeAuction } from "./utils";
+
+describe("config", () => {
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializers = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+  const thief = anchor.web3.Keypair.generate();
+
+  let config: PublicKey = null;
+
+  const updateConfig = (minAuctionDuration: number, authority: anchor.web3.Keypair | null = null) => {
+    const builder = program.methods
+      .updateConfig(new anchor.BN(MAX_AUCTION_DURATION_SECS), new anchor.BN(minAuctionDuration), 0, provider.wallet.publicKey, new anchor.BN(0), new anchor.BN(BID_COOLDOWN_SECONDS), provider.wallet.publicKey, new anchor.BN(SHILL_LOOKBACK_SECS), LOYALTY_DISCOUNT_BPS)
+      .accounts({ config, authority: authority ? authority.publicKey : provider.wallet.publicKey });
+    return authority ? builder.signers([authority]).rpc() : builder.rpc();
+  };
+
+  before(async () => {
+    for (let initializer of initializers) {
+      await fundAccount(provider, initializer.publicKey);
+    }
+    await fundAccount(provider, thief.publicKey);
+
+    config = await ensureConfig(program);
+  });
+
+  it("Only the config authority can update it", async () => {
+    try {
+      await updateConfig(10, thief);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Unauthorized");
+    }
+
+    try {
+      await updateConfig(0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidConfig");
+    }
+  });
+
+  it("A new minimum duration applies to the next auctions", async () => {
+    await updateConfig(10);
+    expect(Number((await program.account.config.fetch(config)).minAuctionDuration)).equal(10);
+
+    try {
+      await initializeAuction(program, initializers[0], { durationInSecs: 3 });
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidAuctionDuration");
+    } finally {
+      // the other suites rely on the default config
+      await updateConfig(1);
+    }
+
+    await initializeAuction(program, initializers[1], { durationInSecs: 3 });
+  });
+});
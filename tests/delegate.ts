@@ -0,0 +1,131 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import {
+  convertSolToLamports,
+  findBidEscrowPda,
+  findConfigPda,
+  findDelegateAuthPda,
+  findLeaderboardPda,
+  findProfilePda,
+  findSybilCheckPda,
+  findUserBidPda,
+  fundAccount,
+  initializeAuction,
+  placeBid,
+  refund,
+} from "./utils";
+
+describe("bid delegation", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const principal = anchor.web3.Keypair.generate();
+  const delegate = anchor.web3.Keypair.generate();
+  const otherBidder = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, principal.publicKey);
+    await fundAccount(provider, delegate.publicKey);
+    await fundAccount(provider, otherBidder.publicKey);
+  });
+
+  it("Delegate bids for the principal within its allowance", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 60 });
+    const delegateAuth = await findDelegateAuthPda(principal.publicKey, state, program.programId);
+    const userBid = await findUserBidPda(principal.publicKey, state, program.programId);
+    const expiry = Math.floor(Date.now() / 1000) + 60;
+
+    await program.methods
+      .grantDelegate(delegate.publicKey, 2.0, new anchor.BN(expiry))
+      .accounts({
+        state,
+        principal: principal.publicKey,
+        delegateAuth,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([principal])
+      .rpc();
+
+    const delegatedBid = (amount: number, signer = delegate) => program.methods
+      .delegatedBid(amount)
+      .accounts({
+        state,
+        delegate: signer.publicKey,
+        principal: principal.publicKey,
+        delegateAuth,
+        userBid,
+        bidEscrow: await findBidEscrowPda(principal.publicKey, state, program.programId),
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        profile: await findProfilePda(principal.publicKey, program.programId),
+        config: await findConfigPda(program.programId),
+        sybilCheck: await findSybilCheckPda(principal.publicKey, state, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+    try {
+      await delegatedBid(3.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("DelegateLimitExceeded");
+    }
+
+    try {
+      await delegatedBid(1.0, otherBidder);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Unauthorized");
+    }
+
+    await delegatedBid(1.5);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(principal.publicKey.toBase58());
+    expect((await program.account.delegateAuth.fetch(delegateAuth)).maxAmount.toNumber())
+      .equal(convertSolToLamports(0.5));
+    expect((await program.account.userBid.fetch(userBid)).amount.toNumber())
+      .equal(convertSolToLamports(1.5));
+
+    // the principal gets the unspent allowance back and keeps the refund of the bid
+    await program.methods
+      .revokeDelegate()
+      .accounts({ state, principal: principal.publicKey, delegateAuth })
+      .signers([principal])
+      .rpc();
+    expect(await provider.connection.getAccountInfo(delegateAuth)).equal(null);
+
+    await placeBid(program, state, otherBidder, 2.0);
+    const balanceBefore = await provider.connection.getBalance(principal.publicKey);
+    await refund(program, state, principal, delegate.publicKey);
+    const balanceAfter = await provider.connection.getBalance(principal.publicKey);
+    expect(balanceAfter - balanceBefore).equal(convertSolToLamports(1.5));
+  });
+
+  it("Expired delegation can not bid", async () => {
+    const { state } = await initializeAuction(program, otherBidder, { durationInSecs: 60 });
+    const delegateAuth = await findDelegateAuthPda(principal.publicKey, state, program.programId);
+
+    try {
+      await program.methods
+        .grantDelegate(delegate.publicKey, 1.0, new anchor.BN(Math.floor(Date.now() / 1000) - 10))
+        .accounts({
+          state,
+          principal: principal.publicKey,
+          delegateAuth,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([principal])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("DelegateExpired");
+    }
+  });
+});
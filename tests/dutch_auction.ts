@@ -0,0 +1,198 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, feeRecipientAccounts, findBidEscrowPda, findConfigPda, findLeaderboardPda, findProfilePda, findSybilCheckPda, findUserBidPda, fundAccount, initializeAuction, placeBid, refund, rentOf } from "./utils";
+
+describe("dutch auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const sellers = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ];
+  const unitBuyers = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+  const buyer = anchor.web3.Keypair.generate();
+  const latecomer = anchor.web3.Keypair.generate();
+
+  // Returns the bid escrow holding the price
+  const acceptPrice = async (state: PublicKey, bidder: anchor.web3.Keypair): Promise<PublicKey> => {
+    const bidEscrow = await findBidEscrowPda(bidder.publicKey, state, program.programId);
+    await program.methods
+      .acceptPrice()
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        payer: bidder.publicKey,
+        userBid: await findUserBidPda(bidder.publicKey, state, program.programId),
+        bidEscrow,
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        sybilCheck: await findSybilCheckPda(bidder.publicKey, state, program.programId),
+        profile: await findProfilePda(bidder.publicKey, program.programId),
+        config: await findConfigPda(program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([bidder])
+      .rpc();
+    return bidEscrow;
+  };
+
+  // the price drops by 0.5 SOL every 5 seconds, from 2 SOL down to 1 SOL at the deadline
+  const steppedAuction = (seller: anchor.web3.Keypair) => initializeAuction(program, seller, {
+    durationInSecs: 10,
+    auctionType: { dutch: {} },
+    startPrice: convertSolToLamports(2.0),
+    reservePrice: convertSolToLamports(1.0),
+    priceStepInSecs: 5,
+  });
+
+  before(async () => {
+    for (const account of [...sellers, buyer, latecomer, ...unitBuyers]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Sells to the first taker at the asking price", async () => {
+    const { state } = await steppedAuction(sellers[0]);
+
+    try {
+      await placeBid(program, state, buyer, 2.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("DutchBidNotAllowed");
+    }
+
+    const bidEscrow = await acceptPrice(state, buyer);
+    const escrowed = await provider.connection.getBalance(bidEscrow) - await rentOf(provider, bidEscrow);
+    expect(escrowed).equal(convertSolToLamports(2.0));
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(buyer.publicKey.toBase58());
+    expect(auction.highestBidAmount.toNumber()).equal(convertSolToLamports(2.0));
+    expect(auction.endedEarly).equal(true);
+
+    // bidding closed with the sale
+    try {
+      await acceptPrice(state, latecomer);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Finished");
+    }
+
+    await endAuction(program, state, sellers[0]);
+    expect((await program.account.state.fetch(state)).winningAmount.toNumber())
+      .equal(convertSolToLamports(2.0));
+  });
+
+  it("Drops the price in steps", async () => {
+    const { state } = await steppedAuction(sellers[1]);
+
+    // one step passed, the next one is still 4 seconds away
+    await delay(6000);
+    const bidEscrow = await acceptPrice(state, buyer);
+    const escrowed = await provider.connection.getBalance(bidEscrow) - await rentOf(provider, bidEscrow);
+    expect(escrowed).equal(convertSolToLamports(1.5));
+  });
+
+  it("Drops the price linearly without a step", async () => {
+    const duration = 60;
+    const { state } = await initializeAuction(program, sellers[2], {
+      durationInSecs: duration,
+      auctionType: { dutch: {} },
+      startPrice: convertSolToLamports(2.0),
+      reservePrice: convertSolToLamports(1.0),
+    });
+
+    await delay(3000);
+    await acceptPrice(state, buyer);
+
+    // the sale moved the deadline to the time of the sale
+    const auction = await program.account.state.fetch(state);
+    const elapsed = auction.deadline.toNumber() - auction.startTime.toNumber();
+    expect(elapsed).greaterThan(0);
+    const drop = Math.floor(convertSolToLamports(1.0) * elapsed / duration);
+    expect(auction.highestBidAmount.toNumber()).equal(convertSolToLamports(2.0) - drop);
+  });
+
+  it("Sells one unit per buyer as the price falls until every unit is sold", async () => {
+    const { state } = await initializeAuction(program, sellers[3], {
+      durationInSecs: 15,
+      auctionType: { dutch: {} },
+      startPrice: convertSolToLamports(2.5),
+      reservePrice: convertSolToLamports(1.0),
+      priceStepInSecs: 5,
+      quantity: 3,
+    });
+    const buyers = [buyer, ...unitBuyers];
+
+    // a buyer takes a single unit
+    await acceptPrice(state, buyers[0]);
+    try {
+      await acceptPrice(state, buyers[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("UnitAlreadyBought");
+    }
+
+    // the next units go one price step later each
+    await delay(6000);
+    await acceptPrice(state, buyers[1]);
+    expect((await program.account.state.fetch(state)).unitsRemaining).equal(1);
+    await delay(5000);
+    await acceptPrice(state, buyers[2]);
+
+    // the last unit closed the sale before the deadline
+    const auction = await program.account.state.fetch(state);
+    expect(auction.unitsRemaining).equal(0);
+    expect(auction.endedEarly).equal(true);
+    try {
+      await acceptPrice(state, latecomer);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Finished");
+    }
+
+    // each buyer pays the price it took, in leaderboard order from the highest one
+    const prices = [2.5, 2.0, 1.5];
+    const remainingAccounts = [];
+    for (const bidder of buyers) {
+      remainingAccounts.push(
+        { pubkey: bidder.publicKey, isSigner: false, isWritable: false },
+        { pubkey: await findUserBidPda(bidder.publicKey, state, program.programId), isSigner: false, isWritable: true },
+        { pubkey: await findBidEscrowPda(bidder.publicKey, state, program.programId), isSigner: false, isWritable: true },
+      );
+    }
+    const sellerBalance = await provider.connection.getBalance(sellers[3].publicKey);
+    await program.methods
+      .settleMultiUnit()
+      .accounts({
+        state,
+        authority: sellers[3].publicKey,
+        beneficiary: sellers[3].publicKey,
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        ...await feeRecipientAccounts(program),
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([sellers[3]])
+      .rpc();
+    const proceeds = prices.reduce((sum, price) => sum + convertSolToLamports(price), 0);
+    expect(await provider.connection.getBalance(sellers[3].publicKey)).equal(sellerBalance + proceeds);
+    expect((await program.account.state.fetch(state)).winningAmount.toNumber()).equal(proceeds);
+
+    // nothing is left in the escrows of a sale that went through but their rent
+    for (const bidder of buyers) {
+      const userBid = await findUserBidPda(bidder.publicKey, state, program.programId);
+      const bidEscrow = await findBidEscrowPda(bidder.publicKey, state, program.programId);
+      const rent = await rentOf(provider, userBid) + await rentOf(provider, bidEscrow);
+      const balance = await provider.connection.getBalance(bidder.publicKey);
+      await refund(program, state, bidder);
+      expect(await provider.connection.getBalance(bidder.publicKey)).equal(balance + rent);
+    }
+  });
+});
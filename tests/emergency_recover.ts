@@ -0,0 +1,120 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { delay, findBidEscrowPda, fundAccount, initializeAuction, placeBid, rentOf } from "./utils";
+const { SystemProgram } = anchor.web3;
+
+const BPF_UPGRADEABLE_LOADER = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+describe("emergency recover", () => {
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+  const recoveryTimeoutInSecs = 5;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+  const thief = anchor.web3.Keypair.generate();
+  const recoveryVault = anchor.web3.Keypair.generate();
+
+  let recoveryConfig: PublicKey = null;
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+    await fundAccount(provider, thief.publicKey);
+
+    [recoveryConfig] = await PublicKey.findProgramAddress([Buffer.from("recovery-config")], program.programId);
+    const [programData] = await PublicKey.findProgramAddress([program.programId.toBytes()], BPF_UPGRADEABLE_LOADER);
+
+    // the provider wallet deployed the program, so it is the upgrade authority
+    await program.methods
+      .initializeRecoveryConfig(recoveryVault.publicKey, new anchor.BN(recoveryTimeoutInSecs))
+      .accounts({
+        recoveryConfig,
+        authority: provider.wallet.publicKey,
+        program: program.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Recovers a stuck treasury only after the timeout", async () => {
+    const { state, treasury, treasuryAuthority } = await initializeAuction(program, initializer, { durationInSecs: 2 });
+    const userBid = await placeBid(program, state, bidder, 1.0);
+    const bidEscrow = await findBidEscrowPda(bidder.publicKey, state, program.programId);
+    const escrowBalance = await provider.connection.getBalance(userBid) + await provider.connection.getBalance(bidEscrow);
+
+    const recover = (admin: anchor.web3.Keypair | null, withEscrow: boolean = true) => {
+      const builder = program.methods
+        .emergencyRecover()
+        .accounts({
+          state,
+          treasury,
+          treasuryAuthority,
+          recoveryConfig,
+          admin: admin ? admin.publicKey : provider.wallet.publicKey,
+          recoveryVault: recoveryVault.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(withEscrow ? [
+          { pubkey: bidder.publicKey, isSigner: false, isWritable: false },
+          { pubkey: userBid, isSigner: false, isWritable: true },
+          { pubkey: bidEscrow, isSigner: false, isWritable: true },
+        ] : []);
+      return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    };
+
+    // the seller never settles, but the auction ended too recently
+    await delay(3000);
+    try {
+      await recover(null);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("RecoveryTimeoutNotElapsed");
+    }
+
+    await delay((recoveryTimeoutInSecs + 1) * 1000);
+
+    try {
+      await recover(thief);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Unauthorized");
+    }
+
+    let recovered = null;
+    const listener = program.addEventListener("EmergencyRecovery", (event) => { recovered = event; });
+
+    await recover(null);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    const rent = await rentOf(provider, treasury);
+    expect(await provider.connection.getBalance(recoveryVault.publicKey)).equal(escrowBalance);
+    expect(await provider.connection.getBalance(treasury)).equal(rent);
+    expect(await provider.connection.getBalance(treasuryAuthority)).equal(await rentOf(provider, treasuryAuthority));
+    expect(await provider.connection.getAccountInfo(userBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(bidEscrow)).equal(null);
+
+    expect(recovered).not.equal(null);
+    expect(recovered.auction.toString()).equal(state.toString());
+    expect(recovered.recoveryVault.toString()).equal(recoveryVault.publicKey.toString());
+    expect(recovered.admin.toString()).equal(provider.wallet.publicKey.toString());
+    expect(Number(recovered.amount)).equal(escrowBalance);
+    expect(recovered.sellerPayed).equal(false);
+    expect(recovered.highestBidderAccount.toString()).equal(bidder.publicKey.toString());
+
+    // nothing is left to recover
+    try {
+      await recover(null, false);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("NothingToRecover");
+    }
+  });
+});
@@ -0,0 +1,61 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { delay, ensureConfig, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("extend deadline", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const otherInitializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+
+  const extend = async (seller: anchor.web3.Keypair, state: anchor.web3.PublicKey, newDurationInSecs: number) => {
+    await program.methods
+      .extendDeadline(new anchor.BN(newDurationInSecs))
+      .accounts({ state, initializer: seller.publicKey, config: await ensureConfig(program) })
+      .signers([seller])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, otherInitializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  it("Seller extends the deadline before the first bid", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 3600 });
+    const before = await program.account.state.fetch(state);
+
+    let changed = null;
+    const listener = program.addEventListener("DeadlineChanged", (event) => { changed = event; });
+
+    await extend(initializer, state, 7200);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    const after = await program.account.state.fetch(state);
+    expect(after.deadline.toNumber()).equal(after.startTime.toNumber() + 7200);
+    expect(changed.auction.toString()).equal(state.toString());
+    expect(changed.oldDeadline.toNumber()).equal(before.deadline.toNumber());
+    expect(changed.newDeadline.toNumber()).equal(after.deadline.toNumber());
+  });
+
+  it("Seller can not move the deadline once there are bids", async () => {
+    const { state } = await initializeAuction(program, otherInitializer, { durationInSecs: 3600 });
+
+    await placeBid(program, state, bidder, 1.0);
+
+    try {
+      await extend(otherInitializer, state, 7200);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("CannotChangeAfterBids");
+    }
+  });
+});
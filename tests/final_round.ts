@@ -0,0 +1,182 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { createHash } from "crypto";
+import { convertSolToLamports, delay, endAuction, findBidEscrowPda, findCommitBidPda, findLeaderboardPda, findUserBidPda, fundAccount, initializeAuction, placeBid, refund, rentOf, settlementRent } from "./utils";
+
+describe("final round", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const seller = anchor.web3.Keypair.generate();
+  const bidders = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+  const lateBidder = anchor.web3.Keypair.generate();
+
+  // sha256 of the amount in lamports (little endian) followed by the nonce
+  const commitmentOf = (amount: anchor.BN, nonce: Buffer): number[] =>
+    Array.from(createHash("sha256").update(Buffer.concat([amount.toArrayLike(Buffer, "le", 8), nonce])).digest());
+
+  const commit = async (state: anchor.web3.PublicKey, bidder: anchor.web3.Keypair, commitment: number[]) => {
+    await program.methods
+      .commitFinalOffer(commitment)
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        commitBid: await findCommitBidPda(bidder.publicKey, state, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  const reveal = async (state: anchor.web3.PublicKey, bidder: anchor.web3.Keypair, amount: anchor.BN, nonce: Buffer) => {
+    await program.methods
+      .revealFinalOffer(amount, Array.from(nonce))
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        commitBid: await findCommitBidPda(bidder.publicKey, state, program.programId),
+        userBid: await findUserBidPda(bidder.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(bidder.publicKey, state, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  const refundNonFinalist = async (state: anchor.web3.PublicKey, bidder: anchor.web3.Keypair) => {
+    await program.methods
+      .refundNonFinalist()
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        userBid: await findUserBidPda(bidder.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(bidder.publicKey, state, program.programId),
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        rentPayer: bidder.publicKey,
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, seller.publicKey);
+    for (let bidder of [...bidders, lateBidder]) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("The highest revealed offer of the finalists wins when the open leader never reveals", async () => {
+    const { state } = await initializeAuction(program, seller, {
+      durationInSecs: 20,
+      finalistCount: 2,
+      openDurationInSecs: 4,
+      finalCommitDurationInSecs: 6,
+    });
+
+    // the first two bids make the finalists
+    for (let [i, amount] of [3.0, 2.0, 1.0].entries()) {
+      await placeBid(program, state, bidders[i], amount);
+    }
+
+    try {
+      await refundNonFinalist(state, bidders[2]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("OpenPhaseNotOver");
+    }
+
+    // wait for the end of the open phase
+    await delay(4500);
+
+    // the open leader commits but never reveals, the runner-up raises its bid
+    const amount = new anchor.BN(convertSolToLamports(2.5));
+    const nonce = Buffer.from(anchor.web3.Keypair.generate().secretKey.slice(0, 32));
+    await commit(state, bidders[0], commitmentOf(new anchor.BN(convertSolToLamports(4.0)), Buffer.alloc(32)));
+    await commit(state, bidders[1], commitmentOf(amount, nonce));
+
+    try {
+      await reveal(state, bidders[1], amount, nonce);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("FinalRevealPhaseNotOpen");
+    }
+    // a finalist commits a single offer
+    try {
+      await commit(state, bidders[1], commitmentOf(amount, nonce));
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.message).not.equal("Should have failed!");
+    }
+    try {
+      await commit(state, bidders[2], commitmentOf(new anchor.BN(convertSolToLamports(5.0)), Buffer.alloc(32)));
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("NotAFinalist");
+    }
+    try {
+      await placeBid(program, state, lateBidder, 4.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("OpenPhaseClosed");
+    }
+    try {
+      await refundNonFinalist(state, bidders[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("WithdrawalNotAllowed");
+    }
+
+    // the bidder left out gets its whole bid and the rent of its user bid and escrow back
+    const userBid = await findUserBidPda(bidders[2].publicKey, state, program.programId);
+    const bidEscrow = await findBidEscrowPda(bidders[2].publicKey, state, program.programId);
+    const escrowRent = await rentOf(provider, userBid) + await rentOf(provider, bidEscrow);
+    const nonFinalistBalance = await provider.connection.getBalance(bidders[2].publicKey);
+    await refundNonFinalist(state, bidders[2]);
+    expect(await provider.connection.getBalance(bidders[2].publicKey)).equal(nonFinalistBalance + convertSolToLamports(1.0) + escrowRent);
+    expect(await provider.connection.getAccountInfo(userBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(bidEscrow)).equal(null);
+
+    // wait for the reveal phase
+    await delay(5000);
+
+    try {
+      await commit(state, bidders[1], commitmentOf(amount, nonce));
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("FinalCommitPhaseNotOpen");
+    }
+
+    let revealed = null;
+    const listener = program.addEventListener("FinalOfferRevealed", (event) => { revealed = event; });
+
+    await reveal(state, bidders[1], amount, nonce);
+
+    await delay(1000);
+    await program.removeEventListener(listener);
+    expect(revealed.leader.toBase58()).equal(bidders[1].publicKey.toBase58());
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(bidders[1].publicKey.toBase58());
+    expect(Number(auction.highestBidAmount)).equal(amount.toNumber());
+    expect(auction.finalsRevealed).equal(1);
+
+    // wait for auction to finish
+    await delay(8000);
+
+    const sellerBalance = await provider.connection.getBalance(seller.publicKey);
+    await endAuction(program, state, seller);
+    // the seller paid the rent of the summary and the tax record
+    const recordsRent = await settlementRent(provider, state, program.programId);
+    expect(await provider.connection.getBalance(seller.publicKey)).equal(sellerBalance + amount.toNumber() - recordsRent);
+
+    // the finalist who never revealed gets its open bid back in full
+    const leaderBalance = await provider.connection.getBalance(bidders[0].publicKey);
+    await refund(program, state, bidders[0]);
+    expect(await provider.connection.getBalance(bidders[0].publicKey)).equal(leaderBalance + convertSolToLamports(3.0) + escrowRent);
+  });
+});
@@ -0,0 +1,131 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { getAccount, getOrCreateAssociatedTokenAccount, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, feeRecipientAccounts, findBidEscrowPda, findConfigPda, findProfilePda, findSybilCheckPda, findUserBidPda, fundAccount, initializeAuction } from "./utils";
+
+describe("fractional auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [...Array(5)].map(() => anchor.web3.Keypair.generate());
+  const latecomer = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    for (const account of [initializer, ...bidders, latecomer]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Mints the shares of every bidder and pays the seller the whole pool", async () => {
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 10,
+      reservePrice: convertSolToLamports(1.0),
+      totalShares: 100,
+    });
+    const [shareMint] = await PublicKey.findProgramAddress(
+      [Buffer.from("share-mint"), state.toBytes()],
+      program.programId
+    );
+
+    const fractionalBid = async (bidder: anchor.web3.Keypair, amount: number, shares: number) => program.methods
+      .fractionalBid(new anchor.BN(convertSolToLamports(amount)), new anchor.BN(shares))
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        userBid: await findUserBidPda(bidder.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(bidder.publicKey, state, program.programId),
+        shareMint,
+        profile: await findProfilePda(bidder.publicKey, program.programId),
+        config: await findConfigPda(program.programId),
+        sybilCheck: await findSybilCheckPda(bidder.publicKey, state, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([bidder])
+      .rpc();
+
+    // each bidder asks for 20% of the item, at a fifth of the reserve price
+    for (const bidder of bidders) {
+      await fractionalBid(bidder, 0.2, 20);
+    }
+    try {
+      await fractionalBid(latecomer, 0.2, 1);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InsufficientSharesAvailable");
+    }
+    expect((await program.account.state.fetch(state)).allocatedShares.toNumber()).equal(100);
+
+    const shareAccounts = [];
+    const remainingAccounts = [];
+    for (const bidder of bidders) {
+      const shareAccount = await getOrCreateAssociatedTokenAccount(provider.connection, bidder, shareMint, bidder.publicKey);
+      shareAccounts.push(shareAccount.address);
+      remainingAccounts.push(
+        { pubkey: bidder.publicKey, isSigner: false, isWritable: false },
+        { pubkey: await findUserBidPda(bidder.publicKey, state, program.programId), isSigner: false, isWritable: true },
+        { pubkey: await findBidEscrowPda(bidder.publicKey, state, program.programId), isSigner: false, isWritable: true },
+        { pubkey: shareAccount.address, isSigner: false, isWritable: true },
+      );
+    }
+
+    const settle = () => program.methods
+      .settleFractional()
+      .accounts({
+        state,
+        authority: initializer.publicKey,
+        beneficiary: initializer.publicKey,
+        shareMint,
+        ...await feeRecipientAccounts(program),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([initializer])
+      .rpc();
+
+    try {
+      await settle();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("StillActive");
+    }
+
+    // a paused auction does not settle until bidding resumes
+    await program.methods
+      .pauseBidding()
+      .accounts({ state, initializer: initializer.publicKey })
+      .signers([initializer])
+      .rpc();
+    await delay(11000);
+    try {
+      await settle();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AuctionPaused");
+    }
+    await program.methods
+      .resumeBidding(false)
+      .accounts({ state, initializer: initializer.publicKey })
+      .signers([initializer])
+      .rpc();
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    await settle();
+
+    for (const shareAccount of shareAccounts) {
+      expect(Number((await getAccount(provider.connection, shareAccount)).amount)).equal(20);
+    }
+    expect(await provider.connection.getBalance(initializer.publicKey))
+      .equal(sellerBalance + convertSolToLamports(1.0));
+    const auction = await program.account.state.fetch(state);
+    expect(auction.sellerPayed).equal(true);
+    expect(auction.mintedShares.toNumber()).equal(100);
+  });
+});
@@ -0,0 +1,140 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, ensureConfig, fundAccount, initializeAuction, placeBid, refund } from "./utils";
+
+describe("fraud flags", () => {
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+  const lateSeller = anchor.web3.Keypair.generate();
+  const flaggers = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ];
+
+  const findFlagPda = async (flagger: PublicKey, state: PublicKey): Promise<PublicKey> => {
+    const [pda, _nonce] = await PublicKey.findProgramAddress(
+      [Buffer.from("flag"), flagger.toBytes(), state.toBytes()],
+      program.programId
+    );
+    return pda;
+  };
+
+  const reasonOf = (text: string): number[] => {
+    const reason = Array(128).fill(0);
+    Buffer.from(text).forEach((byte, index) => { reason[index] = byte; });
+    return reason;
+  };
+
+  const flagAuction = async (state: PublicKey, flagger: anchor.web3.Keypair) => program.methods
+    .flagAuction(reasonOf("item does not exist"))
+    .accounts({
+      state,
+      flag: await findFlagPda(flagger.publicKey, state),
+      flagger: flagger.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([flagger])
+    .rpc();
+
+  before(async () => {
+    for (const account of [initializer, bidder, lateSeller, ...flaggers]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Flags pause the auction until the platform reviews them", async () => {
+    const config = await ensureConfig(program);
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 60 });
+    await placeBid(program, state, bidder, 1.0);
+
+    for (const flagger of flaggers) {
+      await flagAuction(state, flagger);
+    }
+
+    let auction = await program.account.state.fetch(state);
+    expect(auction.flagCount).equal(3);
+    expect(auction.paused).equal(true);
+    const flag = await program.account.fraudFlag.fetch(await findFlagPda(flaggers[0].publicKey, state));
+    expect(flag.flagger.toBase58()).equal(flaggers[0].publicKey.toBase58());
+    expect(Buffer.from(flag.reason).toString().replace(/\0+$/, "")).equal("item does not exist");
+
+    try {
+      await program.methods
+        .resumeBidding(true)
+        .accounts({ state, initializer: initializer.publicKey })
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AuctionFlagged");
+    }
+
+    const dismissAccounts = async (flagger: anchor.web3.Keypair, platformAuthority: PublicKey) => ({
+      state,
+      flag: await findFlagPda(flagger.publicKey, state),
+      flagger: flagger.publicKey,
+      config,
+      platformAuthority,
+    });
+
+    try {
+      await program.methods
+        .dismissFlag()
+        .accounts(await dismissAccounts(flaggers[0], bidder.publicKey))
+        .signers([bidder])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Unauthorized");
+    }
+
+    const pausedDeadline = Number(auction.deadline);
+    await program.methods
+      .dismissFlag()
+      .accounts(await dismissAccounts(flaggers[0], provider.wallet.publicKey))
+      .rpc();
+    // below the threshold bidding resumes, the deadline moves by the pause
+    auction = await program.account.state.fetch(state);
+    expect(auction.flagCount).equal(2);
+    expect(auction.paused).equal(false);
+    expect(Number(auction.deadline)).greaterThanOrEqual(pausedDeadline);
+    expect(await provider.connection.getAccountInfo(await findFlagPda(flaggers[0].publicKey, state))).equal(null);
+
+    await program.methods
+      .confirmFraud()
+      .accounts({ state, config, platformAuthority: provider.wallet.publicKey })
+      .rpc();
+    auction = await program.account.state.fetch(state);
+    expect(auction.cancelled).equal(true);
+    expect(auction.paused).equal(false);
+
+    // the bid is refunded in full right away
+    const balanceBefore = await provider.connection.getBalance(bidder.publicKey);
+    await refund(program, state, bidder);
+    expect(await provider.connection.getBalance(bidder.publicKey) - balanceBefore)
+      .greaterThan(convertSolToLamports(1.0));
+  });
+
+  it("Auctions can not be flagged once bidding closed", async () => {
+    const { state } = await initializeAuction(program, lateSeller, { durationInSecs: 3 });
+
+    // wait for auction to finish
+    await delay(4000);
+
+    try {
+      await flagAuction(state, flaggers[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Finished");
+    }
+  });
+});
@@ -0,0 +1,37 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { delay, fundAccount, initializeAuction } from "./utils";
+
+describe("get remaining time", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+  });
+
+  it("Counts down to the deadline by the cluster clock and stops at zero", async () => {
+    const durationInSecs = 4;
+    const { state } = await initializeAuction(program, initializer, { durationInSecs });
+    const deadline = (await program.account.state.fetch(state)).deadline.toNumber();
+
+    const before = await program.methods.getRemainingTime().accounts({ state }).view();
+    expect(before.deadline.toNumber()).equal(deadline);
+    expect(before.remainingSecs.toNumber()).greaterThan(0);
+    expect(before.remainingSecs.toNumber()).lessThanOrEqual(durationInSecs);
+    expect(before.remainingSecs.toNumber()).equal(deadline - before.now.toNumber());
+
+    await delay((durationInSecs + 2) * 1000);
+
+    const after = await program.methods.getRemainingTime().accounts({ state }).view();
+    expect(after.deadline.toNumber()).equal(deadline);
+    expect(after.now.toNumber()).greaterThanOrEqual(deadline);
+    expect(after.remainingSecs.toNumber()).equal(0);
+  });
+});
@@ -0,0 +1,27 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { fundAccount, initializeAuction } from "./utils";
+
+describe("get version", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+  });
+
+  it("Reports the layout version of the state accounts it writes", async () => {
+    const { state } = await initializeAuction(program, initializer);
+
+    const version = await program.methods.getVersion().accounts({}).view();
+    expect(version.version).match(/^\d+\.\d+\.\d+/);
+    expect(version.stateSchema).equal((await program.account.state.fetch(state)).version);
+    expect(version.features).not.include("no-entrypoint");
+  });
+});
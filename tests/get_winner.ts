@@ -0,0 +1,69 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("get winner", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const vickreySeller = anchor.web3.Keypair.generate();
+  const bidders = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ];
+
+  before(async () => {
+    for (const account of [initializer, vickreySeller, ...bidders]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Returns the winner once the deadline passed", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 5 });
+    await placeBid(program, state, bidders[0], 1.0);
+    await placeBid(program, state, bidders[1], 1.5);
+
+    try {
+      await program.methods.getWinner().accounts({ state }).rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("StillActive");
+    }
+
+    await delay(6000);
+
+    // the return data is decoded from a simulation of the instruction
+    let result = await program.methods.getWinner().accounts({ state }).view();
+    expect(result.winner.toBase58()).equal(bidders[1].publicKey.toBase58());
+    expect(result.amount.toNumber()).equal(convertSolToLamports(1.5));
+    expect(result.settled).equal(false);
+
+    await endAuction(program, state, initializer);
+
+    result = await program.methods.getWinner().accounts({ state }).view();
+    expect(result.winner.toBase58()).equal(bidders[1].publicKey.toBase58());
+    expect(result.amount.toNumber()).equal(convertSolToLamports(1.5));
+    expect(result.settled).equal(true);
+  });
+
+  it("Returns the price a Vickrey winner pays", async () => {
+    const { state } = await initializeAuction(program, vickreySeller, {
+      durationInSecs: 5,
+      auctionType: { vickrey: {} },
+    });
+    await placeBid(program, state, bidders[0], 3.0);
+    await placeBid(program, state, bidders[1], 2.0);
+
+    await delay(6000);
+
+    const result = await program.methods.getWinner().accounts({ state }).view();
+    expect(result.winner.toBase58()).equal(bidders[0].publicKey.toBase58());
+    expect(result.amount.toNumber()).equal(convertSolToLamports(2.0));
+    expect(result.settled).equal(false);
+  });
+});
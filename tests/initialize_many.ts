@@ -0,0 +1,134 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import {
+  auctionConfig,
+  convertSolToLamports,
+  ensureConfig,
+  findLeaderboardPda,
+  findSellerBondPda,
+  findSellerCounterPda,
+  findSellerProfilePda,
+  findStatePda,
+  findTreasuryAuthorityPda,
+  findTreasuryPda,
+  fundAccount,
+  placeBid,
+  registryAccounts,
+  rentOf,
+} from "./utils";
+
+describe("initialize many", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  const auctionAccounts = async (firstIndex: number, count: number) => {
+    const accounts = [];
+    for (let index = firstIndex; index < firstIndex + count; index++) {
+      const state = await findStatePda(initializer.publicKey, program.programId, index);
+      const treasury = await findTreasuryPda(state, program.programId);
+      const treasuryAuthority = await findTreasuryAuthorityPda(state, program.programId);
+      const sellerBond = await findSellerBondPda(state, program.programId);
+      const leaderboard = await findLeaderboardPda(state, program.programId);
+      accounts.push({ state, treasury, treasuryAuthority, sellerBond, leaderboard });
+    }
+    return accounts;
+  };
+
+  const remainingAccounts = (accounts: { state: anchor.web3.PublicKey, treasury: anchor.web3.PublicKey, treasuryAuthority: anchor.web3.PublicKey, sellerBond: anchor.web3.PublicKey, leaderboard: anchor.web3.PublicKey }[]) =>
+    accounts.flatMap(({ state, treasury, treasuryAuthority, sellerBond, leaderboard }) => [
+      { pubkey: state, isWritable: true, isSigner: false },
+      { pubkey: treasury, isWritable: true, isSigner: false },
+      { pubkey: treasuryAuthority, isWritable: true, isSigner: false },
+      { pubkey: sellerBond, isWritable: true, isSigner: false },
+      { pubkey: leaderboard, isWritable: true, isSigner: false },
+    ]);
+
+  it("Creates three auctions in one transaction", async () => {
+    const config = await ensureConfig(program);
+    const accounts = await auctionAccounts(1, 3);
+
+    await program.methods
+      .initializeMany(1, [
+        auctionConfig({ durationInSecs: 60, title: "First" }),
+        auctionConfig({ durationInSecs: 60, title: "Second" }),
+        auctionConfig({ durationInSecs: 60, title: "Third", sellerBond: convertSolToLamports(0.5) }),
+      ])
+      .accounts({
+        initializer: initializer.publicKey,
+        sellerCounter: await findSellerCounterPda(initializer.publicKey, program.programId),
+        sellerProfile: await findSellerProfilePda(initializer.publicKey, program.programId),
+        config,
+        ...await registryAccounts(program),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts(accounts))
+      .signers([initializer])
+      .rpc();
+
+    for (const [offset, { state }] of accounts.entries()) {
+      const auction = await program.account.state.fetch(state);
+      expect(auction.auctionIndex).equal(offset + 1);
+      expect(auction.initializer.toBase58()).equal(initializer.publicKey.toBase58());
+    }
+    // the bond is held by the seller bond PDA, the treasury only holds its rent
+    expect((await program.account.treasury.fetch(accounts[2].treasury)).totalCollected.toNumber()).equal(0);
+    expect(await provider.connection.getBalance(accounts[2].sellerBond))
+      .equal(await rentOf(provider, accounts[2].sellerBond) + convertSolToLamports(0.5));
+    expect(await provider.connection.getBalance(accounts[2].treasuryAuthority))
+      .equal(await rentOf(provider, accounts[2].treasuryAuthority));
+    const sellerProfile = await program.account.sellerProfile.fetch(
+      await findSellerProfilePda(initializer.publicKey, program.programId)
+    );
+    expect(sellerProfile.totalAuctionsCreated).equal(3);
+
+    // every auction takes its own bids
+    await placeBid(program, accounts[0].state, bidder, 1.0);
+    await placeBid(program, accounts[2].state, bidder, 2.0);
+    expect((await program.account.state.fetch(accounts[0].state)).highestBidAmount.toNumber())
+      .equal(convertSolToLamports(1.0));
+    expect((await program.account.state.fetch(accounts[1].state)).highestBidAmount.toNumber()).equal(0);
+    expect((await program.account.state.fetch(accounts[2].state)).highestBidAmount.toNumber())
+      .equal(convertSolToLamports(2.0));
+  });
+
+  it("Fails atomically on an account that is not the expected PDA", async () => {
+    const config = await ensureConfig(program);
+    const accounts = await auctionAccounts(4, 2);
+    // the second auction gets the PDAs of another index
+    accounts[1] = (await auctionAccounts(7, 1))[0];
+
+    try {
+      await program.methods
+        .initializeMany(4, [auctionConfig({ durationInSecs: 60 }), auctionConfig({ durationInSecs: 60 })])
+        .accounts({
+          initializer: initializer.publicKey,
+          sellerCounter: await findSellerCounterPda(initializer.publicKey, program.programId),
+          sellerProfile: await findSellerProfilePda(initializer.publicKey, program.programId),
+          config,
+          ...await registryAccounts(program),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts(remainingAccounts(accounts))
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AuctionAccountMismatch");
+    }
+
+    // the first auction was rolled back with the rest of the transaction
+    expect(await provider.connection.getAccountInfo(accounts[0].state)).equal(null);
+  });
+});
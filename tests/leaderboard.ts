@@ -0,0 +1,49 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, findLeaderboardPda, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("leaderboard", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  // a Vickrey auction takes bids below the highest one, so the amounts can be mixed
+  const amounts = [0.5, 0.2, 0.9, 0.1, 0.7, 0.3, 1.0, 0.6, 0.05, 0.8, 0.4, 0.95, 0.15, 0.65, 0.25];
+  const bidders = amounts.map(() => anchor.web3.Keypair.generate());
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    for (const bidder of bidders) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("Keeps the ten highest of 15 bids sorted", async () => {
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 60,
+      auctionType: { vickrey: {} },
+    });
+
+    for (const [index, bidder] of bidders.entries()) {
+      await placeBid(program, state, bidder, amounts[index]);
+    }
+
+    const expected = bidders
+      .map((bidder, index) => ({ bidder: bidder.publicKey.toBase58(), amount: convertSolToLamports(amounts[index]) }))
+      .sort((a, b) => b.amount - a.amount)
+      .slice(0, 10)
+      .map((entry, index) => ({ rank: index + 1, ...entry }));
+
+    const leaderboard = await program.account.leaderboard.fetch(await findLeaderboardPda(state, program.programId));
+    const entries = (leaderboard.entries as any[]).map((entry) => ({
+      rank: entry.rank,
+      bidder: entry.bidder.toBase58(),
+      amount: entry.amount.toNumber(),
+    }));
+    expect(entries).deep.equal(expected);
+  });
+});
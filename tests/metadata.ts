@@ -0,0 +1,62 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { delay, fundAccount, initializeAuction } from "./utils";
+
+describe("auction metadata", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const MAX_TITLE_LEN = 64;
+  const MAX_URI_LEN = 200;
+
+  const itemHash = Array.from({ length: 32 }, (_, i) => i);
+
+  it("Stores maximum length metadata at initialize", async () => {
+    const initializer = anchor.web3.Keypair.generate();
+    await fundAccount(provider, initializer.publicKey);
+
+    const title = "t".repeat(MAX_TITLE_LEN);
+    const uri = "u".repeat(MAX_URI_LEN);
+
+    let initialized = null;
+    const listener = program.addEventListener("AuctionInitialized", (event) => { initialized = event; });
+
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 3600, title, itemHash, uri });
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.title).equal(title);
+    expect(auction.uri).equal(uri);
+    expect(auction.itemHash).deep.equal(itemHash);
+
+    expect(initialized.auction.toString()).equal(state.toString());
+    expect(initialized.initializer.toString()).equal(initializer.publicKey.toString());
+    expect(initialized.title).equal(title);
+    expect(initialized.itemHash).deep.equal(itemHash);
+    expect(initialized.uri).equal(uri);
+  });
+
+  const overLengthCases = [
+    { title: "Title longer than the maximum - should fail", options: { title: "t".repeat(MAX_TITLE_LEN + 1) } },
+    { title: "URI longer than the maximum - should fail", options: { uri: "u".repeat(MAX_URI_LEN + 1) } },
+  ];
+
+  for (let c of overLengthCases) {
+    it(c.title, async () => {
+      const initializer = anchor.web3.Keypair.generate();
+      await fundAccount(provider, initializer.publicKey);
+
+      try {
+        await initializeAuction(program, initializer, { durationInSecs: 3600, itemHash, ...c.options });
+        throw new Error("Should have failed!");
+      } catch (error) {
+        expect(error.error.errorCode.code).equal("MetadataTooLong");
+      }
+    });
+  }
+});
@@ -0,0 +1,86 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, feeAccounts, findBidEscrowPda, findUserBidPda, fundAccount, initializeAuction, placeBid, refund, rentOf } from "./utils";
+
+describe("milestones", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const winner = anchor.web3.Keypair.generate();
+  const loser = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    for (const account of [initializer, winner, loser]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Pays the winning bid to the seller as the winner approves milestones", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 5, milestoneCount: 3 });
+    await placeBid(program, state, loser, 0.5);
+    const userBid = await placeBid(program, state, winner, 1.0);
+    const bidEscrow = await findBidEscrowPda(winner.publicKey, state, program.programId);
+
+    await delay(6000);
+    await endAuction(program, state, initializer);
+
+    // the winning bid stays in escrow at settlement
+    const rent = await rentOf(provider, bidEscrow);
+    expect(await provider.connection.getBalance(bidEscrow)).equal(rent + convertSolToLamports(1.0));
+    try {
+      await refund(program, state, winner);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("MilestonesPending");
+    }
+
+    const release = async (method: "releaseMilestone" | "claimFinalMilestone", signer: anchor.web3.Keypair) => program.methods[method]()
+      .accounts({
+        state,
+        winner: signer.publicKey,
+        bidEscrow: await findBidEscrowPda(signer.publicKey, state, program.programId),
+        beneficiary: initializer.publicKey,
+        ...await feeAccounts(program, signer.publicKey),
+      })
+      .signers([signer])
+      .rpc();
+
+    const expectReleased = async (method: "releaseMilestone" | "claimFinalMilestone", amount: number) => {
+      const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+      await release(method, winner);
+      expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance + amount);
+    };
+
+    const expectFailure = async (method: "releaseMilestone" | "claimFinalMilestone", signer: anchor.web3.Keypair, code: string) => {
+      try {
+        await release(method, signer);
+        throw new Error("Should have failed!");
+      } catch (error) {
+        expect(error.error.errorCode.code).equal(code);
+      }
+    };
+
+    await expectFailure("releaseMilestone", loser, "NotTheWinner");
+    await expectFailure("claimFinalMilestone", winner, "MilestonesPending");
+
+    // 1 SOL does not split evenly in three, the last milestone gets the remainder
+    await expectReleased("releaseMilestone", 333_333_333);
+    await expectReleased("releaseMilestone", 333_333_333);
+    await expectFailure("releaseMilestone", winner, "FinalMilestone");
+    await expectReleased("claimFinalMilestone", 333_333_334);
+    await expectFailure("claimFinalMilestone", winner, "AllMilestonesReleased");
+    expect((await program.account.state.fetch(state)).milestonesReleased).equal(3);
+
+    // only the rent is left once every milestone was paid
+    expect(await provider.connection.getBalance(bidEscrow)).equal(rent);
+    await refund(program, state, winner);
+    expect(await provider.connection.getAccountInfo(userBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(bidEscrow)).equal(null);
+  });
+});
@@ -0,0 +1,50 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, findSellerCounterPda, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("multiple auctions per seller", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  it("Runs two auctions of the same seller side by side", async () => {
+    const first = await initializeAuction(program, initializer, { durationInSecs: 60, sellerBond: convertSolToLamports(0.1) });
+    const second = await initializeAuction(program, initializer, { durationInSecs: 60, auctionIndex: 1 });
+
+    const counter = await findSellerCounterPda(initializer.publicKey, program.programId);
+    expect((await program.account.sellerCounter.fetch(counter)).nextIndex).equal(2);
+    expect(first.state.toBase58()).not.equal(second.state.toBase58());
+    expect(first.treasury.toBase58()).not.equal(second.treasury.toBase58());
+    expect((await program.account.treasury.fetch(first.treasury)).totalCollected.toNumber())
+      .equal(convertSolToLamports(0.1));
+    expect((await program.account.treasury.fetch(second.treasury)).totalCollected.toNumber()).equal(0);
+
+    // the same bidder holds a bid in each auction
+    await placeBid(program, first.state, bidder, 1.0);
+    await placeBid(program, second.state, bidder, 3.0);
+    expect((await program.account.state.fetch(first.state)).highestBidAmount.toNumber())
+      .equal(convertSolToLamports(1.0));
+    expect((await program.account.state.fetch(second.state)).highestBidAmount.toNumber())
+      .equal(convertSolToLamports(3.0));
+    expect((await program.account.state.fetch(second.state)).auctionIndex).equal(1);
+
+    // an index below the counter was already handed out
+    try {
+      await initializeAuction(program, initializer, { durationInSecs: 60, auctionIndex: 1 });
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidAuctionIndex");
+    }
+  });
+});
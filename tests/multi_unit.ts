@@ -0,0 +1,145 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, feeRecipientAccounts, findBidEscrowPda, findLeaderboardPda, findUserBidPda, fundAccount, initializeAuction, placeBid, refund, rentOf } from "./utils";
+
+describe("multi-unit auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const bidders = [...Array(4)].map(() => anchor.web3.Keypair.generate());
+
+  before(async () => {
+    for (const bidder of bidders) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  // Settles the auction with the escrows of `winners`, in leaderboard order
+  const settleMultiUnit = async (state: PublicKey, initializer: anchor.web3.Keypair, winners: anchor.web3.Keypair[]) => {
+    const remainingAccounts = [];
+    for (const winner of winners) {
+      remainingAccounts.push(
+        { pubkey: winner.publicKey, isSigner: false, isWritable: false },
+        { pubkey: await findUserBidPda(winner.publicKey, state, program.programId), isSigner: false, isWritable: true },
+        { pubkey: await findBidEscrowPda(winner.publicKey, state, program.programId), isSigner: false, isWritable: true },
+      );
+    }
+    await program.methods
+      .settleMultiUnit()
+      .accounts({
+        state,
+        authority: initializer.publicKey,
+        beneficiary: initializer.publicKey,
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        ...await feeRecipientAccounts(program),
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([initializer])
+      .rpc();
+  };
+
+  it("Sells every unit at the lowest winning bid, a tie at the cutoff goes to the first bid", async () => {
+    const initializer = anchor.web3.Keypair.generate();
+    await fundAccount(provider, initializer.publicKey);
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 10, quantity: 3 });
+
+    // the last two bids tie for the third unit, the earlier one takes it
+    await placeBid(program, state, bidders[0], 1.0);
+    await placeBid(program, state, bidders[1], 0.8);
+    await placeBid(program, state, bidders[2], 0.5);
+    await placeBid(program, state, bidders[3], 0.5);
+    await delay(11000);
+
+    try {
+      await endAuction(program, state, initializer);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("MultiUnitSettlementRequired");
+    }
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    await settleMultiUnit(state, initializer, bidders.slice(0, 3));
+
+    expect(await provider.connection.getBalance(initializer.publicKey))
+      .equal(sellerBalance + 3 * convertSolToLamports(0.5));
+    const auction = await program.account.state.fetch(state);
+    expect(auction.sellerPayed).equal(true);
+    expect(auction.winningAmount.toNumber()).equal(3 * convertSolToLamports(0.5));
+
+    // winners get back what they bid above the clearing price, the loser its whole bid
+    const refunds = [0.5, 0.3, 0, 0.5];
+    for (const [index, bidder] of bidders.entries()) {
+      const userBid = await findUserBidPda(bidder.publicKey, state, program.programId);
+      const bidEscrow = await findBidEscrowPda(bidder.publicKey, state, program.programId);
+      const rent = await rentOf(provider, userBid) + await rentOf(provider, bidEscrow);
+      const balance = await provider.connection.getBalance(bidder.publicKey);
+      await refund(program, state, bidder);
+      expect(await provider.connection.getBalance(bidder.publicKey))
+        .equal(balance + convertSolToLamports(refunds[index]) + rent);
+    }
+  });
+
+  it("Sells a unit to every bid when there are fewer bids than units", async () => {
+    const initializer = anchor.web3.Keypair.generate();
+    await fundAccount(provider, initializer.publicKey);
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 10, quantity: 5 });
+
+    await placeBid(program, state, bidders[0], 1.0);
+    await placeBid(program, state, bidders[1], 0.6);
+    await delay(11000);
+
+    // every winner has to be passed
+    try {
+      await settleMultiUnit(state, initializer, bidders.slice(0, 1));
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidEscrow");
+    }
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    await settleMultiUnit(state, initializer, bidders.slice(0, 2));
+
+    expect(await provider.connection.getBalance(initializer.publicKey))
+      .equal(sellerBalance + 2 * convertSolToLamports(0.6));
+    const auction = await program.account.state.fetch(state);
+    expect(auction.winningAmount.toNumber()).equal(2 * convertSolToLamports(0.6));
+  });
+
+  it("Charges every winner its own bid when the auction is pay-as-bid", async () => {
+    const initializer = anchor.web3.Keypair.generate();
+    await fundAccount(provider, initializer.publicKey);
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 10, quantity: 2, payAsBid: true });
+
+    // the third bidder ties the second one late with a top-up and stays outside the two units
+    await placeBid(program, state, bidders[0], 1.0);
+    await placeBid(program, state, bidders[1], 0.7);
+    await placeBid(program, state, bidders[2], 0.4);
+    await placeBid(program, state, bidders[2], 0.7);
+    await delay(11000);
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    await settleMultiUnit(state, initializer, bidders.slice(0, 2));
+
+    expect(await provider.connection.getBalance(initializer.publicKey))
+      .equal(sellerBalance + convertSolToLamports(1.0) + convertSolToLamports(0.7) - 5000);
+    const auction = await program.account.state.fetch(state);
+    expect(auction.winningAmount.toNumber()).equal(convertSolToLamports(1.0) + convertSolToLamports(0.7));
+
+    // winners paid their whole bids, the bidder past the cutoff gets all of it back
+    const refunds = [0, 0, 0.7];
+    for (const [index, bidder] of bidders.slice(0, 3).entries()) {
+      const userBid = await findUserBidPda(bidder.publicKey, state, program.programId);
+      const bidEscrow = await findBidEscrowPda(bidder.publicKey, state, program.programId);
+      const rent = await rentOf(provider, userBid) + await rentOf(provider, bidEscrow);
+      const balance = await provider.connection.getBalance(bidder.publicKey);
+      await refund(program, state, bidder);
+      expect(await provider.connection.getBalance(bidder.publicKey))
+        .equal(balance + convertSolToLamports(refunds[index]) + rent - 5000);
+    }
+  });
+});
@@ -0,0 +1,45 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { auctionConfig, ensureConfig, findLeaderboardPda, findSellerBondPda, findSellerCounterPda, findSellerProfilePda, findStatePda, findTreasuryAuthorityPda, findTreasuryPda, fundAccount, registryAccounts } from "./utils";
+const { SystemProgram } = anchor.web3;
+
+describe("overflow", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const I64_MAX = new anchor.BN("9223372036854775807");
+  const U64_MAX = new anchor.BN("18446744073709551615");
+
+  const overflowCases = [
+    { title: "Auction duration of i64::MAX - should fail", duration: I64_MAX, sellerBond: new anchor.BN(0), errorCode: "InvalidAuctionDuration" },
+    { title: "Negative auction duration - should fail", duration: new anchor.BN(-1), sellerBond: new anchor.BN(0), errorCode: "InvalidAuctionDuration" },
+    { title: "Seller bond of u64::MAX - should fail", duration: new anchor.BN(3), sellerBond: U64_MAX, errorCode: "ArithmeticOverflow" },
+  ];
+
+  for (let c of overflowCases) {
+    it(c.title, async () => {
+      const initializer = anchor.web3.Keypair.generate();
+      await fundAccount(provider, initializer.publicKey);
+
+      const state = await findStatePda(initializer.publicKey, program.programId);
+      const treasury = await findTreasuryPda(state, program.programId);
+      const treasuryAuthority = await findTreasuryAuthorityPda(state, program.programId);
+      const sellerBond = await findSellerBondPda(state, program.programId);
+
+      try {
+        await program.methods
+          .initialize(0, { ...auctionConfig(), auctionDuration: c.duration, sellerBond: c.sellerBond })
+          .accounts({ state, initializer: initializer.publicKey, sellerCounter: await findSellerCounterPda(initializer.publicKey, program.programId), sellerProfile: await findSellerProfilePda(initializer.publicKey, program.programId), ...await registryAccounts(program), treasury, treasuryAuthority, sellerBond, leaderboard: await findLeaderboardPda(state, program.programId), config: await ensureConfig(program), systemProgram: SystemProgram.programId })
+          .signers([initializer])
+          .rpc();
+        throw new Error("Should have failed!");
+      } catch (error) {
+        expect(error.error.errorCode.code).equal(c.errorCode);
+      }
+    });
+  }
+});
@@ -0,0 +1,138 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { delay, ensureConfig, findAuctionRegistryPagePda, fundAccount, initializeAuction } from "./utils";
+
+describe("ownership", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const newOwner = anchor.web3.Keypair.generate();
+  const thief = anchor.web3.Keypair.generate();
+
+  let state: anchor.web3.PublicKey = null;
+  let treasury: anchor.web3.PublicKey = null;
+  let treasuryAuthority: anchor.web3.PublicKey = null;
+  let sellerBond: anchor.web3.PublicKey = null;
+
+  const proposeOwner = async (owner: anchor.web3.Keypair, proposed: anchor.web3.PublicKey) => {
+    await program.methods
+      .proposeOwner(proposed)
+      .accounts({ state, initializer: owner.publicKey })
+      .signers([owner])
+      .rpc();
+  }
+
+  const cancelProposal = async (owner: anchor.web3.Keypair) => {
+    await program.methods
+      .cancelOwnerProposal()
+      .accounts({ state, initializer: owner.publicKey })
+      .signers([owner])
+      .rpc();
+  }
+
+  const acceptOwnership = async (owner: anchor.web3.Keypair) => {
+    await program.methods
+      .acceptOwnership()
+      .accounts({ state, newOwner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+  }
+
+  const extendDeadline = async (owner: anchor.web3.Keypair) => {
+    await program.methods
+      .extendDeadline(new anchor.BN(7200))
+      .accounts({ state, initializer: owner.publicKey, config: await ensureConfig(program) })
+      .signers([owner])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, newOwner.publicKey);
+    await fundAccount(provider, thief.publicKey);
+
+    ({ state, treasury, treasuryAuthority, sellerBond } = await initializeAuction(program, initializer, { durationInSecs: 3600 }));
+  });
+
+  it("Owner cancels a pending proposal", async () => {
+    await proposeOwner(initializer, newOwner.publicKey);
+    expect((await program.account.state.fetch(state)).pendingAuthority.toString()).equal(newOwner.publicKey.toString());
+
+    await cancelProposal(initializer);
+    expect((await program.account.state.fetch(state)).pendingAuthority).equal(null);
+
+    try {
+      await acceptOwnership(newOwner);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("NoPendingOwner");
+    }
+
+    try {
+      await cancelProposal(initializer);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("NoPendingOwner");
+    }
+  });
+
+  it("Proposed owner takes over and the old key loses its powers", async () => {
+    await proposeOwner(initializer, newOwner.publicKey);
+
+    try {
+      await acceptOwnership(thief);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Unauthorized");
+    }
+
+    let transferred = null;
+    const listener = program.addEventListener("OwnershipTransferred", (event) => { transferred = event; });
+
+    await acceptOwnership(newOwner);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.authority.toString()).equal(newOwner.publicKey.toString());
+    expect(auction.initializer.toString()).equal(initializer.publicKey.toString());
+    expect(auction.pendingAuthority).equal(null);
+    expect(transferred.previousOwner.toString()).equal(initializer.publicKey.toString());
+    expect(transferred.newOwner.toString()).equal(newOwner.publicKey.toString());
+
+    for (const action of [() => extendDeadline(initializer), () => proposeOwner(initializer, thief.publicKey)]) {
+      try {
+        await action();
+        throw new Error("Should have failed!");
+      } catch (error) {
+        expect(error.error.errorCode.code).equal("Unauthorized");
+      }
+    }
+
+    await extendDeadline(newOwner);
+
+    const registryPage = await findAuctionRegistryPagePda(program, state);
+    try {
+      await program.methods
+        .cancelAuction()
+        .accounts({ state, initializer: initializer.publicKey, treasury, treasuryAuthority, sellerBond, registryPage, systemProgram: anchor.web3.SystemProgram.programId })
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Unauthorized");
+    }
+
+    await program.methods
+      .cancelAuction()
+      .accounts({ state, initializer: newOwner.publicKey, treasury, treasuryAuthority, sellerBond, registryPage, systemProgram: anchor.web3.SystemProgram.programId })
+      .signers([newOwner])
+      .rpc();
+    expect(await provider.connection.getAccountInfo(state)).equal(null);
+  });
+});
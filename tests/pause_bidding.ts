@@ -0,0 +1,75 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { delay, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("pause bidding", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  it("Pausing across the deadline shifts the effective deadline", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 5 });
+    const originalDeadline = (await program.account.state.fetch(state)).deadline.toNumber();
+
+    try {
+      await program.methods
+        .resumeBidding(true)
+        .accounts({ state, initializer: initializer.publicKey })
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AuctionNotPaused");
+    }
+
+    await program.methods
+      .pauseBidding()
+      .accounts({ state, initializer: initializer.publicKey })
+      .signers([initializer])
+      .rpc();
+    expect((await program.account.state.fetch(state)).paused).equal(true);
+
+    try {
+      await placeBid(program, state, bidder, 1.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AuctionPaused");
+    }
+
+    // the original deadline passes while bidding is paused
+    await delay(7000);
+
+    let resumed = null;
+    const listener = program.addEventListener("BiddingResumed", (event) => { resumed = event; });
+
+    await program.methods
+      .resumeBidding(true)
+      .accounts({ state, initializer: initializer.publicKey })
+      .signers([initializer])
+      .rpc();
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    const auction = await program.account.state.fetch(state);
+    const pausedSecs = resumed.pausedSecs.toNumber();
+    expect(pausedSecs).greaterThanOrEqual(6);
+    expect(auction.paused).equal(false);
+    expect(auction.totalPausedSecs.toNumber()).equal(pausedSecs);
+    expect(auction.deadline.toNumber()).equal(originalDeadline + pausedSecs);
+    expect(resumed.deadline.toNumber()).equal(auction.deadline.toNumber());
+
+    // bidders got the paused time back
+    await placeBid(program, state, bidder, 1.0);
+  });
+});
@@ -0,0 +1,70 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findBidEscrowPda, findUserBidPda, fundAccount, initializeAuction, placeBid, refund, rentOf, settlementRent } from "./utils";
+
+describe("penny auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+  before(async () => {
+    for (const account of [initializer, ...bidders]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Keeps every bid fee and sells at the final price", async () => {
+    // every bid pays 0.1 SOL, raises the price by 0.5 SOL and adds 5 seconds
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 5,
+      auctionType: { penny: {} },
+      bidFee: convertSolToLamports(0.1),
+      minBidIncrement: convertSolToLamports(0.5),
+      extensionInSecs: 5,
+    });
+    const stateBefore = await provider.connection.getBalance(state);
+
+    await placeBid(program, state, bidders[0], 0.5);
+    try {
+      await placeBid(program, state, bidders[1], 1.5);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidPennyBid");
+    }
+    await placeBid(program, state, bidders[1], 1.0);
+
+    let auction = await program.account.state.fetch(state);
+    expect(auction.deadline.toNumber()).equal(auction.startTime.toNumber() + 15);
+    expect(auction.feesCollected.toNumber()).equal(convertSolToLamports(0.2));
+    expect(await provider.connection.getBalance(state) - stateBefore).equal(convertSolToLamports(0.2));
+    const userBid = await program.account.userBid.fetch(await findUserBidPda(bidders[1].publicKey, state, program.programId));
+    expect(userBid.amount.toNumber()).equal(convertSolToLamports(1.0));
+    expect(userBid.feesPaid.toNumber()).equal(convertSolToLamports(0.1));
+
+    await delay(16000);
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    await endAuction(program, state, initializer);
+
+    // the seller gets the final price and both fees, and paid the rent of the summary and the tax record
+    const recordsRent = await settlementRent(provider, state, program.programId);
+    expect(await provider.connection.getBalance(initializer.publicKey))
+      .equal(sellerBalance + convertSolToLamports(1.0) + convertSolToLamports(0.2) - recordsRent);
+    auction = await program.account.state.fetch(state);
+    expect(auction.feesCollected.toNumber()).equal(0);
+
+    // the loser gets its price back, not its fee
+    const loserBid = await findUserBidPda(bidders[0].publicKey, state, program.programId);
+    const loserEscrow = await findBidEscrowPda(bidders[0].publicKey, state, program.programId);
+    const rent = await rentOf(provider, loserBid) + await rentOf(provider, loserEscrow);
+    const loserBalance = await provider.connection.getBalance(bidders[0].publicKey);
+    await refund(program, state, bidders[0]);
+    expect(await provider.connection.getBalance(bidders[0].publicKey))
+      .equal(loserBalance + convertSolToLamports(0.5) + rent);
+  });
+});
@@ -0,0 +1,152 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, feeAccounts, findBidEscrowPda, findLeaderboardPda, findProfilePda, findSellerProfilePda, findSummaryPda, findTaxRecordPda, findTreasuryAuthorityPda, findTreasuryPda, findUserBidPda, fundAccount, initializeAuction, placeBid, settlementRent } from "./utils";
+
+describe("perpetual auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const winner = anchor.web3.Keypair.generate();
+  const loser = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, winner.publicKey);
+    await fundAccount(provider, loser.publicKey);
+  });
+
+  // Settles the current round of a perpetual auction won by `winner`, the losing
+  // escrows are refunded and closed
+  const endRound = async (
+    state: anchor.web3.PublicKey,
+    winner: anchor.web3.Keypair,
+    winnerBid: anchor.web3.PublicKey,
+    losers: { bidder: anchor.web3.Keypair, userBid: anchor.web3.PublicKey }[],
+  ): Promise<void> => {
+    const loserAccounts = [];
+    for (const { bidder, userBid } of losers) {
+      loserAccounts.push(
+        { pubkey: bidder.publicKey, isWritable: true, isSigner: false },
+        { pubkey: userBid, isWritable: true, isSigner: false },
+        { pubkey: await findBidEscrowPda(bidder.publicKey, state, program.programId), isWritable: true, isSigner: false },
+        { pubkey: bidder.publicKey, isWritable: true, isSigner: false },
+      );
+    }
+    await program.methods
+      .endAuction()
+      .accounts({
+        state,
+        authority: initializer.publicKey,
+        treasury: await findTreasuryPda(state, program.programId),
+        treasuryAuthority: await findTreasuryAuthorityPda(state, program.programId),
+        summary: await findSummaryPda(state, program.programId),
+        taxRecord: await findTaxRecordPda(state, program.programId),
+        userBid: winnerBid,
+        bidEscrow: await findBidEscrowPda(winner.publicKey, state, program.programId),
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        beneficiary: initializer.publicKey,
+        highestBidder: winner.publicKey,
+        winnerProfile: await findProfilePda(winner.publicKey, program.programId),
+        sellerProfile: await findSellerProfilePda(initializer.publicKey, program.programId),
+        ...await feeAccounts(program, winner.publicKey),
+        priceFeed: anchor.web3.SystemProgram.programId,
+        conditionProgram: anchor.web3.SystemProgram.programId,
+        conditionAccount: anchor.web3.SystemProgram.programId,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: winner.publicKey, isWritable: true, isSigner: false },
+        ...loserAccounts,
+      ])
+      .signers([initializer])
+      .rpc();
+  };
+
+  it("Sells each round and starts the next one", async () => {
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 5,
+      auctionType: { perpetual: {} },
+    });
+
+    const loserBid = await placeBid(program, state, loser, 1.0);
+    const winnerBid = await placeBid(program, state, winner, 2.0);
+
+    await delay(6000);
+
+    let completed = null;
+    const listener = program.addEventListener("AuctionRoundCompleted", (event) => { completed = event; });
+
+    const sellerBefore = await provider.connection.getBalance(initializer.publicKey);
+    const loserBefore = await provider.connection.getBalance(loser.publicKey);
+    await endRound(state, winner, winnerBid, [{ bidder: loser, userBid: loserBid }]);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    // the first round also paid the rent of the summary and the tax record
+    const recordsRent = await settlementRent(provider, state, program.programId);
+    expect(await provider.connection.getBalance(initializer.publicKey) - sellerBefore)
+      .equal(convertSolToLamports(2.0) - recordsRent);
+    // the loser gets its bid and the rent of its escrow back
+    expect(await provider.connection.getBalance(loser.publicKey) - loserBefore)
+      .greaterThan(convertSolToLamports(1.0));
+    expect(await provider.connection.getAccountInfo(winnerBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(loserBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(await findBidEscrowPda(winner.publicKey, state, program.programId))).equal(null);
+    expect(await provider.connection.getAccountInfo(await findBidEscrowPda(loser.publicKey, state, program.programId))).equal(null);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.round).equal(1);
+    expect(auction.highestBidAmount.toNumber()).equal(0);
+    expect(auction.highestBidderAccount.toBase58()).equal(anchor.web3.PublicKey.default.toBase58());
+    expect(auction.sellerPayed).equal(false);
+    expect(auction.bidCount).equal(0);
+    expect(auction.deadline.toNumber()).greaterThan(Date.now() / 1000);
+
+    expect(completed.round).equal(1);
+    expect(completed.winner.toBase58()).equal(winner.publicKey.toBase58());
+    expect(completed.amount.toNumber()).equal(convertSolToLamports(2.0));
+
+    // the previous winner can bid again in the next round
+    await placeBid(program, state, winner, 0.5);
+    expect((await program.account.state.fetch(state)).highestBidderAccount.toBase58())
+      .equal(winner.publicKey.toBase58());
+    expect(await findUserBidPda(winner.publicKey, state, program.programId)).deep.equal(winnerBid);
+  });
+
+  it("Ranks the bids of each round on their own", async () => {
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 5,
+      auctionType: { perpetual: {} },
+    });
+
+    const loserBid = await placeBid(program, state, loser, 1.0);
+    const winnerBid = await placeBid(program, state, winner, 2.0);
+    await delay(6000);
+    await endRound(state, winner, winnerBid, [{ bidder: loser, userBid: loserBid }]);
+
+    const leaderboardPda = await findLeaderboardPda(state, program.programId);
+    let leaderboard = await program.account.leaderboard.fetch(leaderboardPda);
+    expect(leaderboard.entries.every((entry) => entry.rank === 0)).equal(true);
+
+    // a bid below the high bid of the first round still leads the second one
+    await placeBid(program, state, loser, 0.5);
+    leaderboard = await program.account.leaderboard.fetch(leaderboardPda);
+    expect(leaderboard.entries[0].bidder.toBase58()).equal(loser.publicKey.toBase58());
+    expect(leaderboard.entries[0].amount.toNumber()).equal(convertSolToLamports(0.5));
+    expect(leaderboard.entries[1].rank).equal(0);
+
+    await delay(6000);
+    const sellerBefore = await provider.connection.getBalance(initializer.publicKey);
+    await endRound(state, loser, loserBid, []);
+
+    expect(await provider.connection.getBalance(initializer.publicKey) - sellerBefore)
+      .equal(convertSolToLamports(0.5));
+    const auction = await program.account.state.fetch(state);
+    expect(auction.round).equal(2);
+  });
+});
@@ -0,0 +1,92 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findBidEscrowPda, findUserBidPda, fundAccount, initializeAuction, placeBid, refund, rentOf } from "./utils";
+
+describe("preview refund", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const sponsor = anchor.web3.Keypair.generate();
+  const bidders = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ];
+
+  const errorCode = (name: string): number => program.idl.errors.find((error) => error.name === name).code;
+
+  const previewRefund = async (state: PublicKey, bidder: anchor.web3.Keypair) => program.methods
+    .previewRefund()
+    .accounts({
+      state,
+      user: bidder.publicKey,
+      userBid: await findUserBidPda(bidder.publicKey, state, program.programId),
+      bidEscrow: await findBidEscrowPda(bidder.publicKey, state, program.programId),
+    })
+    .view();
+
+  // refunds `bidder` and checks it received exactly what the preview announced
+  const expectRefundAsPreviewed = async (state: PublicKey, bidder: anchor.web3.Keypair, rentPayer?: PublicKey) => {
+    const preview = await previewRefund(state, bidder);
+    const balanceBefore = await provider.connection.getBalance(bidder.publicKey);
+    await refund(program, state, bidder, rentPayer);
+    const balanceAfter = await provider.connection.getBalance(bidder.publicKey);
+    expect(balanceAfter - balanceBefore).equal(preview.refundableLamports.toNumber());
+    return preview;
+  };
+
+  before(async () => {
+    for (const account of [initializer, sponsor, ...bidders]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Matches the outcome of refund", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 5 });
+
+    const loserBid = await placeBid(program, state, bidders[0], 1.0);
+    const sponsoredBid = await placeBid(program, state, bidders[1], 1.2, sponsor);
+    const winnerBid = await placeBid(program, state, bidders[2], 1.5);
+
+    // nothing is refundable before settlement
+    const early = await previewRefund(state, bidders[0]);
+    expect(early.refundableLamports.toNumber()).equal(0);
+    expect(early.reason).equal(errorCode("UnclaimedPrize"));
+    try {
+      await refund(program, state, bidders[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("UnclaimedPrize");
+    }
+
+    await delay(6000);
+    await endAuction(program, state, initializer);
+
+    // a loser gets its bid and the rent of its user bid and escrow back
+    const loserRent = await rentOf(provider, loserBid)
+      + await rentOf(provider, await findBidEscrowPda(bidders[0].publicKey, state, program.programId));
+    const loser = await expectRefundAsPreviewed(state, bidders[0]);
+    expect(loser.refundableLamports.toNumber()).equal(convertSolToLamports(1.0) + loserRent);
+    expect(loser.includesRent).equal(true);
+    expect(loser.reason).equal(null);
+
+    // the rent of a sponsored bid goes back to the sponsor
+    const sponsored = await expectRefundAsPreviewed(state, bidders[1], sponsor.publicKey);
+    expect(sponsored.refundableLamports.toNumber()).equal(convertSolToLamports(1.2));
+    expect(sponsored.includesRent).equal(false);
+    expect(await provider.connection.getAccountInfo(sponsoredBid)).equal(null);
+
+    // the seller was paid with the winning bid, only the rent is left
+    const winnerRent = await rentOf(provider, winnerBid)
+      + await rentOf(provider, await findBidEscrowPda(bidders[2].publicKey, state, program.programId));
+    const winner = await expectRefundAsPreviewed(state, bidders[2]);
+    expect(winner.refundableLamports.toNumber()).equal(winnerRent);
+    expect(winner.includesRent).equal(true);
+  });
+});
@@ -0,0 +1,138 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, findBidEscrowPda, findConfigPda, findLeaderboardPda, findProfilePda, findSybilCheckPda, findUserBidPda, fundAccount, initializeAuction, placeBid, rentOf } from "./utils";
+
+describe("proxy bid", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const proxyBidder = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+  const lateBidder = anchor.web3.Keypair.generate();
+
+  // bids `amount` SOL, passing the escrow of `proxyOwner` in case its proxy answers
+  const bidAgainstProxy = async (state: anchor.web3.PublicKey, user: anchor.web3.Keypair, amount: number, proxyOwner?: anchor.web3.Keypair) => {
+    const remainingAccounts = proxyOwner ? [
+      { pubkey: proxyOwner.publicKey, isSigner: false, isWritable: false },
+      { pubkey: await findUserBidPda(proxyOwner.publicKey, state, program.programId), isSigner: false, isWritable: true },
+    ] : [];
+
+    await program.methods
+      .bid(amount)
+      .accounts({
+        state,
+        user: user.publicKey,
+        payer: user.publicKey,
+        userBid: await findUserBidPda(user.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(user.publicKey, state, program.programId),
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        sybilCheck: await findSybilCheckPda(user.publicKey, state, program.programId),
+        profile: await findProfilePda(user.publicKey, program.programId),
+        config: await findConfigPda(program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([user])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, proxyBidder.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+    await fundAccount(provider, lateBidder.publicKey);
+  });
+
+  it("Proxy answers competing bids up to its maximum", async () => {
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 3600,
+      minBidIncrement: convertSolToLamports(0.5),
+    });
+
+    const proxyUserBid = await findUserBidPda(proxyBidder.publicKey, state, program.programId);
+    const proxyEscrow = await findBidEscrowPda(proxyBidder.publicKey, state, program.programId);
+    await program.methods
+      .proxyBid(1.0, 3.0)
+      .accounts({
+        state,
+        user: proxyBidder.publicKey,
+        payer: proxyBidder.publicKey,
+        userBid: proxyUserBid,
+        bidEscrow: proxyEscrow,
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        sybilCheck: await findSybilCheckPda(proxyBidder.publicKey, state, program.programId),
+        profile: await findProfilePda(proxyBidder.publicKey, program.programId),
+        config: await findConfigPda(program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([proxyBidder])
+      .rpc();
+
+    // the whole maximum is escrowed
+    expect(await provider.connection.getBalance(proxyEscrow)).equal(await rentOf(provider, proxyEscrow) + convertSolToLamports(3.0));
+
+    // the proxy answers, so its escrow is required
+    try {
+      await bidAgainstProxy(state, bidder, 2.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidEscrow");
+    }
+
+    await bidAgainstProxy(state, bidder, 2.0, proxyBidder);
+
+    let auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toString()).equal(proxyBidder.publicKey.toString());
+    expect(auction.highestBidAmount.toNumber()).equal(convertSolToLamports(2.5));
+    expect((await program.account.userBid.fetch(proxyUserBid)).amount.toNumber()).equal(convertSolToLamports(2.5));
+
+    // a bid above the maximum wins as submitted
+    await placeBid(program, state, lateBidder, 3.5);
+
+    auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toString()).equal(lateBidder.publicKey.toString());
+    expect(auction.highestBidAmount.toNumber()).equal(convertSolToLamports(3.5));
+
+    // the outbid proxy gets its whole maximum back
+    const balance = await provider.connection.getBalance(proxyBidder.publicKey);
+    await program.methods
+      .withdrawOutbid()
+      .accounts({ state, user: proxyBidder.publicKey, userBid: proxyUserBid, bidEscrow: proxyEscrow, rentPayer: proxyBidder.publicKey })
+      .signers([proxyBidder])
+      .rpc();
+    expect(await provider.connection.getBalance(proxyBidder.publicKey)).greaterThan(balance + convertSolToLamports(3.0));
+  });
+
+  it("Proxy bids are only accepted in English auctions", async () => {
+    const seller = anchor.web3.Keypair.generate();
+    await fundAccount(provider, seller.publicKey);
+    const { state } = await initializeAuction(program, seller, { durationInSecs: 3600, auctionType: { vickrey: {} } });
+
+    try {
+      await program.methods
+        .proxyBid(1.0, 3.0)
+        .accounts({
+          state,
+          user: proxyBidder.publicKey,
+          payer: proxyBidder.publicKey,
+          userBid: await findUserBidPda(proxyBidder.publicKey, state, program.programId),
+          bidEscrow: await findBidEscrowPda(proxyBidder.publicKey, state, program.programId),
+          leaderboard: await findLeaderboardPda(state, program.programId),
+          sybilCheck: await findSybilCheckPda(proxyBidder.publicKey, state, program.programId),
+          profile: await findProfilePda(proxyBidder.publicKey, program.programId),
+          config: await findConfigPda(program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([proxyBidder])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("ProxyBidsNotAllowed");
+    }
+  });
+});
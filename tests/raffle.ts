@@ -0,0 +1,91 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, feeRecipientAccounts, findBidEscrowPda, findUserBidPda, fundAccount, initializeAuction, placeBid, refund, settlementRent } from "./utils";
+
+describe("raffle", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ].sort((a, b) => a.publicKey.toBuffer().compare(b.publicKey.toBuffer()));
+
+  before(async () => {
+    for (const account of [initializer, ...bidders]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Draws a winner once and pays the whole pool to the seller", async () => {
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 5,
+      auctionType: { raffle: {} },
+    });
+
+    // contributions do not have to beat each other, a bidder raises its own total
+    await placeBid(program, state, bidders[0], 2.0);
+    await placeBid(program, state, bidders[1], 1.0);
+    await placeBid(program, state, bidders[1], 1.5);
+    await delay(6000);
+
+    const bidAccounts = async (bidder: anchor.web3.Keypair) => [
+      { pubkey: bidder.publicKey, isWritable: false, isSigner: false },
+      { pubkey: await findUserBidPda(bidder.publicKey, state, program.programId), isWritable: true, isSigner: false },
+    ];
+    const escrowAccounts = async (bidder: anchor.web3.Keypair) => [
+      ...await bidAccounts(bidder),
+      { pubkey: await findBidEscrowPda(bidder.publicKey, state, program.programId), isWritable: true, isSigner: false },
+    ];
+    const drawWinner = async (order: anchor.web3.Keypair[]) => program.methods
+      .drawWinner()
+      .accounts({ state, authority: initializer.publicKey, slotHashes: anchor.web3.SYSVAR_SLOT_HASHES_PUBKEY })
+      .remainingAccounts((await Promise.all(order.map(bidAccounts))).flat())
+      .signers([initializer])
+      .rpc();
+    const expectFailure = async (order: anchor.web3.Keypair[], code: string) => {
+      try {
+        await drawWinner(order);
+        throw new Error("Should have failed!");
+      } catch (error) {
+        expect(error.error.errorCode.code).equal(code);
+      }
+    };
+
+    await expectFailure([bidders[1], bidders[0]], "RaffleBidsMissing");
+    await expectFailure([bidders[0]], "RaffleBidsMissing");
+    await drawWinner(bidders);
+    // the draw can not be run again for another outcome
+    await expectFailure(bidders, "RaffleAlreadyDrawn");
+
+    const auction = await program.account.state.fetch(state);
+    const winner = bidders.find((bidder) => bidder.publicKey.equals(auction.highestBidderAccount as PublicKey));
+    const loser = bidders.find((bidder) => bidder !== winner);
+    const contributions = new Map([[bidders[0], 2.0], [bidders[1], 1.5]]);
+    expect(auction.highestBidAmount.toNumber()).equal(convertSolToLamports(contributions.get(winner)));
+
+    const sellerBefore = await provider.connection.getBalance(initializer.publicKey);
+    await endAuction(program, state, initializer);
+    await program.methods
+      .collectBids()
+      .accounts({ state, authority: initializer.publicKey, beneficiary: initializer.publicKey, ...await feeRecipientAccounts(program) })
+      .remainingAccounts(await escrowAccounts(loser))
+      .signers([initializer])
+      .rpc();
+    // the seller paid the rent of the summary and the tax record out of the pool
+    expect(await provider.connection.getBalance(initializer.publicKey) - sellerBefore)
+      .equal(convertSolToLamports(3.5) - await settlementRent(provider, state, program.programId));
+
+    // the loser only gets the rent of its user bid and escrow back
+    const loserBefore = await provider.connection.getBalance(loser.publicKey);
+    await refund(program, state, loser);
+    expect(await provider.connection.getBalance(loser.publicKey) - loserBefore)
+      .lessThan(convertSolToLamports(0.01));
+  });
+});
@@ -0,0 +1,88 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findBidEscrowPda, findConfigPda, findLeaderboardPda, findProfilePda, findSybilCheckPda, findUserBidPda, fundAccount, initializeAuction } from "./utils";
+
+describe("receive address", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const winner = anchor.web3.Keypair.generate();
+  const firstAddress = anchor.web3.Keypair.generate().publicKey;
+  const secondAddress = anchor.web3.Keypair.generate().publicKey;
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, winner.publicKey);
+  });
+
+  const setReceiveAddress = (state: anchor.web3.PublicKey, address: anchor.web3.PublicKey) =>
+    program.methods
+      .setReceiveAddress(address)
+      .accounts({ state, winner: winner.publicKey })
+      .signers([winner])
+      .rpc();
+
+  const claimPrize = async (state: anchor.web3.PublicKey, receiver: anchor.web3.PublicKey) =>
+    program.methods
+      .claimPrize(null)
+      .accounts({
+        state,
+        winner: winner.publicKey,
+        userBid: await findUserBidPda(winner.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(winner.publicKey, state, program.programId),
+        receiver,
+        rentPayer: winner.publicKey,
+      })
+      .signers([winner])
+      .rpc();
+
+  it("Sends the escrowed prize to the latest receive address", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 3 });
+
+    // the proxy maximum above the winning bid stays escrowed until the prize is claimed
+    await program.methods
+      .proxyBid(1.0, 3.0)
+      .accounts({
+        state,
+        user: winner.publicKey,
+        payer: winner.publicKey,
+        userBid: await findUserBidPda(winner.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(winner.publicKey, state, program.programId),
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        sybilCheck: await findSybilCheckPda(winner.publicKey, state, program.programId),
+        profile: await findProfilePda(winner.publicKey, program.programId),
+        config: await findConfigPda(program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([winner])
+      .rpc();
+    await delay(4000);
+    await endAuction(program, state, initializer);
+
+    await setReceiveAddress(state, firstAddress);
+    await setReceiveAddress(state, secondAddress);
+    expect((await program.account.state.fetch(state)).receiveAddress.toBase58()).equal(secondAddress.toBase58());
+
+    try {
+      await claimPrize(state, firstAddress);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidPrizeReceiver");
+    }
+
+    await claimPrize(state, secondAddress);
+    expect(await provider.connection.getBalance(secondAddress)).equal(convertSolToLamports(2.0));
+
+    try {
+      await setReceiveAddress(state, firstAddress);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("PrizeAlreadyClaimed");
+    }
+  });
+});
@@ -0,0 +1,82 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import {
+  findAuctionRegistryPagePda,
+  findRegistryPagePda,
+  findRegistryPda,
+  fundAccount,
+  initializeAuction,
+  REGISTRY_PAGE_SIZE,
+} from "./utils";
+
+describe("auction registry", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+  });
+
+  // every auction listed in the registry, in creation order
+  const walkRegistry = async () => {
+    const registry = await program.account.registry.fetch(await findRegistryPda(program.programId));
+    const count = registry.auctionCount.toNumber();
+    const entries = [];
+    for (let page = 0; page * REGISTRY_PAGE_SIZE < count; page++) {
+      const registryPage = await program.account.registryPage.fetch(await findRegistryPagePda(page, program.programId));
+      expect(registryPage.index.toNumber()).equal(page);
+      entries.push(...registryPage.entries.slice(0, registryPage.count));
+    }
+    expect(entries.length).equal(count);
+    return entries;
+  };
+
+  it("Lists auctions in creation order and tombstones closed ones", async () => {
+    const auctions = [];
+    for (let auctionIndex = 0; auctionIndex < 3; auctionIndex++) {
+      auctions.push(await initializeAuction(program, initializer, { durationInSecs: 3600, auctionIndex }));
+    }
+
+    const sequences = [];
+    for (const { state } of auctions) {
+      sequences.push((await program.account.state.fetch(state)).sequence.toNumber());
+    }
+    expect(sequences[1]).equal(sequences[0] + 1);
+    expect(sequences[2]).equal(sequences[1] + 1);
+
+    let entries = await walkRegistry();
+    for (const [position, { state }] of auctions.entries()) {
+      const entry = entries[sequences[position] - 1];
+      expect(entry.auction.toBase58()).equal(state.toBase58());
+      expect(entry.tombstoned).equal(false);
+    }
+
+    const { state, treasury, treasuryAuthority, sellerBond } = auctions[1];
+    await program.methods
+      .cancelAuction()
+      .accounts({
+        state,
+        initializer: initializer.publicKey,
+        treasury,
+        treasuryAuthority,
+        sellerBond,
+        registryPage: await findAuctionRegistryPagePda(program, state),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([initializer])
+      .rpc();
+
+    // the closed auction keeps its slot, the next one did not move
+    entries = await walkRegistry();
+    expect(entries[sequences[1] - 1].auction.toBase58()).equal(state.toBase58());
+    expect(entries[sequences[1] - 1].tombstoned).equal(true);
+    expect(entries[sequences[2] - 1].auction.toBase58()).equal(auctions[2].state.toBase58());
+    expect(entries[sequences[2] - 1].tombstoned).equal(false);
+  });
+});
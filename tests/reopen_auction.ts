@@ -0,0 +1,83 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, ensureConfig, fundAccount, initializeAuction, placeBid, refund } from "./utils";
+
+describe("reopen auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+
+  const reopen = async (state: anchor.web3.PublicKey, newDurationInSecs: number, newReserve: number) => {
+    await program.methods
+      .reopenAuction(new anchor.BN(newDurationInSecs), new anchor.BN(newReserve))
+      .accounts({ state, initializer: initializer.publicKey, config: await ensureConfig(program) })
+      .signers([initializer])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  it("Runs a second round after the first one missed its reserve", async () => {
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 3,
+      reservePrice: convertSolToLamports(2.0),
+    });
+
+    // round 0 misses its reserve
+    await placeBid(program, state, bidder, 1.0);
+
+    try {
+      await reopen(state, 3, convertSolToLamports(0.5));
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("CannotReopen");
+    }
+
+    await delay(4000);
+    await endAuction(program, state, initializer);
+    expect((await program.account.state.fetch(state)).reserveNotMet).equal(true);
+
+    // the bid of the previous round is still escrowed
+    try {
+      await reopen(state, 3, convertSolToLamports(0.5));
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("AuctionHasBids");
+    }
+
+    await refund(program, state, bidder);
+
+    let reopened = null;
+    const listener = program.addEventListener("AuctionReopened", (event) => { reopened = event; });
+
+    await reopen(state, 3, convertSolToLamports(0.5));
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    let auction = await program.account.state.fetch(state);
+    expect(auction.round).equal(1);
+    expect(auction.reserveNotMet).equal(false);
+    expect(auction.highestBidAmount.toNumber()).equal(0);
+    expect(auction.reservePrice.toNumber()).equal(convertSolToLamports(0.5));
+    expect(reopened.round).equal(1);
+    expect(reopened.deadline.toNumber()).equal(auction.deadline.toNumber());
+
+    // round 1 clears the lowered reserve
+    await placeBid(program, state, bidder, 1.0);
+    await delay(3000);
+    await endAuction(program, state, initializer);
+
+    auction = await program.account.state.fetch(state);
+    expect(auction.sellerPayed).equal(true);
+    expect(auction.highestBidAmount.toNumber()).equal(convertSolToLamports(1.0));
+  });
+});
@@ -0,0 +1,66 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findProfilePda, fundAccount, initializeAuction, placeBid, refund, rentOf, settlementRent } from "./utils";
+
+describe("reserve price", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+  const initialFunds = 10000000000;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [
+    { account: anchor.web3.Keypair.generate(), amount: 1.0 },
+    { account: anchor.web3.Keypair.generate(), amount: 1.5 },
+  ];
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey, initialFunds);
+    for (let bidder of bidders) {
+      await fundAccount(provider, bidder.account.publicKey, initialFunds);
+    }
+  });
+
+  it("Auction ending below reserve refunds every bidder", async () => {
+    const { state, treasuryAuthority } = await initializeAuction(program, initializer, {
+      durationInSecs: 3,
+      reservePrice: convertSolToLamports(2),
+    });
+    const treasuryRent = await provider.connection.getBalance(treasuryAuthority);
+
+    for (let bidder of bidders) {
+      await placeBid(program, state, bidder.account, bidder.amount);
+    }
+
+    await delay(5000);
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    await endAuction(program, state, initializer);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.reserveNotMet).equal(true);
+    expect(auction.sellerPayed).equal(false);
+    // the seller only paid the rent of the summary and the tax record
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance - await settlementRent(provider, state, program.programId));
+
+    try {
+      await endAuction(program, state, initializer);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("ReserveNotMet");
+    }
+
+    // the highest bidder gets the full bid back as well
+    for (let bidder of bidders) {
+      await refund(program, state, bidder.account);
+      // only the rent of the bidder profile is kept
+      const profileRent = await rentOf(provider, await findProfilePda(bidder.account.publicKey, program.programId));
+      expect(await provider.connection.getBalance(bidder.account.publicKey)).equal(initialFunds - profileRent);
+    }
+
+    expect(await provider.connection.getBalance(treasuryAuthority)).equal(treasuryRent);
+  });
+});
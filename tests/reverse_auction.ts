@@ -0,0 +1,139 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findAuctionRegistryPagePda, findBidEscrowPda, findUserBidPda, fundAccount, initializeAuction, placeBid, refund, rentOf, settlementRent } from "./utils";
+
+describe("reverse auction", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const buyer = anchor.web3.Keypair.generate();
+  const providers = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ];
+
+  before(async () => {
+    await fundAccount(provider, buyer.publicKey);
+    for (const bidder of providers) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("Budget pays the lowest offer, losing offers have nothing to refund", async () => {
+    const { state, treasuryAuthority } = await initializeAuction(program, buyer, {
+      durationInSecs: 5,
+      auctionType: { reverse: {} },
+      maxAcceptablePrice: convertSolToLamports(3.0),
+    });
+    const treasuryRent = await rentOf(provider, treasuryAuthority);
+    // the whole budget is escrowed up front
+    expect(await provider.connection.getBalance(treasuryAuthority)).equal(treasuryRent + convertSolToLamports(3.0));
+    expect((await program.account.state.fetch(state)).budget.toNumber()).equal(convertSolToLamports(3.0));
+
+    const updates = [];
+    const listener = program.addEventListener("LowestBidUpdated", (event) => { updates.push(event); });
+    await placeBid(program, state, providers[0], 2.0);
+    const winnerBid = await placeBid(program, state, providers[1], 1.5);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    expect(updates.map((update) => update.amount.toNumber()))
+      .deep.equal([convertSolToLamports(2.0), convertSolToLamports(1.5)]);
+    // offers are not escrowed, the user bid and bid escrow only hold their rent
+    const winnerEscrow = await findBidEscrowPda(providers[1].publicKey, state, program.programId);
+    expect(await provider.connection.getBalance(winnerBid)).equal(await rentOf(provider, winnerBid));
+    expect(await provider.connection.getBalance(winnerEscrow)).equal(await rentOf(provider, winnerEscrow));
+
+    try {
+      await placeBid(program, state, providers[2], 1.8);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("OfferNotLowest");
+    }
+    try {
+      await placeBid(program, state, providers[2], 3.5);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("OfferAboveMaximumPrice");
+    }
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.lowestBidAmount.toNumber()).equal(convertSolToLamports(1.5));
+    expect(auction.lowestBidderAccount.toBase58()).equal(providers[1].publicKey.toBase58());
+
+    await delay(5000);
+
+    const winnerBefore = await provider.connection.getBalance(providers[1].publicKey);
+    const buyerBefore = await provider.connection.getBalance(buyer.publicKey);
+    await endAuction(program, state, buyer);
+    expect(await provider.connection.getBalance(providers[1].publicKey))
+      .equal(winnerBefore + convertSolToLamports(1.5));
+    // the buyer gets the rest of its budget back and pays the rent of the summary and the tax record
+    expect(await provider.connection.getBalance(buyer.publicKey))
+      .equal(buyerBefore + convertSolToLamports(1.5) - await settlementRent(provider, state, program.programId));
+    expect(await provider.connection.getBalance(treasuryAuthority)).equal(treasuryRent);
+    expect((await program.account.state.fetch(state)).budget.toNumber()).equal(0);
+
+    // the losing provider only gets the rent of its user bid and escrow back
+    const loserBid = await findUserBidPda(providers[0].publicKey, state, program.programId);
+    const loserEscrow = await findBidEscrowPda(providers[0].publicKey, state, program.programId);
+    const loserBefore = await provider.connection.getBalance(providers[0].publicKey);
+    const rent = await provider.connection.getBalance(loserBid) + await provider.connection.getBalance(loserEscrow);
+    await refund(program, state, providers[0]);
+    expect(await provider.connection.getBalance(providers[0].publicKey)).equal(loserBefore + rent);
+  });
+
+  it("Buyer gets its whole budget back when no offer came in", async () => {
+    const { state, treasury, treasuryAuthority, sellerBond } = await initializeAuction(program, buyer, {
+      durationInSecs: 2,
+      auctionType: { reverse: {} },
+      maxAcceptablePrice: convertSolToLamports(2.0),
+      auctionIndex: 1,
+    });
+    await delay(3000);
+
+    const treasuryBalance = await provider.connection.getBalance(treasury);
+    const treasuryAuthorityBalance = await provider.connection.getBalance(treasuryAuthority);
+    const stateBalance = await provider.connection.getBalance(state);
+    const buyerBefore = await provider.connection.getBalance(buyer.publicKey);
+    await program.methods
+      .cancelAuction()
+      .accounts({
+        state,
+        initializer: buyer.publicKey,
+        treasury,
+        treasuryAuthority,
+        sellerBond,
+        registryPage: await findAuctionRegistryPagePda(program, state),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+
+    // emptying the treasury authority returns the budget with the rent
+    expect(treasuryAuthorityBalance).greaterThan(convertSolToLamports(2.0));
+    expect(await provider.connection.getBalance(buyer.publicKey))
+      .equal(buyerBefore + treasuryBalance + treasuryAuthorityBalance + stateBalance);
+  });
+
+  it("Reverse auction can not have a reserve price", async () => {
+    const otherBuyer = anchor.web3.Keypair.generate();
+    await fundAccount(provider, otherBuyer.publicKey);
+
+    try {
+      await initializeAuction(program, otherBuyer, {
+        auctionType: { reverse: {} },
+        reservePrice: convertSolToLamports(1.0),
+        maxAcceptablePrice: convertSolToLamports(3.0),
+      });
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("ReverseAuctionWithReserve");
+    }
+  });
+});
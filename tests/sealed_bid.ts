@@ -0,0 +1,289 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { createHash } from "crypto";
+import { convertSolToLamports, delay, endAuction, findBidEscrowPda, findCommitBidPda, findConfigPda, findLeaderboardPda, findProfilePda, findSybilCheckPda, findUserBidPda, fundAccount, initializeAuction, placeBid, rentOf, settlementRent } from "./utils";
+
+describe("sealed bid", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const seller = anchor.web3.Keypair.generate();
+  const depositSeller = anchor.web3.Keypair.generate();
+  const englishSeller = anchor.web3.Keypair.generate();
+  const bidders = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+  const randomCommitment = (): number[] => Array.from(anchor.web3.Keypair.generate().publicKey.toBytes());
+
+  // sha256 of the amount in lamports (little endian) followed by the nonce
+  const commitmentOf = (amount: anchor.BN, nonce: Buffer): number[] =>
+    Array.from(createHash("sha256").update(Buffer.concat([amount.toArrayLike(Buffer, "le", 8), nonce])).digest());
+
+  const commit = async (state: anchor.web3.PublicKey, bidder: anchor.web3.Keypair, commitment: number[]) => {
+    await program.methods
+      .commitBid(commitment)
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        commitBid: await findCommitBidPda(bidder.publicKey, state, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  const reveal = async (state: anchor.web3.PublicKey, bidder: anchor.web3.Keypair, amount: anchor.BN, nonce: Buffer) => {
+    await program.methods
+      .revealBid(amount, Array.from(nonce))
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        commitBid: await findCommitBidPda(bidder.publicKey, state, program.programId),
+        userBid: await findUserBidPda(bidder.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(bidder.publicKey, state, program.programId),
+        leaderboard: await findLeaderboardPda(state, program.programId),
+        profile: await findProfilePda(bidder.publicKey, program.programId),
+        config: await findConfigPda(program.programId),
+        sybilCheck: await findSybilCheckPda(bidder.publicKey, state, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, seller.publicKey);
+    await fundAccount(provider, depositSeller.publicKey);
+    await fundAccount(provider, englishSeller.publicKey);
+    for (let bidder of bidders) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("Bids can only be committed before the reveal phase", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 30, commitDurationInSecs: 3 });
+    const commitBid = await findCommitBidPda(bidders[0].publicKey, state, program.programId);
+
+    let committed = null;
+    const listener = program.addEventListener("BidCommitted", (event) => { committed = event; });
+
+    const commitment = randomCommitment();
+    await program.methods
+      .commitBid(commitment)
+      .accounts({ state, user: bidders[0].publicKey, commitBid, systemProgram: anchor.web3.SystemProgram.programId })
+      .signers([bidders[0]])
+      .rpc();
+
+    expect((await program.account.commitBid.fetch(commitBid)).commitment).deep.equal(commitment);
+
+    const recommitment = randomCommitment();
+    await program.methods
+      .recommitBid(recommitment)
+      .accounts({ state, user: bidders[0].publicKey, commitBid })
+      .signers([bidders[0]])
+      .rpc();
+
+    const stored = await program.account.commitBid.fetch(commitBid);
+    expect(stored.commitment).deep.equal(recommitment);
+    expect(stored.revealed).equal(false);
+
+    await delay(1000);
+    await program.removeEventListener(listener);
+    expect(committed.commitment).deep.equal(recommitment);
+
+    // wait for the reveal phase
+    await delay(3000);
+
+    try {
+      await program.methods
+        .recommitBid(randomCommitment())
+        .accounts({ state, user: bidders[0].publicKey, commitBid })
+        .signers([bidders[0]])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("CommitPhaseClosed");
+    }
+
+    try {
+      await program.methods
+        .commitBid(randomCommitment())
+        .accounts({
+          state,
+          user: bidders[1].publicKey,
+          commitBid: await findCommitBidPda(bidders[1].publicKey, state, program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([bidders[1]])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("CommitPhaseClosed");
+    }
+  });
+
+  it("Revealed bids are placed once the commit phase is over", async () => {
+    const { state } = await initializeAuction(program, seller, { durationInSecs: 8, commitDurationInSecs: 3, auctionType: { vickrey: {} } });
+
+    const amounts = [new anchor.BN(convertSolToLamports(2.0)), new anchor.BN(convertSolToLamports(1.0))];
+    const nonces = [anchor.web3.Keypair.generate().secretKey.slice(0, 32), anchor.web3.Keypair.generate().secretKey.slice(0, 32)].map(nonce => Buffer.from(nonce));
+
+    for (let [i, bidder] of bidders.entries()) {
+      await commit(state, bidder, commitmentOf(amounts[i], nonces[i]));
+    }
+
+    // open bids and early reveals are rejected
+    try {
+      await placeBid(program, state, bidders[0], 3.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("SealedBidsOnly");
+    }
+    try {
+      await reveal(state, bidders[0], amounts[0], nonces[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("RevealPhaseNotOpen");
+    }
+
+    // wait for the reveal phase
+    await delay(4000);
+
+    try {
+      await reveal(state, bidders[0], amounts[1], nonces[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidBidCommitment");
+    }
+
+    for (let [i, bidder] of bidders.entries()) {
+      await reveal(state, bidder, amounts[i], nonces[i]);
+      expect((await program.account.commitBid.fetch(await findCommitBidPda(bidder.publicKey, state, program.programId))).revealed).equal(true);
+    }
+
+    // a bid can only be revealed once
+    try {
+      await reveal(state, bidders[0], amounts[0], nonces[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.message).not.equal("Should have failed!");
+    }
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(bidders[0].publicKey.toBase58());
+    expect(Number(auction.secondHighestBidAmount)).equal(convertSolToLamports(1.0));
+
+    // wait for auction to finish
+    await delay(3000);
+
+    const sellerBalance = await provider.connection.getBalance(seller.publicKey);
+    await endAuction(program, state, seller);
+    // the seller paid the rent of the summary and the tax record
+    const recordsRent = await settlementRent(provider, state, program.programId);
+    expect(await provider.connection.getBalance(seller.publicKey)).equal(sellerBalance + convertSolToLamports(1.0) - recordsRent);
+  });
+
+  it("Sealed bids that are never revealed forfeit their deposit", async () => {
+    const commitDeposit = convertSolToLamports(0.1);
+    const { state } = await initializeAuction(program, depositSeller, { durationInSecs: 8, commitDurationInSecs: 3, commitDeposit });
+
+    const amount = new anchor.BN(convertSolToLamports(1.0));
+    const nonce = Buffer.from(anchor.web3.Keypair.generate().secretKey.slice(0, 32));
+    for (let bidder of bidders) {
+      await commit(state, bidder, commitmentOf(amount, nonce));
+    }
+    const commitBids = await Promise.all(bidders.map(bidder => findCommitBidPda(bidder.publicKey, state, program.programId)));
+    const rent = await rentOf(provider, commitBids[0]);
+    expect(await provider.connection.getBalance(commitBids[0])).equal(rent + commitDeposit);
+
+    const forfeit = async (bidder: anchor.web3.Keypair, commitBid: anchor.web3.PublicKey) => program.methods
+      .forfeitDeposit()
+      .accounts({ state, initializer: depositSeller.publicKey, user: bidder.publicKey, commitBid, beneficiary: depositSeller.publicKey })
+      .signers([depositSeller])
+      .rpc();
+
+    // wait for the reveal phase, only the first bidder reveals and gets its deposit back
+    await delay(4000);
+    await reveal(state, bidders[0], amount, nonce);
+    expect(await provider.connection.getBalance(commitBids[0])).equal(rent);
+    expect(Number((await program.account.commitBid.fetch(commitBids[0])).deposit)).equal(0);
+
+    try {
+      await forfeit(bidders[1], commitBids[1]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("StillActive");
+    }
+
+    // wait for auction to finish, the reveal phase is over
+    await delay(3000);
+
+    try {
+      await reveal(state, bidders[1], amount, nonce);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("RevealPhaseNotOpen");
+    }
+    try {
+      await forfeit(bidders[0], commitBids[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("BidAlreadyRevealed");
+    }
+
+    const sellerBalance = await provider.connection.getBalance(depositSeller.publicKey);
+    const bidderBalance = await provider.connection.getBalance(bidders[1].publicKey);
+    await forfeit(bidders[1], commitBids[1]);
+    expect(await provider.connection.getBalance(depositSeller.publicKey)).equal(sellerBalance + commitDeposit);
+    expect(await provider.connection.getBalance(bidders[1].publicKey)).equal(bidderBalance + rent);
+    expect(await provider.connection.getAccountInfo(commitBids[1])).equal(null);
+  });
+
+  it("A lower bid revealed second in an English sealed auction keeps its deposit", async () => {
+    const commitDeposit = convertSolToLamports(0.1);
+    const { state } = await initializeAuction(program, englishSeller, { durationInSecs: 8, commitDurationInSecs: 3, commitDeposit });
+
+    const amounts = [new anchor.BN(convertSolToLamports(2.0)), new anchor.BN(convertSolToLamports(1.0))];
+    const nonces = [anchor.web3.Keypair.generate().secretKey.slice(0, 32), anchor.web3.Keypair.generate().secretKey.slice(0, 32)].map(nonce => Buffer.from(nonce));
+    for (let [i, bidder] of bidders.entries()) {
+      await commit(state, bidder, commitmentOf(amounts[i], nonces[i]));
+    }
+    const commitBids = await Promise.all(bidders.map(bidder => findCommitBidPda(bidder.publicKey, state, program.programId)));
+    const rent = await rentOf(provider, commitBids[0]) - commitDeposit;
+
+    // wait for the reveal phase, the higher bid is revealed first
+    await delay(4000);
+    await reveal(state, bidders[0], amounts[0], nonces[0]);
+
+    // the lower bid lost, it is not placed but its deposit goes back
+    const bidderBalance = await provider.connection.getBalance(bidders[1].publicKey);
+    await reveal(state, bidders[1], amounts[1], nonces[1]);
+    expect(await provider.connection.getBalance(bidders[1].publicKey)).equal(bidderBalance + commitDeposit);
+    expect(await provider.connection.getBalance(commitBids[1])).equal(rent);
+    expect((await program.account.commitBid.fetch(commitBids[1])).revealed).equal(true);
+    expect(await provider.connection.getAccountInfo(await findUserBidPda(bidders[1].publicKey, state, program.programId))).equal(null);
+    expect(await provider.connection.getAccountInfo(await findBidEscrowPda(bidders[1].publicKey, state, program.programId))).equal(null);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(bidders[0].publicKey.toBase58());
+    expect(auction.bidCount).equal(1);
+
+    // wait for auction to finish, nothing is left to forfeit
+    await delay(3000);
+    try {
+      await program.methods
+        .forfeitDeposit()
+        .accounts({ state, initializer: englishSeller.publicKey, user: bidders[1].publicKey, commitBid: commitBids[1], beneficiary: englishSeller.publicKey })
+        .signers([englishSeller])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("BidAlreadyRevealed");
+    }
+  });
+});
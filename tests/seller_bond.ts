@@ -0,0 +1,210 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findUserBidPda, fundAccount, initializeAuction, placeBid, rentOf } from "./utils";
+const { SystemProgram } = anchor.web3;
+
+describe("seller bond", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+  const sellerBond = convertSolToLamports(1);
+  const disputeWindowInSecs = 3;
+
+  type SettledAuction = { initializer: Keypair, winner: Keypair, state: PublicKey, treasury: PublicKey, treasuryAuthority: PublicKey, sellerBondPda: PublicKey };
+
+  // Runs an auction with a single bid up to settlement
+  const settleAuction = async (): Promise<SettledAuction> => {
+    const initializer = anchor.web3.Keypair.generate();
+    const winner = anchor.web3.Keypair.generate();
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, winner.publicKey);
+
+    const { state, treasury, treasuryAuthority, sellerBond: sellerBondPda } = await initializeAuction(program, initializer, {
+      durationInSecs: 2,
+      sellerBond,
+      disputeWindowInSecs,
+    });
+    await placeBid(program, state, winner, 1.0);
+
+    await delay(3000);
+    await endAuction(program, state, initializer);
+
+    return { initializer, winner, state, treasury, treasuryAuthority, sellerBondPda };
+  }
+
+  it("Seller bond is escrowed apart from the treasury", async () => {
+    const { state, treasury, treasuryAuthority, sellerBondPda } = await settleAuction();
+    const auction = await program.account.state.fetch(state);
+    const ledger = await program.account.treasury.fetch(treasury);
+
+    expect(Number(auction.sellerBond)).equal(sellerBond);
+    // the seller bond PDA holds the bond, the treasury never sees it
+    expect(await provider.connection.getBalance(sellerBondPda)).equal(await rentOf(provider, sellerBondPda) + sellerBond);
+    expect(await provider.connection.getBalance(treasuryAuthority)).equal(await rentOf(provider, treasuryAuthority));
+    expect(Number(ledger.totalCollected)).equal(0);
+    expect(Number(ledger.totalPaidOut)).equal(0);
+  });
+
+  it("Winner confirms delivery and seller reclaims the bond", async () => {
+    const { initializer, winner, state, treasury, treasuryAuthority, sellerBondPda } = await settleAuction();
+
+    await program.methods
+      .confirmDelivery()
+      .accounts({ state, winner: winner.publicKey })
+      .signers([winner])
+      .rpc();
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+
+    await program.methods
+      .reclaimBond()
+      .accounts({ state, initializer: initializer.publicKey, treasury, treasuryAuthority, sellerBond: sellerBondPda, systemProgram: SystemProgram.programId })
+      .signers([initializer])
+      .rpc();
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.disputeStatus).deep.equal({ reclaimed: {} });
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance + sellerBond);
+
+    // the bond left the seller bond PDA without going through the treasury
+    const ledger = await program.account.treasury.fetch(treasury);
+    expect(Number(ledger.totalPaidOut)).equal(0);
+    expect(await provider.connection.getBalance(sellerBondPda)).equal(await rentOf(provider, sellerBondPda));
+  });
+
+  it("Unresolved dispute slashes the bond to the winner", async () => {
+    const { initializer, winner, state, treasury, treasuryAuthority, sellerBondPda } = await settleAuction();
+
+    await program.methods
+      .openDispute()
+      .accounts({ state, winner: winner.publicKey })
+      .signers([winner])
+      .rpc();
+
+    try {
+      await program.methods
+        .reclaimBond()
+        .accounts({ state, initializer: initializer.publicKey, treasury, treasuryAuthority, sellerBond: sellerBondPda, systemProgram: SystemProgram.programId })
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidDisputeStatus");
+    }
+
+    try {
+      await program.methods
+        .claimBond()
+        .accounts({ state, winner: winner.publicKey, sellerBond: sellerBondPda, systemProgram: SystemProgram.programId })
+        .signers([winner])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("DisputeWindowOpen");
+    }
+
+    await delay((disputeWindowInSecs + 2) * 1000);
+
+    const winnerBalance = await provider.connection.getBalance(winner.publicKey);
+
+    await program.methods
+      .claimBond()
+      .accounts({ state, winner: winner.publicKey, sellerBond: sellerBondPda, systemProgram: SystemProgram.programId })
+      .signers([winner])
+      .rpc();
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.disputeStatus).deep.equal({ slashed: {} });
+    expect(await provider.connection.getBalance(winner.publicKey)).equal(winnerBalance + sellerBond);
+
+    expect(await provider.connection.getBalance(sellerBondPda)).equal(await rentOf(provider, sellerBondPda));
+  });
+
+  it("Dispute opened after the window - should fail", async () => {
+    const { initializer, winner, state, treasury, treasuryAuthority, sellerBondPda } = await settleAuction();
+
+    await delay((disputeWindowInSecs + 2) * 1000);
+
+    try {
+      await program.methods
+        .openDispute()
+        .accounts({ state, winner: winner.publicKey })
+        .signers([winner])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("DisputeWindowClosed");
+    }
+
+    // the seller gets the bond back once the window passed without dispute
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+
+    await program.methods
+      .reclaimBond()
+      .accounts({ state, initializer: initializer.publicKey, treasury, treasuryAuthority, sellerBond: sellerBondPda, systemProgram: SystemProgram.programId })
+      .signers([initializer])
+      .rpc();
+
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance + sellerBond);
+  });
+
+  it("Bidders split the bond of a seller who does not settle", async () => {
+    const initializer = anchor.web3.Keypair.generate();
+    const bidders = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+    for (const account of [initializer, ...bidders]) {
+      await fundAccount(provider, account.publicKey);
+    }
+
+    const sellerClaimWindowInSecs = 2;
+    const { state, sellerBond: sellerBondPda } = await initializeAuction(program, initializer, {
+      durationInSecs: 2,
+      sellerBond,
+      sellerClaimWindowInSecs,
+    });
+    await placeBid(program, state, bidders[0], 1.0);
+    await placeBid(program, state, bidders[1], 1.5);
+
+    const claimSellerBond = async (bidder: Keypair) => program.methods
+      .claimSellerBond()
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        userBid: await findUserBidPda(bidder.publicKey, state, program.programId),
+        sellerBond: sellerBondPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([bidder])
+      .rpc();
+
+    try {
+      await claimSellerBond(bidders[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("SellerClaimWindowOpen");
+    }
+
+    await delay((2 + sellerClaimWindowInSecs + 1) * 1000);
+
+    for (const bidder of bidders) {
+      const balance = await provider.connection.getBalance(bidder.publicKey);
+      await claimSellerBond(bidder);
+      expect(await provider.connection.getBalance(bidder.publicKey)).equal(balance + sellerBond / 2);
+    }
+
+    try {
+      await claimSellerBond(bidders[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("SellerBondAlreadyClaimed");
+    }
+
+    // the bids are untouched, the seller can still settle and owes no bond back
+    expect(Number((await program.account.state.fetch(state)).bondClaimed)).equal(sellerBond);
+    expect(await provider.connection.getBalance(sellerBondPda)).equal(await rentOf(provider, sellerBondPda));
+    await endAuction(program, state, initializer);
+  });
+});
@@ -0,0 +1,49 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findSellerProfilePda, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("seller profile", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+    anchor.web3.Keypair.generate(),
+  ];
+
+  before(async () => {
+    for (const account of [initializer, ...bidders]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Records the auctions of a seller from creation to settlement", async () => {
+    const sellerProfile = await findSellerProfilePda(initializer.publicKey, program.programId);
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 5 });
+
+    let profile = await program.account.sellerProfile.fetch(sellerProfile);
+    expect(profile.totalAuctionsCreated).equal(1);
+    expect(profile.totalAuctionsSettled).equal(0);
+    expect(profile.totalRevenue.toNumber()).equal(0);
+    expect(profile.totalBiddersServed.toNumber()).equal(0);
+
+    await placeBid(program, state, bidders[0], 1.0);
+    await placeBid(program, state, bidders[1], 1.5);
+    await placeBid(program, state, bidders[2], 2.0);
+
+    await delay(6000);
+    await endAuction(program, state, initializer);
+
+    profile = await program.account.sellerProfile.fetch(sellerProfile);
+    expect(profile.totalAuctionsCreated).equal(1);
+    expect(profile.totalAuctionsSettled).equal(1);
+    expect(profile.totalRevenue.toNumber()).equal(convertSolToLamports(2.0));
+    expect(profile.totalBiddersServed.toNumber()).equal(3);
+  });
+});
@@ -0,0 +1,99 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("settlement authority", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const sellers = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+  const bidder = anchor.web3.Keypair.generate();
+  const hotKey = anchor.web3.Keypair.generate();
+  const randomKey = anchor.web3.Keypair.generate();
+
+  const setSettlementAuthority = async (state: anchor.web3.PublicKey, seller: anchor.web3.Keypair, authority: anchor.web3.PublicKey | null) => {
+    await program.methods
+      .setSettlementAuthority(authority)
+      .accounts({ state, initializer: seller.publicKey })
+      .signers([seller])
+      .rpc();
+  }
+
+  before(async () => {
+    for (let seller of sellers) {
+      await fundAccount(provider, seller.publicKey);
+    }
+    await fundAccount(provider, bidder.publicKey);
+    await fundAccount(provider, hotKey.publicKey);
+    await fundAccount(provider, randomKey.publicKey);
+  });
+
+  it("Delegated authority settles and the seller gets paid", async () => {
+    const seller = sellers[0];
+    const { state } = await initializeAuction(program, seller);
+    await setSettlementAuthority(state, seller, hotKey.publicKey);
+
+    await placeBid(program, state, bidder, 1.0);
+
+    // wait for auction to finish
+    await delay(5000);
+
+    try {
+      await endAuction(program, state, randomKey);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Unauthorized");
+    }
+
+    const sellerBalance = await provider.connection.getBalance(seller.publicKey);
+    await endAuction(program, state, hotKey);
+
+    expect(await provider.connection.getBalance(seller.publicKey)).equal(sellerBalance + convertSolToLamports(1.0));
+  });
+
+  it("Revoked authority can not settle", async () => {
+    const seller = sellers[1];
+    const { state } = await initializeAuction(program, seller);
+    await setSettlementAuthority(state, seller, hotKey.publicKey);
+
+    await placeBid(program, state, bidder, 1.0);
+    await setSettlementAuthority(state, seller, null);
+
+    // wait for auction to finish
+    await delay(5000);
+
+    try {
+      await endAuction(program, state, hotKey);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Unauthorized");
+    }
+
+    await endAuction(program, state, seller);
+  });
+
+  it("Delegated authority settles a reverse auction and the buyer gets the rest of its budget", async () => {
+    const buyer = sellers[2];
+    const { state } = await initializeAuction(program, buyer, {
+      auctionType: { reverse: {} },
+      maxAcceptablePrice: convertSolToLamports(2.0),
+    });
+    await setSettlementAuthority(state, buyer, hotKey.publicKey);
+
+    await placeBid(program, state, bidder, 1.5);
+
+    // wait for auction to finish
+    await delay(5000);
+
+    const buyerBalance = await provider.connection.getBalance(buyer.publicKey);
+    const bidderBalance = await provider.connection.getBalance(bidder.publicKey);
+    await endAuction(program, state, hotKey);
+
+    expect(await provider.connection.getBalance(buyer.publicKey)).equal(buyerBalance + convertSolToLamports(0.5));
+    expect(await provider.connection.getBalance(bidder.publicKey)).equal(bidderBalance + convertSolToLamports(1.5));
+  });
+});
@@ -0,0 +1,135 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import {
+  SHILL_LOOKBACK_SECS,
+  ensureConfig,
+  findBidEscrowPda,
+  findDelegateAuthPda,
+  findLeaderboardPda,
+  findProfilePda,
+  findSybilCheckPda,
+  findUserBidPda,
+  fundAccount,
+  initializeAuction,
+  placeBid,
+} from "./utils";
+
+describe("shill bid detection", () => {
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const shill = anchor.web3.Keypair.generate();
+  const formerShill = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+  const delegate = anchor.web3.Keypair.generate();
+
+  // The provider wallet is the platform authority of the config
+  const registerRelatedAccount = async (state: PublicKey, related: PublicKey, fundedAt: number) => program.methods
+    .registerRelatedAccount(related, new anchor.BN(fundedAt))
+    .accounts({
+      state,
+      sybilCheck: await findSybilCheckPda(related, state, program.programId),
+      config: await ensureConfig(program),
+      platformAuthority: provider.wallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+  before(async () => {
+    for (const account of [initializer, shill, formerShill, bidder, delegate]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Rejects the bids of a wallet the seller funded within the lookback", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 60 });
+    const now = Math.floor(Date.now() / 1000);
+
+    await registerRelatedAccount(state, shill.publicKey, now);
+    // funded long enough ago to bid again
+    await registerRelatedAccount(state, formerShill.publicKey, now - SHILL_LOOKBACK_SECS - 60);
+
+    const sybilCheck = await program.account.sybilCheck.fetch(await findSybilCheckPda(shill.publicKey, state, program.programId));
+    expect(sybilCheck.related.toBase58()).equal(shill.publicKey.toBase58());
+    expect(sybilCheck.fundedAt.toNumber()).equal(now);
+
+    try {
+      await placeBid(program, state, shill, 1.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("SuspectedShillBid");
+    }
+
+    await placeBid(program, state, formerShill, 1.0);
+    await placeBid(program, state, bidder, 2.0);
+    const auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(bidder.publicKey.toBase58());
+  });
+
+  it("Rejects the bids a delegate places for a wallet the seller funded", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 60, auctionIndex: 2 });
+    await registerRelatedAccount(state, shill.publicKey, Math.floor(Date.now() / 1000));
+
+    const delegateAuth = await findDelegateAuthPda(shill.publicKey, state, program.programId);
+    await program.methods
+      .grantDelegate(delegate.publicKey, 2.0, new anchor.BN(Math.floor(Date.now() / 1000) + 60))
+      .accounts({
+        state,
+        principal: shill.publicKey,
+        delegateAuth,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([shill])
+      .rpc();
+
+    try {
+      await program.methods
+        .delegatedBid(1.0)
+        .accounts({
+          state,
+          delegate: delegate.publicKey,
+          principal: shill.publicKey,
+          delegateAuth,
+          userBid: await findUserBidPda(shill.publicKey, state, program.programId),
+          bidEscrow: await findBidEscrowPda(shill.publicKey, state, program.programId),
+          leaderboard: await findLeaderboardPda(state, program.programId),
+          profile: await findProfilePda(shill.publicKey, program.programId),
+          config: await ensureConfig(program),
+          sybilCheck: await findSybilCheckPda(shill.publicKey, state, program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([delegate])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("SuspectedShillBid");
+    }
+  });
+
+  it("Only the platform authority registers related wallets", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 60, auctionIndex: 1 });
+
+    try {
+      await program.methods
+        .registerRelatedAccount(shill.publicKey, new anchor.BN(0))
+        .accounts({
+          state,
+          sybilCheck: await findSybilCheckPda(shill.publicKey, state, program.programId),
+          config: await ensureConfig(program),
+          platformAuthority: initializer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([initializer])
+        .rpc();
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("Unauthorized");
+    }
+  });
+});
@@ -0,0 +1,58 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findBidEscrowPda, findProfilePda, fundAccount, initializeAuction, placeBid, refund, rentOf } from "./utils";
+
+describe("sponsored bids", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+  const initialFunds = 10000000000;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const sponsor = anchor.web3.Keypair.generate();
+  const loser = anchor.web3.Keypair.generate();
+  const winner = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    for (let account of [initializer, sponsor, loser, winner]) {
+      await fundAccount(provider, account.publicKey, initialFunds);
+    }
+  });
+
+  it("Rent of a sponsored bid goes back to the sponsor on refund", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 2 });
+
+    const userBid = await placeBid(program, state, loser, 1.0, sponsor);
+    await placeBid(program, state, winner, 1.5);
+
+    const bidEscrow = await findBidEscrowPda(loser.publicKey, state, program.programId);
+    const rent = await rentOf(provider, userBid) + await rentOf(provider, bidEscrow);
+    const auctionBid = await program.account.userBid.fetch(userBid);
+    expect(auctionBid.rentPayer.toString()).equal(sponsor.publicKey.toString());
+    // the bidder only paid the bid, the sponsor paid the rent of the user bid and bid
+    // escrow, and the rent of the profile opened by the first bid of the bidder
+    const profileRent = await rentOf(provider, await findProfilePda(loser.publicKey, program.programId));
+    expect(await provider.connection.getBalance(loser.publicKey)).equal(initialFunds - convertSolToLamports(1.0));
+    expect(await provider.connection.getBalance(sponsor.publicKey)).equal(initialFunds - rent - profileRent);
+
+    await delay(3000);
+    await endAuction(program, state, initializer);
+
+    try {
+      await refund(program, state, loser);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidRentPayer");
+    }
+
+    await refund(program, state, loser, sponsor.publicKey);
+
+    expect(await provider.connection.getBalance(loser.publicKey)).equal(initialFunds);
+    expect(await provider.connection.getBalance(sponsor.publicKey)).equal(initialFunds - profileRent);
+    expect(await provider.connection.getAccountInfo(userBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(bidEscrow)).equal(null);
+  });
+});
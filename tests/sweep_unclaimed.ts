@@ -0,0 +1,98 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, findBidEscrowPda, fundAccount, initializeAuction, placeBid, refund } from "./utils";
+
+describe("sweep unclaimed refunds", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+  const refundWindowInSecs = 3;
+
+  // Runs an auction where the loser never claims the refund and returns it settled
+  const settleWithUnclaimedRefund = async (unclaimedBeneficiary?: PublicKey) => {
+    const initializer = anchor.web3.Keypair.generate();
+    const loser = anchor.web3.Keypair.generate();
+    const winner = anchor.web3.Keypair.generate();
+    for (let account of [initializer, loser, winner]) {
+      await fundAccount(provider, account.publicKey);
+    }
+
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 2,
+      refundWindowInSecs,
+      unclaimedBeneficiary,
+    });
+    const loserBid = await placeBid(program, state, loser, 1.0);
+    await placeBid(program, state, winner, 1.5);
+
+    await delay(3000);
+    await endAuction(program, state, initializer);
+
+    return { initializer, loser, loserBid, winner, state };
+  }
+
+  const sweep = async (state: PublicKey, bidder: PublicKey, userBid: PublicKey, destination: PublicKey) =>
+    program.methods
+      .sweepUnclaimed()
+      .accounts({ state, user: bidder, userBid, bidEscrow: await findBidEscrowPda(bidder, state, program.programId), destination })
+      .rpc();
+
+  // lamports of the user bid and bid escrow of `bidder`, a sweep takes both
+  const escrowedBalance = async (state: PublicKey, bidder: PublicKey, userBid: PublicKey) =>
+    await provider.connection.getBalance(userBid)
+    + await provider.connection.getBalance(await findBidEscrowPda(bidder, state, program.programId));
+
+  it("Unclaimed refunds go to the initializer when no beneficiary is set", async () => {
+    const { initializer, loser, loserBid, winner, state } = await settleWithUnclaimedRefund();
+
+    try {
+      await sweep(state, loser.publicKey, loserBid, initializer.publicKey);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("RefundWindowOpen");
+    }
+
+    await delay((refundWindowInSecs + 2) * 1000);
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    const escrowBalance = await escrowedBalance(state, loser.publicKey, loserBid);
+    await sweep(state, loser.publicKey, loserBid, initializer.publicKey);
+
+    // the unclaimed bid is swept together with the rent of its user bid and escrow
+    expect(escrowBalance).greaterThan(convertSolToLamports(1.0));
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance + escrowBalance);
+
+    // the first sweep closes the refunds of the bids that are still escrowed
+    try {
+      await refund(program, state, winner);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("RefundWindowClosed");
+    }
+  });
+
+  it("Unclaimed refunds are donated to the beneficiary", async () => {
+    const beneficiary = anchor.web3.Keypair.generate();
+    const { initializer, loser, loserBid, state } = await settleWithUnclaimedRefund(beneficiary.publicKey);
+
+    await delay((refundWindowInSecs + 2) * 1000);
+
+    try {
+      await sweep(state, loser.publicKey, loserBid, initializer.publicKey);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("InvalidBeneficiary");
+    }
+
+    const escrowBalance = await escrowedBalance(state, loser.publicKey, loserBid);
+    await sweep(state, loser.publicKey, loserBid, beneficiary.publicKey);
+
+    expect(await provider.connection.getBalance(beneficiary.publicKey)).equal(escrowBalance);
+    expect(await provider.connection.getAccountInfo(loserBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(await findBidEscrowPda(loser.publicKey, state, program.programId))).equal(null);
+  });
+});
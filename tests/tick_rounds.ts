@@ -0,0 +1,59 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("auction with tick rounds", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+  before(async () => {
+    for (const account of [initializer, ...bidders]) {
+      await fundAccount(provider, account.publicKey);
+    }
+  });
+
+  it("Ascends one tick per round and ends after a round without acceptance", async () => {
+    // 1 SOL in the first round, 0.25 SOL more every 4 seconds
+    const { state } = await initializeAuction(program, initializer, {
+      durationInSecs: 60,
+      startPrice: convertSolToLamports(1.0),
+      roundDurationInSecs: 4,
+      tick: convertSolToLamports(0.25),
+    });
+
+    await placeBid(program, state, bidders[0], 1.0);
+    try {
+      await placeBid(program, state, bidders[1], 1.0);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("RoundAlreadyAccepted");
+    }
+
+    await delay(5000);
+    try {
+      await placeBid(program, state, bidders[1], 1.5);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("BidNotAtRoundPrice");
+    }
+    await placeBid(program, state, bidders[1], 1.25);
+
+    // the auction ends with the third round, nobody accepted it
+    let auction = await program.account.state.fetch(state);
+    expect(auction.deadline.toNumber()).equal(auction.startTime.toNumber() + 12);
+    expect(auction.roundsAccepted).equal(2);
+    await delay(8000);
+
+    await endAuction(program, state, initializer);
+    auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(bidders[1].publicKey.toBase58());
+    expect(auction.winningAmount.toNumber()).equal(convertSolToLamports(1.25));
+  });
+});
@@ -0,0 +1,88 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { delay, fundAccount, initializeAuction, placeBid, rentOf } from "./utils";
+
+describe("update metadata", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+
+  // the seller also pays the transaction fee
+  const fee = 5000;
+
+  const updateMetadata = async (state: anchor.web3.PublicKey, title: string, uri: string) => {
+    await program.methods
+      .updateMetadata(title, uri)
+      .accounts({ state, initializer: initializer.publicKey, systemProgram: anchor.web3.SystemProgram.programId })
+      .signers([initializer])
+      .rpc();
+  }
+
+  const accountSize = async (account: anchor.web3.PublicKey) =>
+    (await provider.connection.getAccountInfo(account)).data.length;
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  it("Seller grows and shrinks the metadata before the first bid", async () => {
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 3600 });
+    const initialSize = await accountSize(state);
+
+    let updated = null;
+    const listener = program.addEventListener("MetadataUpdated", (event) => { updated = event; });
+
+    const title = "Vintage camera";
+    const uri = "https://example.com/items/vintage-camera.json";
+    let sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    let stateRent = await rentOf(provider, state);
+
+    await updateMetadata(state, title, uri);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    expect(await accountSize(state)).equal(initialSize + title.length + uri.length);
+    const grownRent = await rentOf(provider, state);
+    expect(grownRent).greaterThan(stateRent);
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance - (grownRent - stateRent) - fee);
+    expect(updated.auction.toString()).equal(state.toString());
+    expect(updated.title).equal(title);
+    expect(updated.uri).equal(uri);
+
+    const shortUri = "ipfs://camera";
+    sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+
+    await updateMetadata(state, title, shortUri);
+
+    expect(await accountSize(state)).equal(initialSize + title.length + shortUri.length);
+    const shrunkRent = await rentOf(provider, state);
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance + (grownRent - shrunkRent) - fee);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.title).equal(title);
+    expect(auction.uri).equal(shortUri);
+
+    try {
+      await updateMetadata(state, "t".repeat(65), shortUri);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("MetadataTooLong");
+    }
+
+    await placeBid(program, state, bidder, 1.0);
+
+    try {
+      await updateMetadata(state, "Camera", shortUri);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("CannotChangeAfterBids");
+    }
+  });
+});
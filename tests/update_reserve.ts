@@ -0,0 +1,64 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, fundAccount, initializeAuction, placeBid } from "./utils";
+
+describe("update reserve", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidder = anchor.web3.Keypair.generate();
+
+  const updateReserve = async (state: anchor.web3.PublicKey, newReserve: number) => {
+    await program.methods
+      .updateReserve(new anchor.BN(newReserve))
+      .accounts({ state, initializer: initializer.publicKey })
+      .signers([initializer])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, bidder.publicKey);
+  });
+
+  it("Seller only lowers the reserve and settlement uses the new one", async () => {
+    const reservePrice = convertSolToLamports(2.0);
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 5, reservePrice });
+
+    for (const newReserve of [reservePrice, convertSolToLamports(3.0)]) {
+      try {
+        await updateReserve(state, newReserve);
+        throw new Error("Should have failed!");
+      } catch (error) {
+        expect(error.error.errorCode.code).equal("ReserveCanOnlyDecrease");
+      }
+    }
+
+    let lowered = null;
+    const listener = program.addEventListener("ReserveLowered", (event) => { lowered = event; });
+
+    const newReserve = convertSolToLamports(0.5);
+    await updateReserve(state, newReserve);
+    await delay(1000);
+    await program.removeEventListener(listener);
+
+    expect(lowered.auction.toString()).equal(state.toString());
+    expect(lowered.oldReserve.toNumber()).equal(reservePrice);
+    expect(lowered.newReserve.toNumber()).equal(newReserve);
+    expect((await program.account.state.fetch(state)).reservePrice.toNumber()).equal(newReserve);
+
+    // the bid is below the original reserve but above the lowered one
+    await placeBid(program, state, bidder, 1.0);
+    await delay(5000);
+    await endAuction(program, state, initializer);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.sellerPayed).equal(true);
+    expect(auction.reserveNotMet).equal(false);
+  });
+});
@@ -0,0 +1,448 @@
+import * as anchor from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+
+export const fundAccount = async (provider: anchor.Provider, accountPubkey: anchor.web3.PublicKey, amount: number = 10000000000): Promise<void> => {
+  const tx = await provider.connection.requestAirdrop(accountPubkey, amount);
+  const { blockhash, lastValidBlockHeight } = await provider.connection.getLatestBlockhash();
+  await provider.connection.confirmTransaction({
+    blockhash,
+    lastValidBlockHeight,
+    signature: tx
+  });
+}
+
+// State PDA of the auction `index` of `initializer`, index 0 is the one created by `initialize`
+export const findStatePda = async (initializer: PublicKey, programId: PublicKey, index: number = 0): Promise<PublicKey> => {
+  const seeds = [Buffer.from("state"), initializer.toBytes()];
+  if (index > 0) {
+    seeds.push(new anchor.BN(index).toArrayLike(Buffer, "le", 4));
+  }
+  const [pda, _nonce] = await PublicKey.findProgramAddress(seeds, programId);
+  return pda;
+}
+
+// Counter of the auction indexes of `initializer`, it holds the next free index
+export const findSellerCounterPda = async (initializer: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("seller-counter"), initializer.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const REGISTRY_PAGE_SIZE = 32;
+
+export const findRegistryPda = async (programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress([Buffer.from("registry")], programId);
+  return pda;
+}
+
+export const findRegistryPagePda = async (page: number, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("registry-page"), new anchor.BN(page).toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+  return pda;
+}
+
+// Registry accounts `initialize` lists the next auction with
+export const registryAccounts = async (program: anchor.Program<any>): Promise<{ registry: PublicKey, registryPage: PublicKey }> => {
+  const registry = await findRegistryPda(program.programId);
+  const count = (await program.account.registry.fetchNullable(registry))?.auctionCount.toNumber() ?? 0;
+  const registryPage = await findRegistryPagePda(Math.floor(count / REGISTRY_PAGE_SIZE), program.programId);
+  return { registry, registryPage };
+}
+
+// Registry page listing the auction `state`
+export const findAuctionRegistryPagePda = async (program: anchor.Program<any>, state: PublicKey): Promise<PublicKey> => {
+  const sequence = (await program.account.state.fetch(state)).sequence.toNumber();
+  return findRegistryPagePda(Math.floor(Math.max(sequence - 1, 0) / REGISTRY_PAGE_SIZE), program.programId);
+}
+
+export const findConfigPda = async (programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress([Buffer.from("config")], programId);
+  return pda;
+}
+
+export const findLoyaltyPda = async (bidder: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress([Buffer.from("loyalty"), bidder.toBytes()], programId);
+  return pda;
+}
+
+// Accounts charging the platform fee of a sale without a loyalty discount
+export const feeRecipientAccounts = async (program: anchor.Program<any>): Promise<{ config: PublicKey, feeRecipient: PublicKey }> => {
+  const config = await ensureConfig(program);
+  const feeRecipient = (await program.account.config.fetch(config)).feeRecipient as PublicKey;
+  return { config, feeRecipient };
+}
+
+// Accounts charging the platform fee of the sale won by `winner`
+export const feeAccounts = async (program: anchor.Program<any>, winner: PublicKey): Promise<{ config: PublicKey, feeRecipient: PublicKey, winnerLoyalty: PublicKey }> => {
+  return { ...await feeRecipientAccounts(program), winnerLoyalty: await findLoyaltyPda(winner, program.programId) };
+}
+
+export const findTreasuryPda = async (state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("treasury"), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+// System account holding the lamports of the treasury of `state`, it signs their transfers
+export const findTreasuryAuthorityPda = async (state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("treasury-auth"), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+// System account holding the seller bond of `state`, apart from its treasury
+export const findSellerBondPda = async (state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("bond"), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findLeaderboardPda = async (state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("leaderboard"), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findSybilCheckPda = async (related: PublicKey, state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("sybil"), state.toBytes(), related.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findSummaryPda = async (state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("summary"), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findTaxRecordPda = async (state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("tax"), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findProfilePda = async (bidder: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("profile"), bidder.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findSellerProfilePda = async (seller: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("seller-profile"), seller.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findUserBidPda = async (user: PublicKey, state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("user-bid"), user.toBytes(), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findBidEscrowPda = async (user: PublicKey, state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("escrow"), user.toBytes(), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findCommitBidPda = async (user: PublicKey, state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("commit"), user.toBytes(), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+export const findDelegateAuthPda = async (principal: PublicKey, state: PublicKey, programId: PublicKey): Promise<PublicKey> => {
+  const [pda, _nonce] = await PublicKey.findProgramAddress(
+    [Buffer.from("delegate"), principal.toBytes(), state.toBytes()],
+    programId
+  );
+  return pda;
+}
+
+// Rent exempt minimum of an existing account, based on its data length
+export const rentOf = async (provider: anchor.Provider, account: PublicKey): Promise<number> => {
+  const info = await provider.connection.getAccountInfo(account);
+  return provider.connection.getMinimumBalanceForRentExemption(info.data.length);
+}
+
+// Rent of the summary and the tax record the first settlement of an auction creates
+export const settlementRent = async (provider: anchor.Provider, state: PublicKey, programId: PublicKey): Promise<number> =>
+  await rentOf(provider, await findSummaryPda(state, programId)) + await rentOf(provider, await findTaxRecordPda(state, programId));
+
+export const convertSolToLamports = (solAmount: number): number => solAmount * 10 ** 9;
+
+export const delay = ms => new Promise(res => setTimeout(res, ms));
+
+const BPF_UPGRADEABLE_LOADER = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+export const MAX_AUCTION_DURATION_SECS = 30 * 24 * 60 * 60;
+export const BID_COOLDOWN_SECONDS = 5;
+export const SHILL_LOOKBACK_SECS = 30 * 24 * 60 * 60;
+export const LOYALTY_DISCOUNT_BPS = 1_000;
+
+// Creates the program-wide config with the default parameters unless it already exists.
+// The provider wallet deployed the program, so it is the upgrade authority
+export const ensureConfig = async (program: anchor.Program<any>): Promise<PublicKey> => {
+  const config = await findConfigPda(program.programId);
+  if (await program.account.config.fetchNullable(config) !== null) {
+    return config;
+  }
+
+  const [programData] = await PublicKey.findProgramAddress([program.programId.toBytes()], BPF_UPGRADEABLE_LOADER);
+  await program.methods
+    .initializeConfig(new anchor.BN(MAX_AUCTION_DURATION_SECS), new anchor.BN(1), 0, (program.provider as anchor.AnchorProvider).wallet.publicKey, new anchor.BN(0))
+    .accounts({
+      config,
+      authority: (program.provider as anchor.AnchorProvider).wallet.publicKey,
+      program: program.programId,
+      programData,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+  return config;
+}
+
+export type AuctionOptions = {
+  durationInSecs?: number,
+  reservePrice?: number,
+  sellerBond?: number,
+  disputeWindowInSecs?: number,
+  refundWindowInSecs?: number,
+  unclaimedBeneficiary?: PublicKey,
+  cancelCompensationBps?: number,
+  auctionType?: { english: {} } | { vickrey: {} } | { dutch: {} } | { allPay: {} } | { perpetual: {} } | { reverse: {} } | { raffle: {} } | { penny: {} },
+  startPrice?: number,
+  commitDurationInSecs?: number,
+  withdrawalPenaltyBps?: number,
+  title?: string,
+  itemHash?: number[],
+  uri?: string,
+  minBidIncrement?: number,
+  auctionIndex?: number,
+  bundleItems?: PublicKey[],
+  maxAcceptablePrice?: number,
+  priceStepInSecs?: number,
+  sellerClaimWindowInSecs?: number,
+  commitDeposit?: number,
+  milestoneCount?: number,
+  conditionProgram?: PublicKey,
+  conditionAccount?: PublicKey,
+  candleWindowInSecs?: number,
+  totalShares?: number,
+  quantity?: number,
+  roundDurationInSecs?: number,
+  tick?: number,
+  bidFee?: number,
+  extensionInSecs?: number,
+  payAsBid?: boolean,
+  finalistCount?: number,
+  openDurationInSecs?: number,
+  finalCommitDurationInSecs?: number,
+  bidMint?: PublicKey,
+  prizeMint?: PublicKey,
+  prizeAmount?: number,
+};
+
+// `AuctionConfig` argument of `initialize` and `initialize_many`
+export const auctionConfig = (options: AuctionOptions = {}) => ({
+  auctionDuration: new anchor.BN(options.durationInSecs ?? 3),
+  reservePrice: new anchor.BN(options.reservePrice ?? 0),
+  reservePriceUsdCents: new anchor.BN(0),
+  priceFeed: PublicKey.default,
+  sellerBond: new anchor.BN(options.sellerBond ?? 0),
+  disputeWindow: new anchor.BN(options.disputeWindowInSecs ?? 0),
+  refundWindow: new anchor.BN(options.refundWindowInSecs ?? 0),
+  unclaimedBeneficiary: options.unclaimedBeneficiary ?? PublicKey.default,
+  cancelCompensationBps: options.cancelCompensationBps ?? 0,
+  auctionType: options.auctionType ?? { english: {} },
+  startPrice: new anchor.BN(options.startPrice ?? 0),
+  commitDuration: new anchor.BN(options.commitDurationInSecs ?? 0),
+  withdrawalPenaltyBps: options.withdrawalPenaltyBps ?? 0,
+  title: options.title ?? "",
+  itemHash: options.itemHash ?? Array(32).fill(0),
+  uri: options.uri ?? "",
+  minBidIncrement: new anchor.BN(options.minBidIncrement ?? 0),
+  bundleItems: options.bundleItems ?? [],
+  maxAcceptablePrice: new anchor.BN(options.maxAcceptablePrice ?? 0),
+  priceStepSecs: new anchor.BN(options.priceStepInSecs ?? 0),
+  sellerClaimWindow: new anchor.BN(options.sellerClaimWindowInSecs ?? 0),
+  commitDeposit: new anchor.BN(options.commitDeposit ?? 0),
+  milestoneCount: options.milestoneCount ?? 0,
+  conditionProgram: options.conditionProgram ?? PublicKey.default,
+  conditionAccount: options.conditionAccount ?? PublicKey.default,
+  candleWindow: new anchor.BN(options.candleWindowInSecs ?? 0),
+  totalShares: new anchor.BN(options.totalShares ?? 0),
+  quantity: options.quantity ?? 0,
+  roundDurationSecs: new anchor.BN(options.roundDurationInSecs ?? 0),
+  tick: new anchor.BN(options.tick ?? 0),
+  bidFee: new anchor.BN(options.bidFee ?? 0),
+  extensionSecs: new anchor.BN(options.extensionInSecs ?? 0),
+  payAsBid: options.payAsBid ?? false,
+  finalistCount: options.finalistCount ?? 0,
+  openDuration: new anchor.BN(options.openDurationInSecs ?? 0),
+  finalCommitDuration: new anchor.BN(options.finalCommitDurationInSecs ?? 0),
+  bidMint: options.bidMint ?? PublicKey.default,
+  prizeMint: options.prizeMint ?? PublicKey.default,
+  prizeAmount: new anchor.BN(options.prizeAmount ?? 0),
+});
+
+// Creates the auction `auctionIndex` of `initializer`, its first one by default, and
+// returns its state, treasury, treasury authority and seller bond PDAs
+export const initializeAuction = async (
+  program: anchor.Program<any>,
+  initializer: anchor.web3.Keypair,
+  options: AuctionOptions = {},
+): Promise<{ state: PublicKey, treasury: PublicKey, treasuryAuthority: PublicKey, sellerBond: PublicKey }> => {
+  const auctionIndex = options.auctionIndex ?? 0;
+  const state = await findStatePda(initializer.publicKey, program.programId, auctionIndex);
+  const treasury = await findTreasuryPda(state, program.programId);
+  const treasuryAuthority = await findTreasuryAuthorityPda(state, program.programId);
+  const sellerBond = await findSellerBondPda(state, program.programId);
+  const config = await ensureConfig(program);
+
+  await program.methods
+    .initialize(auctionIndex, auctionConfig(options))
+    .accounts({
+      state,
+      initializer: initializer.publicKey,
+      sellerCounter: await findSellerCounterPda(initializer.publicKey, program.programId),
+      sellerProfile: await findSellerProfilePda(initializer.publicKey, program.programId),
+      treasury,
+      treasuryAuthority,
+      sellerBond,
+      leaderboard: await findLeaderboardPda(state, program.programId),
+      config,
+      ...await registryAccounts(program),
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([initializer])
+    .rpc();
+
+  return { state, treasury, treasuryAuthority, sellerBond };
+}
+
+// Places a bid of `amount` SOL from `bidder` and returns the user bid PDA.
+// The rent of the PDA and of the bid escrow is paid by `sponsor`, or by the bidder when
+// there is none
+export const placeBid = async (
+  program: anchor.Program<any>,
+  state: PublicKey,
+  bidder: anchor.web3.Keypair,
+  amount: number,
+  sponsor?: anchor.web3.Keypair,
+): Promise<PublicKey> => {
+  const userBid = await findUserBidPda(bidder.publicKey, state, program.programId);
+  const payer = sponsor ?? bidder;
+
+  await program.methods
+    .bid(amount)
+    .accounts({
+      state,
+      user: bidder.publicKey,
+      payer: payer.publicKey,
+      userBid,
+      bidEscrow: await findBidEscrowPda(bidder.publicKey, state, program.programId),
+      leaderboard: await findLeaderboardPda(state, program.programId),
+      sybilCheck: await findSybilCheckPda(bidder.publicKey, state, program.programId),
+      profile: await findProfilePda(bidder.publicKey, program.programId),
+      config: await findConfigPda(program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers(sponsor ? [bidder, sponsor] : [bidder])
+    .rpc();
+
+  return userBid;
+}
+
+// Settles the auction by paying the highest bid to the auction beneficiary, `authority`
+// is the seller or its settlement authority
+export const endAuction = async (
+  program: anchor.Program<any>,
+  state: PublicKey,
+  authority: anchor.web3.Keypair,
+): Promise<void> => {
+  const auction = await program.account.state.fetch(state);
+  // a reverse auction settles with its lowest offer
+  const winner = (auction.auctionType.reverse ? auction.lowestBidderAccount : auction.highestBidderAccount) as PublicKey;
+  const userBid = await findUserBidPda(winner, state, program.programId);
+  const conditional = !(auction.conditionProgram as PublicKey).equals(PublicKey.default);
+
+  await program.methods
+    .endAuction()
+    .accounts({
+      state,
+      authority: authority.publicKey,
+      treasury: await findTreasuryPda(state, program.programId),
+      treasuryAuthority: await findTreasuryAuthorityPda(state, program.programId),
+      summary: await findSummaryPda(state, program.programId),
+      taxRecord: await findTaxRecordPda(state, program.programId),
+      userBid,
+      bidEscrow: await findBidEscrowPda(winner, state, program.programId),
+      leaderboard: await findLeaderboardPda(state, program.programId),
+      beneficiary: auction.beneficiary,
+      highestBidder: winner,
+      winnerProfile: await findProfilePda(winner, program.programId),
+      sellerProfile: await findSellerProfilePda(auction.initializer, program.programId),
+      ...await feeAccounts(program, winner),
+      priceFeed: anchor.web3.SystemProgram.programId,
+      // placeholders for auctions without a condition
+      conditionProgram: conditional ? auction.conditionProgram : anchor.web3.SystemProgram.programId,
+      conditionAccount: conditional ? auction.conditionAccount : anchor.web3.SystemProgram.programId,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([authority])
+    .rpc();
+}
+
+// Claims the refund of `bidder`'s bid escrow, the rent of the escrow and user bid goes
+// back to `rentPayer`
+export const refund = async (
+  program: anchor.Program<any>,
+  state: PublicKey,
+  bidder: anchor.web3.Keypair,
+  rentPayer: PublicKey = bidder.publicKey,
+): Promise<void> => {
+  const userBid = await findUserBidPda(bidder.publicKey, state, program.programId);
+
+  await program.methods
+    .refund()
+    .accounts({
+      state,
+      user: bidder.publicKey,
+      userBid,
+      bidEscrow: await findBidEscrowPda(bidder.publicKey, state, program.programId),
+      profile: await findProfilePda(bidder.publicKey, program.programId),
+      rentPayer,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([bidder])
+    .rpc();
+}
@@ -0,0 +1,72 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, endAuction, fundAccount, initializeAuction, placeBid, refund } from "./utils";
+
+describe("vickrey", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const winner = anchor.web3.Keypair.generate();
+  const runnerUp = anchor.web3.Keypair.generate();
+  const soloSeller = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    await fundAccount(provider, winner.publicKey);
+    await fundAccount(provider, runnerUp.publicKey);
+    await fundAccount(provider, soloSeller.publicKey);
+  });
+
+  it("Winner pays the second highest bid", async () => {
+    const { state } = await initializeAuction(program, initializer, { auctionType: { vickrey: {} } });
+
+    // a lower bid is still accepted since it sets the price
+    await placeBid(program, state, winner, 5.0);
+    await placeBid(program, state, runnerUp, 3.0);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(winner.publicKey.toBase58());
+    expect(Number(auction.secondHighestBidAmount)).equal(convertSolToLamports(3.0));
+
+    // wait for auction to finish
+    await delay(5000);
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    const winnerBalance = await provider.connection.getBalance(winner.publicKey);
+
+    await endAuction(program, state, initializer);
+
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance + convertSolToLamports(3.0));
+    expect(await provider.connection.getBalance(winner.publicKey)).equal(winnerBalance + convertSolToLamports(2.0));
+
+    // the runner up gets its whole bid back
+    const runnerUpBalance = await provider.connection.getBalance(runnerUp.publicKey);
+    await refund(program, state, runnerUp);
+    expect(await provider.connection.getBalance(runnerUp.publicKey)).greaterThan(runnerUpBalance + convertSolToLamports(3.0));
+  });
+
+  it("Single bidder pays the reserve price", async () => {
+    const { state } = await initializeAuction(program, soloSeller, {
+      auctionType: { vickrey: {} },
+      reservePrice: convertSolToLamports(1.0),
+    });
+    await placeBid(program, state, winner, 4.0);
+
+    // wait for auction to finish
+    await delay(5000);
+
+    const sellerBalance = await provider.connection.getBalance(soloSeller.publicKey);
+    const winnerBalance = await provider.connection.getBalance(winner.publicKey);
+
+    await endAuction(program, state, soloSeller);
+
+    expect(await provider.connection.getBalance(soloSeller.publicKey)).equal(sellerBalance + convertSolToLamports(1.0));
+    expect(await provider.connection.getBalance(winner.publicKey)).equal(winnerBalance + convertSolToLamports(3.0));
+    expect(Number((await program.account.state.fetch(state)).winningAmount)).equal(convertSolToLamports(1.0));
+  });
+});
@@ -0,0 +1,83 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, delay, findBidEscrowPda, findTreasuryAuthorityPda, findUserBidPda, fundAccount, initializeAuction, placeBid, rentOf } from "./utils";
+
+describe("withdraw bid", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+  const withdraw = async (state: anchor.web3.PublicKey, treasury: anchor.web3.PublicKey, bidder: anchor.web3.Keypair) => {
+    await program.methods
+      .withdrawBid()
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        userBid: await findUserBidPda(bidder.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(bidder.publicKey, state, program.programId),
+        rentPayer: bidder.publicKey,
+        treasury,
+        treasuryAuthority: await findTreasuryAuthorityPda(state, program.programId),
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    for (let bidder of bidders) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("Outbid bidders withdraw minus the penalty, the highest bidder can not", async () => {
+    // 2.5% penalty
+    const { state, treasury, treasuryAuthority } = await initializeAuction(program, initializer, { durationInSecs: 5, withdrawalPenaltyBps: 250 });
+
+    const userBid = await placeBid(program, state, bidders[0], 2.0);
+    await placeBid(program, state, bidders[1], 3.0);
+
+    try {
+      await withdraw(state, treasury, bidders[1]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("HighestBidderCannotWithdraw");
+    }
+
+    const bidderBalance = await provider.connection.getBalance(bidders[0].publicKey);
+    const treasuryBalance = await provider.connection.getBalance(treasuryAuthority);
+    const bidEscrow = await findBidEscrowPda(bidders[0].publicKey, state, program.programId);
+    const rent = await rentOf(provider, userBid) + await rentOf(provider, bidEscrow);
+
+    await withdraw(state, treasury, bidders[0]);
+
+    const penalty = convertSolToLamports(0.05);
+    expect(await provider.connection.getBalance(bidders[0].publicKey)).equal(bidderBalance + convertSolToLamports(2.0) - penalty + rent);
+    expect(await provider.connection.getBalance(treasuryAuthority)).equal(treasuryBalance + penalty);
+    expect(await provider.connection.getAccountInfo(userBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(bidEscrow)).equal(null);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.bidCount).equal(1);
+    expect(Number(auction.penaltiesOwed)).equal(penalty);
+
+    // wait for auction to finish, the seller collects the penalty
+    await delay(5000);
+
+    const sellerBalance = await provider.connection.getBalance(initializer.publicKey);
+    await program.methods
+      .claimPenalties()
+      .accounts({ state, initializer: initializer.publicKey, beneficiary: initializer.publicKey, treasury, treasuryAuthority, systemProgram: anchor.web3.SystemProgram.programId })
+      .signers([initializer])
+      .rpc();
+
+    expect(await provider.connection.getBalance(initializer.publicKey)).equal(sellerBalance + penalty);
+    expect(await provider.connection.getBalance(treasuryAuthority)).equal(treasuryBalance);
+  });
+});
@@ -0,0 +1,69 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { Auction } from "../target/types/auction";
+import { expect } from "chai"
+import { convertSolToLamports, findBidEscrowPda, findUserBidPda, fundAccount, initializeAuction, placeBid, rentOf } from "./utils";
+
+describe("withdraw outbid", () => {
+  const provider = anchor.getProvider();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Auction as Program<Auction>;
+
+  const initializer = anchor.web3.Keypair.generate();
+  const bidders = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+  const withdraw = async (state: anchor.web3.PublicKey, bidder: anchor.web3.Keypair) => {
+    await program.methods
+      .withdrawOutbid()
+      .accounts({
+        state,
+        user: bidder.publicKey,
+        userBid: await findUserBidPda(bidder.publicKey, state, program.programId),
+        bidEscrow: await findBidEscrowPda(bidder.publicKey, state, program.programId),
+        rentPayer: bidder.publicKey,
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  before(async () => {
+    await fundAccount(provider, initializer.publicKey);
+    for (let bidder of bidders) {
+      await fundAccount(provider, bidder.publicKey);
+    }
+  });
+
+  it("Outbid bidder withdraws without penalty and bids again", async () => {
+    // the penalty only applies to withdraw_bid
+    const { state } = await initializeAuction(program, initializer, { durationInSecs: 3600, withdrawalPenaltyBps: 1000 });
+
+    const userBid = await placeBid(program, state, bidders[0], 1.0);
+
+    try {
+      await withdraw(state, bidders[0]);
+      throw new Error("Should have failed!");
+    } catch (error) {
+      expect(error.error.errorCode.code).equal("HighestBidderCannotWithdraw");
+    }
+
+    await placeBid(program, state, bidders[1], 2.0);
+
+    const balance = await provider.connection.getBalance(bidders[0].publicKey);
+    const bidEscrow = await findBidEscrowPda(bidders[0].publicKey, state, program.programId);
+    const rent = await rentOf(provider, userBid) + await rentOf(provider, bidEscrow);
+    await withdraw(state, bidders[0]);
+
+    expect(await provider.connection.getBalance(bidders[0].publicKey)).equal(balance + convertSolToLamports(1.0) + rent);
+    expect(await provider.connection.getAccountInfo(userBid)).equal(null);
+    expect(await provider.connection.getAccountInfo(bidEscrow)).equal(null);
+    expect((await program.account.state.fetch(state)).bidCount).equal(1);
+
+    // the user bid PDA is free again
+    await placeBid(program, state, bidders[0], 3.0);
+
+    const auction = await program.account.state.fetch(state);
+    expect(auction.highestBidderAccount.toBase58()).equal(bidders[0].publicKey.toBase58());
+    expect(auction.bidCount).equal(2);
+  });
+});